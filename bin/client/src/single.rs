@@ -9,12 +9,13 @@ use kona_driver::{Driver, DriverError};
 use kona_executor::{ExecutorError, KonaHandleRegister, TrieDBProvider};
 use kona_preimage::{CommsClient, HintWriterClient, PreimageKey, PreimageOracleClient};
 use kona_proof::{
-    BootInfo, CachingOracle, HintType,
+    BootInfo, CachingOracle, HintType, OUTPUT_ROOT_PREIMAGE_LEN, OutputRootComponents,
     errors::OracleProviderError,
     executor::KonaExecutor,
     l1::{OracleBlobProvider, OracleL1ChainProvider, OraclePipeline},
     l2::OracleL2ChainProvider,
     sync::new_pipeline_cursor,
+    verify_output_root,
 };
 use thiserror::Error;
 use tracing::{error, info};
@@ -165,7 +166,7 @@ pub async fn fetch_safe_head_hash<O>(
 where
     O: CommsClient,
 {
-    let mut output_preimage = [0u8; 128];
+    let mut output_preimage = [0u8; OUTPUT_ROOT_PREIMAGE_LEN];
     HintType::StartingL2Output
         .with_data(&[agreed_l2_output_root.as_ref()])
         .send(caching_oracle)
@@ -174,5 +175,7 @@ where
         .get_exact(PreimageKey::new_keccak256(*agreed_l2_output_root), output_preimage.as_mut())
         .await?;
 
-    output_preimage[96..128].try_into().map_err(OracleProviderError::SliceConversion)
+    let components = OutputRootComponents::from_preimage(&output_preimage);
+    verify_output_root(&components, agreed_l2_output_root)?;
+    Ok(components.block_hash)
 }
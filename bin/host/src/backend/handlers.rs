@@ -0,0 +1,101 @@
+//! Contains the [HintHandlerMap] definition.
+
+use crate::SharedKeyValueStore;
+use alloy_primitives::Bytes;
+use anyhow::{Result, anyhow};
+use kona_proof::errors::HintParsingError;
+use std::{collections::HashMap, fmt, future::Future, pin::Pin, str::FromStr, sync::Arc};
+
+/// A custom hint handler, called with the hint data and the key-value store to populate.
+pub type CustomHintHandler = Arc<
+    dyn Fn(Bytes, SharedKeyValueStore) -> Pin<Box<dyn Future<Output = Result<()>> + Send>>
+        + Send
+        + Sync,
+>;
+
+/// A registry of [CustomHintHandler]s, keyed by the hint type string.
+///
+/// This allows hosts built on kona to serve new hint types, or to override how a built-in hint
+/// type is served, without modifying the [HintHandler]. When a hint is received by the
+/// [OnlineHostBackend], a handler registered for its type takes precedence over the built-in
+/// [HintHandler]. Hints with no registered handler fall back to the built-in dispatch.
+/// Registering a handler for a hint type that already has one replaces it.
+///
+/// [HintHandler]: crate::HintHandler
+/// [OnlineHostBackend]: crate::OnlineHostBackend
+#[derive(Clone, Default)]
+pub struct HintHandlerMap {
+    /// The registered handlers, keyed by the hint type string.
+    handlers: HashMap<String, CustomHintHandler>,
+}
+
+impl fmt::Debug for HintHandlerMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HintHandlerMap").field("hint_types", &self.handlers.keys()).finish()
+    }
+}
+
+impl HintHandlerMap {
+    /// Creates a new, empty [HintHandlerMap].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a handler for hints of the given type, replacing any handler already registered
+    /// for it.
+    pub fn register<F, Fut>(&mut self, hint_type: impl Into<String>, handler: F) -> &mut Self
+    where
+        F: Fn(Bytes, SharedKeyValueStore) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        let boxed: CustomHintHandler =
+            Arc::new(move |data, kv| -> Pin<Box<dyn Future<Output = Result<()>> + Send>> {
+                Box::pin(handler(data, kv))
+            });
+        self.handlers.insert(hint_type.into(), boxed);
+        self
+    }
+
+    /// Returns `true` if a handler is registered for the given hint type.
+    pub fn contains(&self, hint_type: &str) -> bool {
+        self.handlers.contains_key(hint_type)
+    }
+
+    /// Returns the number of registered handlers.
+    pub fn len(&self) -> usize {
+        self.handlers.len()
+    }
+
+    /// Returns `true` if no handlers are registered.
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+
+    /// Serves a hint of the given type with its registered handler.
+    pub async fn fetch_hint(
+        &self,
+        hint_type: &str,
+        data: Bytes,
+        kv: SharedKeyValueStore,
+    ) -> Result<()> {
+        let handler = self
+            .handlers
+            .get(hint_type)
+            .ok_or_else(|| anyhow!("No handler registered for hint type {hint_type}"))?;
+        handler(data, kv).await
+    }
+}
+
+/// The unparsed type of a [Hint], used to look up its handler in a [HintHandlerMap].
+///
+/// [Hint]: kona_proof::Hint
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct RawHintType(pub(crate) String);
+
+impl FromStr for RawHintType {
+    type Err = HintParsingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(s.to_string()))
+    }
+}
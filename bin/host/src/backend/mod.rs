@@ -3,6 +3,12 @@
 mod offline;
 pub use offline::OfflineHostBackend;
 
+mod handlers;
+pub use handlers::{CustomHintHandler, HintHandlerMap};
+
+mod retry;
+pub use retry::RetryBudget;
+
 mod online;
 pub use online::{HintHandler, OnlineHostBackend, OnlineHostBackendCfg};
 
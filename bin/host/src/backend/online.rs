@@ -1,5 +1,9 @@
 //! Contains the [OnlineHostBackend] definition.
 
+use super::{
+    RetryBudget,
+    handlers::{HintHandlerMap, RawHintType},
+};
 use crate::SharedKeyValueStore;
 use anyhow::Result;
 use async_trait::async_trait;
@@ -10,7 +14,7 @@ use kona_preimage::{
 use kona_proof::{Hint, errors::HintParsingError};
 use std::{collections::HashSet, hash::Hash, str::FromStr, sync::Arc};
 use tokio::sync::RwLock;
-use tracing::{debug, error, trace};
+use tracing::{debug, error, trace, warn};
 
 /// The [OnlineHostBackendCfg] trait is used to define the type configuration for the
 /// [OnlineHostBackend].
@@ -20,6 +24,12 @@ pub trait OnlineHostBackendCfg {
 
     /// The providers that are used to fetch data in response to hints.
     type Providers: Send + Sync;
+
+    /// Validates a hint for the built-in [HintHandler] as it is parsed, so that malformed hints are
+    /// rejected before they are routed. By default, every hint is accepted.
+    fn validate_hint(_hint: &Hint<Self::HintType>) -> Result<(), HintParsingError> {
+        Ok(())
+    }
 }
 
 /// A [HintHandler] is an interface for receiving hints, fetching remote data, and storing it in the
@@ -38,6 +48,15 @@ pub trait HintHandler {
     ) -> Result<()>;
 }
 
+/// A hint received by the [OnlineHostBackend], parsed for the handler that serves it.
+#[derive(Debug, Clone)]
+enum RoutedHint<HT> {
+    /// A hint served by a handler registered in the [HintHandlerMap].
+    Custom(Hint<RawHintType>),
+    /// A hint served by the built-in [HintHandler].
+    BuiltIn(Hint<HT>),
+}
+
 /// The [OnlineHostBackend] is a [HintRouter] and [PreimageFetcher] that is used to fetch data from
 /// remote sources in response to hints.
 ///
@@ -56,8 +75,12 @@ where
     providers: C::Providers,
     /// Hints that should be immediately executed by the host.
     proactive_hints: HashSet<C::HintType>,
+    /// Custom hint handlers, which take precedence over the built-in [HintHandler].
+    hint_handlers: HintHandlerMap,
     /// The last hint that was received.
-    last_hint: Arc<RwLock<Option<Hint<C::HintType>>>>,
+    last_hint: Arc<RwLock<Option<RoutedHint<C::HintType>>>>,
+    /// The budget of prefetch retries, shared across every preimage request.
+    retry_budget: RetryBudget,
     /// Phantom marker for the [HintHandler].
     _hint_handler: std::marker::PhantomData<H>,
 }
@@ -75,7 +98,9 @@ where
             kv,
             providers,
             proactive_hints: HashSet::default(),
+            hint_handlers: HintHandlerMap::default(),
             last_hint: Arc::new(RwLock::new(None)),
+            retry_budget: RetryBudget::default(),
             _hint_handler: std::marker::PhantomData,
         }
     }
@@ -85,6 +110,52 @@ where
         self.proactive_hints.insert(hint_type);
         self
     }
+
+    /// Sets the custom hint handlers of the [OnlineHostBackend]. Hints with a registered handler
+    /// are served by it rather than by the built-in [HintHandler].
+    pub fn with_hint_handlers(mut self, handlers: HintHandlerMap) -> Self {
+        self.hint_handlers = handlers;
+        self
+    }
+
+    /// Sets the [RetryBudget] of the [OnlineHostBackend]. Each failed prefetch of a hint consumes
+    /// a retry, and once the budget is exhausted, a failed prefetch fails the preimage request
+    /// immediately. By default, prefetches are retried indefinitely.
+    pub fn with_retry_budget(mut self, budget: RetryBudget) -> Self {
+        self.retry_budget = budget;
+        self
+    }
+
+    /// Parses a raw hint, routing it to a registered custom handler if there is one for its
+    /// type, and to the built-in [HintHandler] otherwise. Hints for the built-in [HintHandler] are
+    /// validated with [OnlineHostBackendCfg::validate_hint].
+    fn parse_hint(&self, hint: &str) -> Result<RoutedHint<C::HintType>, HintParsingError> {
+        let raw = hint.parse::<Hint<RawHintType>>()?;
+        if self.hint_handlers.contains(&raw.ty.0) {
+            return Ok(RoutedHint::Custom(raw));
+        }
+        let hint = hint.parse::<Hint<C::HintType>>()?;
+        C::validate_hint(&hint)?;
+        Ok(RoutedHint::BuiltIn(hint))
+    }
+}
+
+impl<C, H> OnlineHostBackend<C, H>
+where
+    C: OnlineHostBackendCfg,
+    H: HintHandler<Cfg = C>,
+{
+    /// Fetches data in response to a routed hint, with the handler it was routed to.
+    async fn fetch_hint(&self, hint: RoutedHint<C::HintType>) -> Result<()> {
+        match hint {
+            RoutedHint::Custom(hint) => {
+                self.hint_handlers.fetch_hint(&hint.ty.0, hint.data, self.kv.clone()).await
+            }
+            RoutedHint::BuiltIn(hint) => {
+                H::fetch_hint(hint, &self.cfg, &self.providers, self.kv.clone()).await
+            }
+        }
+    }
 }
 
 #[async_trait]
@@ -97,11 +168,13 @@ where
     async fn route_hint(&self, hint: String) -> PreimageOracleResult<()> {
         trace!(target: "host-backend", "Received hint: {hint}");
 
-        let parsed_hint =
-            hint.parse::<Hint<C::HintType>>().map_err(|_| PreimageOracleError::KeyNotFound)?;
-        if self.proactive_hints.contains(&parsed_hint.ty) {
+        let parsed_hint = self.parse_hint(&hint).map_err(|e| {
+            warn!(target: "host-backend", "Rejected hint {hint}: {e}");
+            PreimageOracleError::KeyNotFound
+        })?;
+        if matches!(&parsed_hint, RoutedHint::BuiltIn(h) if self.proactive_hints.contains(&h.ty)) {
             debug!(target: "host-backend", "Proactive hint received; Immediately fetching {hint}");
-            H::fetch_hint(parsed_hint, &self.cfg, &self.providers, self.kv.clone())
+            self.fetch_hint(parsed_hint)
                 .await
                 .map_err(|e| PreimageOracleError::Other(e.to_string()))?;
         } else {
@@ -133,10 +206,15 @@ where
         // Use a loop to keep retrying the prefetch as long as the key is not found
         while preimage.is_none() {
             if let Some(hint) = self.last_hint.read().await.as_ref() {
-                let value =
-                    H::fetch_hint(hint.clone(), &self.cfg, &self.providers, self.kv.clone()).await;
+                let value = self.fetch_hint(hint.clone()).await;
 
                 if let Err(e) = value {
+                    if !self.retry_budget.try_consume() {
+                        error!(target: "host-backend", "Retry budget exhausted: {e}");
+                        return Err(PreimageOracleError::Other(format!(
+                            "retry budget exhausted: {e}"
+                        )));
+                    }
                     error!(target: "host-backend", "Failed to prefetch hint: {e}");
                     continue;
                 }
@@ -149,3 +227,135 @@ where
         preimage.ok_or(PreimageOracleError::KeyNotFound)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MemoryKeyValueStore;
+    use alloy_primitives::{B256, Bytes, keccak256};
+    use kona_proof::HintType;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct TestCfg;
+
+    impl OnlineHostBackendCfg for TestCfg {
+        type HintType = HintType;
+        type Providers = ();
+
+        fn validate_hint(hint: &Hint<HintType>) -> Result<(), HintParsingError> {
+            hint.ty.validate_data(&hint.data)
+        }
+    }
+
+    /// A built-in [HintHandler] that stores the hint data as the preimage of its hash.
+    struct TestHintHandler;
+
+    #[async_trait]
+    impl HintHandler for TestHintHandler {
+        type Cfg = TestCfg;
+
+        async fn fetch_hint(
+            hint: Hint<HintType>,
+            _: &TestCfg,
+            _: &(),
+            kv: SharedKeyValueStore,
+        ) -> Result<()> {
+            let key = PreimageKey::new_keccak256(*keccak256(hint.data.as_ref()));
+            kv.write().await.set(key.into(), b"built-in".to_vec())
+        }
+    }
+
+    fn test_backend(handlers: HintHandlerMap) -> OnlineHostBackend<TestCfg, TestHintHandler> {
+        let kv: SharedKeyValueStore = Arc::new(RwLock::new(MemoryKeyValueStore::new()));
+        OnlineHostBackend::new(TestCfg, kv, (), TestHintHandler).with_hint_handlers(handlers)
+    }
+
+    fn custom_handlers(hint_type: &str) -> HintHandlerMap {
+        let mut handlers = HintHandlerMap::new();
+        handlers.register(hint_type, |data: Bytes, kv: SharedKeyValueStore| async move {
+            let key = PreimageKey::new_keccak256(*keccak256(data.as_ref()));
+            kv.write().await.set(key.into(), b"custom".to_vec())
+        });
+        handlers
+    }
+
+    #[tokio::test]
+    async fn test_custom_hint_handler_takes_precedence() {
+        let data = B256::repeat_byte(0xAA);
+        let key = PreimageKey::new_keccak256(*keccak256(data));
+        let hint = HintType::L1BlockHeader.with_data(&[data.as_slice()]).encode();
+
+        let backend = test_backend(custom_handlers("l1-block-header"));
+        backend.route_hint(hint.clone()).await.unwrap();
+        assert_eq!(backend.get_preimage(key).await.unwrap(), b"custom");
+
+        // Without a custom handler, the hint falls back to the built-in handler.
+        let backend = test_backend(HintHandlerMap::new());
+        backend.route_hint(hint).await.unwrap();
+        assert_eq!(backend.get_preimage(key).await.unwrap(), b"built-in");
+    }
+
+    #[tokio::test]
+    async fn test_custom_hint_handler_unknown_hint_type() {
+        let data = B256::repeat_byte(0xBB);
+        let key = PreimageKey::new_keccak256(*keccak256(data));
+        let hint = format!("my-custom-hint {data}");
+
+        // Hint types unknown to the built-in handler are rejected unless a handler is registered.
+        let err = test_backend(HintHandlerMap::new()).route_hint(hint.clone()).await.unwrap_err();
+        assert!(matches!(err, PreimageOracleError::KeyNotFound));
+
+        let backend = test_backend(custom_handlers("my-custom-hint"));
+        backend.route_hint(hint).await.unwrap();
+        assert_eq!(backend.get_preimage(key).await.unwrap(), b"custom");
+    }
+
+    #[tokio::test]
+    async fn test_retry_budget_exhausted() {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let mut handlers = HintHandlerMap::new();
+        let counter = attempts.clone();
+        handlers.register("failing-hint", move |_: Bytes, _: SharedKeyValueStore| {
+            let counter = counter.clone();
+            async move {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Err(anyhow::anyhow!("transient failure"))
+            }
+        });
+        let backend = test_backend(handlers).with_retry_budget(RetryBudget::new(2));
+        let key = PreimageKey::new_keccak256(*B256::repeat_byte(0xDD));
+
+        // The first request is retried until the budget is exhausted.
+        backend.route_hint("failing-hint 0xdd".to_string()).await.unwrap();
+        let err = backend.get_preimage(key).await.unwrap_err();
+        assert!(
+            matches!(err, PreimageOracleError::Other(e) if e.contains("retry budget exhausted"))
+        );
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+
+        // Subsequent transient errors fail immediately.
+        assert!(backend.get_preimage(key).await.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_wrong_sized_hint_rejected() {
+        let backend = test_backend(HintHandlerMap::new());
+        let hint = HintType::L1BlockHeader.with_data(&[&[0xCC; 31]]).encode();
+        assert!(matches!(
+            backend.parse_hint(&hint),
+            Err(HintParsingError(e)) if e.contains("expected 32 bytes, got 31")
+        ));
+        assert!(matches!(
+            backend.route_hint(hint).await.unwrap_err(),
+            PreimageOracleError::KeyNotFound
+        ));
+
+        // Custom handlers are responsible for validating their own hints.
+        let backend = test_backend(custom_handlers("l1-block-header"));
+        backend
+            .route_hint(HintType::L1BlockHeader.with_data(&[&[0xCC; 31]]).encode())
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,76 @@
+//! Contains the [RetryBudget], which bounds the total number of retries across a proof run.
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
+
+/// A budget of retries shared across every preimage request of a proof run.
+///
+/// Retrying each failed request on its own may compound into an unbounded total retry time.
+/// Each retry consumes one unit of the shared budget, and once it is exhausted, every subsequent
+/// failure is final. Clones of the budget share the same remaining retries.
+#[derive(Debug, Clone)]
+pub struct RetryBudget {
+    /// The number of retries remaining.
+    remaining: Arc<AtomicU64>,
+}
+
+impl Default for RetryBudget {
+    fn default() -> Self {
+        Self::unlimited()
+    }
+}
+
+impl RetryBudget {
+    /// Creates a new [RetryBudget] allowing the given number of retries in total.
+    pub fn new(retries: u64) -> Self {
+        Self { remaining: Arc::new(AtomicU64::new(retries)) }
+    }
+
+    /// Creates a new [RetryBudget] that is never exhausted.
+    pub fn unlimited() -> Self {
+        Self::new(u64::MAX)
+    }
+
+    /// Returns the number of retries remaining.
+    pub fn remaining(&self) -> u64 {
+        self.remaining.load(Ordering::Relaxed)
+    }
+
+    /// Returns `true` if no retries remain.
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// Consumes a retry from the budget, returning `false` if it is exhausted. An unlimited
+    /// budget is never consumed.
+    pub fn try_consume(&self) -> bool {
+        self.remaining
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |remaining| match remaining {
+                u64::MAX => Some(u64::MAX),
+                remaining => remaining.checked_sub(1),
+            })
+            .is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_budget_shared() {
+        let budget = RetryBudget::new(2);
+        let shared = budget.clone();
+        assert!(budget.try_consume());
+        assert!(shared.try_consume());
+        assert!(budget.is_exhausted());
+        assert!(!shared.try_consume());
+        assert_eq!(budget.remaining(), 0);
+
+        let unlimited = RetryBudget::default();
+        assert!(unlimited.try_consume());
+        assert_eq!(unlimited.remaining(), u64::MAX);
+    }
+}
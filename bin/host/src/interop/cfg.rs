@@ -3,7 +3,7 @@
 use super::{InteropHintHandler, InteropLocalInputs};
 use crate::{
     DiskKeyValueStore, MemoryKeyValueStore, OfflineHostBackend, OnlineHostBackend,
-    OnlineHostBackendCfg, PreimageServer, SharedKeyValueStore, SplitKeyValueStore,
+    OnlineHostBackendCfg, PreimageServer, RetryBudget, SharedKeyValueStore, SplitKeyValueStore,
     eth::http_provider, server::PreimageServerError,
 };
 use alloy_primitives::{B256, Bytes};
@@ -92,6 +92,10 @@ pub struct InteropHost {
     /// look up the configs in the superchain registry.
     #[clap(long, alias = "rollup-cfgs", value_delimiter = ',', env)]
     pub rollup_config_paths: Option<Vec<PathBuf>>,
+    /// The total number of times failed hint prefetches may be retried over the proof run. If not
+    /// provided, prefetches are retried indefinitely.
+    #[clap(long, env)]
+    pub retry_budget: Option<u64>,
 }
 
 /// An error that can occur when handling interop hosts
@@ -164,7 +168,10 @@ impl InteropHost {
                 providers,
                 InteropHintHandler,
             )
-            .with_proactive_hint(HintType::L2BlockData);
+            .with_proactive_hint(HintType::L2BlockData)
+            .with_retry_budget(
+                self.retry_budget.map_or_else(RetryBudget::unlimited, RetryBudget::new),
+            );
 
             task::spawn(async {
                 PreimageServer::new(
@@ -19,14 +19,14 @@ use async_trait::async_trait;
 use kona_driver::Driver;
 use kona_executor::TrieDBProvider;
 use kona_preimage::{
-    BidirectionalChannel, HintReader, HintWriter, OracleReader, OracleServer, PreimageKey,
+    BidirectionalChannel, BlobKey, HintReader, HintWriter, OracleReader, OracleServer, PreimageKey,
     PreimageKeyType,
 };
 use kona_proof::{
     CachingOracle, Hint,
     executor::KonaExecutor,
     l1::{OracleBlobProvider, OracleL1ChainProvider, OraclePipeline},
-    l2::OracleL2ChainProvider,
+    l2::{OracleL2ChainProvider, code_length_key},
     sync::new_pipeline_cursor,
 };
 use kona_proof_interop::{HintType, PreState};
@@ -124,29 +124,28 @@ impl HintHandler for InteropHintHandler {
                 // Write all the field elements to the key-value store. There should be 4096.
                 // The preimage oracle key for each field element is the keccak256 hash of
                 // `abi.encodePacked(sidecar.KZGCommitment, uint256(i))`
-                let mut blob_key = [0u8; 80];
-                blob_key[..48].copy_from_slice(sidecar.kzg_commitment.as_ref());
-                for i in 0..FIELD_ELEMENTS_PER_BLOB {
-                    blob_key[72..].copy_from_slice(i.to_be_bytes().as_ref());
-                    let blob_key_hash = keccak256(blob_key.as_ref());
+                let commitment: [u8; 48] = *sidecar.kzg_commitment;
+                for blob_key in BlobKey::field_elements(commitment) {
+                    let i = blob_key.index as usize;
+                    let preimage = blob_key.preimage();
 
-                    kv_lock
-                        .set(PreimageKey::new_keccak256(*blob_key_hash).into(), blob_key.into())?;
                     kv_lock.set(
-                        PreimageKey::new(*blob_key_hash, PreimageKeyType::Blob).into(),
-                        sidecar.blob[(i as usize) << 5..(i as usize + 1) << 5].to_vec(),
+                        PreimageKey::new_keccak256(*keccak256(preimage)).into(),
+                        preimage.into(),
                     )?;
+                    kv_lock
+                        .set(blob_key.key().into(), sidecar.blob[i << 5..(i + 1) << 5].to_vec())?;
                 }
 
                 // Write the KZG Proof as the 4096th element.
-                blob_key[72..].copy_from_slice((FIELD_ELEMENTS_PER_BLOB).to_be_bytes().as_ref());
-                let blob_key_hash = keccak256(blob_key.as_ref());
+                let blob_key = BlobKey::new(commitment, FIELD_ELEMENTS_PER_BLOB);
+                let preimage = blob_key.preimage();
 
-                kv_lock.set(PreimageKey::new_keccak256(*blob_key_hash).into(), blob_key.into())?;
                 kv_lock.set(
-                    PreimageKey::new(*blob_key_hash, PreimageKeyType::Blob).into(),
-                    sidecar.kzg_proof.to_vec(),
+                    PreimageKey::new_keccak256(*keccak256(preimage)).into(),
+                    preimage.into(),
                 )?;
+                kv_lock.set(blob_key.key().into(), sidecar.kzg_proof.to_vec())?;
             }
             HintType::L1Precompile => {
                 ensure!(hint.data.len() >= 20, "Invalid hint data length");
@@ -322,7 +321,10 @@ impl HintHandler for InteropHintHandler {
                         .map_err(|e| anyhow!("Error fetching code hash preimage: {e}"))?,
                 };
 
+                // The length of the code is served alongside it, so that it may be fetched first.
+                let length = (code.len() as u64).to_be_bytes();
                 let mut kv_lock = kv.write().await;
+                kv_lock.set(code_length_key(hash).into(), length.to_vec())?;
                 kv_lock.set(PreimageKey::new_keccak256(*hash).into(), code.into())?;
             }
             HintType::L2StateNode => {
@@ -10,7 +10,10 @@ pub use kv::{
 };
 
 mod backend;
-pub use backend::{HintHandler, OfflineHostBackend, OnlineHostBackend, OnlineHostBackendCfg};
+pub use backend::{
+    CustomHintHandler, HintHandler, HintHandlerMap, OfflineHostBackend, OnlineHostBackend,
+    OnlineHostBackendCfg, RetryBudget,
+};
 
 pub mod eth;
 
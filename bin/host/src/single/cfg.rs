@@ -3,7 +3,7 @@
 use super::{SingleChainHintHandler, SingleChainLocalInputs};
 use crate::{
     DiskKeyValueStore, MemoryKeyValueStore, OfflineHostBackend, OnlineHostBackend,
-    OnlineHostBackendCfg, PreimageServer, SharedKeyValueStore, SplitKeyValueStore,
+    OnlineHostBackendCfg, PreimageServer, RetryBudget, SharedKeyValueStore, SplitKeyValueStore,
     eth::http_provider, server::PreimageServerError,
 };
 use alloy_primitives::B256;
@@ -14,7 +14,7 @@ use kona_genesis::RollupConfig;
 use kona_preimage::{
     BidirectionalChannel, Channel, HintReader, HintWriter, OracleReader, OracleServer,
 };
-use kona_proof::HintType;
+use kona_proof::{Hint, HintType, errors::HintParsingError};
 use kona_providers_alloy::{OnlineBeaconClient, OnlineBlobProvider};
 use kona_std_fpvm::{FileChannel, FileDescriptor};
 use op_alloy_network::Optimism;
@@ -106,6 +106,10 @@ pub struct SingleChainHost {
         env
     )]
     pub rollup_config_path: Option<PathBuf>,
+    /// The total number of times failed hint prefetches may be retried over the proof run. If not
+    /// provided, prefetches are retried indefinitely.
+    #[clap(long, env)]
+    pub retry_budget: Option<u64>,
 }
 
 /// An error that can occur when handling single chain hosts
@@ -172,7 +176,10 @@ impl SingleChainHost {
                 providers,
                 SingleChainHintHandler,
             )
-            .with_proactive_hint(HintType::L2PayloadWitness);
+            .with_proactive_hint(HintType::L2PayloadWitness)
+            .with_retry_budget(
+                self.retry_budget.map_or_else(RetryBudget::unlimited, RetryBudget::new),
+            );
 
             task::spawn(async {
                 PreimageServer::new(
@@ -274,6 +281,10 @@ impl SingleChainHost {
 impl OnlineHostBackendCfg for SingleChainHost {
     type HintType = HintType;
     type Providers = SingleChainProviders;
+
+    fn validate_hint(hint: &Hint<HintType>) -> Result<(), HintParsingError> {
+        hint.ty.validate_data(&hint.data)
+    }
 }
 
 /// The providers required for the single chain host.
@@ -4,20 +4,27 @@ use crate::{
     HintHandler, OnlineHostBackendCfg, backend::util::store_ordered_trie, kv::SharedKeyValueStore,
     single::cfg::SingleChainHost,
 };
-use alloy_consensus::Header;
+use alloy_consensus::{Header, Transaction};
 use alloy_eips::{
+    BlockNumberOrTag,
     eip2718::Encodable2718,
     eip4844::{FIELD_ELEMENTS_PER_BLOB, IndexedBlobHash},
 };
-use alloy_primitives::{Address, B256, Bytes, address, keccak256};
+use alloy_primitives::{Address, B256, Bytes, U256, address, keccak256};
 use alloy_provider::Provider;
 use alloy_rlp::Decodable;
 use alloy_rpc_types::{Block, debug::ExecutionWitness};
 use anyhow::{Result, anyhow, ensure};
 use async_trait::async_trait;
-use kona_preimage::{PreimageKey, PreimageKeyType};
-use kona_proof::{Hint, HintType};
-use kona_protocol::BlockInfo;
+use kona_genesis::RollupConfig;
+use kona_preimage::{BlobKey, PreimageKey, PreimageKeyType};
+use kona_proof::{
+    CompressionLevel, Hint, HintCapabilities, HintType, MAX_L1_BLOCK_RANGE, MAX_L2_STORAGE_RANGE,
+    MAX_L2_STORAGE_SLOTS, encode_preimage_response, l2::code_length_key,
+};
+use kona_protocol::{BlockInfo, L2BlockInfo};
+use kona_providers_alloy::{OnlineBeaconClient, OnlineBlobProvider};
+use kona_registry::ROLLUP_CONFIGS;
 use op_alloy_rpc_types_engine::OpPayloadAttributes;
 use tracing::warn;
 
@@ -109,30 +116,208 @@ impl HintHandler for SingleChainHintHandler {
                 // Write all the field elements to the key-value store. There should be 4096.
                 // The preimage oracle key for each field element is the keccak256 hash of
                 // `abi.encodePacked(sidecar.KZGCommitment, uint256(i))`
-                let mut blob_key = [0u8; 80];
-                blob_key[..48].copy_from_slice(sidecar.kzg_commitment.as_ref());
-                for i in 0..FIELD_ELEMENTS_PER_BLOB {
-                    blob_key[72..].copy_from_slice(i.to_be_bytes().as_ref());
-                    let blob_key_hash = keccak256(blob_key.as_ref());
+                let commitment: [u8; 48] = *sidecar.kzg_commitment;
+                for blob_key in BlobKey::field_elements(commitment) {
+                    let i = blob_key.index as usize;
+                    let preimage = blob_key.preimage();
 
-                    kv_lock
-                        .set(PreimageKey::new_keccak256(*blob_key_hash).into(), blob_key.into())?;
                     kv_lock.set(
-                        PreimageKey::new(*blob_key_hash, PreimageKeyType::Blob).into(),
-                        sidecar.blob[(i as usize) << 5..(i as usize + 1) << 5].to_vec(),
+                        PreimageKey::new_keccak256(*keccak256(preimage)).into(),
+                        preimage.into(),
                     )?;
+                    kv_lock
+                        .set(blob_key.key().into(), sidecar.blob[i << 5..(i + 1) << 5].to_vec())?;
                 }
 
                 // Write the KZG Proof as the 4096th element.
-                blob_key[72..].copy_from_slice((FIELD_ELEMENTS_PER_BLOB).to_be_bytes().as_ref());
-                let blob_key_hash = keccak256(blob_key.as_ref());
+                let blob_key = BlobKey::new(commitment, FIELD_ELEMENTS_PER_BLOB);
+                let preimage = blob_key.preimage();
+
+                kv_lock.set(
+                    PreimageKey::new_keccak256(*keccak256(preimage)).into(),
+                    preimage.into(),
+                )?;
+                kv_lock.set(blob_key.key().into(), sidecar.kzg_proof.to_vec())?;
+            }
+            HintType::L1BlobInclusionProof => {
+                ensure!(hint.data.len() == 48, "Invalid hint data length");
+
+                let hash_data_bytes: [u8; 32] = hint.data[0..32].try_into()?;
+                let index_data_bytes: [u8; 8] = hint.data[32..40].try_into()?;
+                let timestamp_data_bytes: [u8; 8] = hint.data[40..48].try_into()?;
+
+                let hash: B256 = hash_data_bytes.into();
+                let index = u64::from_be_bytes(index_data_bytes);
+                let timestamp = u64::from_be_bytes(timestamp_data_bytes);
+
+                let slot = OnlineBlobProvider::<OnlineBeaconClient>::slot(
+                    providers.blobs.genesis_time,
+                    providers.blobs.slot_interval,
+                    timestamp,
+                )
+                .map_err(|e| anyhow!("Failed to compute slot: {e}"))?;
+                let indexed_hash = IndexedBlobHash { index, hash };
+
+                // Fetch the full blob sidecar, including the commitment inclusion proof, from the
+                // beacon node.
+                let mut sidecars = providers
+                    .blobs
+                    .fetch_sidecars(slot, &[indexed_hash])
+                    .await
+                    .map_err(|e| anyhow!("Failed to fetch blob sidecars: {e}"))?;
+                if sidecars.len() != 1 {
+                    anyhow::bail!("Expected 1 sidecar, got {}", sidecars.len());
+                }
+                let sidecar = sidecars.remove(0);
+
+                // The inclusion proof is stored as the concatenation of its branch nodes, keyed
+                // by the generic key of the hint.
+                let proof = sidecar
+                    .kzg_commitment_inclusion_proof
+                    .iter()
+                    .flat_map(|node| node.0)
+                    .collect::<Vec<_>>();
+
+                let mut kv_lock = kv.write().await;
+                kv_lock.set(hint.ty.generic_key(hint.data.as_ref()).into(), proof)?;
+            }
+            HintType::L1BlobProof => {
+                ensure!(hint.data.len() == 48, "Invalid hint data length");
+
+                let hash_data_bytes: [u8; 32] = hint.data[0..32].try_into()?;
+                let index_data_bytes: [u8; 8] = hint.data[32..40].try_into()?;
+                let timestamp_data_bytes: [u8; 8] = hint.data[40..48].try_into()?;
+
+                let hash: B256 = hash_data_bytes.into();
+                let index = u64::from_be_bytes(index_data_bytes);
+                let timestamp = u64::from_be_bytes(timestamp_data_bytes);
+
+                let partial_block_ref = BlockInfo { timestamp, ..Default::default() };
+                let indexed_hash = IndexedBlobHash { index, hash };
+
+                // Fetch the blob sidecar from the blob provider.
+                let mut sidecars = providers
+                    .blobs
+                    .fetch_filtered_sidecars(&partial_block_ref, &[indexed_hash])
+                    .await
+                    .map_err(|e| anyhow!("Failed to fetch blob sidecars: {e}"))?;
+                if sidecars.len() != 1 {
+                    anyhow::bail!("Expected 1 sidecar, got {}", sidecars.len());
+                }
+                let sidecar = sidecars.remove(0);
+
+                let mut kv_lock = kv.write().await;
+
+                // Set the preimage for the blob commitment, which the proof is verified against.
+                kv_lock.set(
+                    PreimageKey::new(*hash, PreimageKeyType::Sha256).into(),
+                    sidecar.kzg_commitment.to_vec(),
+                )?;
 
-                kv_lock.set(PreimageKey::new_keccak256(*blob_key_hash).into(), blob_key.into())?;
+                // The KZG proof is keyed by the generic key of the hint.
                 kv_lock.set(
-                    PreimageKey::new(*blob_key_hash, PreimageKeyType::Blob).into(),
+                    hint.ty.generic_key(hint.data.as_ref()).into(),
                     sidecar.kzg_proof.to_vec(),
                 )?;
             }
+            HintType::L1BeaconBlock => {
+                // The block is identified by its slot or by its block root.
+                let block_id = match hint.data.len() {
+                    8 => u64::from_be_bytes(hint.data.as_ref().try_into()?).to_string(),
+                    32 => B256::from_slice(hint.data.as_ref()).to_string(),
+                    _ => anyhow::bail!("Invalid hint data length"),
+                };
+
+                let block = providers
+                    .blobs
+                    .beacon_client
+                    .beacon_block(&block_id)
+                    .await
+                    .map_err(|e| anyhow!("Failed to fetch beacon block: {e}"))?;
+
+                // The SSZ-encoded block is compressed, and keyed by the generic key of the hint.
+                let response = encode_preimage_response(&block, Some(CompressionLevel::BALANCED));
+                let mut kv_lock = kv.write().await;
+                kv_lock.set(hint.ty.generic_key(hint.data.as_ref()).into(), response)?;
+            }
+            HintType::L1FinalizedHead => {
+                ensure!(hint.data.len() == 32, "Invalid hint data length");
+
+                // Resolve the finalized block from the beacon state the L1 head commits to, rather
+                // than the live finalized tag, so that the preimage does not change over time.
+                let l1_head: B256 = hint.data.as_ref().try_into()?;
+                let head = providers
+                    .l1
+                    .get_block_by_hash(l1_head)
+                    .await?
+                    .ok_or(anyhow!("Block not found"))?;
+                let beacon_root = head
+                    .header
+                    .parent_beacon_block_root
+                    .ok_or(anyhow!("L1 head has no parent beacon block root"))?;
+                let hash = providers
+                    .blobs
+                    .beacon_client
+                    .finalized_block_hash(beacon_root)
+                    .await
+                    .map_err(|e| anyhow!("Failed to resolve the finalized block: {e}"))?;
+                let raw_header: Bytes =
+                    providers.l1.client().request("debug_getRawHeader", [hash]).await?;
+
+                // Store the finalized header both by its hash, and keyed by the generic key of the
+                // hint (the L1 head the finalized block was observed from).
+                let mut kv_lock = kv.write().await;
+                kv_lock.set(PreimageKey::new_keccak256(*hash).into(), raw_header.to_vec())?;
+                kv_lock.set(hint.ty.generic_key(hint.data.as_ref()).into(), raw_header.into())?;
+            }
+            HintType::L1ParentHash => {
+                ensure!(hint.data.len() == 32, "Invalid hint data length");
+
+                let hash: B256 = hint.data.as_ref().try_into()?;
+                let block = providers
+                    .l1
+                    .get_block_by_hash(hash)
+                    .await?
+                    .ok_or(anyhow!("Block not found"))?;
+
+                // Store the parent hash keyed by the generic key of the hint (the block hash).
+                let mut kv_lock = kv.write().await;
+                kv_lock.set(
+                    hint.ty.generic_key(hint.data.as_ref()).into(),
+                    block.header.parent_hash.to_vec(),
+                )?;
+            }
+            HintType::L1BlockRange => {
+                ensure!(hint.data.len() == 16, "Invalid hint data length");
+
+                let start = u64::from_be_bytes(hint.data[..8].try_into()?);
+                let end = u64::from_be_bytes(hint.data[8..].try_into()?);
+                ensure!(
+                    start <= end && end - start < MAX_L1_BLOCK_RANGE,
+                    "Invalid block range: {start} to {end}"
+                );
+
+                let mut hashes = Vec::with_capacity((end - start + 1) as usize * 32);
+                for number in start..=end {
+                    let block = providers
+                        .l1
+                        .get_block_by_number(BlockNumberOrTag::Number(number))
+                        .await?
+                        .ok_or(anyhow!("Block not found"))?;
+                    let hash = block.header.hash;
+                    let raw_header: Bytes =
+                        providers.l1.client().request("debug_getRawHeader", [hash]).await?;
+
+                    kv.write()
+                        .await
+                        .set(PreimageKey::new_keccak256(*hash).into(), raw_header.into())?;
+                    hashes.extend_from_slice(hash.as_slice());
+                }
+
+                // Store the ordered block hashes of the range keyed by the generic key of the hint.
+                let mut kv_lock = kv.write().await;
+                kv_lock.set(hint.ty.generic_key(hint.data.as_ref()).into(), hashes)?;
+            }
             HintType::L1Precompile => {
                 ensure!(hint.data.len() >= 20, "Invalid hint data length");
 
@@ -186,6 +371,184 @@ impl HintHandler for SingleChainHintHandler {
                     .collect::<Vec<_>>();
                 store_ordered_trie(kv.as_ref(), encoded_transactions.as_slice()).await?;
             }
+            HintType::L2Genesis => {
+                ensure!(hint.data.len() == 8, "Invalid hint data length");
+
+                let chain_id = u64::from_be_bytes(hint.data.as_ref().try_into()?);
+                let l2_chain_id = providers.l2.get_chain_id().await?;
+                ensure!(chain_id == l2_chain_id, "L2 chain ID mismatch");
+
+                let genesis = providers
+                    .l2
+                    .get_block_by_number(BlockNumberOrTag::Earliest)
+                    .await?
+                    .ok_or(anyhow!("Genesis block not found"))?;
+                let hash = genesis.header.hash;
+                let raw_header: Bytes =
+                    providers.l2.client().request("debug_getRawHeader", [hash]).await?;
+
+                // Store the genesis header by its hash, and the genesis hash keyed by the hash of
+                // the hint data (the chain ID).
+                let mut kv_lock = kv.write().await;
+                kv_lock.set(PreimageKey::new_keccak256(*hash).into(), raw_header.into())?;
+                kv_lock.set(hint.ty.generic_key(hint.data.as_ref()).into(), hash.to_vec())?;
+            }
+            HintType::L2ActiveFork => {
+                ensure!(hint.data.len() == 8, "Invalid hint data length");
+
+                let timestamp = u64::from_be_bytes(hint.data.as_ref().try_into()?);
+                let rollup_config = rollup_config(cfg)?;
+
+                // Store the name of the active fork keyed by the generic key of the hint (the
+                // timestamp).
+                let mut kv_lock = kv.write().await;
+                kv_lock.set(
+                    hint.ty.generic_key(hint.data.as_ref()).into(),
+                    active_fork(&rollup_config, timestamp).as_bytes().to_vec(),
+                )?;
+            }
+            HintType::L2BlockByTimestamp => {
+                ensure!(hint.data.len() == 8, "Invalid hint data length");
+
+                let timestamp = u64::from_be_bytes(hint.data.as_ref().try_into()?);
+                let rollup_config = rollup_config(cfg)?;
+                ensure!(
+                    timestamp >= rollup_config.genesis.l2_time,
+                    "Timestamp is before the L2 genesis"
+                );
+
+                // L2 blocks are produced at a fixed interval, so the block number follows from
+                // the timestamp.
+                let block_number = rollup_config.genesis.l2.number +
+                    (timestamp - rollup_config.genesis.l2_time) / rollup_config.block_time;
+                let block = providers
+                    .l2
+                    .get_block_by_number(block_number.into())
+                    .await?
+                    .ok_or(anyhow!("Block not found"))?;
+                let hash = block.header.hash;
+                let raw_header: Bytes =
+                    providers.l2.client().request("debug_getRawHeader", [hash]).await?;
+
+                // Store the header by its hash, and the block hash and number keyed by the hash of
+                // the hint data (the timestamp).
+                let mut kv_lock = kv.write().await;
+                kv_lock.set(PreimageKey::new_keccak256(*hash).into(), raw_header.into())?;
+                kv_lock.set(
+                    hint.ty.generic_key(hint.data.as_ref()).into(),
+                    [hash.as_slice(), &block_number.to_be_bytes()].concat(),
+                )?;
+            }
+            HintType::L1EpochBoundary => {
+                ensure!(hint.data.len() == 8, "Invalid hint data length");
+
+                let block_number = u64::from_be_bytes(hint.data.as_ref().try_into()?);
+                let rollup_config = rollup_config(cfg)?;
+                let block = providers
+                    .l2
+                    .get_block_by_number(block_number.into())
+                    .full()
+                    .await?
+                    .ok_or(anyhow!("Block not found"))?
+                    .into_consensus();
+                let l1_origin =
+                    L2BlockInfo::from_block_and_genesis(&block, &rollup_config.genesis)?.l1_origin;
+                let raw_header: Bytes =
+                    providers.l1.client().request("debug_getRawHeader", [l1_origin.hash]).await?;
+
+                // Store the L1 origin header by its hash, and the L1 origin hash and number keyed
+                // by the generic key of the hint (the L2 block number).
+                let mut kv_lock = kv.write().await;
+                kv_lock
+                    .set(PreimageKey::new_keccak256(*l1_origin.hash).into(), raw_header.into())?;
+                kv_lock.set(
+                    hint.ty.generic_key(hint.data.as_ref()).into(),
+                    [l1_origin.hash.as_slice(), &l1_origin.number.to_be_bytes()].concat(),
+                )?;
+            }
+            HintType::L1Checkpoint => {
+                ensure!(hint.data.len() == 8, "Invalid hint data length");
+
+                let block_number = u64::from_be_bytes(hint.data.as_ref().try_into()?);
+                let block = providers
+                    .l1
+                    .get_block_by_number(block_number.into())
+                    .await?
+                    .ok_or(anyhow!("Block not found"))?;
+                let (hash, state_root) = (block.header.hash, block.header.state_root);
+                let raw_header: Bytes =
+                    providers.l1.client().request("debug_getRawHeader", [hash]).await?;
+
+                // Store the checkpoint header by its hash, and the checkpoint hash and state root
+                // keyed by the generic key of the hint (the L1 block number).
+                let mut kv_lock = kv.write().await;
+                kv_lock.set(PreimageKey::new_keccak256(*hash).into(), raw_header.into())?;
+                kv_lock.set(
+                    hint.ty.generic_key(hint.data.as_ref()).into(),
+                    [hash.as_slice(), state_root.as_slice()].concat(),
+                )?;
+            }
+            HintType::L2BatchData => {
+                ensure!(hint.data.len() == 16, "Invalid hint data length");
+
+                let block_number = u64::from_be_bytes(hint.data[..8].try_into()?);
+                let batch_index = u64::from_be_bytes(hint.data[8..].try_into()?);
+                let rollup_config = rollup_config(cfg)?;
+                let Block { transactions, .. } = providers
+                    .l1
+                    .get_block_by_number(block_number.into())
+                    .full()
+                    .await?
+                    .ok_or(anyhow!("Block not found"))?;
+
+                // Batches are served as the host sees them: the calldata of the transactions sent
+                // to the batch inbox, in block order, without checking the sender against the
+                // batcher. Blob batches are served through the `l1-blob` hint instead.
+                let batch_tx = transactions
+                    .into_transactions()
+                    .filter(|tx| tx.to() == Some(rollup_config.batch_inbox_address))
+                    .nth(batch_index as usize)
+                    .ok_or(anyhow!("Batch not found"))?;
+                ensure!(
+                    batch_tx.blob_versioned_hashes().is_none(),
+                    "Blob batches are not served as raw batch data"
+                );
+
+                // Store the compressed batch data keyed by the generic key of the hint (the L1
+                // block number and batch index).
+                let response =
+                    encode_preimage_response(batch_tx.input(), Some(CompressionLevel::BALANCED));
+                let mut kv_lock = kv.write().await;
+                kv_lock.set(hint.ty.generic_key(hint.data.as_ref()).into(), response)?;
+            }
+            HintType::L2BlockSignature => {
+                // The signatures of gossiped blocks are not retained by the L2 execution node.
+                anyhow::bail!("Block signatures are not available from the L2 node");
+            }
+            HintType::L2DepositNonce => {
+                ensure!(hint.data.len() == 32, "Invalid hint data length");
+
+                let hash: B256 = hint.data.as_ref().try_into()?;
+                let receipt = providers
+                    .l2
+                    .get_transaction_receipt(hash)
+                    .await?
+                    .ok_or(anyhow!("Receipt not found"))?;
+                let nonce = receipt
+                    .inner
+                    .inner
+                    .as_deposit_receipt()
+                    .ok_or(anyhow!("Transaction is not a deposit"))?
+                    .deposit_nonce
+                    .ok_or(anyhow!("Deposit receipt has no nonce"))?;
+
+                // The nonce is keyed by the generic key of the hint (the transaction hash).
+                let mut kv_lock = kv.write().await;
+                kv_lock.set(
+                    hint.ty.generic_key(hint.data.as_ref()).into(),
+                    nonce.to_be_bytes().to_vec(),
+                )?;
+            }
             HintType::StartingL2Output => {
                 const OUTPUT_ROOT_VERSION: u8 = 0;
                 const L2_TO_L1_MESSAGE_PASSER_ADDRESS: Address =
@@ -252,7 +615,10 @@ impl HintHandler for SingleChainHintHandler {
                         .map_err(|e| anyhow!("Error fetching code hash preimage: {e}"))?,
                 };
 
+                // The length of the code is served alongside it, so that it may be fetched first.
+                let length = (code.len() as u64).to_be_bytes();
                 let mut kv_lock = kv.write().await;
+                kv_lock.set(code_length_key(hash).into(), length.to_vec())?;
                 kv_lock.set(PreimageKey::new_keccak256(*hash).into(), code.into())?;
             }
             HintType::L2StateNode => {
@@ -325,6 +691,81 @@ impl HintHandler for SingleChainHintHandler {
                     Ok::<(), anyhow::Error>(())
                 })?;
             }
+            HintType::L2StorageRange => {
+                ensure!(hint.data.len() == 8 + 20 + 32 + 8, "Invalid hint data length");
+
+                let block_number = u64::from_be_bytes(hint.data.as_ref()[..8].try_into()?);
+                let address = Address::from_slice(&hint.data.as_ref()[8..28]);
+                let start = U256::from_be_slice(&hint.data.as_ref()[28..60]);
+                let count = u64::from_be_bytes(hint.data.as_ref()[60..].try_into()?);
+                ensure!(
+                    count > 0 && count <= MAX_L2_STORAGE_RANGE,
+                    "Invalid storage range length: {count}"
+                );
+
+                let slots = (0..count)
+                    .map(|i| {
+                        start
+                            .checked_add(U256::from(i))
+                            .map(B256::from)
+                            .ok_or(anyhow!("Storage range overflows the slot space"))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+
+                let proof_response =
+                    providers.l2.get_proof(address, slots).block_id(block_number.into()).await?;
+
+                let mut kv_lock = kv.write().await;
+
+                // Write the account proof nodes and the storage proof nodes of every slot in the
+                // range to the key-value store.
+                proof_response
+                    .account_proof
+                    .into_iter()
+                    .chain(proof_response.storage_proof.into_iter().flat_map(|p| p.proof))
+                    .try_for_each(|node| {
+                        let node_hash = keccak256(node.as_ref());
+                        let key = PreimageKey::new_keccak256(*node_hash);
+                        kv_lock.set(key.into(), node.into())?;
+                        Ok::<(), anyhow::Error>(())
+                    })?;
+            }
+            HintType::L2StorageSlots => {
+                ensure!(
+                    hint.data.len() > 8 + 20 && (hint.data.len() - 28) % 32 == 0,
+                    "Invalid hint data length"
+                );
+
+                let block_number = u64::from_be_bytes(hint.data.as_ref()[..8].try_into()?);
+                let address = Address::from_slice(&hint.data.as_ref()[8..28]);
+                let slots = hint.data.as_ref()[28..]
+                    .chunks_exact(32)
+                    .map(B256::from_slice)
+                    .collect::<Vec<_>>();
+                ensure!(
+                    slots.len() <= MAX_L2_STORAGE_SLOTS,
+                    "Too many storage slots: {}",
+                    slots.len()
+                );
+
+                let proof_response =
+                    providers.l2.get_proof(address, slots).block_id(block_number.into()).await?;
+
+                let mut kv_lock = kv.write().await;
+
+                // Write the account proof nodes and the storage proof nodes of every slot in the
+                // batch to the key-value store.
+                proof_response
+                    .account_proof
+                    .into_iter()
+                    .chain(proof_response.storage_proof.into_iter().flat_map(|p| p.proof))
+                    .try_for_each(|node| {
+                        let node_hash = keccak256(node.as_ref());
+                        let key = PreimageKey::new_keccak256(*node_hash);
+                        kv_lock.set(key.into(), node.into())?;
+                        Ok::<(), anyhow::Error>(())
+                    })?;
+            }
             HintType::L2PayloadWitness => {
                 ensure!(hint.data.len() >= 32, "Invalid hint data length");
 
@@ -360,8 +801,60 @@ impl HintHandler for SingleChainHintHandler {
                     kv_lock.set(key.into(), preimage.into())?;
                 }
             }
+            HintType::Hello => {
+                // The client's capabilities are not needed to serve ours, but must be well-formed.
+                HintCapabilities::decode(hint.data.as_ref())?;
+
+                // The host's capabilities are keyed by the generic key of the hint. Block
+                // signatures cannot be served, so they are not advertised.
+                let mut kv_lock = kv.write().await;
+                kv_lock.set(
+                    hint.ty.generic_key(hint.data.as_ref()).into(),
+                    HintCapabilities::all().without(HintType::L2BlockSignature).encode().into(),
+                )?;
+            }
+            #[cfg(feature = "debug-echo")]
+            HintType::DebugEcho => {
+                // Echo the hint data back as the preimage of its keccak256 hash.
+                let key = PreimageKey::new_keccak256(*keccak256(hint.data.as_ref()));
+                kv.write().await.set(key.into(), hint.data.into())?;
+            }
         }
 
         Ok(())
     }
 }
+
+/// Returns the [RollupConfig] of the L2 chain, from the registry if the chain ID is known, or from
+/// the rollup config file otherwise.
+fn rollup_config(cfg: &SingleChainHost) -> Result<RollupConfig> {
+    cfg.l2_chain_id
+        .and_then(|chain_id| ROLLUP_CONFIGS.get(&chain_id).cloned())
+        .or_else(|| cfg.read_rollup_config().ok())
+        .ok_or(anyhow!("No rollup config found"))
+}
+
+/// Returns the name of the latest hardfork that is active at the given timestamp.
+fn active_fork(rollup_config: &RollupConfig, timestamp: u64) -> &'static str {
+    if rollup_config.is_interop_active(timestamp) {
+        "interop"
+    } else if rollup_config.is_isthmus_active(timestamp) {
+        "isthmus"
+    } else if rollup_config.is_holocene_active(timestamp) {
+        "holocene"
+    } else if rollup_config.is_granite_active(timestamp) {
+        "granite"
+    } else if rollup_config.is_fjord_active(timestamp) {
+        "fjord"
+    } else if rollup_config.is_ecotone_active(timestamp) {
+        "ecotone"
+    } else if rollup_config.is_delta_active(timestamp) {
+        "delta"
+    } else if rollup_config.is_canyon_active(timestamp) {
+        "canyon"
+    } else if rollup_config.is_regolith_active(timestamp) {
+        "regolith"
+    } else {
+        "bedrock"
+    }
+}
@@ -0,0 +1,156 @@
+//! Contains the [EngineCircuitBreaker], which stops the engine task queue from executing tasks
+//! against an execution engine that fails repeatedly.
+
+use std::time::{Duration, Instant};
+
+/// The default number of consecutive engine failures after which the [EngineCircuitBreaker]
+/// opens.
+pub const DEFAULT_ENGINE_FAILURE_THRESHOLD: u32 = 5;
+
+/// The default duration for which the [EngineCircuitBreaker] stays open before it allows a probe.
+pub const DEFAULT_ENGINE_PROBE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The delay before the first retry of a task that failed against the engine. The delay doubles
+/// with each consecutive retry, up to [ENGINE_RETRY_MAX_BACKOFF].
+pub const ENGINE_RETRY_MIN_BACKOFF: Duration = Duration::from_millis(10);
+
+/// The maximum delay between retries of a task that failed against the engine.
+pub const ENGINE_RETRY_MAX_BACKOFF: Duration = Duration::from_secs(1);
+
+/// The state of an [EngineCircuitBreaker].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Tasks are executed against the engine.
+    Closed,
+    /// The engine failed repeatedly, and tasks are short-circuited without being executed.
+    Open,
+    /// The breaker has been open for the probe interval, and the next task is executed as a
+    /// probe of whether the engine has recovered.
+    HalfOpen,
+}
+
+/// A circuit breaker that short-circuits engine tasks once the engine has failed
+/// `threshold` times in a row.
+///
+/// While the breaker is [CircuitState::Open], tasks are not executed, rather than retried against
+/// an engine that is down. Once the probe interval has elapsed, the breaker is
+/// [CircuitState::HalfOpen], and the next task is executed as a probe. A successful probe closes
+/// the breaker, while a failed probe opens it again for another probe interval.
+#[derive(Debug, Clone, Copy)]
+pub struct EngineCircuitBreaker {
+    /// The number of consecutive failures after which the breaker opens.
+    threshold: u32,
+    /// The duration for which the breaker stays open before it allows a probe.
+    probe_interval: Duration,
+    /// The number of consecutive failures.
+    failures: u32,
+    /// When the breaker was last opened, if it is open.
+    opened_at: Option<Instant>,
+}
+
+impl Default for EngineCircuitBreaker {
+    fn default() -> Self {
+        Self::new(DEFAULT_ENGINE_FAILURE_THRESHOLD, DEFAULT_ENGINE_PROBE_INTERVAL)
+    }
+}
+
+impl EngineCircuitBreaker {
+    /// Creates a new, closed [EngineCircuitBreaker] that opens after `threshold` consecutive
+    /// failures, and allows a probe once it has been open for `probe_interval`. A threshold of
+    /// `0` is treated as `1`.
+    pub const fn new(threshold: u32, probe_interval: Duration) -> Self {
+        let threshold = if threshold == 0 { 1 } else { threshold };
+        Self { threshold, probe_interval, failures: 0, opened_at: None }
+    }
+
+    /// Returns the number of consecutive failures after which the breaker opens.
+    pub const fn threshold(&self) -> u32 {
+        self.threshold
+    }
+
+    /// Returns the number of consecutive failures recorded.
+    pub const fn failures(&self) -> u32 {
+        self.failures
+    }
+
+    /// Returns the [CircuitState] of the breaker at the given instant.
+    pub fn state_at(&self, now: Instant) -> CircuitState {
+        match self.opened_at {
+            None => CircuitState::Closed,
+            Some(at) if now.saturating_duration_since(at) >= self.probe_interval => {
+                CircuitState::HalfOpen
+            }
+            Some(_) => CircuitState::Open,
+        }
+    }
+
+    /// Returns the current [CircuitState] of the breaker.
+    pub fn state(&self) -> CircuitState {
+        self.state_at(Instant::now())
+    }
+
+    /// Returns `true` if a task may be executed at the given instant, either because the breaker
+    /// is closed or as a probe.
+    pub fn allows_at(&self, now: Instant) -> bool {
+        self.state_at(now) != CircuitState::Open
+    }
+
+    /// Records an engine failure at the given instant, opening the breaker if the threshold is
+    /// reached or if the failure was a probe.
+    pub const fn record_failure(&mut self, at: Instant) {
+        self.failures = self.failures.saturating_add(1);
+        if self.opened_at.is_some() || self.failures >= self.threshold {
+            self.opened_at = Some(at);
+        }
+    }
+
+    /// Records a successful task, closing the breaker.
+    pub const fn record_success(&mut self) {
+        self.failures = 0;
+        self.opened_at = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breaker_opens_after_threshold() {
+        let start = Instant::now();
+        let mut breaker = EngineCircuitBreaker::new(3, Duration::from_secs(5));
+
+        breaker.record_failure(start);
+        breaker.record_failure(start);
+        assert_eq!(breaker.state_at(start), CircuitState::Closed);
+
+        // A success resets the consecutive failures.
+        breaker.record_success();
+        for _ in 0..3 {
+            assert!(breaker.allows_at(start));
+            breaker.record_failure(start);
+        }
+        assert_eq!(breaker.state_at(start), CircuitState::Open);
+        assert!(!breaker.allows_at(start + Duration::from_secs(4)));
+    }
+
+    #[test]
+    fn test_breaker_half_open_probe() {
+        let start = Instant::now();
+        let mut breaker = EngineCircuitBreaker::new(1, Duration::from_secs(5));
+        breaker.record_failure(start);
+
+        // A failed probe opens the breaker for another probe interval.
+        let probe = start + Duration::from_secs(5);
+        assert_eq!(breaker.state_at(probe), CircuitState::HalfOpen);
+        breaker.record_failure(probe);
+        assert_eq!(breaker.state_at(probe + Duration::from_secs(4)), CircuitState::Open);
+
+        // A successful probe closes it.
+        let probe = probe + Duration::from_secs(5);
+        assert!(breaker.allows_at(probe));
+        breaker.record_success();
+        assert_eq!(breaker.state_at(probe), CircuitState::Closed);
+        assert_eq!(breaker.failures(), 0);
+    }
+}
@@ -2,17 +2,20 @@
 
 use alloy_eips::eip1898::BlockNumberOrTag;
 use alloy_network::AnyNetwork;
-use alloy_primitives::{B256, BlockHash, Bytes};
-use alloy_provider::{Provider, RootProvider};
+use alloy_primitives::{B256, BlockHash, Bytes, keccak256};
+use alloy_provider::{Provider, RootProvider, ext::EngineApi};
 use alloy_rpc_client::RpcClient;
 use alloy_rpc_types_engine::{
     ClientVersionV1, ExecutionPayloadBodiesV1, ExecutionPayloadEnvelopeV2, ExecutionPayloadInputV2,
-    ExecutionPayloadV3, ForkchoiceState, ForkchoiceUpdated, JwtSecret, PayloadId, PayloadStatus,
+    ExecutionPayloadV1, ExecutionPayloadV3, ForkchoiceState, ForkchoiceUpdated, JwtSecret,
+    PayloadAttributes, PayloadId, PayloadStatus,
 };
-use alloy_transport::TransportResult;
+use alloy_rpc_types_eth::SyncStatus;
+use alloy_transport::{RpcError, TransportErrorKind, TransportResult};
 use alloy_transport_http::{
     AuthLayer, AuthService, Http, HyperClient,
     hyper_util::{
+        self,
         client::legacy::{Client, connect::HttpConnector},
         rt::TokioExecutor,
     },
@@ -24,42 +27,260 @@ use op_alloy_provider::ext::engine::OpEngineApi;
 use op_alloy_rpc_types_engine::{
     OpExecutionPayloadEnvelopeV3, OpExecutionPayloadEnvelopeV4, OpPayloadAttributes,
 };
-use std::sync::Arc;
+use std::{
+    future::Future,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Instant,
+};
+use tokio::sync::{Semaphore, SemaphorePermit};
 use tower::ServiceBuilder;
 use url::Url;
 
 use kona_genesis::RollupConfig;
-use kona_protocol::L2BlockInfo;
+use kona_protocol::{L2BlockInfo, Predeploys};
+
+#[cfg(feature = "metrics")]
+use crate::MetricsRpcLatencySink;
+use crate::RpcLatencySink;
 
 /// A Hyper HTTP client with a JWT authentication layer.
 type HyperAuthClient<B = Full<Bytes>> = HyperClient<B, AuthService<Client<HttpConnector, B>>>;
 
+/// Returns `true` if the transport error means that the engine endpoint could not be reached,
+/// rather than that a call on a reachable endpoint failed or timed out.
+fn is_connection_error(err: &TransportErrorKind) -> bool {
+    let TransportErrorKind::Custom(err) = err else {
+        return matches!(err, TransportErrorKind::BackendGone);
+    };
+    let mut source: Option<&(dyn std::error::Error + 'static)> = Some(err.as_ref());
+    while let Some(err) = source {
+        if err.downcast_ref::<hyper_util::client::legacy::Error>().is_some_and(|e| e.is_connect()) {
+            return true;
+        }
+        source = err.source();
+    }
+    false
+}
+
 /// An external engine api client
+///
+/// The client may be configured with multiple engine endpoints. Engine API calls are routed to
+/// the active endpoint, and fail over to the next endpoint in the list on connection errors.
+///
+/// Engine API calls are made through the client's [OpEngineApi] implementation, along with
+/// [EngineClient::new_payload_v1] and [EngineClient::fork_choice_updated_v1] for the V1 methods
+/// that [OpEngineApi] does not cover.
 #[derive(Debug, Clone)]
 pub struct EngineClient {
-    /// The L2 engine provider.
-    engine: RootProvider<AnyNetwork>,
+    /// The L2 engine providers, in order of priority, along with their [Url]s.
+    engines: Arc<[(Url, RootProvider<AnyNetwork>)]>,
+    /// The index of the active engine provider.
+    active: Arc<AtomicUsize>,
     /// The L2 chain provider.
     rpc: RootProvider<Optimism>,
     /// The [RollupConfig] for the chain used to timestamp which version of the engine api to use.
     cfg: Arc<RollupConfig>,
+    /// The sink for the latency of engine API calls, if latencies are recorded.
+    latency_sink: Option<Arc<dyn RpcLatencySink>>,
+    /// The permits of the engine API calls that may be in flight at once, if limited.
+    call_permits: Option<Arc<Semaphore>>,
 }
 
 impl EngineClient {
     /// Creates a new [`EngineClient`] from the provided [Url] and [JwtSecret].
     pub fn new_http(engine: Url, rpc: Url, cfg: Arc<RollupConfig>, jwt: JwtSecret) -> Self {
+        Self::new_http_with_fallbacks(engine, Vec::new(), rpc, cfg, jwt)
+    }
+
+    /// Creates a new [`EngineClient`] from the provided primary engine [Url], a list of fallback
+    /// engine [Url]s, and the [JwtSecret] shared by all engine endpoints.
+    ///
+    /// If an engine API call fails with a connection error, the call is retried against the next
+    /// endpoint in the list, which then becomes the active endpoint.
+    pub fn new_http_with_fallbacks(
+        engine: Url,
+        fallbacks: Vec<Url>,
+        rpc: Url,
+        cfg: Arc<RollupConfig>,
+        jwt: JwtSecret,
+    ) -> Self {
+        let engines = core::iter::once(engine)
+            .chain(fallbacks)
+            .map(|url| (url.clone(), Self::engine_provider(url, jwt)))
+            .collect();
+
+        let rpc = RootProvider::<Optimism>::new_http(rpc);
+        Self {
+            engines,
+            active: Arc::new(AtomicUsize::new(0)),
+            rpc,
+            cfg,
+            #[cfg(feature = "metrics")]
+            latency_sink: Some(Arc::new(MetricsRpcLatencySink)),
+            #[cfg(not(feature = "metrics"))]
+            latency_sink: None,
+            call_permits: None,
+        }
+    }
+
+    /// Sets the [RpcLatencySink] that the latency of `engine_forkchoiceUpdated`,
+    /// `engine_newPayload` and `engine_getPayload` calls is recorded in.
+    ///
+    /// With the `metrics` feature, latencies are recorded in the global metrics recorder by
+    /// default.
+    pub fn with_latency_sink(mut self, sink: Arc<dyn RpcLatencySink>) -> Self {
+        self.latency_sink = Some(sink);
+        self
+    }
+
+    /// Limits the number of engine API calls that may be in flight at once to `max`, protecting
+    /// execution clients that degrade under many concurrent `engine_*` calls. A limit of `0` is
+    /// treated as `1`.
+    ///
+    /// Calls beyond the limit wait for an in-flight call to complete before they are sent. A call
+    /// holds its permit across failovers, and the time spent waiting for a permit is not recorded
+    /// in the [RpcLatencySink]. Clones of the client share the same limit.
+    pub fn with_max_concurrent_calls(mut self, max: usize) -> Self {
+        self.call_permits = Some(Arc::new(Semaphore::new(max.max(1))));
+        self
+    }
+
+    /// Creates a new engine provider for the given [Url], authenticated with the [JwtSecret].
+    fn engine_provider(url: Url, jwt: JwtSecret) -> RootProvider<AnyNetwork> {
         let hyper_client = Client::builder(TokioExecutor::new()).build_http::<Full<Bytes>>();
 
         let auth_layer = AuthLayer::new(jwt);
         let service = ServiceBuilder::new().layer(auth_layer).service(hyper_client);
 
         let layer_transport = HyperClient::with_service(service);
-        let http_hyper = Http::with_client(layer_transport, engine);
+        let http_hyper = Http::with_client(layer_transport, url);
         let rpc_client = RpcClient::new(http_hyper, true);
-        let engine = RootProvider::<AnyNetwork>::new(rpc_client);
+        RootProvider::<AnyNetwork>::new(rpc_client)
+    }
 
-        let rpc = RootProvider::<Optimism>::new_http(rpc);
-        Self { engine, rpc, cfg }
+    /// Returns the [Url] of the active engine endpoint.
+    pub fn active_engine(&self) -> &Url {
+        &self.engines[self.active.load(Ordering::Relaxed)].0
+    }
+
+    /// Waits until an engine API call may be sent, returning its permit, if the number of
+    /// concurrent calls is limited.
+    async fn acquire_call_permit(&self) -> Option<SemaphorePermit<'_>> {
+        match &self.call_permits {
+            Some(permits) => Some(permits.acquire().await.expect("call permits are never closed")),
+            None => None,
+        }
+    }
+
+    /// Executes an engine API call against the active engine endpoint, once the limit on
+    /// concurrent calls allows it. See [EngineClient::try_engines].
+    async fn with_failover<T, F, Fut>(&self, call: F) -> TransportResult<T>
+    where
+        F: Fn(RootProvider<AnyNetwork>) -> Fut,
+        Fut: Future<Output = TransportResult<T>>,
+    {
+        let _permit = self.acquire_call_permit().await;
+        self.try_engines(call).await
+    }
+
+    /// Executes an engine API call against the active engine endpoint. If the endpoint cannot be
+    /// connected to, the next endpoint is made active and the call is retried, until every
+    /// endpoint has been tried once. Other errors, such as HTTP errors or a call that times out on
+    /// an established connection, are returned without failing over.
+    async fn try_engines<T, F, Fut>(&self, call: F) -> TransportResult<T>
+    where
+        F: Fn(RootProvider<AnyNetwork>) -> Fut,
+        Fut: Future<Output = TransportResult<T>>,
+    {
+        let start = self.active.load(Ordering::Relaxed);
+        let mut attempt = 0;
+        loop {
+            let index = (start + attempt) % self.engines.len();
+            let (url, engine) = &self.engines[index];
+            match call(engine.clone()).await {
+                Err(RpcError::Transport(e))
+                    if is_connection_error(&e) && attempt + 1 < self.engines.len() =>
+                {
+                    let next = (index + 1) % self.engines.len();
+                    warn!(
+                        target: "engine",
+                        "Engine endpoint {url} unreachable ({e}), failing over to {}",
+                        self.engines[next].0
+                    );
+                    self.active.store(next, Ordering::Relaxed);
+                    attempt += 1;
+                }
+                res => return res,
+            }
+        }
+    }
+
+    /// Executes an engine API call with [EngineClient::with_failover], recording its latency,
+    /// including any failovers, under the given method name in the [RpcLatencySink], if one is set.
+    /// Failed calls are recorded as well.
+    async fn with_failover_timed<T, F, Fut>(
+        &self,
+        method: &'static str,
+        call: F,
+    ) -> TransportResult<T>
+    where
+        F: Fn(RootProvider<AnyNetwork>) -> Fut,
+        Fut: Future<Output = TransportResult<T>>,
+    {
+        let _permit = self.acquire_call_permit().await;
+        let start = Instant::now();
+        let res = self.try_engines(call).await;
+        if let Some(sink) = &self.latency_sink {
+            sink.record(method, start.elapsed());
+        }
+        res
+    }
+
+    /// Sends the given payload to the execution engine with `engine_newPayloadV1`, as specified for
+    /// the Paris fork. Used for payloads from before the Canyon fork.
+    pub async fn new_payload_v1(
+        &self,
+        payload: ExecutionPayloadV1,
+    ) -> TransportResult<PayloadStatus> {
+        self.with_failover_timed("newPayload", |engine| {
+            let payload = payload.clone();
+            async move {
+                <RootProvider<AnyNetwork> as EngineApi<AnyNetwork>>::new_payload_v1(
+                    &engine, payload,
+                )
+                .await
+            }
+        })
+        .await
+    }
+
+    /// Updates the execution engine with the given forkchoice with `engine_forkchoiceUpdatedV1`,
+    /// as specified for the Paris fork. Used for payloads from before the Canyon fork.
+    pub async fn fork_choice_updated_v1(
+        &self,
+        fork_choice_state: ForkchoiceState,
+        payload_attributes: Option<PayloadAttributes>,
+    ) -> TransportResult<ForkchoiceUpdated> {
+        self.with_failover_timed("forkchoiceUpdated", |engine| {
+            let payload_attributes = payload_attributes.clone();
+            async move {
+                <RootProvider<AnyNetwork> as EngineApi<AnyNetwork>>::fork_choice_updated_v1(
+                    &engine,
+                    fork_choice_state,
+                    payload_attributes,
+                )
+                .await
+            }
+        })
+        .await
+    }
+
+    /// Fetches the sync status of the active engine endpoint with `eth_syncing`.
+    pub async fn syncing(&self) -> TransportResult<SyncStatus> {
+        self.with_failover(|engine| async move { engine.syncing().await }).await
     }
 
     /// Fetches the [L2BlockInfo] by [BlockNumberOrTag].
@@ -79,6 +300,52 @@ impl EngineClient {
             None => Ok(None),
         }
     }
+
+    /// Fetches the hash of the L2 block with the given [BlockNumberOrTag], if it exists.
+    pub async fn l2_block_hash_by_label(&self, numtag: BlockNumberOrTag) -> Result<Option<B256>> {
+        let block = <RootProvider<Optimism>>::get_block_by_number(&self.rpc, numtag)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+        Ok(block.map(|b| b.header.hash))
+    }
+
+    /// Fetches the [L2BlockInfo] of the L2 block with the given number along with its output
+    /// root, if the block exists.
+    ///
+    /// The output root commits to the block's state root, the storage root of the
+    /// `L2ToL1MessagePasser` at the block, and the block hash.
+    pub async fn l2_output_at_block(&self, number: u64) -> Result<Option<(L2BlockInfo, B256)>> {
+        let Some(block) = <RootProvider<Optimism>>::get_block_by_number(
+            &self.rpc,
+            BlockNumberOrTag::Number(number),
+        )
+        .full()
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?
+        else {
+            return Ok(None);
+        };
+        let hash = block.header.hash;
+        let block = block.into_consensus();
+
+        let message_passer = <RootProvider<Optimism>>::get_proof(
+            &self.rpc,
+            Predeploys::L2_TO_L1_MESSAGE_PASSER,
+            Default::default(),
+        )
+        .block_id(hash.into())
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+        // Version 0 output roots are prefixed by a zeroed version word.
+        let mut raw_output = [0u8; 128];
+        raw_output[32..64].copy_from_slice(block.header.state_root.as_ref());
+        raw_output[64..96].copy_from_slice(message_passer.storage_hash.as_ref());
+        raw_output[96..128].copy_from_slice(hash.as_ref());
+
+        let block_info = L2BlockInfo::from_block_and_genesis(&block, &self.cfg.genesis)?;
+        Ok(Some((block_info, keccak256(raw_output))))
+    }
 }
 
 #[async_trait::async_trait]
@@ -87,10 +354,16 @@ impl OpEngineApi<AnyNetwork, Http<HyperAuthClient>> for EngineClient {
         &self,
         payload: ExecutionPayloadInputV2,
     ) -> TransportResult<PayloadStatus> {
-        <RootProvider<AnyNetwork> as OpEngineApi<
-            AnyNetwork,
-            Http<HyperAuthClient>,
-        >>::new_payload_v2(&self.engine, payload).await
+        self.with_failover_timed("newPayload", |engine| {
+            let payload = payload.clone();
+            async move {
+                <RootProvider<AnyNetwork> as OpEngineApi<
+                    AnyNetwork,
+                    Http<HyperAuthClient>,
+                >>::new_payload_v2(&engine, payload).await
+            }
+        })
+        .await
     }
 
     async fn new_payload_v3(
@@ -98,10 +371,16 @@ impl OpEngineApi<AnyNetwork, Http<HyperAuthClient>> for EngineClient {
         payload: ExecutionPayloadV3,
         parent_beacon_block_root: B256,
     ) -> TransportResult<PayloadStatus> {
-        <RootProvider<AnyNetwork> as OpEngineApi<
-            AnyNetwork,
-            Http<HyperAuthClient>,
-        >>::new_payload_v3(&self.engine, payload, parent_beacon_block_root).await
+        self.with_failover_timed("newPayload", |engine| {
+            let payload = payload.clone();
+            async move {
+                <RootProvider<AnyNetwork> as OpEngineApi<
+                    AnyNetwork,
+                    Http<HyperAuthClient>,
+                >>::new_payload_v3(&engine, payload, parent_beacon_block_root).await
+            }
+        })
+        .await
     }
 
     async fn new_payload_v4(
@@ -109,10 +388,16 @@ impl OpEngineApi<AnyNetwork, Http<HyperAuthClient>> for EngineClient {
         payload: ExecutionPayloadV3,
         parent_beacon_block_root: B256,
     ) -> TransportResult<PayloadStatus> {
-        <RootProvider<AnyNetwork> as OpEngineApi<
-            AnyNetwork,
-            Http<HyperAuthClient>,
-        >>::new_payload_v4(&self.engine, payload, parent_beacon_block_root).await
+        self.with_failover_timed("newPayload", |engine| {
+            let payload = payload.clone();
+            async move {
+                <RootProvider<AnyNetwork> as OpEngineApi<
+                    AnyNetwork,
+                    Http<HyperAuthClient>,
+                >>::new_payload_v4(&engine, payload, parent_beacon_block_root).await
+            }
+        })
+        .await
     }
 
     async fn fork_choice_updated_v2(
@@ -120,10 +405,16 @@ impl OpEngineApi<AnyNetwork, Http<HyperAuthClient>> for EngineClient {
         fork_choice_state: ForkchoiceState,
         payload_attributes: Option<OpPayloadAttributes>,
     ) -> TransportResult<ForkchoiceUpdated> {
-        <RootProvider<AnyNetwork> as OpEngineApi<
-            AnyNetwork,
-            Http<HyperAuthClient>,
-        >>::fork_choice_updated_v2(&self.engine, fork_choice_state, payload_attributes).await
+        self.with_failover_timed("forkchoiceUpdated", |engine| {
+            let payload_attributes = payload_attributes.clone();
+            async move {
+                <RootProvider<AnyNetwork> as OpEngineApi<
+                    AnyNetwork,
+                    Http<HyperAuthClient>,
+                >>::fork_choice_updated_v2(&engine, fork_choice_state, payload_attributes).await
+            }
+        })
+        .await
     }
 
     async fn fork_choice_updated_v3(
@@ -131,50 +422,71 @@ impl OpEngineApi<AnyNetwork, Http<HyperAuthClient>> for EngineClient {
         fork_choice_state: ForkchoiceState,
         payload_attributes: Option<OpPayloadAttributes>,
     ) -> TransportResult<ForkchoiceUpdated> {
-        <RootProvider<AnyNetwork> as OpEngineApi<
-            AnyNetwork,
-            Http<HyperAuthClient>,
-        >>::fork_choice_updated_v3(&self.engine, fork_choice_state, payload_attributes).await
+        self.with_failover_timed("forkchoiceUpdated", |engine| {
+            let payload_attributes = payload_attributes.clone();
+            async move {
+                <RootProvider<AnyNetwork> as OpEngineApi<
+                    AnyNetwork,
+                    Http<HyperAuthClient>,
+                >>::fork_choice_updated_v3(&engine, fork_choice_state, payload_attributes).await
+            }
+        })
+        .await
     }
 
     async fn get_payload_v2(
         &self,
         payload_id: PayloadId,
     ) -> TransportResult<ExecutionPayloadEnvelopeV2> {
-        <RootProvider<AnyNetwork> as OpEngineApi<
-            AnyNetwork,
-            Http<HyperAuthClient>,
-        >>::get_payload_v2(&self.engine, payload_id).await
+        self.with_failover_timed("getPayload", |engine| async move {
+            <RootProvider<AnyNetwork> as OpEngineApi<
+                AnyNetwork,
+                Http<HyperAuthClient>,
+            >>::get_payload_v2(&engine, payload_id).await
+        })
+        .await
     }
 
     async fn get_payload_v3(
         &self,
         payload_id: PayloadId,
     ) -> TransportResult<OpExecutionPayloadEnvelopeV3> {
-        <RootProvider<AnyNetwork> as OpEngineApi<
-            AnyNetwork,
-            Http<HyperAuthClient>,
-        >>::get_payload_v3(&self.engine, payload_id).await
+        self.with_failover_timed("getPayload", |engine| async move {
+            <RootProvider<AnyNetwork> as OpEngineApi<
+                AnyNetwork,
+                Http<HyperAuthClient>,
+            >>::get_payload_v3(&engine, payload_id).await
+        })
+        .await
     }
 
     async fn get_payload_v4(
         &self,
         payload_id: PayloadId,
     ) -> TransportResult<OpExecutionPayloadEnvelopeV4> {
-        <RootProvider<AnyNetwork> as OpEngineApi<
-            AnyNetwork,
-            Http<HyperAuthClient>,
-        >>::get_payload_v4(&self.engine, payload_id).await
+        self.with_failover_timed("getPayload", |engine| async move {
+            <RootProvider<AnyNetwork> as OpEngineApi<
+                AnyNetwork,
+                Http<HyperAuthClient>,
+            >>::get_payload_v4(&engine, payload_id).await
+        })
+        .await
     }
 
     async fn get_payload_bodies_by_hash_v1(
         &self,
         block_hashes: Vec<BlockHash>,
     ) -> TransportResult<ExecutionPayloadBodiesV1> {
-        <RootProvider<AnyNetwork> as OpEngineApi<
-            AnyNetwork,
-            Http<HyperAuthClient>,
-        >>::get_payload_bodies_by_hash_v1(&self.engine, block_hashes).await
+        self.with_failover(|engine| {
+            let block_hashes = block_hashes.clone();
+            async move {
+                <RootProvider<AnyNetwork> as OpEngineApi<
+                    AnyNetwork,
+                    Http<HyperAuthClient>,
+                >>::get_payload_bodies_by_hash_v1(&engine, block_hashes).await
+            }
+        })
+        .await
     }
 
     async fn get_payload_bodies_by_range_v1(
@@ -182,37 +494,158 @@ impl OpEngineApi<AnyNetwork, Http<HyperAuthClient>> for EngineClient {
         start: u64,
         count: u64,
     ) -> TransportResult<ExecutionPayloadBodiesV1> {
-        <RootProvider<AnyNetwork> as OpEngineApi<
-            AnyNetwork,
-            Http<HyperAuthClient>,
-        >>::get_payload_bodies_by_range_v1(&self.engine, start, count).await
+        self.with_failover(|engine| async move {
+            <RootProvider<AnyNetwork> as OpEngineApi<
+                AnyNetwork,
+                Http<HyperAuthClient>,
+            >>::get_payload_bodies_by_range_v1(&engine, start, count).await
+        })
+        .await
     }
 
     async fn get_client_version_v1(
         &self,
         client_version: ClientVersionV1,
     ) -> TransportResult<Vec<ClientVersionV1>> {
-        <RootProvider<AnyNetwork> as OpEngineApi<
-            AnyNetwork,
-            Http<HyperAuthClient>,
-        >>::get_client_version_v1(&self.engine, client_version).await
+        self.with_failover(|engine| {
+            let client_version = client_version.clone();
+            async move {
+                <RootProvider<AnyNetwork> as OpEngineApi<
+                    AnyNetwork,
+                    Http<HyperAuthClient>,
+                >>::get_client_version_v1(&engine, client_version).await
+            }
+        })
+        .await
     }
 
     async fn exchange_capabilities(
         &self,
         capabilities: Vec<String>,
     ) -> TransportResult<Vec<String>> {
-        <RootProvider<AnyNetwork> as OpEngineApi<
-            AnyNetwork,
-            Http<HyperAuthClient>,
-        >>::exchange_capabilities(&self.engine, capabilities).await
+        self.with_failover(|engine| {
+            let capabilities = capabilities.clone();
+            async move {
+                <RootProvider<AnyNetwork> as OpEngineApi<
+                    AnyNetwork,
+                    Http<HyperAuthClient>,
+                >>::exchange_capabilities(&engine, capabilities).await
+            }
+        })
+        .await
     }
 }
 
-impl std::ops::Deref for EngineClient {
-    type Target = RootProvider<AnyNetwork>;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Engine, EngineForkchoiceVersion, EngineTask, InsertUnsafeTask,
+        test_utils::{MockEngine, test_engine_state, test_genesis_envelope, test_sync_config},
+    };
+    use std::time::Duration;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+        task::{JoinSet, LocalSet},
+    };
+
+    #[tokio::test]
+    async fn test_engine_failover() {
+        // Bind and immediately drop a listener to obtain an address with nothing listening on it.
+        let dead = TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap();
+        let dead = Url::parse(&format!("http://{dead}")).unwrap();
+        let mock = MockEngine::spawn().await;
+
+        let client = EngineClient::new_http_with_fallbacks(
+            dead.clone(),
+            vec![mock.url()],
+            mock.url(),
+            Arc::new(RollupConfig::default()),
+            JwtSecret::random(),
+        );
+        assert_eq!(client.active_engine(), &dead);
+
+        let state = ForkchoiceState::default();
+        let updated = client.fork_choice_updated_v3(state, None).await.unwrap();
+        assert!(updated.is_valid());
+        assert_eq!(client.active_engine(), &mock.url());
+        assert_eq!(mock.calls(), vec!["engine_forkchoiceUpdatedV3"]);
+
+        // Subsequent calls are routed directly to the active endpoint.
+        client.fork_choice_updated_v2(state, None).await.unwrap();
+        assert_eq!(client.active_engine(), &mock.url());
+        assert_eq!(mock.calls(), vec!["engine_forkchoiceUpdatedV3", "engine_forkchoiceUpdatedV2"]);
+    }
+
+    #[tokio::test]
+    async fn test_engine_no_failover_on_http_error() {
+        // An endpoint that accepts connections, but fails every call.
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let unavailable =
+            Url::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buf = [0u8; 4096];
+                _ = stream.read(&mut buf).await;
+                let response = "HTTP/1.1 503 Service Unavailable\r\ncontent-length: 0\r\n\r\n";
+                _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        let mock = MockEngine::spawn().await;
+
+        let client = EngineClient::new_http_with_fallbacks(
+            unavailable.clone(),
+            vec![mock.url()],
+            mock.url(),
+            Arc::new(RollupConfig::default()),
+            JwtSecret::random(),
+        );
+
+        // The endpoint is reachable, so the error is returned rather than failed over.
+        let res = client.fork_choice_updated_v3(ForkchoiceState::default(), None).await;
+        assert!(matches!(res, Err(RpcError::Transport(TransportErrorKind::HttpError(_)))));
+        assert_eq!(client.active_engine(), &unavailable);
+        assert!(mock.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_engine_max_concurrent_calls() {
+        let mock = MockEngine::spawn().await;
+        mock.set_delay("engine_newPayloadV1", Duration::from_millis(20));
+        let (envelope, cfg) = test_genesis_envelope();
+        let cfg = Arc::new(cfg);
+        let client = Arc::new(
+            EngineClient::new_http(mock.url(), mock.url(), Arc::clone(&cfg), JwtSecret::random())
+                .with_max_concurrent_calls(2),
+        );
+
+        // A queue executes its tasks one at a time, so the calls of queues sharing the client are
+        // what the limit bounds. Draining a queue is not `Send`, so the queues run on a local set.
+        let local = LocalSet::new();
+        let mut queues = JoinSet::new();
+        for _ in 0..8 {
+            let insert = InsertUnsafeTask::new(
+                Arc::clone(&client),
+                Arc::new(test_sync_config()),
+                Arc::clone(&cfg),
+                EngineForkchoiceVersion::V1,
+                envelope.clone(),
+            );
+            let mut engine = Engine::new(test_engine_state());
+            engine.enqueue(EngineTask::InsertUnsafe(insert)).await;
+            queues.spawn_local_on(async move { engine.drain().await.is_ok() }, &local);
+        }
+        local
+            .run_until(async {
+                while let Some(drained) = queues.join_next().await {
+                    assert!(drained.unwrap());
+                }
+            })
+            .await;
 
-    fn deref(&self) -> &Self::Target {
-        &self.engine
+        assert_eq!(mock.params("engine_newPayloadV1").len(), 8);
+        assert_eq!(mock.params("engine_forkchoiceUpdatedV1").len(), 8);
+        assert_eq!(mock.peak_concurrency(), 2);
     }
 }
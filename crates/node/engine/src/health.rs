@@ -0,0 +1,169 @@
+//! Contains the [EngineHealthMonitor], which derives the [EngineHealth] of the engine from its
+//! progress and failures.
+
+use std::time::{Duration, Instant};
+
+/// The default period after startup during which the engine is reported as
+/// [EngineHealth::Starting].
+pub const DEFAULT_ENGINE_WARMUP: Duration = Duration::from_secs(30);
+
+/// The default duration for which a failure of the engine is reported as
+/// [EngineHealth::Unhealthy], unless the engine makes progress in the meantime.
+pub const DEFAULT_ENGINE_FAILURE_WINDOW: Duration = Duration::from_secs(60);
+
+/// The health of the engine, as reported to orchestrators.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EngineHealth {
+    /// The engine is within its warmup period, and may not have made progress yet.
+    Starting,
+    /// The engine has not failed recently, or has made progress since it last failed.
+    Healthy,
+    /// The engine has failed recently, and has not made progress since.
+    Unhealthy,
+}
+
+/// Derives the [EngineHealth] of the engine from the last time it made progress, and the last time
+/// it failed.
+///
+/// Right after startup, the engine may fail while it connects to the execution layer. To avoid
+/// flapping, the engine is reported as [EngineHealth::Starting] for a warmup period after startup,
+/// regardless of its progress. Once the warmup period has elapsed, the engine is
+/// [EngineHealth::Unhealthy] if it failed within the failure window and has not made progress
+/// since, and [EngineHealth::Healthy] otherwise. An idle engine, which has no tasks to make
+/// progress on, is healthy.
+#[derive(Debug, Clone, Copy)]
+pub struct EngineHealthMonitor {
+    /// When the engine started.
+    started: Instant,
+    /// When the engine last made progress, if it has.
+    last_progress: Option<Instant>,
+    /// When the engine last failed, if it has.
+    last_failure: Option<Instant>,
+    /// The period after startup during which the engine is starting.
+    warmup: Duration,
+    /// The duration for which a failure makes the engine unhealthy.
+    failure_window: Duration,
+}
+
+impl Default for EngineHealthMonitor {
+    fn default() -> Self {
+        Self::new(Instant::now())
+    }
+}
+
+impl EngineHealthMonitor {
+    /// Creates a new [EngineHealthMonitor] for an engine started at the given instant, with the
+    /// [DEFAULT_ENGINE_WARMUP] and [DEFAULT_ENGINE_FAILURE_WINDOW].
+    pub const fn new(started: Instant) -> Self {
+        Self {
+            started,
+            last_progress: None,
+            last_failure: None,
+            warmup: DEFAULT_ENGINE_WARMUP,
+            failure_window: DEFAULT_ENGINE_FAILURE_WINDOW,
+        }
+    }
+
+    /// Sets the period after startup during which the engine is reported as
+    /// [EngineHealth::Starting].
+    pub const fn with_warmup(mut self, warmup: Duration) -> Self {
+        self.warmup = warmup;
+        self
+    }
+
+    /// Sets the duration for which a failure of the engine is reported as
+    /// [EngineHealth::Unhealthy], unless the engine makes progress in the meantime.
+    pub const fn with_failure_window(mut self, failure_window: Duration) -> Self {
+        self.failure_window = failure_window;
+        self
+    }
+
+    /// Returns the warmup period.
+    pub const fn warmup(&self) -> Duration {
+        self.warmup
+    }
+
+    /// Records that the engine made progress at the given instant.
+    pub const fn record_progress(&mut self, at: Instant) {
+        self.last_progress = Some(at);
+    }
+
+    /// Records that the engine failed at the given instant.
+    pub const fn record_failure(&mut self, at: Instant) {
+        self.last_failure = Some(at);
+    }
+
+    /// Returns the [EngineHealth] of the engine at the given instant.
+    pub fn health_at(&self, now: Instant) -> EngineHealth {
+        if now.saturating_duration_since(self.started) < self.warmup {
+            return EngineHealth::Starting;
+        }
+        match self.last_failure {
+            Some(failed)
+                if now.saturating_duration_since(failed) <= self.failure_window &&
+                    self.last_progress.is_none_or(|progress| progress < failed) =>
+            {
+                EngineHealth::Unhealthy
+            }
+            _ => EngineHealth::Healthy,
+        }
+    }
+
+    /// Returns the current [EngineHealth] of the engine.
+    pub fn health(&self) -> EngineHealth {
+        self.health_at(Instant::now())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_health_starting_within_warmup() {
+        let started = Instant::now();
+        let mut monitor = EngineHealthMonitor::new(started)
+            .with_warmup(Duration::from_secs(10))
+            .with_failure_window(Duration::from_secs(5));
+
+        // The engine is starting within the warmup, whether or not it has failed.
+        let within = started + Duration::from_secs(9);
+        assert_eq!(monitor.health_at(within), EngineHealth::Starting);
+        monitor.record_failure(started + Duration::from_secs(8));
+        assert_eq!(monitor.health_at(within), EngineHealth::Starting);
+
+        // Once the warmup has elapsed, a recent failure is unhealthy until the engine makes
+        // progress.
+        let after = started + Duration::from_secs(10);
+        assert_eq!(monitor.health_at(after), EngineHealth::Unhealthy);
+        monitor.record_progress(after);
+        assert_eq!(monitor.health_at(after), EngineHealth::Healthy);
+    }
+
+    #[test]
+    fn test_health_idle_engine() {
+        let started = Instant::now();
+        let monitor = EngineHealthMonitor::new(started)
+            .with_warmup(Duration::ZERO)
+            .with_failure_window(Duration::from_secs(5));
+
+        // An engine that has neither failed nor made progress is idle, not unhealthy.
+        assert_eq!(monitor.health_at(started), EngineHealth::Healthy);
+        assert_eq!(monitor.health_at(started + Duration::from_secs(3600)), EngineHealth::Healthy);
+    }
+
+    #[test]
+    fn test_health_failure_window() {
+        let started = Instant::now();
+        let mut monitor = EngineHealthMonitor::new(started)
+            .with_warmup(Duration::ZERO)
+            .with_failure_window(Duration::from_secs(5));
+
+        // A failure without progress since is unhealthy for the failure window.
+        monitor.record_progress(started);
+        let failed = started + Duration::from_secs(1);
+        monitor.record_failure(failed);
+        assert_eq!(monitor.health_at(failed + Duration::from_secs(5)), EngineHealth::Unhealthy);
+        assert_eq!(monitor.health_at(failed + Duration::from_secs(6)), EngineHealth::Healthy);
+    }
+}
@@ -11,13 +11,23 @@ extern crate tracing;
 
 mod task_queue;
 pub use task_queue::{
-    BuildTask, BuildTaskError, Engine, EngineTask, EngineTaskError, EngineTaskExt, ForkchoiceTask,
-    ForkchoiceTaskError, InsertUnsafeTask, InsertUnsafeTaskError,
+    BatchInsertTask, BatchInsertTaskError, BuildTask, BuildTaskError, CheckpointFinalizeTask,
+    CheckpointFinalizeTaskError, DEFAULT_MAX_BUFFERED_INSERTS, Engine, EngineTask, EngineTaskError,
+    EngineTaskExt, ExecutionSyncStatus, ForkchoiceTask, ForkchoiceTaskError, InsertUnsafeTask,
+    InsertUnsafeTaskError, InvalidPayloadPolicy, MAX_FINISHED_TASK_STATUSES,
+    MAX_SPECULATIVE_WINDOW, ReorderBuffer, SpeculativeCache, SpeculativeValidateTask,
+    SpeculativeValidateTaskError, SyncStatusTask, SyncStatusTaskError, TaskId, TaskStatus,
+    TaskStatusRegistry,
 };
 
 mod client;
 pub use client::EngineClient;
 
+mod metrics;
+#[cfg(feature = "metrics")]
+pub use metrics::MetricsRpcLatencySink;
+pub use metrics::{ENGINE_RPC_LATENCY, RpcLatencySink};
+
 mod versions;
 pub use versions::{EngineForkchoiceVersion, EngineGetPayloadVersion, EngineNewPayloadVersion};
 
@@ -25,7 +35,21 @@ mod sync;
 pub use sync::{SyncConfig, SyncMode, SyncStatus};
 
 mod state;
-pub use state::{EngineState, EngineStateBuilder};
+pub use state::{EngineState, EngineStateBuilder, ForkchoiceState};
 
 mod kinds;
 pub use kinds::EngineKind;
+
+mod breaker;
+pub use breaker::{
+    CircuitState, DEFAULT_ENGINE_FAILURE_THRESHOLD, DEFAULT_ENGINE_PROBE_INTERVAL,
+    ENGINE_RETRY_MAX_BACKOFF, ENGINE_RETRY_MIN_BACKOFF, EngineCircuitBreaker,
+};
+
+mod health;
+pub use health::{
+    DEFAULT_ENGINE_FAILURE_WINDOW, DEFAULT_ENGINE_WARMUP, EngineHealth, EngineHealthMonitor,
+};
+
+#[cfg(test)]
+mod test_utils;
@@ -0,0 +1,30 @@
+//! Contains the [RpcLatencySink], which records the latency of engine API calls.
+
+use std::{fmt::Debug, time::Duration};
+
+/// The name of the histogram of engine API call latencies, in seconds, labeled by `method`.
+pub const ENGINE_RPC_LATENCY: &str = "kona_engine_rpc_latency_seconds";
+
+/// A sink for the latency of engine API calls made by the [EngineClient].
+///
+/// Calls are labeled by their method name without the `engine_` prefix or version suffix, such
+/// as `forkchoiceUpdated`, `newPayload` or `getPayload`.
+///
+/// [EngineClient]: crate::EngineClient
+pub trait RpcLatencySink: Debug + Send + Sync {
+    /// Records the latency of a call to the given engine API method.
+    fn record(&self, method: &'static str, latency: Duration);
+}
+
+/// A [RpcLatencySink] that records latencies in the [ENGINE_RPC_LATENCY] histogram of the global
+/// metrics recorder.
+#[cfg(feature = "metrics")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MetricsRpcLatencySink;
+
+#[cfg(feature = "metrics")]
+impl RpcLatencySink for MetricsRpcLatencySink {
+    fn record(&self, method: &'static str, latency: Duration) {
+        metrics::histogram!(ENGINE_RPC_LATENCY, "method" => method).record(latency.as_secs_f64());
+    }
+}
@@ -1,7 +1,7 @@
 //! The internal state of the engine controller.
 
-use crate::SyncStatus;
-use alloy_rpc_types_engine::ForkchoiceState;
+use crate::{ForkchoiceState, SyncStatus};
+use alloy_rpc_types_engine::ForkchoiceState as EngineForkchoiceState;
 use kona_protocol::L2BlockInfo;
 
 /// The chain state viewed by the engine controller.
@@ -43,19 +43,27 @@ pub struct EngineState {
 }
 
 impl EngineState {
-    /// Creates a `ForkchoiceState`
+    /// Returns the current [ForkchoiceState]: the unsafe, safe, and finalized heads.
+    pub const fn forkchoice(&self) -> ForkchoiceState {
+        ForkchoiceState::new(self.unsafe_head, self.safe_head, self.finalized_head)
+    }
+
+    /// Creates the engine API `ForkchoiceState` from the current [ForkchoiceState].
     ///
     /// - `head_block` = `unsafe_head`
     /// - `safe_block` = `safe_head`
     /// - `finalized_block` = `finalized_head`
     ///
     /// If the block info is not yet available, the default values are used.
-    pub const fn create_forkchoice_state(&self) -> ForkchoiceState {
-        ForkchoiceState {
-            head_block_hash: self.unsafe_head.block_info.hash,
-            safe_block_hash: self.safe_head.block_info.hash,
-            finalized_block_hash: self.finalized_head.block_info.hash,
-        }
+    pub fn create_forkchoice_state(&self) -> EngineForkchoiceState {
+        self.forkchoice().into()
+    }
+
+    /// Sets the unsafe, safe, and finalized heads to those of the given [ForkchoiceState].
+    pub fn set_forkchoice(&mut self, forkchoice: ForkchoiceState) {
+        self.set_unsafe_head(forkchoice.head);
+        self.set_safe_head(forkchoice.safe);
+        self.set_finalized_head(forkchoice.finalized);
     }
 
     /// Returns the current unsafe head.
@@ -0,0 +1,90 @@
+//! The forkchoice of the engine.
+
+use alloy_rpc_types_engine::ForkchoiceState as EngineForkchoiceState;
+use kona_protocol::L2BlockInfo;
+
+/// The forkchoice of the engine: its unsafe, safe, and finalized heads.
+///
+/// Carrying the heads together, by name, avoids mixing up their order when they are passed
+/// between tasks and the engine API.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct ForkchoiceState {
+    /// The unsafe head.
+    pub head: L2BlockInfo,
+    /// The safe head.
+    pub safe: L2BlockInfo,
+    /// The finalized head.
+    pub finalized: L2BlockInfo,
+}
+
+impl ForkchoiceState {
+    /// Creates a new [ForkchoiceState].
+    pub const fn new(head: L2BlockInfo, safe: L2BlockInfo, finalized: L2BlockInfo) -> Self {
+        Self { head, safe, finalized }
+    }
+
+    /// Returns `true` if the finalized head is ahead of the unsafe head.
+    pub const fn is_finalized_ahead_of_head(&self) -> bool {
+        self.head.block_info.number < self.finalized.block_info.number
+    }
+
+    /// Returns the number of blocks the safe head lags behind the unsafe head, or `0` if it is not
+    /// behind.
+    pub const fn lag(&self) -> u64 {
+        self.head.block_info.number.saturating_sub(self.safe.block_info.number)
+    }
+
+    /// Returns the number of blocks the finalized head lags behind the safe head, or `0` if it is
+    /// not behind.
+    pub const fn finality_lag(&self) -> u64 {
+        self.safe.block_info.number.saturating_sub(self.finalized.block_info.number)
+    }
+
+    /// Returns `true` if the safe head has caught up with the unsafe head, so that every unsafe
+    /// block has been derived from L1.
+    ///
+    /// This is unrelated to the [SyncStatus] of the execution layer.
+    ///
+    /// [SyncStatus]: crate::SyncStatus
+    pub const fn is_synced(&self) -> bool {
+        self.lag() == 0
+    }
+}
+
+impl From<ForkchoiceState> for EngineForkchoiceState {
+    fn from(forkchoice: ForkchoiceState) -> Self {
+        Self {
+            head_block_hash: forkchoice.head.block_info.hash,
+            safe_block_hash: forkchoice.safe.block_info.hash,
+            finalized_block_hash: forkchoice.finalized.block_info.hash,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kona_protocol::BlockInfo;
+
+    fn block(number: u64) -> L2BlockInfo {
+        L2BlockInfo { block_info: BlockInfo { number, ..Default::default() }, ..Default::default() }
+    }
+
+    #[test]
+    fn test_forkchoice_lag() {
+        let forkchoice = ForkchoiceState::new(block(120), block(100), block(64));
+        assert_eq!(forkchoice.lag(), 20);
+        assert_eq!(forkchoice.finality_lag(), 36);
+        assert!(!forkchoice.is_synced());
+
+        let synced = ForkchoiceState::new(block(100), block(100), block(100));
+        assert_eq!(synced.lag(), 0);
+        assert_eq!(synced.finality_lag(), 0);
+        assert!(synced.is_synced());
+
+        // A safe head ahead of the unsafe head, as after a reorg, does not lag.
+        let reorged = ForkchoiceState::new(block(90), block(100), block(64));
+        assert_eq!(reorged.lag(), 0);
+        assert!(reorged.is_synced());
+    }
+}
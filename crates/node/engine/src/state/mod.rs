@@ -3,5 +3,8 @@
 mod core;
 pub use core::EngineState;
 
+mod forkchoice;
+pub use forkchoice::ForkchoiceState;
+
 mod builder;
 pub use builder::EngineStateBuilder;
@@ -1,8 +1,12 @@
 //! The [Engine] is a task queue that receives and executes [EngineTask]s.
 
-use super::{EngineTaskError, EngineTaskExt};
-use crate::{EngineState, EngineTask};
-use std::collections::VecDeque;
+use super::{EngineTaskError, EngineTaskExt, TaskId, TaskStatus, TaskStatusRegistry};
+use crate::{
+    CircuitState, ENGINE_RETRY_MAX_BACKOFF, ENGINE_RETRY_MIN_BACKOFF, EngineCircuitBreaker,
+    EngineHealth, EngineHealthMonitor, EngineState, EngineTask,
+};
+use std::{collections::VecDeque, time::Instant};
+use tokio::sync::oneshot;
 
 /// The [Engine] task queue.
 ///
@@ -13,12 +17,63 @@ use std::collections::VecDeque;
 ///
 /// Tasks within the queue are also considered fallible. If they fail with a temporary error,
 /// they are not popped from the queue and are retried on the next call to [Engine::drain].
+///
+/// The queue may be paused with [Engine::pause]. While paused, no tasks are dispatched, but
+/// enqueued tasks are retained and executed in order once the queue is [Engine::resume]d.
+///
+/// Callers may await the completion of a task enqueued with [Engine::enqueue_with_completion].
+/// When the queue is [Engine::shutdown] or dropped, every pending awaiter is resolved with
+/// [EngineTaskError::ShuttingDown].
+///
+/// Each task is assigned a [TaskId] when it is enqueued, which may be used to query its
+/// [TaskStatus] with [Engine::task_status] without awaiting it.
+///
+/// The [EngineHealth] of the queue, as reported by [Engine::health], is derived from when a task
+/// last completed and when a drain last failed. It is [EngineHealth::Starting] for a warmup period
+/// after the queue is created, which may be configured with [Engine::with_health_monitor].
+///
+/// For reproducing bugs, the queue may be put in deterministic mode with
+/// [Engine::with_deterministic]. Outside of it, a task that fails with a temporary error is retried
+/// in place until it succeeds, so the number of calls made to the execution engine depends on how
+/// long it takes to recover. In deterministic mode, each task is executed at most once per
+/// [Engine::drain], and a temporary error ends the drain instead. Tasks are executed strictly in
+/// submission order either way, so replaying the same task submissions against the same sequence
+/// of engine responses, such as from a mock engine, makes the same calls in the same order.
+///
+/// To stop retrying tasks against an engine that is down, the queue may be given an
+/// [EngineCircuitBreaker] with [Engine::with_circuit_breaker]. Failed calls to the engine, as
+/// reported by [EngineTaskError::is_engine_failure], are counted as engine failures, and once the
+/// breaker opens, [Engine::drain] fails with [EngineTaskError::EngineUnavailable] without executing
+/// the task at the front of the queue, until a probe of the engine succeeds.
 #[derive(Debug)]
 pub struct Engine {
     /// The state of the engine.
     state: EngineState,
     /// The task queue.
-    tasks: VecDeque<EngineTask>,
+    tasks: VecDeque<QueuedTask>,
+    /// Whether task dispatching is paused.
+    paused: bool,
+    /// The id to assign to the next enqueued task.
+    next_task_id: u64,
+    /// The status of each enqueued task.
+    statuses: TaskStatusRegistry,
+    /// Whether temporary task errors end the drain, rather than being retried in place.
+    deterministic: bool,
+    /// Tracks the health of the queue.
+    health: EngineHealthMonitor,
+    /// Short-circuits tasks while the engine is failing repeatedly, if enabled.
+    breaker: Option<EngineCircuitBreaker>,
+}
+
+/// A task in the [Engine] queue.
+#[derive(Debug)]
+struct QueuedTask {
+    /// The id of the task.
+    id: TaskId,
+    /// The task to execute.
+    task: EngineTask,
+    /// The channel to notify once the task completes, if it is awaited.
+    completion: Option<oneshot::Sender<Result<(), EngineTaskError>>>,
 }
 
 impl Engine {
@@ -26,18 +81,148 @@ impl Engine {
     ///
     /// An initial [EngineTask::ForkchoiceUpdate] is added to the task queue to synchronize the
     /// engine with the forkchoice state of the [EngineState].
-    pub const fn new(initial_state: EngineState) -> Self {
-        Self { state: initial_state, tasks: VecDeque::new() }
+    pub fn new(initial_state: EngineState) -> Self {
+        Self {
+            state: initial_state,
+            tasks: VecDeque::new(),
+            paused: false,
+            next_task_id: 0,
+            statuses: TaskStatusRegistry::default(),
+            deterministic: false,
+            health: EngineHealthMonitor::default(),
+            breaker: None,
+        }
+    }
+
+    /// Sets the [EngineHealthMonitor] of the queue, to configure its warmup period and failure
+    /// window.
+    pub const fn with_health_monitor(mut self, health: EngineHealthMonitor) -> Self {
+        self.health = health;
+        self
+    }
+
+    /// Returns the current [EngineHealth] of the queue.
+    pub fn health(&self) -> EngineHealth {
+        self.health.health()
+    }
+
+    /// Sets the [EngineCircuitBreaker] of the queue, which short-circuits tasks with
+    /// [EngineTaskError::EngineUnavailable] once the engine has failed repeatedly.
+    pub const fn with_circuit_breaker(mut self, breaker: EngineCircuitBreaker) -> Self {
+        self.breaker = Some(breaker);
+        self
+    }
+
+    /// Returns the [CircuitState] of the queue's [EngineCircuitBreaker]. Without a breaker, the
+    /// circuit is always [CircuitState::Closed].
+    pub fn circuit_state(&self) -> CircuitState {
+        self.breaker.as_ref().map_or(CircuitState::Closed, EngineCircuitBreaker::state)
+    }
+
+    /// Puts the queue in deterministic mode, in which each task is executed at most once per
+    /// [Engine::drain], and a temporary task error is returned rather than retried in place.
+    pub const fn with_deterministic(mut self) -> Self {
+        self.deterministic = true;
+        self
+    }
+
+    /// Returns whether the queue is in deterministic mode.
+    pub const fn is_deterministic(&self) -> bool {
+        self.deterministic
+    }
+
+    /// Enqueues a new [EngineTask] for execution, returning its [TaskId].
+    pub async fn enqueue(&mut self, task: EngineTask) -> TaskId {
+        let id = self.register_task();
+        self.tasks.push_back(QueuedTask { id, task, completion: None });
+        id
+    }
+
+    /// Enqueues a new [EngineTask] for execution, returning a receiver that resolves once the task
+    /// has been executed successfully.
+    ///
+    /// If the queue is [Engine::shutdown] before the task is executed, the receiver resolves with
+    /// [EngineTaskError::ShuttingDown].
+    pub async fn enqueue_with_completion(
+        &mut self,
+        task: EngineTask,
+    ) -> oneshot::Receiver<Result<(), EngineTaskError>> {
+        let (tx, rx) = oneshot::channel();
+        let id = self.register_task();
+        self.tasks.push_back(QueuedTask { id, task, completion: Some(tx) });
+        rx
+    }
+
+    /// Assigns a [TaskId] to a new task, and registers it as [TaskStatus::Queued].
+    fn register_task(&mut self) -> TaskId {
+        let id = TaskId(self.next_task_id);
+        self.next_task_id += 1;
+        self.statuses.set(id, TaskStatus::Queued);
+        id
+    }
+
+    /// Returns the [TaskStatus] of the given task, or `None` if it is unknown or its status is no
+    /// longer retained.
+    pub fn task_status(&self, id: TaskId) -> Option<TaskStatus> {
+        self.statuses.status(id)
+    }
+
+    /// Returns a handle to the [TaskStatusRegistry] of the queue, to observe task statuses from
+    /// outside of the engine, such as while a drain is in progress.
+    pub fn task_statuses(&self) -> TaskStatusRegistry {
+        self.statuses.clone()
     }
 
-    /// Enqueues a new [EngineTask] for execution.
-    pub async fn enqueue(&mut self, task: EngineTask) {
-        self.tasks.push_back(task);
+    /// Pauses the task queue. While paused, [Engine::drain] does not dispatch any tasks, but the
+    /// queue is retained.
+    pub const fn pause(&mut self) {
+        self.paused = true;
     }
 
-    /// Clears the task queue.
+    /// Resumes the task queue, allowing [Engine::drain] to dispatch tasks from where it left off.
+    pub const fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Returns whether the task queue is paused.
+    pub const fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Returns the number of tasks in the queue.
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    /// Returns `true` if the task queue is empty.
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    /// Clears the task queue. The cleared tasks are marked as [TaskStatus::Failed], since they
+    /// will never be executed.
     pub fn clear(&mut self) {
-        self.tasks.clear();
+        for queued in self.tasks.drain(..) {
+            self.statuses.set(queued.id, TaskStatus::Failed);
+        }
+    }
+
+    /// Shuts down the task queue, discarding all pending tasks.
+    ///
+    /// Every caller awaiting the completion of a pending task is resolved with
+    /// [EngineTaskError::ShuttingDown], rather than being left waiting on a dropped channel. The
+    /// discarded tasks are marked as [TaskStatus::Failed].
+    ///
+    /// The queue is also shut down when it is dropped, such as when the actor owning it is
+    /// cancelled.
+    pub fn shutdown(&mut self) {
+        for QueuedTask { id, completion, .. } in self.tasks.drain(..) {
+            self.statuses.set(id, TaskStatus::Failed);
+            if let Some(completion) = completion {
+                // The awaiter may have already gone away, in which case there is nobody to notify.
+                let _ = completion.send(Err(EngineTaskError::ShuttingDown));
+            }
+        }
     }
 
     /// Attempts to drain the queue by executing all [EngineTask]s in-order. If any task returns an
@@ -45,21 +230,455 @@ impl Engine {
     /// the error is returned.
     ///
     /// If an [EngineTaskError::Reset] is encountered, the remaining tasks in the queue are cleared.
+    /// A task that fails with an [EngineTaskError::Dropped] error is popped from the queue, its
+    /// awaiter is notified of the error, and the remaining tasks are executed.
+    ///
+    /// If the queue is paused, no tasks are executed and `Ok(())` is returned.
+    ///
+    /// In deterministic mode, a task that fails with an [EngineTaskError::Temporary] error is left
+    /// at the front of the queue as [TaskStatus::Queued], and the error is returned. Likewise, if
+    /// the [EngineCircuitBreaker] is open, the task at the front of the queue is left queued and
+    /// [EngineTaskError::EngineUnavailable] is returned.
     pub async fn drain(&mut self) -> Result<(), EngineTaskError> {
-        while let Some(task) = self.tasks.front() {
-            match task.execute(&mut self.state).await {
+        if self.paused {
+            return Ok(());
+        }
+
+        while let Some(QueuedTask { id, task, .. }) = self.tasks.front() {
+            let id = *id;
+            self.statuses.set(id, TaskStatus::Running);
+            let result = match self.breaker.as_mut() {
+                Some(breaker) => {
+                    Self::execute_with_breaker(task, &mut self.state, breaker, self.deterministic)
+                        .await
+                }
+                None if self.deterministic => task.execute_inner(&mut self.state).await,
+                None => task.execute(&mut self.state).await,
+            };
+            match result {
                 Ok(_) => {
-                    // Dequeue the task if it was successful.
-                    self.tasks.pop_front();
+                    // Dequeue the task if it was successful, and notify its awaiter.
+                    self.statuses.set(id, TaskStatus::Completed);
+                    self.health.record_progress(Instant::now());
+                    if let Some(QueuedTask { completion: Some(completion), .. }) =
+                        self.tasks.pop_front()
+                    {
+                        let _ = completion.send(Ok(()));
+                    }
+                }
+                Err(EngineTaskError::Temporary(e)) => {
+                    warn!(target: "engine", "{e}");
+                    self.statuses.set(id, TaskStatus::Queued);
+                    self.health.record_failure(Instant::now());
+                    return Err(EngineTaskError::Temporary(e));
+                }
+                Err(EngineTaskError::EngineUnavailable) => {
+                    self.statuses.set(id, TaskStatus::Queued);
+                    self.health.record_failure(Instant::now());
+                    return Err(EngineTaskError::EngineUnavailable);
                 }
                 Err(EngineTaskError::Reset(e)) => {
                     self.clear();
                     return Err(EngineTaskError::Reset(e));
                 }
-                e => return e,
+                Err(EngineTaskError::Dropped(e)) => {
+                    warn!(target: "engine", "Dropping engine task: {e}");
+                    self.statuses.set(id, TaskStatus::Failed);
+                    if let Some(QueuedTask { completion: Some(completion), .. }) =
+                        self.tasks.pop_front()
+                    {
+                        let _ = completion.send(Err(EngineTaskError::Dropped(e)));
+                    }
+                }
+                e => {
+                    self.statuses.set(id, TaskStatus::Failed);
+                    self.health.record_failure(Instant::now());
+                    return e;
+                }
             }
         }
 
         Ok(())
     }
+
+    /// Executes the task, counting each failed call to the engine as an engine failure of the
+    /// [EngineCircuitBreaker], and short-circuiting it with [EngineTaskError::EngineUnavailable]
+    /// while the breaker is open.
+    ///
+    /// Outside of deterministic mode, temporary errors are retried in place, as with
+    /// [EngineTaskExt::execute], with an exponential backoff between [ENGINE_RETRY_MIN_BACKOFF]
+    /// and [ENGINE_RETRY_MAX_BACKOFF], until the task succeeds or the breaker opens.
+    async fn execute_with_breaker(
+        task: &EngineTask,
+        state: &mut EngineState,
+        breaker: &mut EngineCircuitBreaker,
+        deterministic: bool,
+    ) -> Result<(), EngineTaskError> {
+        let mut backoff = ENGINE_RETRY_MIN_BACKOFF;
+        loop {
+            if !breaker.allows_at(Instant::now()) {
+                warn!(target: "engine", "Engine unavailable after {} consecutive failures", breaker.failures());
+                return Err(EngineTaskError::EngineUnavailable);
+            }
+            match task.execute_inner(state).await {
+                Err(e @ EngineTaskError::Temporary(_)) => {
+                    if e.is_engine_failure() {
+                        breaker.record_failure(Instant::now());
+                    }
+                    if deterministic {
+                        return Err(e);
+                    }
+                    warn!(target: "engine", "{e}");
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(ENGINE_RETRY_MAX_BACKOFF);
+                }
+                Ok(()) => {
+                    breaker.record_success();
+                    return Ok(());
+                }
+                res => return res,
+            }
+        }
+    }
+}
+
+impl Drop for Engine {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        EngineForkchoiceVersion, ForkchoiceTask, InsertUnsafeTask,
+        test_utils::{MockEngine, test_engine_state, test_genesis_envelope, test_sync_config},
+    };
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_pause_resume() {
+        let mock = MockEngine::spawn().await;
+        let (envelope, cfg) = test_genesis_envelope();
+        let cfg = Arc::new(cfg);
+        let client = mock.client(Arc::clone(&cfg));
+
+        let mut state = test_engine_state();
+        state.forkchoice_update_needed = true;
+        let mut engine = Engine::new(state);
+
+        engine.pause();
+        assert!(engine.is_paused());
+        engine
+            .enqueue(EngineTask::ForkchoiceUpdate(ForkchoiceTask::new(Arc::clone(&client))))
+            .await;
+        engine
+            .enqueue(EngineTask::InsertUnsafe(InsertUnsafeTask::new(
+                client,
+                Arc::new(test_sync_config()),
+                cfg,
+                EngineForkchoiceVersion::V1,
+                envelope,
+            )))
+            .await;
+
+        engine.drain().await.unwrap();
+        assert!(mock.calls().is_empty());
+        assert_eq!(engine.len(), 2);
+
+        engine.resume();
+        assert!(!engine.is_paused());
+        engine.drain().await.unwrap();
+        assert!(engine.is_empty());
+        assert_eq!(
+            mock.calls(),
+            vec!["engine_forkchoiceUpdatedV3", "engine_newPayloadV1", "engine_forkchoiceUpdatedV1"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_task_status_transitions() {
+        let mock = MockEngine::spawn().await;
+        let (_, cfg) = test_genesis_envelope();
+        let client = mock.client(Arc::new(cfg));
+
+        // Fail the forkchoice update calls, so that the task is retried until the engine recovers.
+        for v in 1..=3 {
+            mock.set_response(&format!("engine_forkchoiceUpdatedV{v}"), serde_json::json!("bad"));
+        }
+
+        let mut state = test_engine_state();
+        state.forkchoice_update_needed = true;
+        let mut engine = Engine::new(state);
+        assert_eq!(engine.task_status(TaskId(0)), None);
+
+        let id = engine.enqueue(EngineTask::ForkchoiceUpdate(ForkchoiceTask::new(client))).await;
+        assert_eq!(engine.task_status(id), Some(TaskStatus::Queued));
+
+        // The task is running while the drain retries it.
+        let statuses = engine.task_statuses();
+        let running = async {
+            while statuses.status(id) != Some(TaskStatus::Running) {
+                tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+            }
+        };
+        tokio::select! {
+            _ = engine.drain() => panic!("drain completed while the engine is failing"),
+            _ = running => {}
+        }
+
+        // Once the engine recovers, the task completes on the next drain.
+        for v in 1..=3 {
+            mock.set_response(
+                &format!("engine_forkchoiceUpdatedV{v}"),
+                serde_json::json!({
+                    "payloadStatus": { "status": "VALID", "latestValidHash": null },
+                    "payloadId": null
+                }),
+            );
+        }
+        engine.drain().await.unwrap();
+        assert_eq!(engine.task_status(id), Some(TaskStatus::Completed));
+    }
+
+    #[tokio::test]
+    async fn test_task_status_completed_and_failed() {
+        let mock = MockEngine::spawn().await;
+        let (_, cfg) = test_genesis_envelope();
+        let client = mock.client(Arc::new(cfg));
+
+        let mut state = test_engine_state();
+        state.forkchoice_update_needed = true;
+        let mut engine = Engine::new(state);
+
+        let id = engine
+            .enqueue(EngineTask::ForkchoiceUpdate(ForkchoiceTask::new(Arc::clone(&client))))
+            .await;
+        engine.drain().await.unwrap();
+        assert_eq!(engine.task_status(id), Some(TaskStatus::Completed));
+
+        // A forkchoice with the finalized head ahead of the unsafe head fails critically.
+        let mut state = test_engine_state();
+        state.forkchoice_update_needed = true;
+        state.finalized_head.block_info.number = 1;
+        let mut engine = Engine::new(state);
+        let id = engine.enqueue(EngineTask::ForkchoiceUpdate(ForkchoiceTask::new(client))).await;
+        assert!(matches!(engine.drain().await, Err(EngineTaskError::Critical(_))));
+        assert_eq!(engine.task_status(id), Some(TaskStatus::Failed));
+        assert_eq!(engine.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_resolves_awaiters() {
+        let mock = MockEngine::spawn().await;
+        let (_, cfg) = test_genesis_envelope();
+        let client = mock.client(Arc::new(cfg));
+
+        let mut state = test_engine_state();
+        state.forkchoice_update_needed = true;
+        let mut engine = Engine::new(state);
+
+        engine.pause();
+        let completion = engine
+            .enqueue_with_completion(EngineTask::ForkchoiceUpdate(ForkchoiceTask::new(client)))
+            .await;
+        engine.drain().await.unwrap();
+
+        engine.shutdown();
+        assert!(engine.is_empty());
+        assert!(matches!(completion.await, Ok(Err(EngineTaskError::ShuttingDown))));
+        assert!(mock.calls().is_empty());
+
+        // Dropping the queue, as when its owner is cancelled, shuts it down as well.
+        let completion = engine
+            .enqueue_with_completion(EngineTask::ForkchoiceUpdate(ForkchoiceTask::new(
+                mock.client(Arc::new(test_genesis_envelope().1)),
+            )))
+            .await;
+        drop(engine);
+        assert!(matches!(completion.await, Ok(Err(EngineTaskError::ShuttingDown))));
+    }
+
+    /// Runs a fixed sequence of tasks in deterministic mode against an engine whose first
+    /// forkchoice update fails, returning the recorded RPC calls.
+    async fn deterministic_replay() -> Vec<String> {
+        let mock = MockEngine::spawn().await;
+        let (envelope, cfg) = test_genesis_envelope();
+        let cfg = Arc::new(cfg);
+        let client = mock.client(Arc::clone(&cfg));
+        mock.queue_response("engine_forkchoiceUpdatedV3", serde_json::json!("bad"));
+
+        let mut state = test_engine_state();
+        state.forkchoice_update_needed = true;
+        let mut engine = Engine::new(state).with_deterministic();
+        assert!(engine.is_deterministic());
+
+        let fcu = engine
+            .enqueue(EngineTask::ForkchoiceUpdate(ForkchoiceTask::new(Arc::clone(&client))))
+            .await;
+        let insert = engine
+            .enqueue(EngineTask::InsertUnsafe(InsertUnsafeTask::new(
+                client,
+                Arc::new(test_sync_config()),
+                cfg,
+                EngineForkchoiceVersion::V1,
+                envelope,
+            )))
+            .await;
+
+        // The temporary error ends the drain, rather than being retried in place.
+        assert!(matches!(engine.drain().await, Err(EngineTaskError::Temporary(_))));
+        assert_eq!(engine.task_status(fcu), Some(TaskStatus::Queued));
+        assert_eq!(engine.len(), 2);
+
+        engine.drain().await.unwrap();
+        assert_eq!(engine.task_status(insert), Some(TaskStatus::Completed));
+        mock.calls()
+    }
+
+    #[tokio::test]
+    async fn test_deterministic_replay() {
+        let first = deterministic_replay().await;
+        assert_eq!(
+            first,
+            vec![
+                "engine_forkchoiceUpdatedV3",
+                "engine_forkchoiceUpdatedV3",
+                "engine_newPayloadV1",
+                "engine_forkchoiceUpdatedV1"
+            ]
+        );
+        assert_eq!(deterministic_replay().await, first);
+    }
+
+    #[tokio::test]
+    async fn test_health_tracks_completed_tasks() {
+        let mock = MockEngine::spawn().await;
+        let (_, cfg) = test_genesis_envelope();
+        let client = mock.client(Arc::new(cfg));
+
+        let mut state = test_engine_state();
+        state.forkchoice_update_needed = true;
+        assert_eq!(Engine::new(state).health(), EngineHealth::Starting);
+
+        // An idle engine is healthy.
+        let monitor = EngineHealthMonitor::default().with_warmup(std::time::Duration::ZERO);
+        let mut engine = Engine::new(state).with_health_monitor(monitor).with_deterministic();
+        assert_eq!(engine.health(), EngineHealth::Healthy);
+
+        // A failed drain makes the engine unhealthy, until a task completes.
+        for v in 1..=3 {
+            mock.set_response(&format!("engine_forkchoiceUpdatedV{v}"), serde_json::json!("bad"));
+        }
+        engine.enqueue(EngineTask::ForkchoiceUpdate(ForkchoiceTask::new(client))).await;
+        assert!(engine.drain().await.is_err());
+        assert_eq!(engine.health(), EngineHealth::Unhealthy);
+
+        for v in 1..=3 {
+            mock.set_response(
+                &format!("engine_forkchoiceUpdatedV{v}"),
+                serde_json::json!({
+                    "payloadStatus": { "status": "VALID", "latestValidHash": null },
+                    "payloadId": null
+                }),
+            );
+        }
+        engine.drain().await.unwrap();
+        assert_eq!(engine.health(), EngineHealth::Healthy);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_opens_and_recovers() {
+        let mock = MockEngine::spawn().await;
+        let (_, cfg) = test_genesis_envelope();
+        let client = mock.client(Arc::new(cfg));
+        for v in 1..=3 {
+            mock.set_response(&format!("engine_forkchoiceUpdatedV{v}"), serde_json::json!("bad"));
+        }
+
+        let mut state = test_engine_state();
+        state.forkchoice_update_needed = true;
+        let probe_interval = std::time::Duration::from_millis(50);
+        let mut engine =
+            Engine::new(state).with_circuit_breaker(EngineCircuitBreaker::new(3, probe_interval));
+        let id = engine.enqueue(EngineTask::ForkchoiceUpdate(ForkchoiceTask::new(client))).await;
+
+        // The breaker opens after three consecutive failures, leaving the task queued.
+        assert!(matches!(engine.drain().await, Err(EngineTaskError::EngineUnavailable)));
+        assert_eq!(engine.circuit_state(), CircuitState::Open);
+        assert_eq!(engine.task_status(id), Some(TaskStatus::Queued));
+        assert_eq!(mock.calls().len(), 3);
+
+        // While open, tasks are short-circuited without calling the engine.
+        assert!(matches!(engine.drain().await, Err(EngineTaskError::EngineUnavailable)));
+        assert_eq!(mock.calls().len(), 3);
+
+        // Once the engine has recovered, a probe succeeds and closes the breaker.
+        for v in 1..=3 {
+            mock.set_response(
+                &format!("engine_forkchoiceUpdatedV{v}"),
+                serde_json::json!({
+                    "payloadStatus": { "status": "VALID", "latestValidHash": null },
+                    "payloadId": null
+                }),
+            );
+        }
+        tokio::time::sleep(probe_interval).await;
+        assert_eq!(engine.circuit_state(), CircuitState::HalfOpen);
+        engine.drain().await.unwrap();
+        assert_eq!(engine.circuit_state(), CircuitState::Closed);
+        assert_eq!(engine.task_status(id), Some(TaskStatus::Completed));
+        assert_eq!(mock.calls().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_completion_resolves_on_success() {
+        let mock = MockEngine::spawn().await;
+        let (_, cfg) = test_genesis_envelope();
+        let client = mock.client(Arc::new(cfg));
+
+        let mut state = test_engine_state();
+        state.forkchoice_update_needed = true;
+        let mut engine = Engine::new(state);
+
+        let completion = engine
+            .enqueue_with_completion(EngineTask::ForkchoiceUpdate(ForkchoiceTask::new(client)))
+            .await;
+        engine.drain().await.unwrap();
+        assert!(matches!(completion.await, Ok(Ok(()))));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_payload_dropped() {
+        let mock = MockEngine::spawn().await;
+        mock.set_response(
+            "engine_newPayloadV1",
+            serde_json::json!({ "status": "INVALID", "latestValidHash": null, "validationError": "bad block" }),
+        );
+        let (envelope, cfg) = test_genesis_envelope();
+        let cfg = Arc::new(cfg);
+        let client = mock.client(Arc::clone(&cfg));
+        let insert = InsertUnsafeTask::new(
+            Arc::clone(&client),
+            Arc::new(test_sync_config()),
+            cfg,
+            EngineForkchoiceVersion::V1,
+            envelope,
+        );
+
+        let mut state = test_engine_state();
+        state.forkchoice_update_needed = true;
+        let mut engine = Engine::new(state);
+        let completion = engine.enqueue_with_completion(EngineTask::InsertUnsafe(insert)).await;
+        let id = engine.enqueue(EngineTask::ForkchoiceUpdate(ForkchoiceTask::new(client))).await;
+
+        // The invalid payload is dropped without being retried, and the queue carries on.
+        engine.drain().await.unwrap();
+        assert!(matches!(completion.await, Ok(Err(EngineTaskError::Dropped(_)))));
+        assert_eq!(engine.task_status(id), Some(TaskStatus::Completed));
+        assert!(engine.is_empty());
+        let calls = mock.calls();
+        assert_eq!(calls.iter().filter(|call| *call == "engine_newPayloadV1").count(), 1);
+    }
 }
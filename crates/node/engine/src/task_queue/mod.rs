@@ -3,5 +3,8 @@
 mod core;
 pub use core::Engine;
 
+mod status;
+pub use status::{MAX_FINISHED_TASK_STATUSES, TaskId, TaskStatus, TaskStatusRegistry};
+
 mod tasks;
 pub use tasks::*;
@@ -0,0 +1,119 @@
+//! Contains the [TaskStatusRegistry], which tracks the [TaskStatus] of each task enqueued in the
+//! [Engine].
+//!
+//! [Engine]: crate::Engine
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+/// The maximum number of finished tasks whose status is retained by a [TaskStatusRegistry].
+pub const MAX_FINISHED_TASK_STATUSES: usize = 1024;
+
+/// The identifier of a task enqueued in the [Engine], assigned in enqueue order.
+///
+/// [Engine]: crate::Engine
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TaskId(pub u64);
+
+impl fmt::Display for TaskId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// The status of a task enqueued in the [Engine].
+///
+/// [Engine]: crate::Engine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskStatus {
+    /// The task is waiting in the queue.
+    Queued,
+    /// The task is being executed.
+    Running,
+    /// The task was executed successfully.
+    Completed,
+    /// The task failed with a non-temporary error, or was discarded from the queue before it
+    /// could be executed. A failed task that is still in the queue is retried on the next drain.
+    Failed,
+}
+
+impl TaskStatus {
+    /// Returns `true` if the task has finished, successfully or not.
+    pub const fn is_finished(&self) -> bool {
+        matches!(self, Self::Completed | Self::Failed)
+    }
+}
+
+/// A shared registry of the [TaskStatus] of each task enqueued in the [Engine].
+///
+/// The registry is updated by the [Engine] as tasks move through their lifecycle, and may be
+/// cloned to observe task statuses from outside of the engine, such as while a drain is in
+/// progress. Only the statuses of the [MAX_FINISHED_TASK_STATUSES] most recently finished tasks
+/// are retained.
+///
+/// [Engine]: crate::Engine
+#[derive(Debug, Clone, Default)]
+pub struct TaskStatusRegistry {
+    inner: Arc<Mutex<RegistryInner>>,
+}
+
+/// The state of a [TaskStatusRegistry].
+#[derive(Debug, Default)]
+struct RegistryInner {
+    /// The status of each tracked task.
+    statuses: HashMap<TaskId, TaskStatus>,
+    /// The finished tasks, in the order they finished.
+    finished: VecDeque<TaskId>,
+}
+
+impl TaskStatusRegistry {
+    /// Returns the status of the given task, or `None` if it is unknown or its status is no
+    /// longer retained.
+    pub fn status(&self, id: TaskId) -> Option<TaskStatus> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner()).statuses.get(&id).copied()
+    }
+
+    /// Sets the status of the given task, evicting the oldest finished statuses if more than
+    /// [MAX_FINISHED_TASK_STATUSES] tasks have finished.
+    pub(crate) fn set(&self, id: TaskId, status: TaskStatus) {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let previous = inner.statuses.insert(id, status);
+
+        // A failed task may be retried, in which case it is no longer finished.
+        if previous.is_some_and(|s| s.is_finished()) {
+            inner.finished.retain(|finished| *finished != id);
+        }
+        if status.is_finished() {
+            inner.finished.push_back(id);
+        }
+        while inner.finished.len() > MAX_FINISHED_TASK_STATUSES {
+            if let Some(evicted) = inner.finished.pop_front() {
+                inner.statuses.remove(&evicted);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_evicts_oldest_finished() {
+        let registry = TaskStatusRegistry::default();
+        let queued = TaskId(u64::MAX);
+        registry.set(queued, TaskStatus::Queued);
+
+        for id in 0..=MAX_FINISHED_TASK_STATUSES as u64 {
+            registry.set(TaskId(id), TaskStatus::Completed);
+        }
+        assert_eq!(registry.status(TaskId(0)), None);
+        assert_eq!(registry.status(TaskId(1)), Some(TaskStatus::Completed));
+
+        // Unfinished tasks are never evicted.
+        assert_eq!(registry.status(queued), Some(TaskStatus::Queued));
+    }
+}
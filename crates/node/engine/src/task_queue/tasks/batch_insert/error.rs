@@ -0,0 +1,39 @@
+//! Contains the error types for the [BatchInsertTask].
+//!
+//! [BatchInsertTask]: crate::BatchInsertTask
+
+use crate::EngineTaskError;
+use alloy_rpc_types_engine::PayloadStatusEnum;
+
+/// An error that occurs when running the [BatchInsertTask].
+///
+/// [BatchInsertTask]: crate::BatchInsertTask
+#[derive(Debug, thiserror::Error)]
+pub enum BatchInsertTaskError {
+    /// A payload in the batch was reported as invalid. The payloads before it were inserted, and
+    /// the payloads after it were not.
+    #[error("Payload {index} of the batch is invalid: {status}")]
+    InvalidPayload {
+        /// The index of the invalid payload within the batch.
+        index: usize,
+        /// The status reported by the execution engine.
+        status: PayloadStatusEnum,
+    },
+}
+
+impl BatchInsertTaskError {
+    /// Returns the number of payloads that were inserted before the batch was aborted.
+    pub const fn inserted(&self) -> usize {
+        match self {
+            Self::InvalidPayload { index, .. } => *index,
+        }
+    }
+}
+
+impl From<BatchInsertTaskError> for EngineTaskError {
+    fn from(value: BatchInsertTaskError) -> Self {
+        match value {
+            BatchInsertTaskError::InvalidPayload { .. } => Self::Critical(Box::new(value)),
+        }
+    }
+}
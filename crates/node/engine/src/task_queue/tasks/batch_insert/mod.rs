@@ -0,0 +1,7 @@
+//! Task to insert a batch of unsafe payloads into the execution engine.
+
+mod task;
+pub use task::BatchInsertTask;
+
+mod error;
+pub use error::BatchInsertTaskError;
@@ -0,0 +1,154 @@
+//! A task to insert a batch of unsafe payloads into the execution engine.
+
+use crate::{
+    BatchInsertTaskError, EngineClient, EngineForkchoiceVersion, EngineState, EngineTaskError,
+    EngineTaskExt, InsertUnsafeTask, InsertUnsafeTaskError, SyncConfig,
+};
+use async_trait::async_trait;
+use kona_genesis::RollupConfig;
+use op_alloy_rpc_types_engine::OpNetworkPayloadEnvelope;
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+/// The task to insert an ordered batch of unsafe payloads into the execution engine.
+///
+/// The payloads are inserted one after another, as with an [InsertUnsafeTask] each, but under a
+/// single task so that no other task is interleaved between them. Insertion stops at the first
+/// payload that the execution engine reports as invalid, with a
+/// [BatchInsertTaskError::InvalidPayload] recording its index.
+///
+/// The number of inserted payloads is shared between clones of the task, so that a retried task
+/// resumes after the last inserted payload rather than inserting the batch again.
+#[derive(Debug, Clone)]
+pub struct BatchInsertTask {
+    /// The engine client.
+    client: Arc<EngineClient>,
+    /// The sync config.
+    sync_config: Arc<SyncConfig>,
+    /// The rollup config.
+    rollup_config: Arc<RollupConfig>,
+    /// The engine forkchoice version.
+    version: EngineForkchoiceVersion,
+    /// The network payload envelopes, in insertion order.
+    envelopes: Arc<[OpNetworkPayloadEnvelope]>,
+    /// The number of payloads inserted so far.
+    inserted: Arc<AtomicUsize>,
+}
+
+impl BatchInsertTask {
+    /// Creates a new batch insert task.
+    pub fn new(
+        client: Arc<EngineClient>,
+        sync_config: Arc<SyncConfig>,
+        rollup_config: Arc<RollupConfig>,
+        version: EngineForkchoiceVersion,
+        envelopes: impl Into<Arc<[OpNetworkPayloadEnvelope]>>,
+    ) -> Self {
+        Self {
+            client,
+            sync_config,
+            rollup_config,
+            version,
+            envelopes: envelopes.into(),
+            inserted: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Returns the number of payloads in the batch.
+    pub fn len(&self) -> usize {
+        self.envelopes.len()
+    }
+
+    /// Returns `true` if the batch is empty.
+    pub fn is_empty(&self) -> bool {
+        self.envelopes.is_empty()
+    }
+
+    /// Returns the number of payloads inserted so far.
+    pub fn inserted(&self) -> usize {
+        self.inserted.load(Ordering::Acquire)
+    }
+}
+
+#[async_trait]
+impl EngineTaskExt for BatchInsertTask {
+    async fn execute(&self, state: &mut EngineState) -> Result<(), EngineTaskError> {
+        for (index, envelope) in self.envelopes.iter().enumerate().skip(self.inserted()) {
+            let task = InsertUnsafeTask::new(
+                Arc::clone(&self.client),
+                Arc::clone(&self.sync_config),
+                Arc::clone(&self.rollup_config),
+                self.version,
+                envelope.clone(),
+            );
+
+            if let Err(e) = task.execute(state).await {
+                let EngineTaskError::Dropped(ref err) = e else {
+                    return Err(e);
+                };
+                return match err.downcast_ref::<InsertUnsafeTaskError>() {
+                    Some(InsertUnsafeTaskError::UnexpectedPayloadStatus(status))
+                        if status.is_invalid() =>
+                    {
+                        warn!(target: "engine", index, "Aborting batch insert at invalid payload");
+                        Err(BatchInsertTaskError::InvalidPayload { index, status: status.clone() }
+                            .into())
+                    }
+                    _ => Err(e),
+                };
+            }
+            self.inserted.store(index + 1, Ordering::Release);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        MockEngine, test_engine_state, test_genesis_envelope, test_sync_config,
+    };
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_batch_insert_stops_at_invalid_payload() {
+        let mock = MockEngine::spawn().await;
+        mock.queue_response(
+            "engine_newPayloadV1",
+            json!({ "status": "VALID", "latestValidHash": null }),
+        );
+        mock.queue_response(
+            "engine_newPayloadV1",
+            json!({ "status": "INVALID", "latestValidHash": null, "validationError": "bad block" }),
+        );
+        let (envelope, cfg) = test_genesis_envelope();
+        let cfg = Arc::new(cfg);
+        let task = BatchInsertTask::new(
+            mock.client(Arc::clone(&cfg)),
+            Arc::new(test_sync_config()),
+            cfg,
+            EngineForkchoiceVersion::V1,
+            vec![envelope; 3],
+        );
+        assert_eq!(task.len(), 3);
+
+        let err = task.execute(&mut test_engine_state()).await.unwrap_err();
+        let EngineTaskError::Critical(err) = err else {
+            panic!("expected a critical error");
+        };
+        let err = err.downcast_ref::<BatchInsertTaskError>().unwrap();
+        assert!(matches!(err, BatchInsertTaskError::InvalidPayload { index: 1, .. }));
+        assert_eq!(err.inserted(), 1);
+        assert_eq!(task.inserted(), 1);
+
+        // The payload after the invalid one is never sent to the engine.
+        assert_eq!(
+            mock.calls(),
+            vec!["engine_newPayloadV1", "engine_forkchoiceUpdatedV1", "engine_newPayloadV1"]
+        );
+    }
+}
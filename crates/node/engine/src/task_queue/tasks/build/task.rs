@@ -5,7 +5,6 @@ use crate::{
     EngineClient, EngineForkchoiceVersion, EngineGetPayloadVersion, EngineState, EngineTaskError,
     EngineTaskExt,
 };
-use alloy_provider::ext::EngineApi;
 use alloy_rpc_types_engine::{
     ExecutionPayload, ExecutionPayloadFieldV2, ExecutionPayloadInputV2, ForkchoiceState, PayloadId,
     PayloadStatusEnum,
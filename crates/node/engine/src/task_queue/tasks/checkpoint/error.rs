@@ -0,0 +1,50 @@
+//! Contains error types for the [crate::CheckpointFinalizeTask].
+
+use crate::EngineTaskError;
+use alloy_primitives::B256;
+use thiserror::Error;
+
+/// An error that occurs when running the [crate::CheckpointFinalizeTask].
+#[derive(Debug, Error)]
+pub enum CheckpointFinalizeTaskError {
+    /// The checkpoint's L2 block is ahead of the safe head, so it cannot be finalized yet.
+    #[error("Checkpoint block {checkpoint} is ahead of the safe head {safe}")]
+    AheadOfSafeHead {
+        /// The number of the checkpoint's L2 block.
+        checkpoint: u64,
+        /// The number of the safe head.
+        safe: u64,
+    },
+    /// The output root of the checkpoint's L2 block could not be fetched.
+    #[error("Failed to fetch the output root of block {0}: {1}")]
+    OutputFetchFailed(u64, anyhow::Error),
+    /// The checkpoint's L2 block was not found.
+    #[error("Checkpoint block {0} not found")]
+    BlockNotFound(u64),
+    /// The output root of the canonical L2 block at the checkpoint's number does not match the
+    /// checkpoint's output root.
+    #[error(
+        "Checkpoint block {number} is not canonical: expected output root {expected}, got {actual}"
+    )]
+    NonCanonical {
+        /// The number of the checkpoint's L2 block.
+        number: u64,
+        /// The checkpoint's output root.
+        expected: B256,
+        /// The output root of the canonical L2 block.
+        actual: B256,
+    },
+}
+
+impl From<CheckpointFinalizeTaskError> for EngineTaskError {
+    fn from(value: CheckpointFinalizeTaskError) -> Self {
+        match value {
+            CheckpointFinalizeTaskError::AheadOfSafeHead { .. } => Self::Temporary(Box::new(value)),
+            CheckpointFinalizeTaskError::OutputFetchFailed(_, _) => {
+                Self::Temporary(Box::new(value))
+            }
+            CheckpointFinalizeTaskError::BlockNotFound(_) => Self::Temporary(Box::new(value)),
+            CheckpointFinalizeTaskError::NonCanonical { .. } => Self::Critical(Box::new(value)),
+        }
+    }
+}
@@ -0,0 +1,7 @@
+//! Task and its associated types for finalizing the engine at an L1-finalized output root.
+
+mod task;
+pub use task::CheckpointFinalizeTask;
+
+mod error;
+pub use error::CheckpointFinalizeTaskError;
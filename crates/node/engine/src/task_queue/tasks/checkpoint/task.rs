@@ -0,0 +1,186 @@
+//! A task for finalizing the engine at an output root that was finalized on L1.
+
+use crate::{
+    CheckpointFinalizeTaskError, EngineClient, EngineState, EngineTaskError, EngineTaskExt,
+    ForkchoiceState, ForkchoiceTask,
+};
+use alloy_primitives::B256;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// The [CheckpointFinalizeTask] advances the engine's finalized head to the L2 block of an output
+/// root that was finalized on L1.
+///
+/// The task resolves the canonical L2 block at the checkpoint's block number, and verifies that
+/// its output root matches the checkpoint. Since the output root commits to the block hash, a
+/// mismatch means the engine's canonical chain diverged from the chain finalized on L1, which
+/// fails the task with [CheckpointFinalizeTaskError::NonCanonical]. Otherwise, the block is
+/// finalized with an `engine_forkchoiceUpdated` call.
+///
+/// Checkpoints at or behind the finalized head are ignored, and checkpoints ahead of the safe head
+/// cannot be finalized until the safe head reaches them.
+#[derive(Debug, Clone)]
+pub struct CheckpointFinalizeTask {
+    /// The engine client.
+    pub client: Arc<EngineClient>,
+    /// The number of the L2 block the output root was proposed for.
+    pub l2_block_number: u64,
+    /// The output root finalized on L1.
+    pub output_root: B256,
+}
+
+impl CheckpointFinalizeTask {
+    /// Creates a new [CheckpointFinalizeTask].
+    pub const fn new(client: Arc<EngineClient>, l2_block_number: u64, output_root: B256) -> Self {
+        Self { client, l2_block_number, output_root }
+    }
+}
+
+#[async_trait]
+impl EngineTaskExt for CheckpointFinalizeTask {
+    async fn execute(&self, state: &mut EngineState) -> Result<(), EngineTaskError> {
+        let number = self.l2_block_number;
+        let finalized = state.finalized_head().block_info.number;
+        if number <= finalized {
+            debug!(target: "engine", number, finalized, "Ignoring checkpoint at or behind finalized head");
+            return Ok(());
+        }
+
+        let safe = state.safe_head().block_info.number;
+        if number > safe {
+            return Err(
+                CheckpointFinalizeTaskError::AheadOfSafeHead { checkpoint: number, safe }.into()
+            );
+        }
+
+        let (block, actual) = self
+            .client
+            .l2_output_at_block(number)
+            .await
+            .map_err(|e| CheckpointFinalizeTaskError::OutputFetchFailed(number, e))?
+            .ok_or(CheckpointFinalizeTaskError::BlockNotFound(number))?;
+        if actual != self.output_root {
+            return Err(CheckpointFinalizeTaskError::NonCanonical {
+                number,
+                expected: self.output_root,
+                actual,
+            }
+            .into());
+        }
+
+        info!(target: "engine", number, hash = %block.block_info.hash, "Finalizing L1 checkpoint");
+        ForkchoiceTask::new(Arc::clone(&self.client))
+            .with_forkchoice(ForkchoiceState::new(state.unsafe_head(), state.safe_head(), block))
+            .execute(state)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        MockEngine, test_engine_state, test_genesis_envelope_at, test_rpc_block_at,
+    };
+    use alloy_primitives::keccak256;
+    use alloy_rpc_types_engine::ForkchoiceState as EngineForkchoiceState;
+    use kona_protocol::Predeploys;
+    use serde_json::json;
+
+    const STORAGE_HASH: B256 = B256::repeat_byte(0x16);
+
+    /// The number of the checkpointed block, ahead of the finalized head of [test_engine_state].
+    const CHECKPOINT: u64 = 1;
+
+    /// Spawns a [MockEngine] serving the genesis block of [test_genesis_envelope_at] the
+    /// [CHECKPOINT], along with a proof of the `L2ToL1MessagePasser` with the [STORAGE_HASH], and
+    /// returns a client for it along with the genesis output root.
+    async fn spawn_mock() -> (MockEngine, Arc<EngineClient>, B256) {
+        let (_, cfg) = test_genesis_envelope_at(CHECKPOINT);
+        let (rpc_block, hash) = test_rpc_block_at(CHECKPOINT);
+
+        let mock = MockEngine::spawn().await;
+        mock.set_response("eth_getBlockByNumber", rpc_block);
+        mock.set_response(
+            "eth_getProof",
+            json!({
+                "address": Predeploys::L2_TO_L1_MESSAGE_PASSER,
+                "balance": "0x0",
+                "codeHash": B256::ZERO,
+                "nonce": "0x0",
+                "storageHash": STORAGE_HASH,
+                "accountProof": [],
+                "storageProof": []
+            }),
+        );
+        let client = mock.client(Arc::new(cfg));
+
+        // The genesis block has a zero state root.
+        let mut raw_output = [0u8; 128];
+        raw_output[64..96].copy_from_slice(STORAGE_HASH.as_ref());
+        raw_output[96..128].copy_from_slice(hash.as_ref());
+        (mock, client, keccak256(raw_output))
+    }
+
+    /// Returns a [test_engine_state] whose unsafe and safe heads are at the [CHECKPOINT].
+    fn checkpoint_state() -> EngineState {
+        let mut state = test_engine_state();
+        state.unsafe_head.block_info.number = CHECKPOINT;
+        state.safe_head.block_info.number = CHECKPOINT;
+        state
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_finalize_canonical() {
+        let (mock, client, output_root) = spawn_mock().await;
+        let (_, hash) = test_rpc_block_at(CHECKPOINT);
+        let task = CheckpointFinalizeTask::new(client, CHECKPOINT, output_root);
+
+        let mut state = checkpoint_state();
+        task.execute(&mut state).await.unwrap();
+
+        let sent: Vec<EngineForkchoiceState> = mock
+            .params("engine_forkchoiceUpdatedV3")
+            .into_iter()
+            .map(|params| serde_json::from_value(params[0].clone()).unwrap())
+            .collect();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].finalized_block_hash, hash);
+        assert_eq!(state.finalized_head().block_info.hash, hash);
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_finalize_non_canonical() {
+        let (mock, client, output_root) = spawn_mock().await;
+        let task = CheckpointFinalizeTask::new(client, CHECKPOINT, B256::ZERO);
+
+        let mut state = checkpoint_state();
+        let err = task.execute(&mut state).await.unwrap_err();
+        let EngineTaskError::Critical(err) = err else {
+            panic!("expected a critical error");
+        };
+        assert!(matches!(
+            err.downcast_ref::<CheckpointFinalizeTaskError>(),
+            Some(CheckpointFinalizeTaskError::NonCanonical { number: CHECKPOINT, expected, actual })
+                if *expected == B256::ZERO && *actual == output_root
+        ));
+
+        // The finalized head is left unchanged.
+        assert!(mock.params("engine_forkchoiceUpdatedV3").is_empty());
+        assert_eq!(state.finalized_head(), test_engine_state().finalized_head());
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_finalize_at_finalized_head() {
+        let (mock, client, output_root) = spawn_mock().await;
+        let task = CheckpointFinalizeTask::new(client, CHECKPOINT, output_root);
+
+        let mut state = checkpoint_state();
+        state.finalized_head.block_info.number = CHECKPOINT;
+        task.execute(&mut state).await.unwrap();
+
+        // The checkpoint is ignored without querying the engine.
+        assert!(mock.calls().is_empty());
+        assert_eq!(state.finalized_head().block_info.number, CHECKPOINT);
+    }
+}
@@ -1,6 +1,7 @@
 //! Contains error types for the [crate::ForkchoiceTask].
 
 use crate::EngineTaskError;
+use alloy_primitives::B256;
 use alloy_transport::{RpcError, TransportErrorKind};
 use thiserror::Error;
 
@@ -22,6 +23,15 @@ pub enum ForkchoiceTaskError {
     /// The forkchoice state is invalid.
     #[error("Invalid forkchoice state")]
     InvalidForkchoiceState,
+    /// The engine accepted the forkchoice update, but did not adopt the requested head within the
+    /// confirmation timeout.
+    #[error("Forkchoice head {expected} not adopted, latest block is {actual:?}")]
+    NotAdopted {
+        /// The requested head.
+        expected: B256,
+        /// The hash of the engine's latest block, when last queried.
+        actual: Option<B256>,
+    },
 }
 
 impl From<ForkchoiceTaskError> for EngineTaskError {
@@ -32,6 +42,7 @@ impl From<ForkchoiceTaskError> for EngineTaskError {
             ForkchoiceTaskError::ForkchoiceUpdateFailed(_) => Self::Temporary(Box::new(value)),
             ForkchoiceTaskError::FinalizedAheadOfUnsafe(_, _) => Self::Critical(Box::new(value)),
             ForkchoiceTaskError::InvalidForkchoiceState => Self::Reset(Box::new(value)),
+            ForkchoiceTaskError::NotAdopted { .. } => Self::Temporary(Box::new(value)),
         }
     }
 }
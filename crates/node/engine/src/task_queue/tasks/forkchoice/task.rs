@@ -1,23 +1,81 @@
 //! A task for the `engine_forkchoiceUpdated` method, with no attributes.
 
-use crate::{EngineClient, EngineState, EngineTaskError, EngineTaskExt, ForkchoiceTaskError};
+use crate::{
+    EngineClient, EngineState, EngineTaskError, EngineTaskExt, ForkchoiceState, ForkchoiceTaskError,
+};
+use alloy_eips::BlockNumberOrTag;
+use alloy_primitives::B256;
 use alloy_rpc_types_engine::INVALID_FORK_CHOICE_STATE_ERROR;
 use async_trait::async_trait;
 use op_alloy_provider::ext::engine::OpEngineApi;
-use std::sync::Arc;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// The interval at which the engine's latest block is queried while confirming that it adopted
+/// the forkchoice head.
+const CONFIRMATION_POLL_INTERVAL: Duration = Duration::from_millis(100);
 
 /// The [ForkchoiceTask] executes an `engine_forkchoiceUpdated` call with the current
 /// [EngineState]'s forkchoice, and no payload attributes.
+///
+/// If the task is given a [ForkchoiceState] with [ForkchoiceTask::with_forkchoice], that
+/// forkchoice is sent instead, and the [EngineState]'s heads are set to it once the engine accepts
+/// it.
+///
+/// If a confirmation timeout is set with [ForkchoiceTask::with_confirmation], the task also
+/// confirms that the engine's latest block is the requested head, failing with
+/// [ForkchoiceTaskError::NotAdopted] if it is not within the timeout.
 #[derive(Debug, Clone)]
 pub struct ForkchoiceTask {
     /// The engine client.
     pub client: Arc<EngineClient>,
+    /// The forkchoice to apply, if not the [EngineState]'s current forkchoice.
+    pub forkchoice: Option<ForkchoiceState>,
+    /// The timeout within which the engine must adopt the forkchoice head, if confirmation is
+    /// enabled.
+    pub confirmation_timeout: Option<Duration>,
 }
 
 impl ForkchoiceTask {
     /// Creates a new [ForkchoiceTask].
     pub const fn new(client: Arc<EngineClient>) -> Self {
-        Self { client }
+        Self { client, forkchoice: None, confirmation_timeout: None }
+    }
+
+    /// Enables confirming that the engine adopted the forkchoice head within the given timeout.
+    pub const fn with_confirmation(mut self, timeout: Duration) -> Self {
+        self.confirmation_timeout = Some(timeout);
+        self
+    }
+
+    /// Waits until the engine's latest block is the given head, polling every
+    /// [CONFIRMATION_POLL_INTERVAL] until the timeout elapses.
+    async fn confirm_head(&self, head: B256, timeout: Duration) -> Result<(), ForkchoiceTaskError> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let actual = match self.client.l2_block_hash_by_label(BlockNumberOrTag::Latest).await {
+                Ok(hash) => hash,
+                Err(e) => {
+                    debug!(target: "engine", "Failed to fetch latest block to confirm forkchoice: {e}");
+                    None
+                }
+            };
+            if actual == Some(head) {
+                return Ok(());
+            }
+            if Instant::now() + CONFIRMATION_POLL_INTERVAL > deadline {
+                return Err(ForkchoiceTaskError::NotAdopted { expected: head, actual });
+            }
+            tokio::time::sleep(CONFIRMATION_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Sets the [ForkchoiceState] to apply, rather than the [EngineState]'s current forkchoice.
+    pub const fn with_forkchoice(mut self, forkchoice: ForkchoiceState) -> Self {
+        self.forkchoice = Some(forkchoice);
+        self
     }
 }
 
@@ -25,7 +83,7 @@ impl ForkchoiceTask {
 impl EngineTaskExt for ForkchoiceTask {
     async fn execute(&self, state: &mut EngineState) -> Result<(), EngineTaskError> {
         // Check if a forkchoice update is not needed, return early.
-        if !state.forkchoice_update_needed {
+        if self.forkchoice.is_none() && !state.forkchoice_update_needed {
             return Err(ForkchoiceTaskError::NoForkchoiceUpdateNeeded.into());
         }
 
@@ -36,19 +94,17 @@ impl EngineTaskExt for ForkchoiceTask {
         }
 
         // Check if the head is behind the finalized head.
-        if state.unsafe_head().block_info.number < state.finalized_head().block_info.number {
+        let forkchoice = self.forkchoice.unwrap_or_else(|| state.forkchoice());
+        if forkchoice.is_finalized_ahead_of_head() {
             return Err(ForkchoiceTaskError::FinalizedAheadOfUnsafe(
-                state.unsafe_head().block_info.number,
-                state.finalized_head().block_info.number,
+                forkchoice.head.block_info.number,
+                forkchoice.finalized.block_info.number,
             )
             .into());
         }
 
-        // Send the forkchoice update through the input.
-        let forkchoice = state.create_forkchoice_state();
-
         // Handle the forkchoice update result.
-        if let Err(e) = self.client.fork_choice_updated_v3(forkchoice, None).await {
+        if let Err(e) = self.client.fork_choice_updated_v3(forkchoice.into(), None).await {
             let e = e
                 .as_error_resp()
                 .and_then(|e| {
@@ -60,7 +116,173 @@ impl EngineTaskExt for ForkchoiceTask {
             return Err(e.into());
         }
 
+        // Confirm that the engine adopted the new head, if enabled.
+        if let Some(timeout) = self.confirmation_timeout {
+            self.confirm_head(forkchoice.head.block_info.hash, timeout).await?;
+        }
+
+        if self.forkchoice.is_some() {
+            state.set_forkchoice(forkchoice);
+        }
         state.forkchoice_update_needed = false;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        EngineClient,
+        test_utils::{MockEngine, RecordingSink, test_engine_state, test_rpc_block},
+    };
+    use alloy_primitives::B256;
+    use alloy_rpc_types_engine::{ForkchoiceState as EngineForkchoiceState, JwtSecret};
+    use kona_genesis::RollupConfig;
+    use kona_protocol::{BlockInfo, L2BlockInfo};
+    use serde_json::Value;
+
+    fn block(number: u64) -> L2BlockInfo {
+        L2BlockInfo {
+            block_info: BlockInfo {
+                number,
+                hash: B256::repeat_byte(number as u8),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Returns the forkchoice sent in each `engine_forkchoiceUpdatedV3` call to the mock engine.
+    fn sent_forkchoices(mock: &MockEngine) -> Vec<EngineForkchoiceState> {
+        mock.params("engine_forkchoiceUpdatedV3")
+            .into_iter()
+            .map(|params| serde_json::from_value(params[0].clone()).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_forkchoice_task_state_forkchoice() {
+        let mock = MockEngine::spawn().await;
+        let task = ForkchoiceTask::new(mock.client(Arc::new(RollupConfig::default())));
+
+        let mut state = test_engine_state();
+        let forkchoice = ForkchoiceState::new(block(3), block(2), block(1));
+        state.set_forkchoice(forkchoice);
+        task.execute(&mut state).await.unwrap();
+
+        assert_eq!(sent_forkchoices(&mock), [EngineForkchoiceState::from(forkchoice)]);
+        assert_eq!(state.forkchoice(), forkchoice);
+        assert!(!state.forkchoice_update_needed);
+    }
+
+    #[tokio::test]
+    async fn test_forkchoice_task_explicit_forkchoice_roundtrip() {
+        let mock = MockEngine::spawn().await;
+        let forkchoice = ForkchoiceState::new(block(3), block(2), block(1));
+        let task = ForkchoiceTask::new(mock.client(Arc::new(RollupConfig::default())))
+            .with_forkchoice(forkchoice);
+
+        let mut state = test_engine_state();
+        task.execute(&mut state).await.unwrap();
+
+        let sent = sent_forkchoices(&mock);
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].head_block_hash, forkchoice.head.block_info.hash);
+        assert_eq!(sent[0].safe_block_hash, forkchoice.safe.block_info.hash);
+        assert_eq!(sent[0].finalized_block_hash, forkchoice.finalized.block_info.hash);
+        assert_eq!(state.forkchoice(), forkchoice);
+    }
+
+    #[tokio::test]
+    async fn test_forkchoice_task_finalized_ahead_of_head() {
+        let mock = MockEngine::spawn().await;
+        let task = ForkchoiceTask::new(mock.client(Arc::new(RollupConfig::default())))
+            .with_forkchoice(ForkchoiceState::new(block(1), block(1), block(2)));
+
+        let mut state = test_engine_state();
+        let err = task.execute(&mut state).await.unwrap_err();
+        let EngineTaskError::Critical(err) = err else {
+            panic!("expected a critical error");
+        };
+        assert!(matches!(
+            err.downcast_ref::<ForkchoiceTaskError>(),
+            Some(ForkchoiceTaskError::FinalizedAheadOfUnsafe(1, 2))
+        ));
+        assert!(mock.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_forkchoice_task_confirms_adopted_head() {
+        let mock = MockEngine::spawn().await;
+        let (rpc_block, hash) = test_rpc_block();
+        mock.set_response("eth_getBlockByNumber", rpc_block);
+
+        let mut head = block(1);
+        head.block_info.hash = hash;
+        let forkchoice = ForkchoiceState::new(head, block(0), block(0));
+        let task = ForkchoiceTask::new(mock.client(Arc::new(RollupConfig::default())))
+            .with_forkchoice(forkchoice)
+            .with_confirmation(Duration::from_secs(1));
+
+        let mut state = test_engine_state();
+        task.execute(&mut state).await.unwrap();
+        assert_eq!(state.forkchoice(), forkchoice);
+    }
+
+    #[tokio::test]
+    async fn test_forkchoice_task_head_not_adopted() {
+        let mock = MockEngine::spawn().await;
+        mock.set_response("eth_getBlockByNumber", Value::Null);
+
+        let forkchoice = ForkchoiceState::new(block(3), block(2), block(1));
+        let task = ForkchoiceTask::new(mock.client(Arc::new(RollupConfig::default())))
+            .with_forkchoice(forkchoice)
+            .with_confirmation(Duration::from_millis(250));
+
+        let mut state = test_engine_state();
+        let err = task.execute(&mut state).await.unwrap_err();
+        let EngineTaskError::Temporary(err) = err else {
+            panic!("expected a temporary error");
+        };
+        assert!(matches!(
+            err.downcast_ref::<ForkchoiceTaskError>(),
+            Some(ForkchoiceTaskError::NotAdopted { expected, actual: None })
+                if *expected == forkchoice.head.block_info.hash
+        ));
+
+        // The forkchoice was sent, but the engine state is left unchanged so that it is retried.
+        assert_eq!(sent_forkchoices(&mock).len(), 1);
+        assert_ne!(state.forkchoice(), forkchoice);
+        assert!(mock.calls().iter().filter(|m| *m == "eth_getBlockByNumber").count() > 1);
+    }
+
+    #[tokio::test]
+    async fn test_forkchoice_task_records_latency() {
+        let mock = MockEngine::spawn().await;
+        let delay = Duration::from_millis(200);
+        mock.set_delay("engine_forkchoiceUpdatedV3", delay);
+
+        let sink = Arc::new(RecordingSink::default());
+        let client = EngineClient::new_http(
+            mock.url(),
+            mock.url(),
+            Arc::new(RollupConfig::default()),
+            JwtSecret::random(),
+        )
+        .with_latency_sink(sink.clone());
+        let task = ForkchoiceTask::new(Arc::new(client)).with_forkchoice(ForkchoiceState::new(
+            block(3),
+            block(2),
+            block(1),
+        ));
+
+        let mut state = test_engine_state();
+        task.execute(&mut state).await.unwrap();
+
+        let recorded = sink.recorded();
+        assert_eq!(recorded.len(), 1);
+        assert_eq!(recorded[0].0, "forkchoiceUpdated");
+        assert!(recorded[0].1 >= delay);
+    }
+}
@@ -3,6 +3,7 @@
 //! [InsertUnsafeTask]: crate::InsertUnsafeTask
 
 use crate::EngineTaskError;
+use alloy_primitives::B256;
 use alloy_rpc_types_engine::PayloadStatusEnum;
 use alloy_transport::{RpcError, TransportErrorKind};
 use kona_protocol::FromBlockError;
@@ -19,6 +20,15 @@ pub enum InsertUnsafeTaskError {
     /// Error converting a payload into a block.
     #[error(transparent)]
     FromBlockError(#[from] OpPayloadError),
+    /// The block hash computed from the payload's header does not match the payload's claimed
+    /// block hash.
+    #[error("Block hash mismatch: expected {expected}, computed {computed}")]
+    BlockHashMismatch {
+        /// The block hash claimed by the payload.
+        expected: B256,
+        /// The block hash computed from the payload's header.
+        computed: B256,
+    },
     /// Failed to insert new payload.
     #[error("Failed to insert new payload: {0}")]
     InsertFailed(RpcError<TransportErrorKind>),
@@ -41,6 +51,7 @@ impl From<InsertUnsafeTaskError> for EngineTaskError {
         match value {
             InsertUnsafeTaskError::FinalizedBlockFetch => Self::Temporary(Box::new(value)),
             InsertUnsafeTaskError::FromBlockError(_) => Self::Critical(Box::new(value)),
+            InsertUnsafeTaskError::BlockHashMismatch { .. } => Self::Critical(Box::new(value)),
             InsertUnsafeTaskError::InsertFailed(_) => Self::Temporary(Box::new(value)),
             InsertUnsafeTaskError::ForkchoiceUpdateFailed(_) => Self::Temporary(Box::new(value)),
             InsertUnsafeTaskError::UnexpectedPayloadStatus(_) => Self::Temporary(Box::new(value)),
@@ -2,6 +2,13 @@
 
 mod task;
 pub use task::InsertUnsafeTask;
+pub(crate) use task::new_payload;
 
 mod error;
 pub use error::InsertUnsafeTaskError;
+
+mod policy;
+pub use policy::InvalidPayloadPolicy;
+
+mod reorder;
+pub use reorder::{DEFAULT_MAX_BUFFERED_INSERTS, ReorderBuffer};
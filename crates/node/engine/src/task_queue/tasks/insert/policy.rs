@@ -0,0 +1,20 @@
+//! Contains the [InvalidPayloadPolicy] for the [InsertUnsafeTask].
+//!
+//! [InsertUnsafeTask]: crate::InsertUnsafeTask
+
+/// The policy applied when the execution engine reports an unsafe payload as `INVALID`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidPayloadPolicy {
+    /// The invalid payload is dropped with an [EngineTaskError::Dropped], regardless of its source,
+    /// and is not retried.
+    ///
+    /// [EngineTaskError::Dropped]: crate::EngineTaskError::Dropped
+    #[default]
+    Drop,
+    /// An invalid payload from a trusted source, such as the sequencer being validated, is
+    /// escalated into an [EngineTaskError::CriticalInvalid] that halts the node. Invalid payloads
+    /// from untrusted sources are dropped.
+    ///
+    /// [EngineTaskError::CriticalInvalid]: crate::EngineTaskError::CriticalInvalid
+    AbortOnTrusted,
+}
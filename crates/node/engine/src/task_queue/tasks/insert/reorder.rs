@@ -0,0 +1,175 @@
+//! Contains the [ReorderBuffer] for out-of-order unsafe payloads.
+
+use op_alloy_rpc_types_engine::OpNetworkPayloadEnvelope;
+use std::collections::{BTreeMap, VecDeque};
+
+/// The default maximum number of payloads held by a [ReorderBuffer].
+pub const DEFAULT_MAX_BUFFERED_INSERTS: usize = 64;
+
+/// A bounded buffer of unsafe payloads that arrived ahead of the next expected block.
+///
+/// Payloads are buffered by block number until the gap before them closes, at which point
+/// [ReorderBuffer::pop_ready] releases the contiguous run starting at the next expected block, so
+/// that they can be inserted in order with an [InsertUnsafeTask] each.
+///
+/// If the gap never closes, the buffer would otherwise grow without bound. Once it holds more than
+/// its maximum number of payloads, the payload that has been buffered the longest is evicted with
+/// a warning, on the assumption that the gap before it is not going to close.
+///
+/// [InsertUnsafeTask]: crate::InsertUnsafeTask
+#[derive(Debug, Clone)]
+pub struct ReorderBuffer {
+    /// The maximum number of buffered payloads.
+    max_buffered: usize,
+    /// The buffered payloads, keyed by block number.
+    payloads: BTreeMap<u64, OpNetworkPayloadEnvelope>,
+    /// The block numbers of the buffered payloads, in the order they were buffered.
+    arrivals: VecDeque<u64>,
+    /// The number of payloads evicted because the buffer was full.
+    evicted: u64,
+}
+
+impl Default for ReorderBuffer {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_BUFFERED_INSERTS)
+    }
+}
+
+impl ReorderBuffer {
+    /// Creates a new [ReorderBuffer] holding at most `max_buffered` payloads.
+    pub const fn new(max_buffered: usize) -> Self {
+        Self { max_buffered, payloads: BTreeMap::new(), arrivals: VecDeque::new(), evicted: 0 }
+    }
+
+    /// Returns the maximum number of buffered payloads.
+    pub const fn max_buffered(&self) -> usize {
+        self.max_buffered
+    }
+
+    /// Returns the number of buffered payloads.
+    pub fn len(&self) -> usize {
+        self.payloads.len()
+    }
+
+    /// Returns `true` if no payloads are buffered.
+    pub fn is_empty(&self) -> bool {
+        self.payloads.is_empty()
+    }
+
+    /// Returns the number of payloads evicted because the buffer was full.
+    pub const fn evicted(&self) -> u64 {
+        self.evicted
+    }
+
+    /// Buffers a payload until the gap before it closes.
+    ///
+    /// A payload for an already buffered block number replaces it. If the buffer is then over
+    /// its maximum size, the payload that has been buffered the longest is evicted and returned.
+    pub fn insert(
+        &mut self,
+        envelope: OpNetworkPayloadEnvelope,
+    ) -> Option<OpNetworkPayloadEnvelope> {
+        let number = envelope.payload.block_number();
+        if self.payloads.insert(number, envelope).is_none() {
+            self.arrivals.push_back(number);
+        }
+        if self.payloads.len() <= self.max_buffered {
+            return None;
+        }
+
+        let oldest = self.arrivals.pop_front()?;
+        let evicted = self.payloads.remove(&oldest)?;
+        self.evicted += 1;
+        warn!(
+            target: "engine",
+            block_number = oldest,
+            max_buffered = self.max_buffered,
+            "Reorder buffer full, evicting the oldest buffered payload"
+        );
+        Some(evicted)
+    }
+
+    /// Removes and returns the contiguous run of buffered payloads starting at block number
+    /// `next`, in order. Buffered payloads below `next` are stale, and are discarded.
+    pub fn pop_ready(&mut self, next: u64) -> Vec<OpNetworkPayloadEnvelope> {
+        self.payloads = self.payloads.split_off(&next);
+
+        let mut ready = Vec::new();
+        let mut number = next;
+        while let Some(envelope) = self.payloads.remove(&number) {
+            ready.push(envelope);
+            number += 1;
+        }
+        self.arrivals.retain(|number| self.payloads.contains_key(number));
+        ready
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::test_genesis_envelope;
+    use op_alloy_rpc_types_engine::OpExecutionPayload;
+
+    fn envelope(number: u64) -> OpNetworkPayloadEnvelope {
+        let (mut envelope, _) = test_genesis_envelope();
+        if let OpExecutionPayload::V1(payload) = &mut envelope.payload {
+            payload.block_number = number;
+        }
+        envelope
+    }
+
+    fn numbers(envelopes: &[OpNetworkPayloadEnvelope]) -> Vec<u64> {
+        envelopes.iter().map(|e| e.payload.block_number()).collect()
+    }
+
+    #[test]
+    fn test_reorder_buffer_releases_contiguous_run() {
+        let mut buffer = ReorderBuffer::default();
+        for number in [13, 11, 12, 15] {
+            assert!(buffer.insert(envelope(number)).is_none());
+        }
+
+        // Nothing is released while the gap at block 10 is open.
+        assert!(buffer.pop_ready(10).is_empty());
+        assert_eq!(buffer.len(), 4);
+
+        assert_eq!(numbers(&buffer.pop_ready(11)), vec![11, 12, 13]);
+        assert_eq!(buffer.len(), 1);
+        assert_eq!(numbers(&buffer.pop_ready(15)), vec![15]);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_reorder_buffer_evicts_oldest() {
+        let mut buffer = ReorderBuffer::new(3);
+        for number in [12, 14, 13] {
+            assert!(buffer.insert(envelope(number)).is_none());
+        }
+
+        // Exceeding the bound evicts the payload that was buffered first, not the lowest one.
+        let evicted = buffer.insert(envelope(11)).unwrap();
+        assert_eq!(evicted.payload.block_number(), 12);
+        assert_eq!(buffer.len(), 3);
+        assert_eq!(buffer.evicted(), 1);
+
+        let evicted = buffer.insert(envelope(15)).unwrap();
+        assert_eq!(evicted.payload.block_number(), 14);
+        assert_eq!(numbers(&buffer.pop_ready(11)), vec![11]);
+        assert_eq!(buffer.evicted(), 2);
+    }
+
+    #[test]
+    fn test_reorder_buffer_discards_stale() {
+        let mut buffer = ReorderBuffer::new(2);
+        buffer.insert(envelope(5));
+        buffer.insert(envelope(8));
+        assert_eq!(numbers(&buffer.pop_ready(8)), vec![8]);
+        assert!(buffer.is_empty());
+
+        // Stale payloads no longer count towards the bound.
+        assert!(buffer.insert(envelope(10)).is_none());
+        assert!(buffer.insert(envelope(11)).is_none());
+        assert_eq!(buffer.evicted(), 0);
+    }
+}
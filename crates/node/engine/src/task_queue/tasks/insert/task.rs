@@ -2,17 +2,20 @@
 
 use crate::{
     EngineClient, EngineForkchoiceVersion, EngineState, EngineTaskError, EngineTaskExt,
-    InsertUnsafeTaskError, SyncConfig, SyncMode, SyncStatus,
+    InsertUnsafeTaskError, InvalidPayloadPolicy, SpeculativeCache, SyncConfig, SyncMode,
+    SyncStatus,
 };
-use alloy_eips::BlockNumberOrTag;
-use alloy_provider::ext::EngineApi;
+use alloy_eips::{BlockNumberOrTag, eip7685::EMPTY_REQUESTS_HASH};
 use alloy_rpc_types_engine::{
-    ExecutionPayloadInputV2, ForkchoiceState, INVALID_FORK_CHOICE_STATE_ERROR, PayloadStatusEnum,
+    ExecutionPayloadInputV2, ForkchoiceState, INVALID_FORK_CHOICE_STATE_ERROR, PayloadStatus,
+    PayloadStatusEnum,
 };
+use alloy_transport::TransportResult;
 use async_trait::async_trait;
 use kona_genesis::RollupConfig;
 use kona_protocol::L2BlockInfo;
 use op_alloy_consensus::OpBlock;
+use op_alloy_provider::ext::engine::OpEngineApi;
 use op_alloy_rpc_types_engine::{OpExecutionPayload, OpNetworkPayloadEnvelope};
 use std::{sync::Arc, time::Instant};
 
@@ -29,6 +32,12 @@ pub struct InsertUnsafeTask {
     version: EngineForkchoiceVersion,
     /// The network payload envelope.
     envelope: OpNetworkPayloadEnvelope,
+    /// The policy applied when the payload is reported as invalid.
+    invalid_payload_policy: InvalidPayloadPolicy,
+    /// Whether the payload was received from a trusted source.
+    trusted: bool,
+    /// The cache of speculatively validated payloads, if the verdicts are reused.
+    speculative: Option<SpeculativeCache>,
 }
 
 impl InsertUnsafeTask {
@@ -40,7 +49,51 @@ impl InsertUnsafeTask {
         version: EngineForkchoiceVersion,
         envelope: OpNetworkPayloadEnvelope,
     ) -> Self {
-        Self { client, sync_config, rollup_config, version, envelope }
+        Self {
+            client,
+            sync_config,
+            rollup_config,
+            version,
+            envelope,
+            invalid_payload_policy: InvalidPayloadPolicy::Drop,
+            trusted: false,
+            speculative: None,
+        }
+    }
+
+    /// Reuses the verdict of the [SpeculativeCache] for the payload, if it was validated
+    /// speculatively, rather than sending it to the execution engine again.
+    pub fn with_speculative_cache(mut self, cache: SpeculativeCache) -> Self {
+        self.speculative = Some(cache);
+        self
+    }
+
+    /// Sets the [InvalidPayloadPolicy] applied when the payload is reported as invalid.
+    pub const fn with_invalid_payload_policy(mut self, policy: InvalidPayloadPolicy) -> Self {
+        self.invalid_payload_policy = policy;
+        self
+    }
+
+    /// Marks the payload as received from a trusted source, such as the sequencer being
+    /// validated.
+    pub const fn with_trusted_source(mut self) -> Self {
+        self.trusted = true;
+        self
+    }
+
+    /// Returns the error for an unexpected payload status. An `INVALID` payload is dropped with an
+    /// [EngineTaskError::Dropped], or escalated into an [EngineTaskError::CriticalInvalid] if the
+    /// payload is from a trusted source and the [InvalidPayloadPolicy] requires it.
+    fn payload_status_error(&self, status: PayloadStatusEnum) -> EngineTaskError {
+        let invalid = status.is_invalid();
+        let err = InsertUnsafeTaskError::UnexpectedPayloadStatus(status);
+        if !invalid {
+            return err.into();
+        }
+        if self.trusted && self.invalid_payload_policy == InvalidPayloadPolicy::AbortOnTrusted {
+            return EngineTaskError::CriticalInvalid(Box::new(err));
+        }
+        EngineTaskError::Dropped(Box::new(err))
     }
 
     /// Checks the response of the `engine_newPayload` call, and updates the sync status if
@@ -79,6 +132,51 @@ impl InsertUnsafeTask {
 
         matches!(status, PayloadStatusEnum::Valid)
     }
+
+    /// Converts the payload into an [OpBlock], and checks that the hash of the block's header
+    /// matches the block hash claimed by the payload.
+    fn checked_block(&self) -> Result<OpBlock, InsertUnsafeTaskError> {
+        let mut block: OpBlock = self.envelope.payload.clone().try_into_block()?;
+
+        // Fill in the header fields that are not part of the execution payload itself.
+        if matches!(self.envelope.payload, OpExecutionPayload::V3(_) | OpExecutionPayload::V4(_)) {
+            block.header.parent_beacon_block_root = self.envelope.parent_beacon_block_root;
+        }
+        if matches!(self.envelope.payload, OpExecutionPayload::V4(_)) {
+            block.header.requests_hash = Some(EMPTY_REQUESTS_HASH);
+        }
+
+        let expected = self.envelope.payload.block_hash();
+        let computed = block.header.hash_slow();
+        if expected != computed {
+            return Err(InsertUnsafeTaskError::BlockHashMismatch { expected, computed });
+        }
+
+        Ok(block)
+    }
+}
+
+/// Sends the payload of the envelope to the execution engine with the `engine_newPayload` method
+/// matching its version.
+pub(crate) async fn new_payload(
+    client: &EngineClient,
+    envelope: &OpNetworkPayloadEnvelope,
+) -> TransportResult<PayloadStatus> {
+    let block_root = envelope.parent_beacon_block_root.unwrap_or_default();
+    match envelope.payload.clone() {
+        OpExecutionPayload::V1(payload) => client.new_payload_v1(payload).await,
+        OpExecutionPayload::V2(payload) => {
+            let payload_input = ExecutionPayloadInputV2 {
+                execution_payload: payload.payload_inner,
+                withdrawals: Some(payload.withdrawals),
+            };
+            client.new_payload_v2(payload_input).await
+        }
+        OpExecutionPayload::V3(payload) => client.new_payload_v3(payload, block_root).await,
+        OpExecutionPayload::V4(payload) => {
+            client.new_payload_v4(payload.payload_inner, block_root).await
+        }
+    }
 }
 
 #[async_trait]
@@ -110,45 +208,36 @@ impl EngineTaskExt for InsertUnsafeTask {
 
         let time_start = Instant::now();
 
-        // Insert the new payload.
-        let block_root = self.envelope.parent_beacon_block_root.unwrap_or_default();
+        // Form the block from the execution payload, rejecting it before it reaches the engine if
+        // its header does not hash to the claimed block hash.
+        let block = self.checked_block()?;
+
+        // Insert the new payload, unless it was already validated speculatively.
         let insert_time_start = Instant::now();
-        let response = match self.envelope.payload.clone() {
-            OpExecutionPayload::V1(payload) => self.client.new_payload_v1(payload).await,
-            OpExecutionPayload::V2(payload) => {
-                let payload_input = ExecutionPayloadInputV2 {
-                    execution_payload: payload.payload_inner,
-                    withdrawals: Some(payload.withdrawals),
-                };
-                self.client.new_payload_v2(payload_input).await
-            }
-            OpExecutionPayload::V3(payload) => {
-                self.client.new_payload_v3(payload, Vec::new(), block_root).await
+        let cached = self.speculative.as_ref().and_then(|cache| {
+            cache.invalidate(state.unsafe_head().block_info.hash);
+            cache.take(self.envelope.payload.block_hash())
+        });
+        let status = match cached {
+            Some(status) => {
+                debug!(target: "engine", "Reusing speculative payload verdict: {status}");
+                status
             }
-            OpExecutionPayload::V4(payload) => {
-                self.client
-                    .new_payload_v4(payload.payload_inner, Vec::new(), block_root, Vec::new())
+            None => {
+                new_payload(&self.client, &self.envelope)
                     .await
+                    .map_err(InsertUnsafeTaskError::InsertFailed)?
+                    .status
             }
         };
 
-        // Check the `engine_newPayload` response.
-        let response = match response {
-            Ok(resp) => resp,
-            Err(e) => return Err(InsertUnsafeTaskError::InsertFailed(e).into()),
-        };
-        if !self.check_new_payload_status(state, &response.status) {
-            return Err(InsertUnsafeTaskError::UnexpectedPayloadStatus(response.status).into());
+        // Check the `engine_newPayload` status.
+        if !self.check_new_payload_status(state, &status) {
+            return Err(self.payload_status_error(status));
         }
         let insert_duration = insert_time_start.elapsed();
 
         // Form the new unsafe block ref from the execution payload.
-        let block: OpBlock = self
-            .envelope
-            .payload
-            .clone()
-            .try_into_block()
-            .map_err(InsertUnsafeTaskError::FromBlockError)?;
         let new_unsafe_ref =
             L2BlockInfo::from_block_and_genesis(&block, &self.rollup_config.genesis)
                 .map_err(InsertUnsafeTaskError::L2BlockInfoConstruction)?;
@@ -196,10 +285,7 @@ impl EngineTaskExt for InsertUnsafeTask {
             }
         };
         if !self.check_forkchoice_updated_status(state, &response.payload_status.status) {
-            return Err(InsertUnsafeTaskError::UnexpectedPayloadStatus(
-                response.payload_status.status,
-            )
-            .into());
+            return Err(self.payload_status_error(response.payload_status.status));
         }
 
         // Update the local engine state.
@@ -229,3 +315,143 @@ impl EngineTaskExt for InsertUnsafeTask {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        MockEngine, RecordingSink, test_engine_state, test_genesis_envelope, test_sync_config,
+    };
+    use alloy_primitives::B256;
+    use alloy_rpc_types_engine::JwtSecret;
+    use serde_json::json;
+    use tokio::net::TcpListener;
+    use url::Url;
+
+    #[tokio::test]
+    async fn test_insert_block_hash_mismatch() {
+        let mock = MockEngine::spawn().await;
+        let (mut envelope, cfg) = test_genesis_envelope();
+        let cfg = Arc::new(cfg);
+
+        let OpExecutionPayload::V1(ref mut payload) = envelope.payload else {
+            unreachable!("genesis envelope is a V1 payload");
+        };
+        let computed = payload.block_hash;
+        payload.block_hash = B256::repeat_byte(0xFF);
+
+        let task = InsertUnsafeTask::new(
+            mock.client(Arc::clone(&cfg)),
+            Arc::new(test_sync_config()),
+            cfg,
+            EngineForkchoiceVersion::V1,
+            envelope,
+        );
+        let err = task.checked_block().unwrap_err();
+        assert!(matches!(
+            err,
+            InsertUnsafeTaskError::BlockHashMismatch { expected, computed: c }
+                if expected == B256::repeat_byte(0xFF) && c == computed
+        ));
+
+        let mut state = test_engine_state();
+        let err = task.execute(&mut state).await.unwrap_err();
+        let EngineTaskError::Critical(err) = err else {
+            panic!("expected a critical error");
+        };
+        assert!(matches!(
+            err.downcast_ref::<InsertUnsafeTaskError>(),
+            Some(InsertUnsafeTaskError::BlockHashMismatch { .. })
+        ));
+        assert!(mock.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_insert_invalid_payload_policy() {
+        let mock = MockEngine::spawn().await;
+        mock.set_response(
+            "engine_newPayloadV1",
+            json!({ "status": "INVALID", "latestValidHash": null, "validationError": "bad block" }),
+        );
+        let (envelope, cfg) = test_genesis_envelope();
+        let cfg = Arc::new(cfg);
+        let task = InsertUnsafeTask::new(
+            mock.client(Arc::clone(&cfg)),
+            Arc::new(test_sync_config()),
+            cfg,
+            EngineForkchoiceVersion::V1,
+            envelope,
+        );
+
+        // Invalid payloads are dropped by default, even from trusted sources.
+        let err = task.clone().with_trusted_source().execute(&mut test_engine_state()).await;
+        assert!(matches!(err, Err(EngineTaskError::Dropped(_))));
+
+        // Invalid payloads from untrusted sources are dropped under the abort policy.
+        let task = task.with_invalid_payload_policy(InvalidPayloadPolicy::AbortOnTrusted);
+        let err = task.clone().execute(&mut test_engine_state()).await;
+        assert!(matches!(err, Err(EngineTaskError::Dropped(_))));
+
+        // Invalid payloads from trusted sources are escalated under the abort policy.
+        let err = task.with_trusted_source().execute(&mut test_engine_state()).await.unwrap_err();
+        let EngineTaskError::CriticalInvalid(err) = err else {
+            panic!("expected a critical invalid error");
+        };
+        assert!(matches!(
+            err.downcast_ref::<InsertUnsafeTaskError>(),
+            Some(InsertUnsafeTaskError::UnexpectedPayloadStatus(PayloadStatusEnum::Invalid { .. }))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_insert_fails_over() {
+        // Bind and immediately drop a listener to obtain an address with nothing listening on it.
+        let dead = TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap();
+        let dead = Url::parse(&format!("http://{dead}")).unwrap();
+        let mock = MockEngine::spawn().await;
+        let (envelope, cfg) = test_genesis_envelope();
+        let cfg = Arc::new(cfg);
+
+        let client = EngineClient::new_http_with_fallbacks(
+            dead,
+            vec![mock.url()],
+            mock.url(),
+            Arc::clone(&cfg),
+            JwtSecret::random(),
+        );
+        let task = InsertUnsafeTask::new(
+            Arc::new(client),
+            Arc::new(test_sync_config()),
+            cfg,
+            EngineForkchoiceVersion::V1,
+            envelope,
+        );
+
+        // Both the payload and the forkchoice update are sent to the fallback endpoint.
+        task.execute(&mut test_engine_state()).await.unwrap();
+        assert_eq!(mock.calls(), vec!["engine_newPayloadV1", "engine_forkchoiceUpdatedV1"]);
+    }
+
+    #[tokio::test]
+    async fn test_insert_records_latency() {
+        let mock = MockEngine::spawn().await;
+        let (envelope, cfg) = test_genesis_envelope();
+        let cfg = Arc::new(cfg);
+
+        let sink = Arc::new(RecordingSink::default());
+        let client =
+            EngineClient::new_http(mock.url(), mock.url(), Arc::clone(&cfg), JwtSecret::random())
+                .with_latency_sink(sink.clone());
+        let task = InsertUnsafeTask::new(
+            Arc::new(client),
+            Arc::new(test_sync_config()),
+            cfg,
+            EngineForkchoiceVersion::V1,
+            envelope,
+        );
+        task.execute(&mut test_engine_state()).await.unwrap();
+
+        let methods: Vec<_> = sink.recorded().into_iter().map(|(method, _)| method).collect();
+        assert_eq!(methods, vec!["newPayload", "forkchoiceUpdated"]);
+    }
+}
@@ -7,7 +7,24 @@ mod forkchoice;
 pub use forkchoice::{ForkchoiceTask, ForkchoiceTaskError};
 
 mod insert;
-pub use insert::{InsertUnsafeTask, InsertUnsafeTaskError};
+pub use insert::{
+    DEFAULT_MAX_BUFFERED_INSERTS, InsertUnsafeTask, InsertUnsafeTaskError, InvalidPayloadPolicy,
+    ReorderBuffer,
+};
+
+mod batch_insert;
+pub use batch_insert::{BatchInsertTask, BatchInsertTaskError};
 
 mod build;
 pub use build::{BuildTask, BuildTaskError};
+
+mod sync_status;
+pub use sync_status::{ExecutionSyncStatus, SyncStatusTask, SyncStatusTaskError};
+
+mod checkpoint;
+pub use checkpoint::{CheckpointFinalizeTask, CheckpointFinalizeTaskError};
+
+mod speculative;
+pub use speculative::{
+    MAX_SPECULATIVE_WINDOW, SpeculativeCache, SpeculativeValidateTask, SpeculativeValidateTaskError,
+};
@@ -0,0 +1,95 @@
+//! Contains the [SpeculativeCache] of payload verdicts.
+
+use alloy_primitives::B256;
+use alloy_rpc_types_engine::PayloadStatusEnum;
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+/// The verdict of the execution engine for a speculatively validated payload.
+#[derive(Debug, Clone)]
+struct CachedVerdict {
+    /// The hash of the payload's block.
+    hash: B256,
+    /// The hash of the payload's parent block.
+    parent_hash: B256,
+    /// The status reported by the execution engine.
+    status: PayloadStatusEnum,
+}
+
+/// A bounded cache of the verdicts of speculatively validated payloads, shared between the
+/// [crate::SpeculativeValidateTask] that fills it and the [crate::InsertUnsafeTask]s that reuse
+/// them.
+///
+/// The cached payloads form a chain extending the unsafe head they were validated on. Once the
+/// unsafe head moves off of that chain, such as on a reorg, [SpeculativeCache::invalidate] drops
+/// the verdicts that no longer extend it.
+#[derive(Debug, Clone)]
+pub struct SpeculativeCache {
+    /// The cached verdicts, in the order they were validated.
+    verdicts: Arc<Mutex<VecDeque<CachedVerdict>>>,
+    /// The maximum number of cached verdicts.
+    capacity: usize,
+}
+
+impl SpeculativeCache {
+    /// Creates a new, empty [SpeculativeCache] holding up to `capacity` verdicts.
+    pub fn new(capacity: usize) -> Self {
+        Self { verdicts: Arc::new(Mutex::new(VecDeque::new())), capacity }
+    }
+
+    /// Returns the maximum number of cached verdicts.
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of cached verdicts.
+    pub fn len(&self) -> usize {
+        self.verdicts.lock().map(|verdicts| verdicts.len()).unwrap_or_default()
+    }
+
+    /// Returns `true` if no verdicts are cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if a verdict is cached for the block with the given hash.
+    pub fn contains(&self, hash: B256) -> bool {
+        self.verdicts.lock().is_ok_and(|verdicts| verdicts.iter().any(|v| v.hash == hash))
+    }
+
+    /// Caches the verdict for a payload, evicting the oldest verdict if the cache is full.
+    pub fn insert(&self, hash: B256, parent_hash: B256, status: PayloadStatusEnum) {
+        let Ok(mut verdicts) = self.verdicts.lock() else {
+            return;
+        };
+        verdicts.retain(|v| v.hash != hash);
+        verdicts.push_back(CachedVerdict { hash, parent_hash, status });
+        while verdicts.len() > self.capacity {
+            verdicts.pop_front();
+        }
+    }
+
+    /// Removes and returns the cached verdict for the block with the given hash, if any.
+    pub fn take(&self, hash: B256) -> Option<PayloadStatusEnum> {
+        let mut verdicts = self.verdicts.lock().ok()?;
+        let index = verdicts.iter().position(|v| v.hash == hash)?;
+        verdicts.remove(index).map(|v| v.status)
+    }
+
+    /// Drops the verdicts of payloads that do not extend the given unsafe head.
+    pub fn invalidate(&self, head: B256) {
+        let Ok(mut verdicts) = self.verdicts.lock() else {
+            return;
+        };
+        let mut chain = HashSet::from([head]);
+        verdicts.retain(|v| {
+            let extends = chain.contains(&v.parent_hash);
+            if extends {
+                chain.insert(v.hash);
+            }
+            extends
+        });
+    }
+}
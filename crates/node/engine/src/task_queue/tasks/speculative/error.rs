@@ -0,0 +1,21 @@
+//! Contains error types for the [crate::SpeculativeValidateTask].
+
+use crate::EngineTaskError;
+use alloy_transport::{RpcError, TransportErrorKind};
+use thiserror::Error;
+
+/// An error that occurs when running the [crate::SpeculativeValidateTask].
+#[derive(Debug, Error)]
+pub enum SpeculativeValidateTaskError {
+    /// The `engine_newPayload` call to validate a payload failed.
+    #[error("Speculative payload validation failed: {0}")]
+    ValidationFailed(RpcError<TransportErrorKind>),
+}
+
+impl From<SpeculativeValidateTaskError> for EngineTaskError {
+    fn from(value: SpeculativeValidateTaskError) -> Self {
+        match value {
+            SpeculativeValidateTaskError::ValidationFailed(_) => Self::Temporary(Box::new(value)),
+        }
+    }
+}
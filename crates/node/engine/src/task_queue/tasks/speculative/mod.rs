@@ -0,0 +1,10 @@
+//! Task and its associated types for speculatively validating upcoming unsafe payloads.
+
+mod task;
+pub use task::{MAX_SPECULATIVE_WINDOW, SpeculativeValidateTask};
+
+mod cache;
+pub use cache::SpeculativeCache;
+
+mod error;
+pub use error::SpeculativeValidateTaskError;
@@ -0,0 +1,158 @@
+//! A task for speculatively validating upcoming unsafe payloads.
+
+use crate::{
+    EngineClient, EngineState, EngineTaskError, EngineTaskExt, SpeculativeCache,
+    SpeculativeValidateTaskError, task_queue::tasks::insert::new_payload,
+};
+use async_trait::async_trait;
+use op_alloy_rpc_types_engine::OpNetworkPayloadEnvelope;
+use std::sync::Arc;
+
+/// The maximum number of payloads validated by a single [SpeculativeValidateTask].
+pub const MAX_SPECULATIVE_WINDOW: usize = 8;
+
+/// The [SpeculativeValidateTask] sends a window of predicted next unsafe payloads to the execution
+/// engine with `engine_newPayload`, ahead of their insertion, and caches the verdicts in a
+/// [SpeculativeCache].
+///
+/// The payloads are validated without updating the forkchoice, so none of them becomes canonical.
+/// An [InsertUnsafeTask] sharing the cache reuses the verdict of its payload rather than
+/// validating it again, leaving only the forkchoice update on the insertion's critical path.
+///
+/// The window must form a chain extending the unsafe head, and is truncated at the first payload
+/// that does not, at the first payload that is not valid, and at [MAX_SPECULATIVE_WINDOW]
+/// payloads. Cached verdicts that no longer extend the unsafe head, such as after a reorg, are
+/// invalidated before the window is validated.
+///
+/// [InsertUnsafeTask]: crate::InsertUnsafeTask
+#[derive(Debug, Clone)]
+pub struct SpeculativeValidateTask {
+    /// The engine client.
+    pub client: Arc<EngineClient>,
+    /// The cache of payload verdicts.
+    pub cache: SpeculativeCache,
+    /// The predicted next payloads, in chain order.
+    pub window: Vec<OpNetworkPayloadEnvelope>,
+}
+
+impl SpeculativeValidateTask {
+    /// Creates a new [SpeculativeValidateTask], keeping at most [MAX_SPECULATIVE_WINDOW] of the
+    /// given payloads.
+    pub fn new(
+        client: Arc<EngineClient>,
+        cache: SpeculativeCache,
+        mut window: Vec<OpNetworkPayloadEnvelope>,
+    ) -> Self {
+        window.truncate(MAX_SPECULATIVE_WINDOW);
+        Self { client, cache, window }
+    }
+}
+
+#[async_trait]
+impl EngineTaskExt for SpeculativeValidateTask {
+    async fn execute(&self, state: &mut EngineState) -> Result<(), EngineTaskError> {
+        let mut parent = state.unsafe_head().block_info.hash;
+        self.cache.invalidate(parent);
+
+        for envelope in &self.window {
+            let hash = envelope.payload.block_hash();
+            if envelope.payload.parent_hash() != parent {
+                debug!(target: "engine", %hash, "Speculative window does not extend the unsafe head");
+                break;
+            }
+            parent = hash;
+            if self.cache.contains(hash) {
+                continue;
+            }
+
+            let status = new_payload(&self.client, envelope)
+                .await
+                .map_err(SpeculativeValidateTaskError::ValidationFailed)?
+                .status;
+            let valid = status.is_valid();
+            trace!(target: "engine", %hash, %status, "Speculatively validated payload");
+            self.cache.insert(hash, envelope.payload.parent_hash(), status);
+
+            // Later payloads build on this one, so they cannot be valid either.
+            if !valid {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        EngineForkchoiceVersion, InsertUnsafeTask,
+        test_utils::{MockEngine, test_engine_state, test_genesis_envelope, test_sync_config},
+    };
+    use alloy_primitives::B256;
+
+    #[tokio::test]
+    async fn test_speculative_verdict_reused_by_insert() {
+        let mock = MockEngine::spawn().await;
+        let (envelope, cfg) = test_genesis_envelope();
+        let cfg = Arc::new(cfg);
+        let client = mock.client(Arc::clone(&cfg));
+        let cache = SpeculativeCache::new(MAX_SPECULATIVE_WINDOW);
+
+        // The genesis payload extends the default unsafe head, which has a zero hash.
+        let mut state = test_engine_state();
+        SpeculativeValidateTask::new(Arc::clone(&client), cache.clone(), vec![envelope.clone()])
+            .execute(&mut state)
+            .await
+            .unwrap();
+        assert_eq!(mock.calls(), ["engine_newPayloadV1"]);
+        assert!(cache.contains(envelope.payload.block_hash()));
+        assert_eq!(state.unsafe_head(), test_engine_state().unsafe_head());
+
+        let insert = InsertUnsafeTask::new(
+            client,
+            Arc::new(test_sync_config()),
+            cfg,
+            EngineForkchoiceVersion::V1,
+            envelope.clone(),
+        )
+        .with_speculative_cache(cache.clone());
+        insert.execute(&mut state).await.unwrap();
+
+        // The payload is not validated again.
+        assert_eq!(mock.calls(), ["engine_newPayloadV1", "engine_forkchoiceUpdatedV1"]);
+        assert_eq!(state.unsafe_head().block_info.hash, envelope.payload.block_hash());
+        assert!(cache.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_speculative_verdict_invalidated_on_reorg() {
+        let mock = MockEngine::spawn().await;
+        let (envelope, cfg) = test_genesis_envelope();
+        let cfg = Arc::new(cfg);
+        let client = mock.client(Arc::clone(&cfg));
+        let cache = SpeculativeCache::new(MAX_SPECULATIVE_WINDOW);
+
+        let mut state = test_engine_state();
+        SpeculativeValidateTask::new(Arc::clone(&client), cache.clone(), vec![envelope.clone()])
+            .execute(&mut state)
+            .await
+            .unwrap();
+        assert_eq!(cache.len(), 1);
+
+        // The unsafe head reorgs away from the speculated chain.
+        let mut head = state.unsafe_head();
+        head.block_info.hash = B256::repeat_byte(0xAA);
+        state.set_unsafe_head(head);
+        cache.invalidate(state.unsafe_head().block_info.hash);
+        assert!(cache.is_empty());
+
+        // A window that does not extend the unsafe head is not validated.
+        SpeculativeValidateTask::new(client, cache.clone(), vec![envelope])
+            .execute(&mut state)
+            .await
+            .unwrap();
+        assert_eq!(mock.calls(), ["engine_newPayloadV1"]);
+        assert!(cache.is_empty());
+    }
+}
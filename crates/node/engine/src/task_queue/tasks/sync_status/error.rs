@@ -0,0 +1,21 @@
+//! Contains error types for the [crate::SyncStatusTask].
+
+use crate::EngineTaskError;
+use alloy_transport::{RpcError, TransportErrorKind};
+use thiserror::Error;
+
+/// An error that occurs when running the [crate::SyncStatusTask].
+#[derive(Debug, Error)]
+pub enum SyncStatusTaskError {
+    /// The `eth_syncing` call to the execution client failed.
+    #[error("Failed to query the execution layer sync status: {0}")]
+    SyncStatusQueryFailed(RpcError<TransportErrorKind>),
+}
+
+impl From<SyncStatusTaskError> for EngineTaskError {
+    fn from(value: SyncStatusTaskError) -> Self {
+        match value {
+            SyncStatusTaskError::SyncStatusQueryFailed(_) => Self::Temporary(Box::new(value)),
+        }
+    }
+}
@@ -0,0 +1,7 @@
+//! Task and its associated types for querying the execution layer's sync status.
+
+mod task;
+pub use task::{ExecutionSyncStatus, SyncStatusTask};
+
+mod error;
+pub use error::SyncStatusTaskError;
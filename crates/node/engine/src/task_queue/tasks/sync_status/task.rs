@@ -0,0 +1,113 @@
+//! A task for querying the sync status of the execution layer.
+
+use crate::{EngineClient, EngineState, EngineTaskError, EngineTaskExt, SyncStatusTaskError};
+use alloy_rpc_types_eth::SyncStatus as EthSyncStatus;
+use async_trait::async_trait;
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// The sync status of the execution layer, as reported by `eth_syncing`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutionSyncStatus {
+    /// The execution client is not syncing.
+    Synced,
+    /// The execution client is syncing.
+    Syncing {
+        /// The current block of the execution client.
+        current_block: u64,
+        /// The highest block known to the execution client.
+        highest_block: u64,
+    },
+}
+
+impl ExecutionSyncStatus {
+    /// Returns `true` if the execution client is syncing.
+    pub const fn is_syncing(&self) -> bool {
+        matches!(self, Self::Syncing { .. })
+    }
+}
+
+impl From<EthSyncStatus> for ExecutionSyncStatus {
+    fn from(status: EthSyncStatus) -> Self {
+        match status {
+            EthSyncStatus::None => Self::Synced,
+            EthSyncStatus::Info(info) => Self::Syncing {
+                current_block: info.current_block.saturating_to(),
+                highest_block: info.highest_block.saturating_to(),
+            },
+        }
+    }
+}
+
+/// The [SyncStatusTask] queries the execution client's sync status with `eth_syncing`, and
+/// publishes the resulting [ExecutionSyncStatus] to its channel.
+#[derive(Debug, Clone)]
+pub struct SyncStatusTask {
+    /// The engine client.
+    pub client: Arc<EngineClient>,
+    /// The channel to publish the queried [ExecutionSyncStatus] to.
+    pub sender: watch::Sender<Option<ExecutionSyncStatus>>,
+}
+
+impl SyncStatusTask {
+    /// Creates a new [SyncStatusTask].
+    pub const fn new(
+        client: Arc<EngineClient>,
+        sender: watch::Sender<Option<ExecutionSyncStatus>>,
+    ) -> Self {
+        Self { client, sender }
+    }
+
+    /// Queries the execution client's sync status.
+    pub async fn query(&self) -> Result<ExecutionSyncStatus, SyncStatusTaskError> {
+        let status =
+            self.client.syncing().await.map_err(SyncStatusTaskError::SyncStatusQueryFailed)?;
+        Ok(status.into())
+    }
+}
+
+#[async_trait]
+impl EngineTaskExt for SyncStatusTask {
+    async fn execute(&self, _: &mut EngineState) -> Result<(), EngineTaskError> {
+        let status = self.query().await?;
+        debug!(target: "engine", ?status, "Queried execution layer sync status");
+        self.sender.send_replace(Some(status));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{MockEngine, test_engine_state};
+    use kona_genesis::RollupConfig;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_sync_status_task() {
+        let mock = MockEngine::spawn().await;
+        let client = mock.client(Arc::new(RollupConfig::default()));
+        let (sender, recv) = watch::channel(None);
+        let task = SyncStatusTask::new(client, sender);
+        let mut state = test_engine_state();
+
+        task.execute(&mut state).await.unwrap();
+        assert_eq!(*recv.borrow(), Some(ExecutionSyncStatus::Synced));
+
+        mock.set_response(
+            "eth_syncing",
+            json!({
+                "startingBlock": "0x0",
+                "currentBlock": "0x10",
+                "highestBlock": "0x20",
+                "warpChunksAmount": null,
+                "warpChunksProcessed": null
+            }),
+        );
+        task.execute(&mut state).await.unwrap();
+        let status = recv.borrow().unwrap();
+        assert!(status.is_syncing());
+        assert_eq!(status, ExecutionSyncStatus::Syncing { current_block: 16, highest_block: 32 });
+        assert_eq!(mock.calls(), vec!["eth_syncing", "eth_syncing"]);
+    }
+}
@@ -2,7 +2,11 @@
 //!
 //! [Engine]: crate::Engine
 
-use super::{BuildTask, ForkchoiceTask, InsertUnsafeTask};
+use super::{
+    BatchInsertTask, BuildTask, BuildTaskError, CheckpointFinalizeTask, ForkchoiceTask,
+    ForkchoiceTaskError, InsertUnsafeTask, InsertUnsafeTaskError, SpeculativeValidateTask,
+    SpeculativeValidateTaskError, SyncStatusTask, SyncStatusTaskError,
+};
 use crate::EngineState;
 use async_trait::async_trait;
 use thiserror::Error;
@@ -17,17 +21,32 @@ pub enum EngineTask {
     ForkchoiceUpdate(ForkchoiceTask),
     /// Inserts an unsafe payload into the execution engine.
     InsertUnsafe(InsertUnsafeTask),
+    /// Inserts an ordered batch of unsafe payloads into the execution engine.
+    BatchInsert(BatchInsertTask),
     /// Builds a new block with the given attributes, and inserts it into the execution engine.
     BuildBlock(BuildTask),
+    /// Queries the sync status of the execution layer.
+    SyncStatus(SyncStatusTask),
+    /// Finalizes the L2 block of an output root that was finalized on L1.
+    CheckpointFinalize(CheckpointFinalizeTask),
+    /// Validates the predicted next unsafe payloads ahead of their insertion.
+    SpeculativeValidate(SpeculativeValidateTask),
 }
 
 impl EngineTask {
-    /// Executes the task without consuming it.
-    async fn execute_inner(&self, state: &mut EngineState) -> Result<(), EngineTaskError> {
+    /// Executes the task once without consuming it, without retrying temporary errors.
+    pub(crate) async fn execute_inner(
+        &self,
+        state: &mut EngineState,
+    ) -> Result<(), EngineTaskError> {
         match self.clone() {
             Self::ForkchoiceUpdate(task) => task.execute(state).await,
             Self::InsertUnsafe(task) => task.execute(state).await,
+            Self::BatchInsert(task) => task.execute(state).await,
             Self::BuildBlock(task) => task.execute(state).await,
+            Self::SyncStatus(task) => task.execute(state).await,
+            Self::CheckpointFinalize(task) => task.execute(state).await,
+            Self::SpeculativeValidate(task) => task.execute(state).await,
         }
     }
 }
@@ -46,10 +65,22 @@ impl EngineTaskExt for EngineTask {
                     error!(target: "engine", "{e}");
                     return Err(EngineTaskError::Critical(e));
                 }
+                EngineTaskError::CriticalInvalid(e) => {
+                    error!(target: "engine", "{e}");
+                    return Err(EngineTaskError::CriticalInvalid(e));
+                }
                 EngineTaskError::Reset(e) => {
                     warn!(target: "engine", "Engine requested derivation reset");
                     return Err(EngineTaskError::Reset(e));
                 }
+                EngineTaskError::Dropped(e) => {
+                    warn!(target: "engine", "{e}");
+                    return Err(EngineTaskError::Dropped(e));
+                }
+                EngineTaskError::ShuttingDown => return Err(EngineTaskError::ShuttingDown),
+                EngineTaskError::EngineUnavailable => {
+                    return Err(EngineTaskError::EngineUnavailable);
+                }
             }
         }
 
@@ -73,7 +104,90 @@ pub enum EngineTaskError {
     /// A critical error within the engine.
     #[error("Critical engine task error: {0}")]
     Critical(Box<dyn std::error::Error>),
+    /// A trusted source produced a payload that the execution engine reported as invalid. This
+    /// error halts the node.
+    #[error("Invalid payload from a trusted source: {0}")]
+    CriticalInvalid(Box<dyn std::error::Error>),
     /// An error that requires a derivation pipeline reset.
     #[error("Derivation pipeline reset required: {0}")]
     Reset(Box<dyn std::error::Error>),
+    /// The task failed in a way that retrying cannot fix, such as an unsafe payload that the
+    /// execution engine reported as invalid. The task is dropped from the queue without halting
+    /// the node.
+    #[error("Engine task dropped: {0}")]
+    Dropped(Box<dyn std::error::Error>),
+    /// The [Engine] shut down before the task was executed.
+    ///
+    /// [Engine]: crate::Engine
+    #[error("Engine task queue is shutting down")]
+    ShuttingDown,
+    /// The execution engine failed repeatedly, and the [EngineCircuitBreaker] of the [Engine] is
+    /// open. The task was not executed, and is retained until a probe of the engine succeeds.
+    ///
+    /// [Engine]: crate::Engine
+    /// [EngineCircuitBreaker]: crate::EngineCircuitBreaker
+    #[error("Engine unavailable: circuit breaker is open")]
+    EngineUnavailable,
+}
+
+impl EngineTaskError {
+    /// Returns `true` if the error is a temporary failure of a call to the execution engine, such
+    /// as a transport error or a malformed RPC response.
+    ///
+    /// Temporary errors that the engine reported deliberately, such as the engine syncing or a
+    /// forkchoice update not being needed, are not engine failures.
+    pub fn is_engine_failure(&self) -> bool {
+        let Self::Temporary(e) = self else {
+            return false;
+        };
+        matches!(e.downcast_ref(), Some(ForkchoiceTaskError::ForkchoiceUpdateFailed(_))) ||
+            matches!(
+                e.downcast_ref(),
+                Some(
+                    InsertUnsafeTaskError::InsertFailed(_) |
+                        InsertUnsafeTaskError::ForkchoiceUpdateFailed(_)
+                )
+            ) ||
+            matches!(
+                e.downcast_ref(),
+                Some(
+                    BuildTaskError::ForkchoiceUpdateFailed(_) |
+                        BuildTaskError::GetPayloadFailed(_) |
+                        BuildTaskError::NewPayloadFailed(_)
+                )
+            ) ||
+            matches!(e.downcast_ref(), Some(SpeculativeValidateTaskError::ValidationFailed(_))) ||
+            matches!(e.downcast_ref(), Some(SyncStatusTaskError::SyncStatusQueryFailed(_)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_transport::{RpcError, TransportErrorKind};
+
+    #[test]
+    fn test_is_engine_failure() {
+        let rpc = || RpcError::Transport(TransportErrorKind::BackendGone);
+        assert!(
+            EngineTaskError::from(ForkchoiceTaskError::ForkchoiceUpdateFailed(rpc()))
+                .is_engine_failure()
+        );
+        assert!(
+            EngineTaskError::from(InsertUnsafeTaskError::InsertFailed(rpc())).is_engine_failure()
+        );
+        assert!(EngineTaskError::from(BuildTaskError::GetPayloadFailed(rpc())).is_engine_failure());
+        assert!(
+            EngineTaskError::from(SyncStatusTaskError::SyncStatusQueryFailed(rpc()))
+                .is_engine_failure()
+        );
+
+        // Temporary errors reported by a responsive engine are not engine failures.
+        assert!(!EngineTaskError::from(ForkchoiceTaskError::EngineSyncing).is_engine_failure());
+        assert!(
+            !EngineTaskError::from(ForkchoiceTaskError::NoForkchoiceUpdateNeeded)
+                .is_engine_failure()
+        );
+        assert!(!EngineTaskError::EngineUnavailable.is_engine_failure());
+    }
 }
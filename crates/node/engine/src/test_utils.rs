@@ -0,0 +1,333 @@
+//! Test utilities for `kona-engine`.
+
+#![allow(missing_docs, unreachable_pub)]
+
+use crate::{EngineClient, EngineState, RpcLatencySink, SyncConfig, SyncMode, SyncStatus};
+use alloy_primitives::{Address, B256, Bloom, Bytes, U256};
+use alloy_rpc_types_engine::{ExecutionPayloadV1, JwtSecret};
+use kona_genesis::RollupConfig;
+use kona_protocol::L2BlockInfo;
+use op_alloy_consensus::OpBlock;
+use op_alloy_rpc_types_engine::{OpExecutionPayload, OpNetworkPayloadEnvelope, PayloadHash};
+use serde_json::{Value, json};
+use std::{
+    collections::{HashMap, VecDeque},
+    net::SocketAddr,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    task::JoinHandle,
+};
+use url::Url;
+
+/// A mock execution engine that serves canned JSON-RPC responses over HTTP and records the
+/// methods that were called, and their parameters, in order.
+#[derive(Debug)]
+pub struct MockEngine {
+    addr: SocketAddr,
+    calls: Arc<Mutex<Vec<(String, Value)>>>,
+    responses: Arc<Mutex<HashMap<String, Value>>>,
+    queued: Arc<Mutex<HashMap<String, VecDeque<Value>>>>,
+    delays: Arc<Mutex<HashMap<String, Duration>>>,
+    concurrency: Arc<Concurrency>,
+    handle: JoinHandle<()>,
+}
+
+/// The number of requests a [MockEngine] is serving, and the highest number it served at once.
+#[derive(Debug, Default)]
+struct Concurrency {
+    active: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+impl MockEngine {
+    /// Spawns a new [MockEngine] on a random local port.
+    pub async fn spawn() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("binds mock engine");
+        let addr = listener.local_addr().expect("mock engine has a local address");
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let responses = Arc::new(Mutex::new(Self::default_responses()));
+        let queued = Arc::new(Mutex::new(HashMap::new()));
+        let delays = Arc::new(Mutex::new(HashMap::new()));
+        let concurrency = Arc::new(Concurrency::default());
+
+        let handle = tokio::spawn({
+            let calls = Arc::clone(&calls);
+            let responses = Arc::clone(&responses);
+            let queued = Arc::clone(&queued);
+            let delays = Arc::clone(&delays);
+            let concurrency = Arc::clone(&concurrency);
+            async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    tokio::spawn(Self::serve(
+                        stream,
+                        Arc::clone(&calls),
+                        Arc::clone(&responses),
+                        Arc::clone(&queued),
+                        Arc::clone(&delays),
+                        Arc::clone(&concurrency),
+                    ));
+                }
+            }
+        });
+
+        Self { addr, calls, responses, queued, delays, concurrency, handle }
+    }
+
+    /// Overrides the `result` returned for the given JSON-RPC method.
+    pub fn set_response(&self, method: &str, result: Value) {
+        self.responses.lock().unwrap().insert(method.to_string(), result);
+    }
+
+    /// Queues a `result` to be returned for a single call to the given JSON-RPC method. Queued
+    /// results are returned in order, before falling back to the method's regular response.
+    pub fn queue_response(&self, method: &str, result: Value) {
+        self.queued.lock().unwrap().entry(method.to_string()).or_default().push_back(result);
+    }
+
+    /// Delays every response to the given JSON-RPC method by the given duration.
+    pub fn set_delay(&self, method: &str, delay: Duration) {
+        self.delays.lock().unwrap().insert(method.to_string(), delay);
+    }
+
+    /// Returns the JSON-RPC methods called on the mock engine, in order.
+    pub fn calls(&self) -> Vec<String> {
+        self.calls.lock().unwrap().iter().map(|(method, _)| method.clone()).collect()
+    }
+
+    /// Returns the parameters of each call to the given JSON-RPC method, in order.
+    pub fn params(&self, method: &str) -> Vec<Value> {
+        self.calls
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(m, _)| m == method)
+            .map(|(_, params)| params.clone())
+            .collect()
+    }
+
+    /// Returns the highest number of requests the mock engine served at once.
+    pub fn peak_concurrency(&self) -> usize {
+        self.concurrency.peak.load(Ordering::SeqCst)
+    }
+
+    /// Returns the URL of the mock engine.
+    pub fn url(&self) -> Url {
+        Url::parse(&format!("http://{}", self.addr)).expect("valid mock engine url")
+    }
+
+    /// Returns an [EngineClient] connected to the mock engine, for both the engine and L2 RPC.
+    pub fn client(&self, cfg: Arc<RollupConfig>) -> Arc<EngineClient> {
+        Arc::new(EngineClient::new_http(self.url(), self.url(), cfg, JwtSecret::random()))
+    }
+
+    fn default_responses() -> HashMap<String, Value> {
+        let valid_status = json!({ "status": "VALID", "latestValidHash": null });
+        let fcu = json!({ "payloadStatus": valid_status, "payloadId": null });
+
+        let mut responses = HashMap::new();
+        responses.insert("eth_syncing".to_string(), Value::Bool(false));
+        for v in 1..=3 {
+            responses.insert(format!("engine_forkchoiceUpdatedV{v}"), fcu.clone());
+        }
+        for v in 1..=4 {
+            responses.insert(format!("engine_newPayloadV{v}"), valid_status.clone());
+        }
+        responses
+    }
+
+    async fn serve(
+        mut stream: TcpStream,
+        calls: Arc<Mutex<Vec<(String, Value)>>>,
+        responses: Arc<Mutex<HashMap<String, Value>>>,
+        queued: Arc<Mutex<HashMap<String, VecDeque<Value>>>>,
+        delays: Arc<Mutex<HashMap<String, Duration>>>,
+        concurrency: Arc<Concurrency>,
+    ) {
+        let mut buf = Vec::new();
+        loop {
+            // Read until the end of the request headers.
+            let header_end = loop {
+                if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                    break pos + 4;
+                }
+                let mut chunk = [0u8; 4096];
+                match stream.read(&mut chunk).await {
+                    Ok(0) | Err(_) => return,
+                    Ok(n) => buf.extend_from_slice(&chunk[..n]),
+                }
+            };
+
+            let headers = String::from_utf8_lossy(&buf[..header_end]).to_lowercase();
+            let content_length = headers
+                .lines()
+                .find_map(|l| l.strip_prefix("content-length:"))
+                .and_then(|l| l.trim().parse::<usize>().ok())
+                .unwrap_or_default();
+
+            // Read the rest of the request body.
+            while buf.len() < header_end + content_length {
+                let mut chunk = [0u8; 4096];
+                match stream.read(&mut chunk).await {
+                    Ok(0) | Err(_) => return,
+                    Ok(n) => buf.extend_from_slice(&chunk[..n]),
+                }
+            }
+            let body: Vec<u8> = buf.drain(..header_end + content_length).skip(header_end).collect();
+
+            let active = concurrency.active.fetch_add(1, Ordering::SeqCst) + 1;
+            concurrency.peak.fetch_max(active, Ordering::SeqCst);
+
+            let request: Value = serde_json::from_slice(&body).unwrap_or_default();
+            let method = request["method"].as_str().unwrap_or_default().to_string();
+            let id = request["id"].clone();
+            calls.lock().unwrap().push((method.clone(), request["params"].clone()));
+
+            let result = queued
+                .lock()
+                .unwrap()
+                .get_mut(&method)
+                .and_then(VecDeque::pop_front)
+                .or_else(|| responses.lock().unwrap().get(&method).cloned());
+            let response = result.map_or_else(
+                || json!({ "jsonrpc": "2.0", "id": id, "error": { "code": -32601, "message": "method not found" } }),
+                |result| json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            );
+            let delay = delays.lock().unwrap().get(&method).copied();
+            if let Some(delay) = delay {
+                tokio::time::sleep(delay).await;
+            }
+
+            let response = response.to_string();
+            let raw = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+                response.len(),
+                response
+            );
+            let written = stream.write_all(raw.as_bytes()).await;
+            concurrency.active.fetch_sub(1, Ordering::SeqCst);
+            if written.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+impl Drop for MockEngine {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+/// A [RpcLatencySink] that records every latency it is given.
+#[derive(Debug, Default)]
+pub struct RecordingSink(Mutex<Vec<(&'static str, Duration)>>);
+
+impl RecordingSink {
+    /// Returns the recorded method names and latencies, in order.
+    pub fn recorded(&self) -> Vec<(&'static str, Duration)> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+impl RpcLatencySink for RecordingSink {
+    fn record(&self, method: &'static str, latency: Duration) {
+        self.0.lock().unwrap().push((method, latency));
+    }
+}
+
+/// Returns an [EngineState] with default heads, in consensus layer sync.
+pub fn test_engine_state() -> EngineState {
+    EngineState {
+        unsafe_head: L2BlockInfo::default(),
+        cross_unsafe_head: L2BlockInfo::default(),
+        pending_safe_head: L2BlockInfo::default(),
+        local_safe_head: L2BlockInfo::default(),
+        safe_head: L2BlockInfo::default(),
+        finalized_head: L2BlockInfo::default(),
+        backup_unsafe_head: None,
+        sync_status: SyncStatus::ConsensusLayer,
+        forkchoice_update_needed: false,
+        need_fcu_call_backup_unsafe_reorg: false,
+    }
+}
+
+/// Returns a consensus layer [SyncConfig].
+pub const fn test_sync_config() -> SyncConfig {
+    SyncConfig {
+        sync_mode: SyncMode::ConsensusLayer,
+        skip_sync_start_check: false,
+        supports_post_finalization_elsync: false,
+    }
+}
+
+/// Returns a V1 genesis payload envelope along with a [RollupConfig] whose L2 genesis matches it,
+/// so that the payload can be converted into an [L2BlockInfo].
+pub fn test_genesis_envelope() -> (OpNetworkPayloadEnvelope, RollupConfig) {
+    test_genesis_envelope_at(0)
+}
+
+/// Returns a V1 payload envelope for the given block number, along with a [RollupConfig] whose L2
+/// genesis is that block, as [test_genesis_envelope] does.
+pub fn test_genesis_envelope_at(number: u64) -> (OpNetworkPayloadEnvelope, RollupConfig) {
+    let mut payload = ExecutionPayloadV1 {
+        parent_hash: B256::ZERO,
+        fee_recipient: Address::ZERO,
+        state_root: B256::ZERO,
+        receipts_root: B256::ZERO,
+        logs_bloom: Bloom::default(),
+        prev_randao: B256::ZERO,
+        block_number: number,
+        gas_limit: 0,
+        gas_used: 0,
+        timestamp: 0,
+        extra_data: Bytes::default(),
+        base_fee_per_gas: U256::from(1),
+        block_hash: B256::ZERO,
+        transactions: vec![],
+    };
+    let block: OpBlock = OpExecutionPayload::V1(payload.clone()).try_into_block().unwrap();
+    payload.block_hash = block.header.hash_slow();
+
+    let mut cfg = RollupConfig::default();
+    cfg.genesis.l2.number = number;
+    cfg.genesis.l2.hash = payload.block_hash;
+
+    let envelope = OpNetworkPayloadEnvelope {
+        payload: OpExecutionPayload::V1(payload),
+        signature: alloy_primitives::PrimitiveSignature::test_signature(),
+        payload_hash: PayloadHash(B256::ZERO),
+        parent_beacon_block_root: None,
+    };
+    (envelope, cfg)
+}
+
+/// Returns the JSON-RPC representation of the block of [test_genesis_envelope], without
+/// transactions, along with its hash.
+pub fn test_rpc_block() -> (Value, B256) {
+    test_rpc_block_at(0)
+}
+
+/// Returns the JSON-RPC representation of the block of [test_genesis_envelope_at] the given
+/// number, without transactions, along with its hash.
+pub fn test_rpc_block_at(number: u64) -> (Value, B256) {
+    let (envelope, _) = test_genesis_envelope_at(number);
+    let block: OpBlock = envelope.payload.try_into_block().unwrap();
+    let header = alloy_rpc_types_eth::Header {
+        hash: block.header.hash_slow(),
+        inner: block.header,
+        total_difficulty: None,
+        size: None,
+    };
+
+    let mut json = serde_json::to_value(&header).unwrap();
+    json["uncles"] = json!([]);
+    json["transactions"] = json!([]);
+    (json, header.hash)
+}
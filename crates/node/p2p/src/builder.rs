@@ -2,21 +2,28 @@
 
 use alloy_primitives::Address;
 use discv5::Config;
+use kona_genesis::RollupConfig;
 use std::{
     net::{IpAddr, SocketAddr},
+    sync::Arc,
     time::Duration,
 };
-use tokio::sync::watch::channel;
+use tokio::sync::watch::{self, channel};
 
 use libp2p::{
-    Multiaddr, SwarmBuilder, gossipsub::Config as GossipConfig, multiaddr::Protocol,
-    noise::Config as NoiseConfig, tcp::Config as TcpConfig, yamux::Config as YamuxConfig,
+    Multiaddr, SwarmBuilder,
+    gossipsub::{Config as GossipConfig, MessageAuthenticity},
+    multiaddr::Protocol,
+    noise::Config as NoiseConfig,
+    tcp::Config as TcpConfig,
+    yamux::Config as YamuxConfig,
 };
 use libp2p_identity::Keypair;
 
 use crate::{
     Behaviour, BehaviourError, BlockHandler, Discv5Builder, Discv5BuilderError, GossipDriver,
-    NetworkDriver,
+    NetworkDriver, PeerWatermarks, PublishOverflow, PublishQueue, SubnetDiversityConfig,
+    SyncBlockPolicy,
 };
 
 /// An error from the [NetworkDriverBuilder].
@@ -37,6 +44,17 @@ pub enum NetworkDriverBuilderError {
     /// A config builder error.
     #[error("config builder error")]
     ConfigBuilderError,
+    /// The gossip config's maximum transmit size is smaller than the expected maximum block size,
+    /// so that valid blocks would be rejected.
+    #[error(
+        "gossip max transmit size {max_transmit_size} is smaller than the expected maximum block size {expected}"
+    )]
+    MaxTransmitSizeTooSmall {
+        /// The maximum transmit size of the gossip config.
+        max_transmit_size: usize,
+        /// The expected maximum block size.
+        expected: usize,
+    },
     /// A TCP error.
     #[error("TCP error")]
     TcpError,
@@ -63,10 +81,15 @@ pub struct NetworkDriverBuilder {
     pub unsafe_block_signer: Option<Address>,
     /// The socket address that the gossip service is listening on.
     pub gossip_addr: Option<SocketAddr>,
+    /// Additional addresses that the gossip service is listening on.
+    pub listen_addrs: Vec<Multiaddr>,
     /// The listen config that the discovery service is listening on.
     pub discovery_addr: Option<SocketAddr>,
     /// The [GossipConfig] constructs the config for `gossipsub`.
     pub gossip_config: Option<GossipConfig>,
+    /// How published gossip messages are authored. Defaults to
+    /// [MessageAuthenticity::Anonymous].
+    pub message_authenticity: Option<MessageAuthenticity>,
     /// The interval to discovery random nodes.
     pub interval: Option<Duration>,
     /// The [Config] constructs the config for `discv5`.
@@ -81,6 +104,37 @@ pub struct NetworkDriverBuilder {
     pub yamux_config: Option<YamuxConfig>,
     /// The idle connection timeout.
     pub timeout: Option<Duration>,
+    /// The gossip topic subscription confirmation timeout.
+    pub subscription_timeout: Option<Duration>,
+    /// The capacity of the broadcast channel for received blocks, if enabled.
+    pub block_broadcast_capacity: Option<usize>,
+    /// The policy applied to blocks received while the engine is syncing, along with a receiver
+    /// of whether it is syncing.
+    pub sync_policy: Option<(SyncBlockPolicy, watch::Receiver<bool>)>,
+    /// The maximum number of messages waiting in the outbound publish queue.
+    pub publish_queue_depth: Option<usize>,
+    /// The policy applied when publishing to a full outbound publish queue.
+    pub publish_overflow: Option<PublishOverflow>,
+    /// The subnet diversity thresholds, if subnet diversity accounting is enabled.
+    pub subnet_diversity: Option<SubnetDiversityConfig>,
+    /// The maximum number of peers per IP subnet, if limited.
+    pub max_peers_per_subnet: Option<usize>,
+    /// The interval at which keepalive pings are sent to connected peers.
+    pub keepalive_interval: Option<Duration>,
+    /// The gossip message validation timeout.
+    pub validation_timeout: Option<Duration>,
+    /// The application score penalty applied for each duplicate block, if enabled.
+    pub duplicate_penalty: Option<f64>,
+    /// The patterns of the topics that may be subscribed to, if restricted.
+    pub allowed_topics: Option<Vec<String>>,
+    /// The rollup config whose hardfork schedule gates the block topics that are subscribed to,
+    /// if enabled.
+    pub fork_schedule: Option<Arc<RollupConfig>>,
+    /// The size of the largest legitimate block message, if the gossip config's maximum transmit
+    /// size should be checked against it.
+    pub expected_max_block_size: Option<usize>,
+    /// The peer-count watermarks that pace discovery.
+    pub peer_watermarks: Option<PeerWatermarks>,
 }
 
 impl NetworkDriverBuilder {
@@ -107,18 +161,126 @@ impl NetworkDriverBuilder {
         self
     }
 
+    /// Specifies the low and high watermarks of the connected peer count. Below the low
+    /// watermark, discovery bursts to find more peers, and at or above the high watermark, it
+    /// slows down. See [PeerWatermarks].
+    pub fn with_peer_watermarks(&mut self, low: usize, high: usize) -> &mut Self {
+        self.peer_watermarks = Some(PeerWatermarks::new(low, high));
+        self
+    }
+
     /// Specifies the socket address that the gossip service is listening on.
     pub fn with_gossip_addr(&mut self, socket: SocketAddr) -> &mut Self {
         self.gossip_addr = Some(socket);
         self
     }
 
+    /// Specifies additional addresses that the gossip service is listening on, such as an IPv6
+    /// address alongside an IPv4 gossip address.
+    ///
+    /// If no gossip address is set, the first of these is used as the gossip address. Addresses
+    /// that fail to bind are reported when the [NetworkDriver] starts, which fails only if none
+    /// of them can be bound.
+    pub fn with_listen_addrs(&mut self, addrs: Vec<Multiaddr>) -> &mut Self {
+        self.listen_addrs = addrs;
+        self
+    }
+
     /// Specifies the address that the discovery service is listening on.
     pub fn with_discovery_addr(&mut self, addr: SocketAddr) -> &mut Self {
         self.discovery_addr = Some(addr);
         self
     }
 
+    /// Specifies the timeout within which gossip topic subscriptions must be confirmed on startup.
+    pub fn with_subscription_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.subscription_timeout = Some(timeout);
+        self
+    }
+
+    /// Specifies the duration within which a received gossip message must be validated before it
+    /// is ignored.
+    pub fn with_validation_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.validation_timeout = Some(timeout);
+        self
+    }
+
+    /// Enables penalizing peers that send duplicate blocks, lowering their application score by
+    /// `penalty` for each duplicate.
+    pub fn with_duplicate_penalty(&mut self, penalty: f64) -> &mut Self {
+        self.duplicate_penalty = Some(penalty);
+        self
+    }
+
+    /// Subscribes only to the block topics whose version is activated by the hardfork schedule of
+    /// the given rollup config. See [BlockHandler::with_fork_schedule].
+    pub fn with_fork_schedule(&mut self, config: Arc<RollupConfig>) -> &mut Self {
+        self.fork_schedule = Some(config);
+        self
+    }
+
+    /// Sets how published gossip messages are authored. See [Behaviour::new].
+    ///
+    /// Signing is refused if the [GossipConfig] validates messages with
+    /// [libp2p::gossipsub::ValidationMode::Anonymous].
+    pub fn with_message_authenticity(&mut self, authenticity: MessageAuthenticity) -> &mut Self {
+        self.message_authenticity = Some(authenticity);
+        self
+    }
+
+    /// Restricts the gossip topics that may be subscribed to, to those matching one of the given
+    /// patterns. See [Behaviour::set_allowed_topics].
+    pub fn with_allowed_topics(&mut self, patterns: Vec<String>) -> &mut Self {
+        self.allowed_topics = Some(patterns);
+        self
+    }
+
+    /// Enables publishing received blocks to a broadcast channel with the given capacity.
+    ///
+    /// Subscribers are obtained through [BlockHandler::subscribe].
+    pub fn with_block_broadcast(&mut self, capacity: usize) -> &mut Self {
+        self.block_broadcast_capacity = Some(capacity);
+        self
+    }
+
+    /// Applies the given [SyncBlockPolicy] to blocks received while the given [watch::Receiver]
+    /// reports that the engine is syncing.
+    pub fn with_sync_policy(
+        &mut self,
+        policy: SyncBlockPolicy,
+        syncing: watch::Receiver<bool>,
+    ) -> &mut Self {
+        self.sync_policy = Some((policy, syncing));
+        self
+    }
+
+    /// Specifies the depth of the outbound publish queue, and the [PublishOverflow] policy
+    /// applied when it is full.
+    pub fn with_publish_queue(&mut self, depth: usize, overflow: PublishOverflow) -> &mut Self {
+        self.publish_queue_depth = Some(depth);
+        self.publish_overflow = Some(overflow);
+        self
+    }
+
+    /// Enables subnet diversity accounting of the gossip peers with the given thresholds.
+    pub fn with_subnet_diversity(&mut self, config: SubnetDiversityConfig) -> &mut Self {
+        self.subnet_diversity = Some(config);
+        self
+    }
+
+    /// Limits the number of peers that may connect from each /24 IPv4 or /64 IPv6 subnet.
+    pub fn with_max_peers_per_subnet(&mut self, max: usize) -> &mut Self {
+        self.max_peers_per_subnet = Some(max);
+        self
+    }
+
+    /// Specifies the interval at which keepalive pings are sent to connected peers, to keep idle
+    /// connections open behind NATs. Defaults to the `ping` protocol's interval of 15 seconds.
+    pub fn with_keepalive_interval(&mut self, interval: Duration) -> &mut Self {
+        self.keepalive_interval = Some(interval);
+        self
+    }
+
     /// Specifies the keypair for the node.
     pub fn with_keypair(&mut self, keypair: Keypair) -> &mut Self {
         self.keypair = Some(keypair);
@@ -181,6 +343,16 @@ impl NetworkDriverBuilder {
         self
     }
 
+    /// Specifies the size in bytes of the largest legitimate block message.
+    ///
+    /// If set, [NetworkDriverBuilder::build] checks that the maximum transmit size of the
+    /// [GossipConfig] is at least this large, since larger messages are silently dropped by
+    /// `gossipsub`.
+    pub fn with_expected_max_block_size(&mut self, size: usize) -> &mut Self {
+        self.expected_max_block_size = Some(size);
+        self
+    }
+
     /// Specifies the [Config] for the `discv5` configuration.
     ///
     /// If not set, the [NetworkDriverBuilder] will fall back to use the [discv5::ListenConfig]
@@ -222,7 +394,10 @@ impl NetworkDriverBuilder {
     /// Returns an error if any of the following required fields are not set:
     /// - [NetworkDriverBuilder::unsafe_block_signer]
     /// - [NetworkDriverBuilder::chain_id]
-    /// - [NetworkDriverBuilder::gossip_addr]
+    /// - [NetworkDriverBuilder::gossip_addr], unless [NetworkDriverBuilder::listen_addrs] is set
+    ///
+    /// Also returns an error if [NetworkDriverBuilder::expected_max_block_size] is set and exceeds
+    /// the maximum transmit size of the [GossipConfig].
     ///
     /// If explicitly set, the following fields are used for discovery address, otherwise the gossip
     /// address is used:
@@ -268,21 +443,48 @@ impl NetworkDriverBuilder {
             None => crate::default_config()
                 .map_err(|_| NetworkDriverBuilderError::ConfigBuilderError)?,
         };
+        if let Some(expected) = self.expected_max_block_size {
+            let max_transmit_size = config.max_transmit_size();
+            if max_transmit_size < expected {
+                return Err(NetworkDriverBuilderError::MaxTransmitSizeTooSmall {
+                    max_transmit_size,
+                    expected,
+                });
+            }
+        }
         let unsafe_block_signer =
             self.unsafe_block_signer.ok_or(NetworkDriverBuilderError::UnsafeBlockSignerNotSet)?;
         let chain_id = self.chain_id.ok_or(NetworkDriverBuilderError::ChainIdNotSet)?;
 
         // Create the block handler.
         let (unsafe_block_signer_sender, unsafe_block_signer_recv) = channel(unsafe_block_signer);
-        let (handler, unsafe_block_recv) = BlockHandler::new(chain_id, unsafe_block_signer_recv);
+        let (mut handler, unsafe_block_recv) =
+            BlockHandler::new(chain_id, unsafe_block_signer_recv);
+        if let Some(capacity) = self.block_broadcast_capacity {
+            handler = handler.with_broadcast(capacity);
+        }
+        if let Some((policy, syncing)) = self.sync_policy.take() {
+            handler = handler.with_sync_policy(policy, syncing);
+        }
+        if let Some(config) = self.fork_schedule.take() {
+            handler = handler.with_fork_schedule(config);
+        }
 
         // Construct the gossipsub behaviour.
-        let behaviour = Behaviour::new(config, &[Box::new(handler.clone())])?;
+        let keypair = self.keypair.take().unwrap_or(Keypair::generate_secp256k1());
+        let authenticity =
+            self.message_authenticity.take().unwrap_or(MessageAuthenticity::Anonymous);
+        let mut behaviour = Behaviour::new(config, &[Box::new(handler.clone())], authenticity)?;
+        behaviour.set_max_peers_per_subnet(self.max_peers_per_subnet.take());
+        behaviour.set_allowed_topics(self.allowed_topics.take())?;
+        if let Some(interval) = self.keepalive_interval.take() {
+            behaviour.set_keepalive_interval(interval);
+        }
+        behaviour.enable_identify(keypair.public());
 
         // Build the swarm.
         let timeout = self.timeout.take().unwrap_or(Duration::from_secs(60));
         let noise_config = self.noise_config.take();
-        let keypair = self.keypair.take().unwrap_or(Keypair::generate_secp256k1());
         let swarm = SwarmBuilder::with_existing_identity(keypair)
             .with_tokio()
             .with_tcp(
@@ -299,15 +501,31 @@ impl NetworkDriverBuilder {
             .with_swarm_config(|c| c.with_idle_connection_timeout(timeout))
             .build();
 
-        let gossip_addr =
-            self.gossip_addr.take().ok_or(NetworkDriverBuilderError::GossipAddrNotSet)?;
-        let mut multiaddr = Multiaddr::empty();
-        match gossip_addr.ip() {
-            IpAddr::V4(ip) => multiaddr.push(Protocol::Ip4(ip)),
-            IpAddr::V6(ip) => multiaddr.push(Protocol::Ip6(ip)),
+        let mut listen_addrs = std::mem::take(&mut self.listen_addrs);
+        let multiaddr = match self.gossip_addr.take() {
+            Some(gossip_addr) => {
+                let mut multiaddr = Multiaddr::empty();
+                match gossip_addr.ip() {
+                    IpAddr::V4(ip) => multiaddr.push(Protocol::Ip4(ip)),
+                    IpAddr::V6(ip) => multiaddr.push(Protocol::Ip6(ip)),
+                }
+                multiaddr.push(Protocol::Tcp(gossip_addr.port()));
+                multiaddr
+            }
+            None if !listen_addrs.is_empty() => listen_addrs.remove(0),
+            None => return Err(NetworkDriverBuilderError::GossipAddrNotSet),
+        };
+        let mut gossip =
+            GossipDriver::new(swarm, multiaddr, handler.clone()).with_listen_addrs(listen_addrs);
+        if let Some(timeout) = self.validation_timeout.take() {
+            gossip = gossip.with_validation_timeout(timeout);
+        }
+        if let Some(config) = self.subnet_diversity.take() {
+            gossip = gossip.with_subnet_diversity(config);
+        }
+        if let Some(penalty) = self.duplicate_penalty.take() {
+            gossip = gossip.with_duplicate_penalty(penalty);
         }
-        multiaddr.push(Protocol::Tcp(gossip_addr.port()));
-        let gossip = GossipDriver::new(swarm, multiaddr, handler.clone());
 
         // Build the discovery service
         let disc_addr =
@@ -322,11 +540,24 @@ impl NetworkDriverBuilder {
         let mut discovery = discovery_builder.build()?;
         discovery.interval = self.interval.unwrap_or(Duration::from_secs(10));
 
+        // Create the outbound publish queue.
+        let (publish_sender, publish_queue) = PublishQueue::new(
+            self.publish_queue_depth.unwrap_or(crate::PUBLISH_QUEUE_DEPTH),
+            self.publish_overflow.unwrap_or_default(),
+        );
+
         Ok(NetworkDriver {
             discovery,
             gossip,
+            publish_queue,
+            publish_sender,
+            subscription_timeout: self
+                .subscription_timeout
+                .take()
+                .unwrap_or(*crate::SUBSCRIPTION_TIMEOUT),
             unsafe_block_recv: Some(unsafe_block_recv),
             unsafe_block_signer_sender: Some(unsafe_block_signer_sender),
+            watermarks: self.peer_watermarks.take().unwrap_or_default(),
         })
     }
 }
@@ -366,6 +597,77 @@ mod tests {
         assert_eq!(err, NetworkDriverBuilderError::GossipAddrNotSet);
     }
 
+    #[test]
+    fn test_build_max_transmit_size_too_small() {
+        let cfg = crate::default_config_builder().max_transmit_size(1 << 16).build().unwrap();
+        let Err(err) = NetworkDriverBuilder::new()
+            .with_gossip_config(cfg)
+            .with_expected_max_block_size(crate::MAX_GOSSIP_SIZE)
+            .build()
+        else {
+            panic!("expected error when the max transmit size is below the expected block size");
+        };
+        assert_eq!(
+            err,
+            NetworkDriverBuilderError::MaxTransmitSizeTooSmall {
+                max_transmit_size: 1 << 16,
+                expected: crate::MAX_GOSSIP_SIZE,
+            }
+        );
+    }
+
+    #[test]
+    fn test_build_max_transmit_size_adequate() {
+        let cfg = crate::default_config_builder()
+            .max_transmit_size(crate::MAX_GOSSIP_SIZE)
+            .build()
+            .unwrap();
+        let result = NetworkDriverBuilder::new()
+            .with_unsafe_block_signer(Address::random())
+            .with_chain_id(10)
+            .with_gossip_addr(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), 9099))
+            .with_discovery_addr(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), 9096))
+            .with_gossip_config(cfg)
+            .with_expected_max_block_size(crate::MAX_GOSSIP_SIZE)
+            .build();
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_build_signed_message_authenticity() {
+        let keypair = Keypair::generate_secp256k1();
+        let builder = || {
+            let mut builder = NetworkDriverBuilder::new();
+            builder
+                .with_unsafe_block_signer(Address::random())
+                .with_chain_id(10)
+                .with_gossip_addr(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), 9099))
+                .with_discovery_addr(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), 9096))
+                .with_message_authenticity(MessageAuthenticity::Signed(keypair.clone()));
+            builder
+        };
+
+        // Signing is rejected if incoming messages must be anonymous.
+        let cfg = crate::default_config_builder()
+            .validation_mode(libp2p::gossipsub::ValidationMode::Anonymous)
+            .build()
+            .unwrap();
+        let err = builder().with_gossip_config(cfg).build().err();
+        assert_eq!(
+            err,
+            Some(NetworkDriverBuilderError::BehaviourError(
+                BehaviourError::GossipsubCreationFailed
+            ))
+        );
+
+        let cfg = crate::default_config_builder()
+            .validation_mode(libp2p::gossipsub::ValidationMode::Strict)
+            .build()
+            .unwrap();
+        let driver = builder().with_gossip_config(cfg).build().unwrap();
+        assert_eq!(driver.gossip.swarm.behaviour().gossipsub.topics().count(), 4);
+    }
+
     #[test]
     fn test_build_custom_gossip_config() {
         let id = 10;
@@ -454,6 +756,24 @@ mod tests {
         assert_eq!(driver.discovery.disc.local_enr().tcp4().unwrap(), 9098);
     }
 
+    #[test]
+    fn test_build_network_driver_with_listen_addrs() {
+        let v4: Multiaddr = "/ip4/0.0.0.0/tcp/9099".parse().unwrap();
+        let v6: Multiaddr = "/ip6/::/tcp/9099".parse().unwrap();
+        let disc = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), 9098);
+        let driver = NetworkDriverBuilder::new()
+            .with_unsafe_block_signer(Address::random())
+            .with_chain_id(10)
+            .with_listen_addrs(vec![v4.clone(), v6.clone()])
+            .with_discovery_addr(disc)
+            .build()
+            .unwrap();
+
+        // Without a gossip address, the first listen address is used in its place.
+        assert_eq!(driver.gossip.addr, v4);
+        assert_eq!(driver.gossip.listen_addrs, vec![v6]);
+    }
+
     #[test]
     fn test_build_network_driver_with_discovery_address() {
         let id = 10;
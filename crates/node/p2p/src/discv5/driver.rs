@@ -8,7 +8,8 @@ use tokio::{
 use discv5::{Discv5, Enr, Event, enr::NodeId};
 
 use crate::{
-    BootNode, BootNodes, Discv5Builder, Discv5Handler, HandlerRequest, HandlerResponse, OpStackEnr,
+    BootNode, BootNodes, DiscoveryPace, Discv5Builder, Discv5Handler, HandlerRequest,
+    HandlerResponse, OpStackEnr,
 };
 
 /// The [`Discv5Driver`] drives the discovery service.
@@ -57,6 +58,8 @@ pub struct Discv5Driver {
     ///
     /// The interval to discovery random nodes.
     pub interval: Duration,
+    /// The pace at which random nodes are looked up, relative to the [`Discv5Driver::interval`].
+    pub pace: DiscoveryPace,
 }
 
 impl Discv5Driver {
@@ -67,7 +70,7 @@ impl Discv5Driver {
 
     /// Instantiates a new [`Discv5Driver`].
     pub fn new(disc: Discv5, chain_id: u64) -> Self {
-        Self { disc, chain_id, interval: Duration::from_secs(10) }
+        Self { disc, chain_id, interval: Duration::from_secs(10), pace: DiscoveryPace::default() }
     }
 
     /// Starts the inner [`Discv5`] service.
@@ -129,7 +132,7 @@ impl Discv5Driver {
             info!(target: "p2p::discv5::driver", "Bootstrapped `Discv5` bootnodes");
 
             // Interval to find new nodes.
-            let mut interval = tokio::time::interval(self.interval);
+            let mut interval = tokio::time::interval(self.pace.interval(self.interval));
 
             // Step 3: Run the core driver loop.
             loop {
@@ -159,6 +162,13 @@ impl Discv5Driver {
                                     let enrs = self.disc.table_entries_enr();
                                     let _ = res_sender.send(HandlerResponse::TableEnrs(enrs)).await;
                                 }
+                                HandlerRequest::SetPace(pace) if pace != self.pace => {
+                                    debug!(target: "p2p::discv5::driver", "Discovery pace changed to {:?}", pace);
+                                    self.pace = pace;
+                                    // A new interval ticks immediately, so a burst starts at once.
+                                    interval = tokio::time::interval(pace.interval(self.interval));
+                                }
+                                HandlerRequest::SetPace(_) => {}
                             }
                             None => {
                                 trace!(target: "p2p::discv5::driver", "Receiver `None` peer enr");
@@ -1,6 +1,8 @@
 //! Handler to the [`discv5::Discv5`] service spawned in a thread.
 
 use discv5::{Enr, Event, metrics::Metrics};
+
+use crate::DiscoveryPace;
 use std::string::String;
 use tokio::sync::mpsc::{Receiver, Sender};
 
@@ -21,6 +23,8 @@ pub enum HandlerRequest {
     LocalEnr,
     /// Requests the table ENRs.
     TableEnrs,
+    /// Sets the [`DiscoveryPace`] at which random nodes are looked up.
+    SetPace(DiscoveryPace),
 }
 
 /// A response from the spawned [`discv5::Discv5`] service thread to the [`Discv5Handler`].
@@ -95,6 +99,11 @@ impl Discv5Handler {
         }
     }
 
+    /// Sets the [`DiscoveryPace`] at which the discovery service looks up random nodes.
+    pub async fn set_pace(&mut self, pace: DiscoveryPace) {
+        let _ = self.sender.send(HandlerRequest::SetPace(pace)).await;
+    }
+
     /// Returns the number of connected peers.
     pub async fn peers(&mut self) -> Option<usize> {
         let _ = self.sender.send(HandlerRequest::PeerCount).await;
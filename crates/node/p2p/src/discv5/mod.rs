@@ -6,5 +6,10 @@ pub use builder::{Discv5Builder, Discv5BuilderError};
 mod driver;
 pub use driver::Discv5Driver;
 
+mod watermarks;
+pub use watermarks::{
+    DEFAULT_HIGH_PEER_WATERMARK, DEFAULT_LOW_PEER_WATERMARK, DiscoveryPace, PeerWatermarks,
+};
+
 mod handler;
 pub use handler::{Discv5Handler, HandlerRequest, HandlerResponse};
@@ -0,0 +1,101 @@
+//! Peer-count watermarks that pace peer discovery.
+
+use std::time::Duration;
+
+/// The default number of connected peers below which discovery bursts.
+pub const DEFAULT_LOW_PEER_WATERMARK: usize = 20;
+
+/// The default number of connected peers at or above which discovery slows down.
+pub const DEFAULT_HIGH_PEER_WATERMARK: usize = 30;
+
+/// The factor by which the discovery interval is shortened while bursting.
+const BURST_FACTOR: u32 = 5;
+
+/// The factor by which the discovery interval is lengthened while slowed down.
+const SLOW_FACTOR: u32 = 6;
+
+/// The pace at which the [crate::Discv5Driver] looks up random nodes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DiscoveryPace {
+    /// The peer count is below the low watermark, and nodes are looked up immediately and then
+    /// more often than the discovery interval.
+    Burst,
+    /// Nodes are looked up at the discovery interval.
+    #[default]
+    Normal,
+    /// The peer count is at or above the high watermark, and nodes are looked up less often than
+    /// the discovery interval.
+    Slow,
+}
+
+impl DiscoveryPace {
+    /// Returns the interval between node lookups at this pace, given the base discovery
+    /// interval.
+    pub fn interval(&self, base: Duration) -> Duration {
+        match self {
+            Self::Burst => base / BURST_FACTOR,
+            Self::Normal => base,
+            Self::Slow => base * SLOW_FACTOR,
+        }
+    }
+}
+
+/// Low and high watermarks of the connected peer count, which keep it in a healthy band by
+/// driving the [DiscoveryPace].
+///
+/// Below the low watermark, discovery bursts to find more peers. At or above the high watermark,
+/// discovery slows down. In between, nodes are looked up at the discovery interval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeerWatermarks {
+    /// The number of connected peers below which discovery bursts.
+    pub low: usize,
+    /// The number of connected peers at or above which discovery slows down.
+    pub high: usize,
+}
+
+impl Default for PeerWatermarks {
+    fn default() -> Self {
+        Self::new(DEFAULT_LOW_PEER_WATERMARK, DEFAULT_HIGH_PEER_WATERMARK)
+    }
+}
+
+impl PeerWatermarks {
+    /// Creates new [PeerWatermarks]. A high watermark below the low watermark is raised to it.
+    pub const fn new(low: usize, high: usize) -> Self {
+        let high = if high < low { low } else { high };
+        Self { low, high }
+    }
+
+    /// Returns the [DiscoveryPace] for the given number of connected peers.
+    pub const fn pace(&self, peers: usize) -> DiscoveryPace {
+        if peers < self.low {
+            DiscoveryPace::Burst
+        } else if peers >= self.high {
+            DiscoveryPace::Slow
+        } else {
+            DiscoveryPace::Normal
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discovery_bursts_below_low_watermark() {
+        let watermarks = PeerWatermarks::new(2, 4);
+        assert_eq!(watermarks.pace(0), DiscoveryPace::Burst);
+        assert_eq!(watermarks.pace(1), DiscoveryPace::Burst);
+        assert_eq!(watermarks.pace(2), DiscoveryPace::Normal);
+        assert_eq!(watermarks.pace(4), DiscoveryPace::Slow);
+
+        let base = Duration::from_secs(10);
+        assert!(DiscoveryPace::Burst.interval(base) < base);
+        assert_eq!(DiscoveryPace::Normal.interval(base), base);
+        assert!(DiscoveryPace::Slow.interval(base) > base);
+
+        // The high watermark is never below the low watermark.
+        assert_eq!(PeerWatermarks::new(5, 1).pace(5), DiscoveryPace::Slow);
+    }
+}
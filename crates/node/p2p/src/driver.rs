@@ -1,13 +1,26 @@
 //! Driver for network services.
 
-use std::sync::mpsc::Receiver;
+use std::{sync::mpsc::Receiver, time::Duration};
 
 use alloy_primitives::Address;
-use libp2p::TransportError;
 use op_alloy_rpc_types_engine::OpNetworkPayloadEnvelope;
 use tokio::{select, sync::watch};
 
-use crate::{Discv5Driver, GossipDriver, NetworkDriverBuilder};
+use crate::{
+    BehaviourError, DiscoveryPace, Discv5Driver, DriverEvent, GOSSIP_HEARTBEAT, GossipDriver,
+    NetworkDriverBuilder, NoListenAddrBound, PeerWatermarks, PublishQueue, PublishSender,
+};
+
+/// An error from starting the [NetworkDriver].
+#[derive(Debug, thiserror::Error)]
+pub enum NetworkDriverError {
+    /// The gossip swarm failed to listen on any of its addresses.
+    #[error(transparent)]
+    Listen(#[from] NoListenAddrBound),
+    /// The gossip behaviour failed to start.
+    #[error(transparent)]
+    Behaviour(#[from] BehaviourError),
+}
 
 /// NetworkDriver
 ///
@@ -24,6 +37,14 @@ pub struct NetworkDriver {
     pub gossip: GossipDriver,
     /// The discovery service driver.
     pub discovery: Discv5Driver,
+    /// The timeout within which the gossip topic subscriptions must be confirmed on startup.
+    pub subscription_timeout: Duration,
+    /// The queue of messages waiting to be published by the gossip driver.
+    pub publish_queue: PublishQueue,
+    /// The producer handle of the [NetworkDriver::publish_queue].
+    pub publish_sender: PublishSender,
+    /// The peer-count watermarks that pace discovery.
+    pub watermarks: PeerWatermarks,
 }
 
 impl NetworkDriver {
@@ -42,12 +63,30 @@ impl NetworkDriver {
         self.unsafe_block_signer_sender.take()
     }
 
+    /// Returns a [PublishSender] to enqueue messages for publishing to the gossip network.
+    ///
+    /// Messages are published in the order they are enqueued.
+    pub fn publisher(&self) -> PublishSender {
+        self.publish_sender.clone()
+    }
+
     /// Starts the Discv5 peer discovery & libp2p services
     /// and continually listens for new peers and messages to handle
-    pub fn start(mut self) -> Result<(), TransportError<std::io::Error>> {
+    ///
+    /// The pace of discovery follows the swarm's peer count, as checked against the
+    /// [NetworkDriver::watermarks] every five seconds.
+    ///
+    /// Fails with [BehaviourError::SubscriptionTimeout] if the gossip topic subscriptions are not
+    /// confirmed within the [NetworkDriver::subscription_timeout], or with [NoListenAddrBound] if
+    /// none of the gossip listen addresses could be bound.
+    pub async fn start(mut self) -> Result<(), NetworkDriverError> {
+        self.gossip.confirm_subscriptions(self.subscription_timeout).await?;
         let mut handler = self.discovery.start();
-        self.gossip.listen()?;
+        let listening = self.gossip.listen()?;
+        debug!(target: "p2p::driver", "Started listening: {:?}", listening);
         let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(5));
+        let mut heartbeat = tokio::time::interval(*GOSSIP_HEARTBEAT);
+        let mut pace = DiscoveryPace::default();
         tokio::spawn(async move {
             loop {
                 select! {
@@ -58,13 +97,37 @@ impl NetworkDriver {
                         };
                         self.gossip.dial(enr.clone());
                     },
-                    event = self.gossip.select_next_some() => {
-                        trace!(target: "p2p::driver", "Received event: {:?}", event);
-                        self.gossip.handle_event(event);
+                    Some(publish) = self.publish_queue.next() => {
+                        if let Err(e) = self.gossip.publish(publish) {
+                            warn!(target: "p2p::driver", "Failed to publish gossip message: {:?}", e);
+                        }
+                    },
+                    event = self.gossip.next_event() => {
+                        if let Some(DriverEvent::HighChurn { rate }) = event {
+                            warn!(target: "p2p::driver", "High peer churn: {} connects and disconnects in the last minute", rate);
+                        }
+                    },
+                    _ = heartbeat.tick() => {
+                        for event in self.gossip.check_topic_isolation() {
+                            if let DriverEvent::TopicIsolated { topic } = event {
+                                warn!(target: "p2p::driver", "Topic {} is isolated: its mesh has no peers", topic);
+                            }
+                        }
                     },
                     _ = interval.tick() => {
+                        if let Err(e) = self.gossip.sync_subscriptions() {
+                            warn!(target: "p2p::driver", "Failed to subscribe to newly active topics: {:?}", e);
+                        }
                         let swarm_peers = self.gossip.connected_peers();
                         info!(target: "p2p::driver", "Swarm peer count: {}", swarm_peers);
+                        let next_pace = self.watermarks.pace(swarm_peers);
+                        if next_pace != pace {
+                            info!(target: "p2p::driver", "Setting discovery pace to {:?} at {} peers", next_pace, swarm_peers);
+                            handler.set_pace(next_pace).await;
+                            pace = next_pace;
+                        }
+                        let gossip_metrics = self.gossip.metrics();
+                        debug!(target: "p2p::driver", "Gossip metrics: {:?}", gossip_metrics);
                         let metrics = handler.metrics().await;
                         debug!(target: "p2p::driver", "Discovery metrics: {:?}", metrics);
                         let peers = handler.peers().await;
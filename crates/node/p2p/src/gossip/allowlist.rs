@@ -0,0 +1,122 @@
+//! Topic allowlist for the gossip behaviour.
+
+use libp2p::{
+    Multiaddr, PeerId,
+    core::{Endpoint, transport::PortUse},
+    gossipsub::TopicHash,
+    swarm::{
+        ConnectionDenied, ConnectionId, FromSwarm, NetworkBehaviour, THandler, THandlerInEvent,
+        THandlerOutEvent, ToSwarm, dummy,
+    },
+};
+use std::{
+    convert::Infallible,
+    task::{Context, Poll},
+};
+
+/// Restricts the topics that may be subscribed to, to those matching a set of patterns, and caps
+/// the number of topics that may be subscribed to at once.
+///
+/// This is a [NetworkBehaviour] that does not handle any protocols, so that it can be composed
+/// into the [crate::Behaviour].
+#[derive(Debug, Clone)]
+pub struct TopicAllowlist {
+    /// The patterns of the topics that may be subscribed to, or `None` if any topic may be.
+    allowed: Option<Vec<String>>,
+    /// The maximum number of topics that may be subscribed to at once.
+    max_topics: usize,
+}
+
+impl TopicAllowlist {
+    /// Creates a new [TopicAllowlist] that allows up to `max_topics` topics of any name to be
+    /// subscribed to.
+    pub const fn new(max_topics: usize) -> Self {
+        Self { allowed: None, max_topics }
+    }
+
+    /// Returns the maximum number of topics that may be subscribed to at once.
+    pub const fn max_topics(&self) -> usize {
+        self.max_topics
+    }
+
+    /// Sets the patterns of the topics that may be subscribed to, or allows any topic if `None`.
+    ///
+    /// A `*` in a pattern matches any sequence of characters, so `/optimism/10/*/blocks` matches
+    /// the block topics of every version on chain 10.
+    pub fn set_allowed(&mut self, patterns: Option<Vec<String>>) {
+        self.allowed = patterns;
+    }
+
+    /// Returns `true` if the given topic matches one of the allowed patterns, or if any topic is
+    /// allowed.
+    pub fn is_allowed(&self, topic: &TopicHash) -> bool {
+        self.allowed.as_ref().is_none_or(|patterns| {
+            patterns.iter().any(|pattern| matches_pattern(pattern, topic.as_str()))
+        })
+    }
+}
+
+/// Returns `true` if the topic matches the pattern, where a `*` matches any sequence of characters.
+fn matches_pattern(pattern: &str, topic: &str) -> bool {
+    let Some((prefix, rest)) = pattern.split_once('*') else {
+        return pattern == topic;
+    };
+    let Some(mut remaining) = topic.strip_prefix(prefix) else {
+        return false;
+    };
+
+    // Each literal between wildcards is matched at its earliest position, and the literal after
+    // the last wildcard must end the topic.
+    let mut parts = rest.split('*').peekable();
+    while let Some(part) = parts.next() {
+        if parts.peek().is_none() {
+            return remaining.ends_with(part);
+        }
+        match remaining.find(part) {
+            Some(index) => remaining = &remaining[index + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+impl NetworkBehaviour for TopicAllowlist {
+    type ConnectionHandler = dummy::ConnectionHandler;
+    type ToSwarm = Infallible;
+
+    fn handle_established_inbound_connection(
+        &mut self,
+        _: ConnectionId,
+        _: PeerId,
+        _: &Multiaddr,
+        _: &Multiaddr,
+    ) -> Result<THandler<Self>, ConnectionDenied> {
+        Ok(dummy::ConnectionHandler)
+    }
+
+    fn handle_established_outbound_connection(
+        &mut self,
+        _: ConnectionId,
+        _: PeerId,
+        _: &Multiaddr,
+        _: Endpoint,
+        _: PortUse,
+    ) -> Result<THandler<Self>, ConnectionDenied> {
+        Ok(dummy::ConnectionHandler)
+    }
+
+    fn on_swarm_event(&mut self, _: FromSwarm<'_>) {}
+
+    fn on_connection_handler_event(
+        &mut self,
+        _: PeerId,
+        _: ConnectionId,
+        event: THandlerOutEvent<Self>,
+    ) {
+        match event {}
+    }
+
+    fn poll(&mut self, _: &mut Context<'_>) -> Poll<ToSwarm<Self::ToSwarm, THandlerInEvent<Self>>> {
+        Poll::Pending
+    }
+}
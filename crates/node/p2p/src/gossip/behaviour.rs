@@ -1,11 +1,18 @@
 //! Network Behaviour Module.
 
 use libp2p::{
-    gossipsub::{Config, IdentTopic, MessageAuthenticity},
-    swarm::NetworkBehaviour,
+    PeerId,
+    gossipsub::{Config, IdentTopic, MessageAuthenticity, TopicHash},
+    identify,
+    identity::PublicKey,
+    swarm::{NetworkBehaviour, behaviour::toggle::Toggle},
 };
+use std::{collections::BTreeSet, time::Duration};
 
-use crate::{Event, Handler};
+use crate::{
+    ConnectionGate, Event, Handler, IDENTIFY_PROTOCOL_VERSION, MAX_SUBSCRIBED_TOPICS, PeerInfo,
+    PeerInfoStore, TopicAllowlist, gossip::handler::unix_timestamp,
+};
 
 /// An error that can occur when creating a [`Behaviour`].
 #[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
@@ -16,42 +23,244 @@ pub enum BehaviourError {
     /// Subscription failed.
     #[error("subscription failed")]
     SubscriptionFailed,
+    /// The handlers specify more topics than the maximum allowed.
+    #[error("too many topics: {count} exceeds the maximum of {max}")]
+    TooManyTopics {
+        /// The number of topics specified by the handlers.
+        count: usize,
+        /// The maximum number of topics allowed.
+        max: usize,
+    },
+    /// The subscriptions were not confirmed within the timeout.
+    #[error("subscriptions not confirmed within {0:?}")]
+    SubscriptionTimeout(Duration),
+    /// The topic does not match any of the allowed topic patterns.
+    #[error("topic {0} is not allowed")]
+    DisallowedTopic(TopicHash),
 }
 
 /// Specifies the [`NetworkBehaviour`] of the node
 #[derive(NetworkBehaviour)]
 #[behaviour(out_event = "Event")]
 pub struct Behaviour {
+    /// Limits the number of peers connected from each IP subnet. This comes first so that
+    /// connections it denies are never established with the other behaviours.
+    pub gate: ConnectionGate,
     /// Responds to inbound pings and send outbound pings.
     pub ping: libp2p::ping::Behaviour,
+    /// Exchanges peer information, including the address each peer observes the node at. This is
+    /// disabled until enabled with [Behaviour::enable_identify].
+    pub identify: Toggle<identify::Behaviour>,
     /// Enables gossipsub as the routing layer.
     pub gossipsub: libp2p::gossipsub::Behaviour,
+    /// Restricts the topics that may be subscribed to, and how many.
+    pub allowlist: TopicAllowlist,
+    /// Tracks diagnostic information about connected peers.
+    pub peers: PeerInfoStore,
 }
 
 impl Behaviour {
     /// Configures the swarm behaviors, subscribes to the gossip topics, and returns a new
     /// [`Behaviour`].
-    pub fn new(cfg: Config, handlers: &[Box<dyn Handler>]) -> Result<Self, BehaviourError> {
+    ///
+    /// Only the [Handler::active_topics] of each handler are subscribed to. At most
+    /// [MAX_SUBSCRIBED_TOPICS] topics may be subscribed to.
+    ///
+    /// Published messages are authored according to `authenticity`. With
+    /// [MessageAuthenticity::Anonymous], messages carry no author, sequence number or signature.
+    /// With [MessageAuthenticity::Signed], messages are signed with the given keypair, which
+    /// attributes each message to the peer that published it. Returns
+    /// [BehaviourError::GossipsubCreationFailed] if the authenticity conflicts with the
+    /// [ValidationMode] of the [Config]: signing is refused under [ValidationMode::Anonymous], and
+    /// [ValidationMode::Strict] requires signing.
+    ///
+    /// [ValidationMode]: libp2p::gossipsub::ValidationMode
+    /// [ValidationMode::Anonymous]: libp2p::gossipsub::ValidationMode::Anonymous
+    /// [ValidationMode::Strict]: libp2p::gossipsub::ValidationMode::Strict
+    pub fn new(
+        cfg: Config,
+        handlers: &[Box<dyn Handler>],
+        authenticity: MessageAuthenticity,
+    ) -> Result<Self, BehaviourError> {
+        Self::new_with_max_topics(cfg, handlers, authenticity, MAX_SUBSCRIBED_TOPICS)
+    }
+
+    /// Configures the swarm behaviors, subscribes to the gossip topics, and returns a new
+    /// [`Behaviour`].
+    ///
+    /// Returns [BehaviourError::TooManyTopics] if the handlers specify more than `max_topics`
+    /// distinct topics, in which case no topics are subscribed to. The same limit applies to the
+    /// topics subscribed to later with [Behaviour::subscribe_topics].
+    pub fn new_with_max_topics(
+        cfg: Config,
+        handlers: &[Box<dyn Handler>],
+        authenticity: MessageAuthenticity,
+        max_topics: usize,
+    ) -> Result<Self, BehaviourError> {
         let ping = libp2p::ping::Behaviour::default();
 
-        let mut gossipsub = libp2p::gossipsub::Behaviour::new(MessageAuthenticity::Anonymous, cfg)
+        let mut gossipsub = libp2p::gossipsub::Behaviour::new(authenticity, cfg)
             .map_err(|_| BehaviourError::GossipsubCreationFailed)?;
 
-        handlers
+        let timestamp = unix_timestamp();
+        let topics = handlers
+            .iter()
+            .flat_map(|handler| handler.active_topics(timestamp))
+            .collect::<BTreeSet<TopicHash>>();
+        if topics.len() > max_topics {
+            return Err(BehaviourError::TooManyTopics { count: topics.len(), max: max_topics });
+        }
+
+        topics
             .iter()
-            .flat_map(|handler| {
-                handler
-                    .topics()
-                    .iter()
-                    .map(|topic| {
-                        let topic = IdentTopic::new(topic.to_string());
-                        gossipsub.subscribe(&topic).map_err(|_| BehaviourError::SubscriptionFailed)
-                    })
-                    .collect::<Vec<_>>()
+            .map(|topic| {
+                let topic = IdentTopic::new(topic.to_string());
+                gossipsub.subscribe(&topic).map_err(|_| BehaviourError::SubscriptionFailed)
             })
             .collect::<Result<Vec<bool>, BehaviourError>>()?;
 
-        Ok(Self { ping, gossipsub })
+        Ok(Self {
+            gate: ConnectionGate::default(),
+            ping,
+            identify: Toggle::from(None),
+            gossipsub,
+            allowlist: TopicAllowlist::new(max_topics),
+            peers: PeerInfoStore::default(),
+        })
+    }
+
+    /// Subscribes to the given topics, independently of any [Handler].
+    ///
+    /// This allows a passive observer node, built with no handlers, to still receive gossip.
+    /// Messages on topics without a handler are not validated, and are surfaced as raw
+    /// [Event::Gossipsub] events by the [crate::GossipDriver]. Topics that are already subscribed
+    /// to are skipped. Returns [BehaviourError::TooManyTopics] if this would subscribe to more
+    /// topics in total than the limit the behaviour was created with, [MAX_SUBSCRIBED_TOPICS] by
+    /// default, or [BehaviourError::DisallowedTopic] if a topic is not allowed by
+    /// [Behaviour::set_allowed_topics], in which case no topics are subscribed to.
+    pub fn subscribe_topics(
+        &mut self,
+        topics: impl IntoIterator<Item = TopicHash>,
+    ) -> Result<(), BehaviourError> {
+        let topics = topics
+            .into_iter()
+            .filter(|topic| !self.is_subscribed(topic))
+            .collect::<BTreeSet<TopicHash>>();
+        if let Some(topic) = topics.iter().find(|topic| !self.allowlist.is_allowed(topic)) {
+            return Err(BehaviourError::DisallowedTopic(topic.clone()));
+        }
+        let count = self.gossipsub.topics().count() + topics.len();
+        let max = self.allowlist.max_topics();
+        if count > max {
+            return Err(BehaviourError::TooManyTopics { count, max });
+        }
+
+        for topic in topics {
+            let topic = IdentTopic::new(topic.to_string());
+            self.gossipsub.subscribe(&topic).map_err(|_| BehaviourError::SubscriptionFailed)?;
+        }
+        Ok(())
+    }
+
+    /// Restricts the topics that may be subscribed to, to those matching one of the given
+    /// patterns, or allows any topic if `None`. A `*` in a pattern matches any sequence of
+    /// characters.
+    ///
+    /// Returns [BehaviourError::DisallowedTopic] if an already subscribed topic, such as one
+    /// specified by a [Handler], does not match any of the patterns, in which case the allowed
+    /// topics are left unchanged.
+    pub fn set_allowed_topics(
+        &mut self,
+        patterns: Option<Vec<String>>,
+    ) -> Result<(), BehaviourError> {
+        let previous = self.allowlist.clone();
+        self.allowlist.set_allowed(patterns);
+        // The subscribed topics are unordered, so the smallest disallowed topic is reported.
+        let disallowed = self.gossipsub.topics().filter(|topic| !self.allowlist.is_allowed(topic));
+        if let Some(topic) = disallowed.min().cloned() {
+            self.allowlist = previous;
+            return Err(BehaviourError::DisallowedTopic(topic));
+        }
+        Ok(())
+    }
+
+    /// Sets the maximum number of peers that may connect from each IP subnet, or removes the
+    /// limit if `None`. Connections from further peers in a full subnet are denied.
+    pub fn set_max_peers_per_subnet(&mut self, max: Option<usize>) {
+        self.gate.set_max_peers_per_subnet(max);
+    }
+
+    /// Sets the interval at which pings are sent to each connected peer. Pings keep otherwise idle
+    /// connections active, which maintains the NAT mappings and the state of intermediaries that
+    /// would otherwise close them. This must be called before the behaviour is added to the
+    /// swarm.
+    pub fn set_keepalive_interval(&mut self, interval: Duration) {
+        let config = libp2p::ping::Config::new().with_interval(interval);
+        self.ping = libp2p::ping::Behaviour::new(config);
+    }
+
+    /// Enables the `identify` protocol, advertising the given public key of the node. This must
+    /// be called before the behaviour is added to the swarm.
+    pub fn enable_identify(&mut self, local_key: PublicKey) {
+        let config = identify::Config::new(IDENTIFY_PROTOCOL_VERSION.to_string(), local_key);
+        self.identify = Toggle::from(Some(identify::Behaviour::new(config)));
+    }
+
+    /// Returns `true` if the gossipsub behaviour is subscribed to the given topic.
+    pub fn is_subscribed(&self, topic: &TopicHash) -> bool {
+        self.gossipsub.topics().any(|t| t == topic)
+    }
+
+    /// Returns the [PeerInfo] of the given peer, combining the remote addresses of its
+    /// connections with the information it reported over `identify`, if it is connected.
+    ///
+    /// The `identify` fields are empty until the peer has identified itself, which requires
+    /// `identify` to be enabled with [Behaviour::enable_identify].
+    pub fn peer_info(&self, peer: &PeerId) -> Option<PeerInfo> {
+        self.peers.get(peer)
+    }
+
+    /// Returns the peers in the gossipsub mesh of the given topic.
+    ///
+    /// The list is empty if the topic is not subscribed to, or if no peers have been grafted into
+    /// its mesh yet.
+    pub fn mesh_peers(&self, topic: &str) -> Vec<PeerId> {
+        self.gossipsub.mesh_peers(&TopicHash::from_raw(topic)).copied().collect()
+    }
+
+    /// Forces an immediate round of mesh maintenance rather than waiting for the next gossipsub
+    /// heartbeat.
+    ///
+    /// The gossipsub heartbeat itself is not exposed, so every subscribed topic with an empty mesh
+    /// is rejoined instead. Rejoining grafts known topic peers into the mesh straight away, which
+    /// lets the mesh converge after topology changes without waiting for the heartbeat interval.
+    /// Topics that already have mesh peers are left untouched, so no peers are pruned.
+    pub fn force_heartbeat(&mut self) {
+        let empty = self
+            .gossipsub
+            .topics()
+            .filter(|topic| self.gossipsub.mesh_peers(topic).next().is_none())
+            .cloned()
+            .collect::<Vec<TopicHash>>();
+        for topic in empty {
+            let topic = IdentTopic::new(topic.to_string());
+            self.gossipsub.unsubscribe(&topic);
+            if self.gossipsub.subscribe(&topic).is_err() {
+                warn!(target: "p2p::gossip::behaviour", "Failed to rejoin topic {} on forced heartbeat", topic);
+            }
+        }
+    }
+
+    /// Sets the application-specific score of the given peer.
+    ///
+    /// The score is fed into the gossipsub peer scoring function, weighted by the
+    /// `app_specific_weight` of the peer score parameters, allowing external reputation signals
+    /// to influence mesh decisions.
+    ///
+    /// Returns `false` if peer scoring is not enabled on the gossipsub behaviour, or if the peer
+    /// is not known.
+    pub fn set_application_score(&mut self, peer: &PeerId, score: f64) -> bool {
+        self.gossipsub.set_application_score(peer, score)
     }
 }
 
@@ -60,7 +269,12 @@ mod tests {
     use super::*;
     use crate::gossip::{config, handler::BlockHandler};
     use alloy_primitives::Address;
-    use libp2p::gossipsub::{IdentTopic, TopicHash};
+    use libp2p::{
+        core::{ConnectedPoint, Multiaddr},
+        gossipsub::{IdentTopic, PeerScoreParams, PeerScoreThresholds, TopicHash, ValidationMode},
+        identity::Keypair,
+        swarm::{ConnectionId, FromSwarm, behaviour::ConnectionEstablished},
+    };
 
     fn zero_topics() -> Vec<TopicHash> {
         vec![
@@ -75,7 +289,95 @@ mod tests {
     fn test_behaviour_no_handlers() {
         let cfg = config::default_config_builder().build().expect("Failed to build default config");
         let handlers = vec![];
-        let _ = Behaviour::new(cfg, &handlers).unwrap();
+        let _ = Behaviour::new(cfg, &handlers, MessageAuthenticity::Anonymous).unwrap();
+    }
+
+    #[test]
+    fn test_behaviour_signed() {
+        let (_, recv) = tokio::sync::watch::channel(Address::default());
+        let (block_handler, _) = BlockHandler::new(0, recv);
+        let handlers: Vec<Box<dyn Handler>> = vec![Box::new(block_handler)];
+        let keypair = Keypair::generate_secp256k1();
+
+        // Signing is rejected if incoming messages must be anonymous.
+        let cfg = config::default_config_builder()
+            .validation_mode(ValidationMode::Anonymous)
+            .build()
+            .unwrap();
+        let err =
+            Behaviour::new(cfg, &handlers, MessageAuthenticity::Signed(keypair.clone())).err();
+        assert_eq!(err, Some(BehaviourError::GossipsubCreationFailed));
+
+        // Strict validation requires published messages to be signed.
+        let cfg = config::default_config_builder()
+            .validation_mode(ValidationMode::Strict)
+            .build()
+            .unwrap();
+        let err = Behaviour::new(cfg, &handlers, MessageAuthenticity::Anonymous).err();
+        assert_eq!(err, Some(BehaviourError::GossipsubCreationFailed));
+
+        let cfg = config::default_config_builder()
+            .validation_mode(ValidationMode::Strict)
+            .build()
+            .unwrap();
+        let behaviour =
+            Behaviour::new(cfg, &handlers, MessageAuthenticity::Signed(keypair)).unwrap();
+        assert_eq!(behaviour.gossipsub.topics().count(), 4);
+        assert!(zero_topics().iter().all(|topic| behaviour.is_subscribed(topic)));
+    }
+
+    #[test]
+    fn test_behaviour_no_handlers_explicit_topics() {
+        let cfg = config::default_config_builder().build().expect("Failed to build default config");
+        let mut behaviour = Behaviour::new(cfg, &[], MessageAuthenticity::Anonymous).unwrap();
+        assert_eq!(behaviour.gossipsub.topics().count(), 0);
+
+        let topics = zero_topics();
+        behaviour.subscribe_topics(topics.clone()).unwrap();
+        assert!(topics.iter().all(|topic| behaviour.is_subscribed(topic)));
+
+        // Subscribing to already subscribed topics is a no-op.
+        behaviour.subscribe_topics(topics).unwrap();
+        assert_eq!(behaviour.gossipsub.topics().count(), 4);
+
+        let too_many = (0..MAX_SUBSCRIBED_TOPICS).map(|i| IdentTopic::new(i.to_string()).hash());
+        let err = behaviour.subscribe_topics(too_many).err();
+        assert_eq!(
+            err,
+            Some(BehaviourError::TooManyTopics {
+                count: MAX_SUBSCRIBED_TOPICS + 4,
+                max: MAX_SUBSCRIBED_TOPICS
+            })
+        );
+        assert_eq!(behaviour.gossipsub.topics().count(), 4);
+    }
+
+    #[test]
+    fn test_behaviour_allowed_topics() {
+        let cfg = config::default_config_builder().build().expect("Failed to build default config");
+        let (_, recv) = tokio::sync::watch::channel(Address::default());
+        let (block_handler, _) = BlockHandler::new(0, recv);
+        let handlers: Vec<Box<dyn Handler>> = vec![Box::new(block_handler)];
+        let mut behaviour = Behaviour::new(cfg, &handlers, MessageAuthenticity::Anonymous).unwrap();
+
+        // The topics of the handler must be allowed.
+        let err = behaviour.set_allowed_topics(Some(vec!["/optimism/10/*/blocks".to_string()]));
+        assert_eq!(err, Err(BehaviourError::DisallowedTopic(zero_topics()[0].clone())));
+        behaviour.set_allowed_topics(Some(vec!["/optimism/0/*/blocks".to_string()])).unwrap();
+
+        let disallowed = IdentTopic::new("/optimism/0/spam").hash();
+        let err = behaviour.subscribe_topics([disallowed.clone()]);
+        assert_eq!(err, Err(BehaviourError::DisallowedTopic(disallowed.clone())));
+        assert!(!behaviour.is_subscribed(&disallowed));
+
+        let allowed = IdentTopic::new("/optimism/0/4/blocks").hash();
+        behaviour.subscribe_topics([allowed.clone()]).unwrap();
+        assert!(behaviour.is_subscribed(&allowed));
+
+        // Any topic may be subscribed to once the allowlist is cleared.
+        behaviour.set_allowed_topics(None).unwrap();
+        behaviour.subscribe_topics([disallowed.clone()]).unwrap();
+        assert!(behaviour.is_subscribed(&disallowed));
     }
 
     #[test]
@@ -84,9 +386,119 @@ mod tests {
         let (_, recv) = tokio::sync::watch::channel(Address::default());
         let (block_handler, _) = BlockHandler::new(0, recv);
         let handlers: Vec<Box<dyn Handler>> = vec![Box::new(block_handler)];
-        let behaviour = Behaviour::new(cfg, &handlers).unwrap();
+        let behaviour = Behaviour::new(cfg, &handlers, MessageAuthenticity::Anonymous).unwrap();
         let mut topics = behaviour.gossipsub.topics().cloned().collect::<Vec<TopicHash>>();
         topics.sort();
         assert_eq!(topics, zero_topics());
     }
+
+    #[test]
+    fn test_behaviour_too_many_topics() {
+        let cfg = config::default_config_builder().build().expect("Failed to build default config");
+        let (_, recv) = tokio::sync::watch::channel(Address::default());
+        let (block_handler, _) = BlockHandler::new(0, recv);
+        let handlers: Vec<Box<dyn Handler>> =
+            vec![Box::new(block_handler.clone()), Box::new(block_handler)];
+
+        // Duplicate topics across handlers only count once.
+        let behaviour = Behaviour::new_with_max_topics(
+            cfg.clone(),
+            &handlers,
+            MessageAuthenticity::Anonymous,
+            4,
+        )
+        .unwrap();
+        assert_eq!(behaviour.gossipsub.topics().count(), 4);
+
+        let (_, recv) = tokio::sync::watch::channel(Address::default());
+        let (other_handler, _) = BlockHandler::new(1, recv);
+        let mut handlers = handlers;
+        handlers.push(Box::new(other_handler));
+        let err =
+            Behaviour::new_with_max_topics(cfg, &handlers, MessageAuthenticity::Anonymous, 4).err();
+        assert_eq!(err, Some(BehaviourError::TooManyTopics { count: 8, max: 4 }));
+    }
+
+    #[test]
+    fn test_behaviour_explicit_topics_max_topics() {
+        let cfg = config::default_config_builder().build().expect("Failed to build default config");
+        let mut behaviour =
+            Behaviour::new_with_max_topics(cfg, &[], MessageAuthenticity::Anonymous, 4).unwrap();
+
+        // Explicitly subscribed topics count towards the same limit as the topics of handlers.
+        behaviour.subscribe_topics(zero_topics()).unwrap();
+        let extra = IdentTopic::new("/optimism/0/4/blocks").hash();
+        let err = behaviour.subscribe_topics([extra.clone()]).err();
+        assert_eq!(err, Some(BehaviourError::TooManyTopics { count: 5, max: 4 }));
+        assert!(!behaviour.is_subscribed(&extra));
+    }
+
+    #[test]
+    fn test_behaviour_mesh_peers() {
+        let cfg = config::default_config_builder().build().expect("Failed to build default config");
+        let (_, recv) = tokio::sync::watch::channel(Address::default());
+        let (block_handler, _) = BlockHandler::new(0, recv);
+        let handlers: Vec<Box<dyn Handler>> = vec![Box::new(block_handler)];
+        let behaviour = Behaviour::new(cfg, &handlers, MessageAuthenticity::Anonymous).unwrap();
+
+        // No peers are connected, so the mesh of a subscribed topic is empty.
+        let topic = zero_topics()[0].to_string();
+        assert!(behaviour.is_subscribed(&TopicHash::from_raw(&topic)));
+        assert!(behaviour.mesh_peers(&topic).is_empty());
+        assert!(behaviour.mesh_peers("/optimism/0/9/blocks").is_empty());
+    }
+
+    #[test]
+    fn test_behaviour_force_heartbeat() {
+        let cfg = config::default_config_builder().build().expect("Failed to build default config");
+        let (_, recv) = tokio::sync::watch::channel(Address::default());
+        let (block_handler, _) = BlockHandler::new(0, recv);
+        let handlers: Vec<Box<dyn Handler>> = vec![Box::new(block_handler)];
+        let mut behaviour = Behaviour::new(cfg, &handlers, MessageAuthenticity::Anonymous).unwrap();
+
+        behaviour.force_heartbeat();
+
+        // Every topic remains subscribed, with an empty mesh since no peers are connected.
+        let mut topics = behaviour.gossipsub.topics().cloned().collect::<Vec<TopicHash>>();
+        topics.sort();
+        assert_eq!(topics, zero_topics());
+        assert_eq!(behaviour.gossipsub.all_mesh_peers().count(), 0);
+    }
+
+    #[test]
+    fn test_behaviour_set_application_score() {
+        let cfg = config::default_config_builder().build().expect("Failed to build default config");
+        let mut behaviour = Behaviour::new(cfg, &[], MessageAuthenticity::Anonymous).unwrap();
+        let peer = PeerId::random();
+
+        // Peer scoring is disabled by default.
+        assert!(!behaviour.set_application_score(&peer, -1.0));
+
+        behaviour
+            .gossipsub
+            .with_peer_score(PeerScoreParams::default(), PeerScoreThresholds::default())
+            .unwrap();
+
+        // The peer is not yet known.
+        assert!(!behaviour.set_application_score(&peer, -1.0));
+
+        let addr: Multiaddr = "/ip4/127.0.0.1/tcp/9000".parse().unwrap();
+        behaviour.gossipsub.on_swarm_event(FromSwarm::ConnectionEstablished(
+            ConnectionEstablished {
+                peer_id: peer,
+                connection_id: ConnectionId::new_unchecked(0),
+                endpoint: &ConnectedPoint::Listener {
+                    local_addr: addr.clone(),
+                    send_back_addr: addr,
+                },
+                failed_addresses: &[],
+                other_established: 0,
+            },
+        ));
+        assert_eq!(behaviour.gossipsub.peer_score(&peer), Some(0.0));
+
+        assert!(behaviour.set_application_score(&peer, -1.0));
+        let weight = PeerScoreParams::default().app_specific_weight;
+        assert_eq!(behaviour.gossipsub.peer_score(&peer), Some(-weight));
+    }
 }
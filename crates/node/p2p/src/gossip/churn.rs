@@ -0,0 +1,117 @@
+//! Peer churn tracking for the gossip swarm.
+
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use crate::{DriverEvent, PEER_CHURN_THRESHOLD};
+
+/// The window over which the peer churn rate is measured.
+pub const PEER_CHURN_WINDOW: Duration = Duration::from_secs(60);
+
+/// Tracks peer connects and disconnects over a sliding one minute window.
+///
+/// When the number of connects and disconnects within the window exceeds the threshold, an
+/// [DriverEvent::HighChurn] is emitted. The event is emitted once when the threshold is crossed,
+/// and again only after the churn rate has dropped back to or below the threshold.
+#[derive(Debug, Clone)]
+pub struct PeerChurn {
+    /// The churn rate, in connects and disconnects per minute, above which churn is high.
+    threshold: usize,
+    /// The instants of connects and disconnects within the window.
+    events: VecDeque<Instant>,
+    /// The total number of connects and disconnects recorded.
+    total: u64,
+    /// Whether churn is currently above the threshold.
+    high: bool,
+}
+
+impl Default for PeerChurn {
+    fn default() -> Self {
+        Self::new(PEER_CHURN_THRESHOLD)
+    }
+}
+
+impl PeerChurn {
+    /// Creates a new [PeerChurn] tracker with the given per-minute threshold.
+    pub const fn new(threshold: usize) -> Self {
+        Self { threshold, events: VecDeque::new(), total: 0, high: false }
+    }
+
+    /// Returns the churn rate threshold, in connects and disconnects per minute.
+    pub const fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// Returns the total number of connects and disconnects recorded.
+    pub const fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// Records a peer connect or disconnect at `now`, returning an [DriverEvent::HighChurn] if the
+    /// churn rate has crossed the threshold.
+    pub fn record(&mut self, now: Instant) -> Option<DriverEvent> {
+        self.total += 1;
+        self.events.push_back(now);
+
+        let rate = self.rate(now);
+        if rate <= self.threshold {
+            self.high = false;
+            return None;
+        }
+        if self.high {
+            return None;
+        }
+
+        self.high = true;
+        Some(DriverEvent::HighChurn { rate })
+    }
+
+    /// Returns the number of connects and disconnects within the window ending at `now`.
+    pub fn rate(&mut self, now: Instant) -> usize {
+        while self
+            .events
+            .front()
+            .is_some_and(|event| now.saturating_duration_since(*event) >= PEER_CHURN_WINDOW)
+        {
+            self.events.pop_front();
+        }
+        self.events.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_high_churn_fires_above_threshold() {
+        let mut churn = PeerChurn::new(3);
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            assert!(churn.record(now).is_none());
+        }
+        assert!(matches!(churn.record(now), Some(DriverEvent::HighChurn { rate: 4 })));
+
+        // The event is not emitted again while churn remains high.
+        assert!(churn.record(now).is_none());
+        assert_eq!(churn.total(), 5);
+    }
+
+    #[test]
+    fn test_high_churn_rearms_after_window() {
+        let mut churn = PeerChurn::new(1);
+        let start = Instant::now();
+
+        assert!(churn.record(start).is_none());
+        assert!(matches!(churn.record(start), Some(DriverEvent::HighChurn { rate: 2 })));
+
+        // Once the old events fall out of the window, churn is no longer high.
+        let later = start + PEER_CHURN_WINDOW;
+        assert!(churn.record(later).is_none());
+        assert_eq!(churn.rate(later), 1);
+        assert!(matches!(churn.record(later), Some(DriverEvent::HighChurn { rate: 2 })));
+    }
+}
@@ -39,6 +39,29 @@ pub const DEFAULT_MESH_DHI: usize = 12;
 /// The default mesh D lazy.
 pub const DEFAULT_MESH_DLAZY: usize = 6;
 
+/// The default maximum number of topics the [crate::Behaviour] may subscribe to.
+pub const MAX_SUBSCRIBED_TOPICS: usize = 64;
+
+/// The default number of peer connects and disconnects per minute above which peer churn is
+/// considered high.
+pub const PEER_CHURN_THRESHOLD: usize = 60;
+
+/// The default number of peers that may share a subnet before subnet diversity is considered low.
+pub const MAX_PEERS_PER_SUBNET: usize = 5;
+
+/// The default maximum number of messages waiting in the outbound publish queue.
+pub const PUBLISH_QUEUE_DEPTH: usize = 64;
+
+/// The maximum number of gossip messages validated at once, off the swarm task.
+pub const MAX_CONCURRENT_VALIDATIONS: usize = 16;
+
+/// The default number of distinct peers that must observe an external address of the node before
+/// it is confirmed.
+pub const MIN_ADDRESS_OBSERVERS: usize = 2;
+
+/// The protocol version advertised over `identify`.
+pub const IDENTIFY_PROTOCOL_VERSION: &str = "ipfs/0.1.0";
+
 ////////////////////////////////////////////////////////////////////////////////////////////////
 // Duration Constants
 ////////////////////////////////////////////////////////////////////////////////////////////////
@@ -54,6 +77,14 @@ lazy_static! {
     /// The pper score inspect frequency.
     /// The frequency at which peer scores are inspected.
     pub static ref PEER_SCORE_INSPECT_FREQUENCY: Duration = 15 * Duration::from_secs(1);
+
+    /// The subscription confirmation timeout.
+    /// The duration within which gossip topic subscriptions must be confirmed on startup.
+    pub static ref SUBSCRIPTION_TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// The message validation timeout.
+    /// The duration within which a gossip handler must validate a message before it is ignored.
+    pub static ref VALIDATION_TIMEOUT: Duration = Duration::from_secs(2);
 }
 
 ////////////////////////////////////////////////////////////////////////////////////////////////
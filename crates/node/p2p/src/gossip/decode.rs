@@ -0,0 +1,194 @@
+//! Decoding for the signed block gossip format.
+
+use op_alloy_rpc_types_engine::{OpNetworkPayloadEnvelope, PayloadEnvelopeError};
+
+use crate::MAX_GOSSIP_SIZE;
+
+/// The length of the signature prepended to gossiped blocks.
+pub const SIGNATURE_LENGTH: usize = 65;
+
+/// An error that can occur when decoding a signed gossip block.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum DecodeError {
+    /// The snappy encoding is broken.
+    #[error("broken snappy encoding")]
+    BrokenSnappyEncoding,
+    /// The decompressed size claimed by the snappy frame exceeds the maximum.
+    #[error("decompressed size {size} exceeds the maximum of {max} bytes")]
+    TooLarge {
+        /// The decompressed size claimed by the snappy frame.
+        size: usize,
+        /// The maximum decompressed size.
+        max: usize,
+    },
+    /// The decompressed data is too short to contain the signature and a payload.
+    #[error("signed block too short: expected more than {SIGNATURE_LENGTH} bytes, got {0}")]
+    TooShort(usize),
+    /// The signature is invalid.
+    #[error("invalid signature")]
+    InvalidSignature,
+    /// The decompressed data is too short for the payload version.
+    #[error("payload envelope too short")]
+    InvalidLength,
+    /// The SSZ encoding of the payload is broken.
+    #[error("broken SSZ encoding")]
+    BrokenSszEncoding,
+}
+
+impl From<PayloadEnvelopeError> for DecodeError {
+    fn from(err: PayloadEnvelopeError) -> Self {
+        match err {
+            PayloadEnvelopeError::BrokenSnappyEncoding => Self::BrokenSnappyEncoding,
+            PayloadEnvelopeError::InvalidSignature => Self::InvalidSignature,
+            PayloadEnvelopeError::BrokenSszEncoding => Self::BrokenSszEncoding,
+            PayloadEnvelopeError::InvalidLength => Self::InvalidLength,
+        }
+    }
+}
+
+/// Decodes a signed block of the given payload version from the OP Stack gossip format.
+///
+/// Gossiped blocks are snappy-compressed, and the decompressed data is a 65-byte signature
+/// followed by the block payload. The payload is decoded into an [OpNetworkPayloadEnvelope] by
+/// the op-alloy decoder for the version, where versions above 2 are decoded as v3 payloads.
+///
+/// The decompressed size is limited to [MAX_GOSSIP_SIZE].
+pub fn decode_signed_block(
+    version: u8,
+    data: &[u8],
+) -> Result<OpNetworkPayloadEnvelope, DecodeError> {
+    decode_signed_block_with_limit(version, data, MAX_GOSSIP_SIZE)
+}
+
+/// Decodes a signed block from the OP Stack gossip format, as [decode_signed_block] does, with a
+/// maximum decompressed size of `max_size` bytes.
+///
+/// The decompressed size claimed by the snappy frame is checked before any data is decompressed,
+/// so that decompression bombs are rejected with [DecodeError::TooLarge] without allocating.
+pub fn decode_signed_block_with_limit(
+    version: u8,
+    data: &[u8],
+    max_size: usize,
+) -> Result<OpNetworkPayloadEnvelope, DecodeError> {
+    let size = check_decompressed_size(data, max_size)?;
+    if size <= SIGNATURE_LENGTH {
+        return Err(DecodeError::TooShort(size));
+    }
+
+    let envelope = match version {
+        1 => OpNetworkPayloadEnvelope::decode_v1(data),
+        2 => OpNetworkPayloadEnvelope::decode_v2(data),
+        _ => OpNetworkPayloadEnvelope::decode_v3(data),
+    };
+    Ok(envelope?)
+}
+
+/// Checks that the decompressed size claimed by the snappy frame of a signed block is at most
+/// `max_size` bytes, returning the claimed size.
+///
+/// Only the frame header is read, so that decompression bombs are rejected with
+/// [DecodeError::TooLarge] before any data is decompressed.
+pub fn check_decompressed_size(data: &[u8], max_size: usize) -> Result<usize, DecodeError> {
+    // The snappy decoder never produces more than the length claimed by the frame header, and
+    // fails if the frame does not decompress to exactly that length.
+    let size = snap::raw::decompress_len(data).map_err(|_| DecodeError::BrokenSnappyEncoding)?;
+    if size > max_size {
+        return Err(DecodeError::TooLarge { size, max: max_size });
+    }
+    Ok(size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{Address, B256, Bloom, Bytes, PrimitiveSignature, U256, keccak256};
+    use alloy_rpc_types_engine::ExecutionPayloadV1;
+    use op_alloy_rpc_types_engine::OpExecutionPayload;
+    use ssz::Encode;
+
+    fn compress(data: &[u8]) -> Vec<u8> {
+        snap::raw::Encoder::new().compress_vec(data).unwrap()
+    }
+
+    fn test_payload() -> ExecutionPayloadV1 {
+        ExecutionPayloadV1 {
+            parent_hash: B256::ZERO,
+            fee_recipient: Address::default(),
+            state_root: B256::ZERO,
+            receipts_root: B256::ZERO,
+            logs_bloom: Bloom::default(),
+            prev_randao: B256::ZERO,
+            block_number: 1,
+            gas_limit: 0,
+            gas_used: 0,
+            timestamp: 0,
+            extra_data: Bytes::default(),
+            base_fee_per_gas: U256::from(0),
+            block_hash: B256::ZERO,
+            transactions: vec![],
+        }
+    }
+
+    /// Returns the signed gossip block of the test payload, before compression.
+    fn signed_block() -> Vec<u8> {
+        let signature = PrimitiveSignature::test_signature();
+        [signature.as_bytes().as_slice(), &test_payload().as_ssz_bytes()].concat()
+    }
+
+    #[test]
+    fn test_decode_signed_block() {
+        let envelope = decode_signed_block(1, &compress(&signed_block())).unwrap();
+        assert_eq!(envelope.signature, PrimitiveSignature::test_signature());
+        assert_eq!(envelope.payload, OpExecutionPayload::V1(test_payload()));
+        assert_eq!(envelope.payload_hash.0, keccak256(test_payload().as_ssz_bytes()));
+
+        // The payload is decoded according to its version.
+        assert_eq!(
+            decode_signed_block(3, &compress(&signed_block())),
+            Err(DecodeError::BrokenSszEncoding)
+        );
+    }
+
+    #[test]
+    fn test_decode_signed_block_too_short() {
+        let data = PrimitiveSignature::test_signature().as_bytes();
+        assert_eq!(
+            decode_signed_block(1, &compress(&data)),
+            Err(DecodeError::TooShort(SIGNATURE_LENGTH))
+        );
+        assert_eq!(decode_signed_block(1, &compress(&[0u8; 10])), Err(DecodeError::TooShort(10)));
+    }
+
+    #[test]
+    fn test_decode_signed_block_too_large() {
+        // A frame of zeros compresses to under a twentieth of its decompressed size.
+        let bomb = compress(&vec![0u8; MAX_GOSSIP_SIZE + 1]);
+        assert!(bomb.len() < MAX_GOSSIP_SIZE / 20);
+        assert_eq!(
+            decode_signed_block(1, &bomb),
+            Err(DecodeError::TooLarge { size: MAX_GOSSIP_SIZE + 1, max: MAX_GOSSIP_SIZE })
+        );
+
+        // A frame whose header claims a huge size is rejected before decompressing.
+        let mut claimed = vec![0x80, 0x80, 0x80, 0x80, 0x04];
+        claimed.extend_from_slice(&[0u8; 8]);
+        assert_eq!(
+            decode_signed_block(1, &claimed),
+            Err(DecodeError::TooLarge { size: 1 << 30, max: MAX_GOSSIP_SIZE })
+        );
+
+        // The limit is configurable.
+        let size = signed_block().len();
+        let data = compress(&signed_block());
+        assert!(decode_signed_block_with_limit(1, &data, size).is_ok());
+        assert_eq!(
+            decode_signed_block_with_limit(1, &data, size - 1),
+            Err(DecodeError::TooLarge { size, max: size - 1 })
+        );
+    }
+
+    #[test]
+    fn test_decode_signed_block_broken_snappy() {
+        assert_eq!(decode_signed_block(1, &[0xFF; 8]), Err(DecodeError::BrokenSnappyEncoding));
+    }
+}
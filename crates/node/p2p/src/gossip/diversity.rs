@@ -0,0 +1,203 @@
+//! Subnet diversity accounting for the gossip swarm.
+
+use libp2p::{Multiaddr, PeerId, multiaddr::Protocol};
+use std::{
+    collections::{HashMap, HashSet},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
+
+use crate::{DriverEvent, MAX_PEERS_PER_SUBNET};
+
+/// Returns the subnet of the first IP address in the given [Multiaddr], with the given prefix
+/// lengths, if it has one.
+pub(crate) fn subnet(addr: &Multiaddr, ipv4_prefix_len: u8, ipv6_prefix_len: u8) -> Option<IpAddr> {
+    addr.iter().find_map(|protocol| match protocol {
+        Protocol::Ip4(ip) => {
+            let mask = u32::MAX.checked_shl(32 - ipv4_prefix_len.min(32) as u32);
+            Some(IpAddr::V4(Ipv4Addr::from(ip.to_bits() & mask.unwrap_or(0))))
+        }
+        Protocol::Ip6(ip) => {
+            let mask = u128::MAX.checked_shl(128 - ipv6_prefix_len.min(128) as u32);
+            Some(IpAddr::V6(Ipv6Addr::from(ip.to_bits() & mask.unwrap_or(0))))
+        }
+        _ => None,
+    })
+}
+
+/// The thresholds applied by [SubnetDiversity].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubnetDiversityConfig {
+    /// The number of peers that may share a subnet before diversity is considered low.
+    pub max_peers_per_subnet: usize,
+    /// The prefix length of IPv4 subnets.
+    pub ipv4_prefix_len: u8,
+    /// The prefix length of IPv6 subnets.
+    pub ipv6_prefix_len: u8,
+    /// Whether peers that push their subnet over the limit are disconnected.
+    pub prune: bool,
+}
+
+impl Default for SubnetDiversityConfig {
+    fn default() -> Self {
+        Self {
+            max_peers_per_subnet: MAX_PEERS_PER_SUBNET,
+            ipv4_prefix_len: 24,
+            ipv6_prefix_len: 48,
+            prune: false,
+        }
+    }
+}
+
+/// Tracks the subnets of connected peers, to detect a swarm dominated by a single subnet.
+///
+/// A node whose peers all share a subnet is vulnerable to being eclipsed by whoever controls it.
+/// When the number of peers in a subnet exceeds the threshold, an [DriverEvent::LowSubnetDiversity]
+/// is emitted. As with [crate::PeerChurn], the event is emitted once when the threshold is crossed,
+/// and again only after the subnet has dropped back to or below the threshold.
+#[derive(Debug, Clone, Default)]
+pub struct SubnetDiversity {
+    /// The diversity thresholds.
+    config: SubnetDiversityConfig,
+    /// The subnet of each tracked peer.
+    peers: HashMap<PeerId, IpAddr>,
+    /// The number of tracked peers in each subnet.
+    subnets: HashMap<IpAddr, usize>,
+    /// The subnets currently above the threshold.
+    crowded: HashSet<IpAddr>,
+}
+
+impl SubnetDiversity {
+    /// Creates a new [SubnetDiversity] tracker with the given thresholds.
+    pub fn new(config: SubnetDiversityConfig) -> Self {
+        Self { config, ..Default::default() }
+    }
+
+    /// Returns the diversity thresholds.
+    pub const fn config(&self) -> SubnetDiversityConfig {
+        self.config
+    }
+
+    /// Returns the subnet of the given address, if it is an IP address.
+    pub fn subnet(&self, addr: &Multiaddr) -> Option<IpAddr> {
+        subnet(addr, self.config.ipv4_prefix_len, self.config.ipv6_prefix_len)
+    }
+
+    /// Returns the number of tracked peers in the given subnet.
+    pub fn peers_in_subnet(&self, subnet: &IpAddr) -> usize {
+        self.subnets.get(subnet).copied().unwrap_or_default()
+    }
+
+    /// Returns `true` if the subnet of the given peer has more peers than the threshold.
+    pub fn is_over_limit(&self, peer: &PeerId) -> bool {
+        self.peers
+            .get(peer)
+            .is_some_and(|subnet| self.peers_in_subnet(subnet) > self.config.max_peers_per_subnet)
+    }
+
+    /// Returns `true` if the given peer should be disconnected, because pruning is enabled and
+    /// its subnet has more peers than the threshold.
+    pub fn should_prune(&self, peer: &PeerId) -> bool {
+        self.config.prune && self.is_over_limit(peer)
+    }
+
+    /// Records a connection to a peer at the given address, returning an
+    /// [DriverEvent::LowSubnetDiversity] if its subnet has crossed the threshold.
+    ///
+    /// Peers without an IP address, and further connections to a tracked peer, are ignored.
+    pub fn record_connected(&mut self, peer: PeerId, addr: &Multiaddr) -> Option<DriverEvent> {
+        if self.peers.contains_key(&peer) {
+            return None;
+        }
+        let subnet = self.subnet(addr)?;
+        self.peers.insert(peer, subnet);
+        let peers = self.subnets.entry(subnet).or_default();
+        *peers += 1;
+
+        if *peers <= self.config.max_peers_per_subnet || !self.crowded.insert(subnet) {
+            return None;
+        }
+        Some(DriverEvent::LowSubnetDiversity { subnet, peers: *peers })
+    }
+
+    /// Records that a peer has disconnected.
+    pub fn record_disconnected(&mut self, peer: &PeerId) {
+        let Some(subnet) = self.peers.remove(peer) else {
+            return;
+        };
+        let peers = self.subnets.entry(subnet).or_default();
+        *peers = peers.saturating_sub(1);
+
+        if *peers <= self.config.max_peers_per_subnet {
+            self.crowded.remove(&subnet);
+        }
+        if *peers == 0 {
+            self.subnets.remove(&subnet);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(max_peers_per_subnet: usize) -> SubnetDiversityConfig {
+        SubnetDiversityConfig { max_peers_per_subnet, ..Default::default() }
+    }
+
+    fn addr(ip: &str) -> Multiaddr {
+        format!("/ip4/{ip}/tcp/9222").parse().unwrap()
+    }
+
+    #[test]
+    fn test_low_subnet_diversity_fires_above_threshold() {
+        let mut diversity = SubnetDiversity::new(config(3));
+        let subnet = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 0));
+
+        for i in 1..=3 {
+            assert!(
+                diversity
+                    .record_connected(PeerId::random(), &addr(&format!("10.0.0.{i}")))
+                    .is_none()
+            );
+        }
+        let peer = PeerId::random();
+        assert!(matches!(
+            diversity.record_connected(peer, &addr("10.0.0.4")),
+            Some(DriverEvent::LowSubnetDiversity { subnet: s, peers: 4 }) if s == subnet
+        ));
+        assert!(diversity.is_over_limit(&peer));
+        assert!(!diversity.should_prune(&peer));
+
+        // The event is not emitted again while the subnet remains crowded.
+        assert!(diversity.record_connected(PeerId::random(), &addr("10.0.0.5")).is_none());
+        assert_eq!(diversity.peers_in_subnet(&subnet), 5);
+
+        // Peers in other subnets are counted separately.
+        let other = PeerId::random();
+        assert!(diversity.record_connected(other, &addr("10.0.1.1")).is_none());
+        assert!(!diversity.is_over_limit(&other));
+    }
+
+    #[test]
+    fn test_low_subnet_diversity_rearms_after_disconnect() {
+        let mut diversity =
+            SubnetDiversity::new(SubnetDiversityConfig { prune: true, ..config(1) });
+
+        let first = PeerId::random();
+        let second = PeerId::random();
+        assert!(diversity.record_connected(first, &addr("192.168.1.1")).is_none());
+        assert!(diversity.record_connected(second, &addr("192.168.1.2")).is_some());
+        assert!(diversity.should_prune(&second));
+
+        diversity.record_disconnected(&second);
+        assert!(!diversity.should_prune(&first));
+        assert!(diversity.record_connected(second, &addr("192.168.1.2")).is_some());
+    }
+
+    #[test]
+    fn test_subnet_diversity_ignores_non_ip_peers() {
+        let mut diversity = SubnetDiversity::new(config(0));
+        let addr = "/dns4/example.com/tcp/9222".parse().unwrap();
+        assert!(diversity.record_connected(PeerId::random(), &addr).is_none());
+    }
+}
@@ -1,10 +1,40 @@
 //! Consensus-layer gossipsub driver for Optimism.
 
+use alloy_primitives::B256;
 use discv5::Enr;
 use futures::stream::StreamExt;
-use libp2p::{Multiaddr, Swarm, TransportError, swarm::SwarmEvent};
+use libp2p::{
+    Multiaddr, Swarm,
+    gossipsub::{Message, MessageAcceptance, MessageId, PublishError, TopicHash},
+    swarm::SwarmEvent,
+};
 
-use crate::{Behaviour, BlockHandler, Event, Handler, OpStackEnr, enr_to_multiaddr};
+use crate::{
+    Behaviour, BehaviourError, BlockHandler, DriverEvent, DuplicateTracker, Event, GossipMetrics,
+    Handler, MAX_CONCURRENT_VALIDATIONS, ObservedAddresses, OpStackEnr, OutboundPublish, PeerChurn,
+    RejectionCounts, SubnetDiversity, SubnetDiversityConfig, TopicIsolation, TopicValidationMode,
+    ValidationResult, enr_to_multiaddr, gossip::handler::unix_timestamp,
+};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::{
+    sync::{OwnedSemaphorePermit, Semaphore},
+    task::JoinSet,
+};
+
+/// The interval at which subscriptions are checked while awaiting their confirmation.
+const SUBSCRIPTION_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// None of the listen addresses of the [`GossipDriver`] could be bound.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("none of the listen addresses could be bound: {failed:?}")]
+pub struct NoListenAddrBound {
+    /// The listen addresses that failed to bind.
+    pub failed: Vec<Multiaddr>,
+}
 
 /// A driver for a [`Swarm`] instance.
 ///
@@ -15,21 +45,218 @@ pub struct GossipDriver {
     pub swarm: Swarm<Behaviour>,
     /// A [`Multiaddr`] to listen on.
     pub addr: Multiaddr,
+    /// Additional [`Multiaddr`]s to listen on, such as the IPv6 address of a dual-stack node.
+    pub listen_addrs: Vec<Multiaddr>,
     /// The [`BlockHandler`].
     pub handler: BlockHandler,
+    /// Tracks the peer churn of the swarm.
+    pub churn: PeerChurn,
+    /// Tracks the subnet diversity of the swarm's peers, if enabled.
+    pub diversity: Option<SubnetDiversity>,
+    /// The duration within which the [`BlockHandler`] must validate a message before it is
+    /// ignored.
+    pub validation_timeout: Duration,
+    /// Tracks the addresses that peers observe the node at.
+    pub observed: ObservedAddresses,
+    /// The validation mode of each topic. Topics without one are validated strictly.
+    pub validation_modes: HashMap<TopicHash, TopicValidationMode>,
+    /// Tracks duplicate blocks and penalizes the peers that send them, if enabled.
+    pub duplicates: Option<DuplicateTracker>,
+    /// The application score of each connected peer, composed of the adjustments made with
+    /// [`GossipDriver::adjust_application_score`].
+    pub application_scores: HashMap<libp2p::PeerId, f64>,
+    /// Tracks the subscribed topics whose mesh has no remote peers.
+    pub isolation: TopicIsolation,
+    /// The number of messages rejected by the [`BlockHandler`], for each reason.
+    pub rejections: RejectionCounts,
+    /// The messages being validated by the [`BlockHandler`], off the swarm task.
+    validations: JoinSet<Validation>,
+    /// Bounds the number of messages validated at once. A permit is held until the handler
+    /// returns, even if its validation timed out.
+    validation_permits: Arc<Semaphore>,
+}
+
+/// A message validated by the [`BlockHandler`] of a [`GossipDriver`], whose result has not been
+/// reported to gossipsub yet.
+#[derive(Debug)]
+struct Validation {
+    /// The id of the message.
+    id: MessageId,
+    /// The peer that propagated the message.
+    source: libp2p::PeerId,
+    /// The topic of the message.
+    topic: TopicHash,
+    /// The result of the validation.
+    result: ValidationResult,
 }
 
 impl GossipDriver {
     /// Creates a new [`GossipDriver`] instance.
     pub fn new(swarm: Swarm<Behaviour>, addr: Multiaddr, handler: BlockHandler) -> Self {
-        Self { swarm, addr, handler }
+        Self {
+            swarm,
+            addr,
+            listen_addrs: Vec::new(),
+            handler,
+            churn: PeerChurn::default(),
+            diversity: None,
+            validation_timeout: *crate::VALIDATION_TIMEOUT,
+            observed: ObservedAddresses::default(),
+            validation_modes: HashMap::new(),
+            duplicates: None,
+            application_scores: HashMap::new(),
+            isolation: TopicIsolation::default(),
+            rejections: RejectionCounts::default(),
+            validations: JoinSet::new(),
+            validation_permits: Arc::new(Semaphore::new(MAX_CONCURRENT_VALIDATIONS)),
+        }
+    }
+
+    /// Sets the [`TopicValidationMode`] of the given topic.
+    pub fn with_validation_mode(mut self, topic: TopicHash, mode: TopicValidationMode) -> Self {
+        self.validation_modes.insert(topic, mode);
+        self
+    }
+
+    /// Returns the [`TopicValidationMode`] of the given topic.
+    pub fn validation_mode(&self, topic: &TopicHash) -> TopicValidationMode {
+        self.validation_modes.get(topic).copied().unwrap_or_default()
+    }
+
+    /// Sets the duration within which the [`BlockHandler`] must validate a message before it is
+    /// ignored.
+    pub const fn with_validation_timeout(mut self, timeout: Duration) -> Self {
+        self.validation_timeout = timeout;
+        self
     }
 
-    /// Listens on the address.
-    pub fn listen(&mut self) -> Result<(), TransportError<std::io::Error>> {
-        self.swarm.listen_on(self.addr.clone())?;
-        info!("Swarm listening on: {:?}", self.addr);
-        Ok(())
+    /// Enables subnet diversity accounting with the given thresholds.
+    ///
+    /// A warning is logged when too many peers share a subnet, and if pruning is enabled, peers
+    /// that push their subnet over the limit are disconnected.
+    pub fn with_subnet_diversity(mut self, config: SubnetDiversityConfig) -> Self {
+        self.diversity = Some(SubnetDiversity::new(config));
+        self
+    }
+
+    /// Enables penalizing peers that send duplicate blocks, lowering their application score by
+    /// `penalty` for each duplicate. Duplicate blocks are validated, and ignored rather than
+    /// accepted.
+    ///
+    /// The application score only affects peers if peer scoring is enabled on the gossipsub
+    /// behaviour.
+    pub fn with_duplicate_penalty(mut self, penalty: f64) -> Self {
+        self.duplicates = Some(DuplicateTracker::new(penalty));
+        self
+    }
+
+    /// Adds `delta` to the application score of the given peer.
+    ///
+    /// The application score of a peer is the sum of all adjustments made to it, so that
+    /// independent reputation signals compose rather than overwrite each other. It is forgotten
+    /// once the peer disconnects. Returns `false` if the score could not be applied to the
+    /// gossipsub behaviour, see [`Behaviour::set_application_score`].
+    pub fn adjust_application_score(&mut self, peer: libp2p::PeerId, delta: f64) -> bool {
+        let score = self.application_scores.entry(peer).or_default();
+        *score += delta;
+        let score = *score;
+        self.swarm.behaviour_mut().set_application_score(&peer, score)
+    }
+
+    /// Penalizes the peer that propagated a block if its payload was already accepted, returning
+    /// `true` if it was.
+    fn penalize_duplicate(&mut self, peer: libp2p::PeerId, hash: B256) -> bool {
+        let Some(duplicates) = self.duplicates.as_mut() else {
+            return false;
+        };
+        let Some(total) = duplicates.record(peer, hash) else {
+            return false;
+        };
+        let penalty = duplicates.penalty();
+        debug!(target: "p2p::gossip::driver", "Duplicate block from peer {}, total penalty {}", peer, total);
+        self.adjust_application_score(peer, -penalty);
+        true
+    }
+
+    /// Sets additional addresses to listen on, alongside the primary address.
+    pub fn with_listen_addrs(mut self, addrs: Vec<Multiaddr>) -> Self {
+        self.listen_addrs = addrs;
+        self
+    }
+
+    /// Listens on the primary address and each of the additional listen addresses.
+    ///
+    /// Addresses that fail to bind are logged and skipped, and the outcome is reported as an
+    /// [`DriverEvent::Listening`]. Returns [`NoListenAddrBound`] if none of the addresses could be
+    /// bound.
+    pub fn listen(&mut self) -> Result<DriverEvent, NoListenAddrBound> {
+        let mut bound = Vec::new();
+        let mut failed = Vec::new();
+        let addrs = std::iter::once(self.addr.clone()).chain(self.listen_addrs.iter().cloned());
+        for addr in addrs {
+            match self.swarm.listen_on(addr.clone()) {
+                Ok(_) => {
+                    info!(target: "p2p::gossip::driver", "Swarm listening on: {}", addr);
+                    bound.push(addr);
+                }
+                Err(e) => {
+                    warn!(target: "p2p::gossip::driver", "Failed to listen on {}: {:?}", addr, e);
+                    failed.push(addr);
+                }
+            }
+        }
+
+        if bound.is_empty() {
+            return Err(NoListenAddrBound { failed });
+        }
+        Ok(DriverEvent::Listening { bound, failed })
+    }
+
+    /// Waits until the swarm is subscribed to every active topic of the [`BlockHandler`].
+    ///
+    /// Returns [`BehaviourError::SubscriptionTimeout`] if the subscriptions are not confirmed
+    /// within the given timeout.
+    pub async fn confirm_subscriptions(&mut self, timeout: Duration) -> Result<(), BehaviourError> {
+        let topics = self.handler.active_topics(unix_timestamp());
+        // The driver is borrowed mutably, so that the future is `Send` without the swarm being
+        // `Sync`.
+        let driver = &mut *self;
+        let confirmed = async move {
+            while !topics.iter().all(|topic| driver.swarm.behaviour().is_subscribed(topic)) {
+                tokio::time::sleep(SUBSCRIPTION_POLL_INTERVAL).await;
+            }
+        };
+
+        tokio::time::timeout(timeout, confirmed)
+            .await
+            .map_err(|_| BehaviourError::SubscriptionTimeout(timeout))
+    }
+
+    /// Subscribes to the topics of the [`BlockHandler`] that have become active since they were
+    /// last synced, such as when a hardfork of its fork schedule activates. Topics that are
+    /// already subscribed to are skipped.
+    pub fn sync_subscriptions(&mut self) -> Result<(), BehaviourError> {
+        self.sync_subscriptions_at(unix_timestamp())
+    }
+
+    /// Subscribes to the topics of the [`BlockHandler`] that are active at the given timestamp.
+    fn sync_subscriptions_at(&mut self, timestamp: u64) -> Result<(), BehaviourError> {
+        let behaviour = self.swarm.behaviour_mut();
+        let topics = self
+            .handler
+            .active_topics(timestamp)
+            .into_iter()
+            .filter(|topic| !behaviour.is_subscribed(topic))
+            .collect::<Vec<_>>();
+        for topic in &topics {
+            info!(target: "p2p::gossip::driver", "Subscribing to newly active topic {}", topic);
+        }
+        behaviour.subscribe_topics(topics)
+    }
+
+    /// Publishes an [`OutboundPublish`] to the gossip network.
+    pub fn publish(&mut self, publish: OutboundPublish) -> Result<MessageId, PublishError> {
+        self.swarm.behaviour_mut().gossipsub.publish(publish.topic, publish.data)
     }
 
     /// Returns the local peer id.
@@ -52,11 +279,38 @@ impl GossipDriver {
         self.swarm.external_addresses().any(|a| *a == *addr)
     }
 
+    /// Returns the confirmed external addresses of the node, as observed by its peers over
+    /// `identify`.
+    pub fn external_addresses(&self) -> Vec<Multiaddr> {
+        self.swarm.external_addresses().cloned().collect()
+    }
+
     /// Returns the number of connected peers.
     pub fn connected_peers(&self) -> usize {
         self.swarm.connected_peers().count()
     }
 
+    /// Checks the mesh of every subscribed topic, returning an [`DriverEvent::TopicIsolated`] for
+    /// each topic whose mesh has had zero remote peers for the [`TopicIsolation`] grace period.
+    /// This is meant to be called on every gossipsub heartbeat, once the mesh has been
+    /// maintained.
+    pub fn check_topic_isolation(&mut self) -> Vec<DriverEvent> {
+        let gossipsub = &self.swarm.behaviour().gossipsub;
+        let meshes =
+            gossipsub.topics().map(|topic| (topic.clone(), gossipsub.mesh_peers(topic).count()));
+        self.isolation.record(meshes, Instant::now())
+    }
+
+    /// Returns a snapshot of the [`GossipMetrics`].
+    pub fn metrics(&mut self) -> GossipMetrics {
+        GossipMetrics {
+            connected_peers: self.connected_peers(),
+            peer_churn: self.churn.total(),
+            churn_rate: self.churn.rate(Instant::now()),
+            rejections: self.rejections,
+        }
+    }
+
     /// Dials the given [`Enr`].
     pub fn dial(&mut self, enr: Enr) {
         let key = OpStackEnr::OP_CL_KEY.as_bytes();
@@ -86,8 +340,89 @@ impl GossipDriver {
         }
     }
 
+    /// Waits for the next event of the swarm or the next message validated by the
+    /// [`BlockHandler`], and handles it, returning the resulting [`DriverEvent`], if any.
+    ///
+    /// Swarm events are handled with [`GossipDriver::handle_event`], and validation results are
+    /// reported with [`GossipDriver::next_validated`]. Blocks buffered by the [`BlockHandler`]
+    /// while the engine was syncing are forwarded as soon as syncing has finished.
+    pub async fn next_event(&mut self) -> Option<DriverEvent> {
+        tokio::select! {
+            event = self.swarm.select_next_some() => self.handle_event(event).await,
+            Some(validation) = self.validations.join_next() => self.report_validation(validation),
+            _ = self.handler.flush_when_synced() => None,
+        }
+    }
+
+    /// Waits for the next message validated by the [`BlockHandler`], and reports its result to
+    /// gossipsub. Invalid messages accepted on a permissive topic are returned as an
+    /// [`DriverEvent::FlaggedMessage`].
+    ///
+    /// Returns `None` immediately if no message is being validated.
+    pub async fn next_validated(&mut self) -> Option<DriverEvent> {
+        let validation = self.validations.join_next().await?;
+        self.report_validation(validation)
+    }
+
+    /// Reports the result of a [`Validation`] to gossipsub.
+    fn report_validation(
+        &mut self,
+        validation: Result<Validation, tokio::task::JoinError>,
+    ) -> Option<DriverEvent> {
+        let Validation { id, source, topic, result } = match validation {
+            Ok(validation) => validation,
+            Err(e) => {
+                warn!(target: "p2p::gossip::driver", "Message validation task failed: {:?}", e);
+                return None;
+            }
+        };
+        self.rejections.record(&result);
+        if let Some(reason) = result.reason {
+            debug!(target: "p2p::gossip::driver", "Message on topic {} rejected: {}", topic, reason);
+        }
+        let mut status = result.acceptance;
+
+        // Ignore accepted blocks whose payload was already accepted, penalizing their sender.
+        if let (MessageAcceptance::Accept, Some(hash)) = (&status, result.payload_hash) {
+            if self.penalize_duplicate(source, hash) {
+                status = MessageAcceptance::Ignore;
+            } else if let Some(duplicates) = self.duplicates.as_mut() {
+                duplicates.record_accepted(hash);
+            }
+        }
+
+        // Forward invalid messages on permissive topics, flagging them.
+        let flagged = matches!(status, MessageAcceptance::Reject) &&
+            self.validation_mode(&topic) == TopicValidationMode::Permissive;
+        if flagged {
+            warn!(target: "p2p::gossip::driver", "Accepting invalid message on permissive topic: {}", topic);
+            status = MessageAcceptance::Accept;
+        }
+
+        debug!(target: "p2p::gossip::driver", "Reporting message validation result: {:?}", status);
+        _ = self
+            .swarm
+            .behaviour_mut()
+            .gossipsub
+            .report_message_validation_result(&id, &source, status);
+        flagged.then_some(DriverEvent::FlaggedMessage {
+            topic,
+            message_id: id,
+            propagation_source: source,
+        })
+    }
+
     /// Handles a [`libp2p::gossipsub::Event`].
-    fn handle_gossipsub_event(&mut self, event: libp2p::gossipsub::Event) {
+    ///
+    /// Messages are validated with [`validate_with_timeout`] off the swarm task, so that a slow
+    /// handler does not stall the driver, and their results are reported by
+    /// [`GossipDriver::next_validated`]. Duplicate blocks are detected from the payload hash
+    /// returned by the handler, so that messages are only decoded off the swarm task. At most
+    /// [`MAX_CONCURRENT_VALIDATIONS`] messages are validated at once, and further messages are
+    /// ignored until a validation completes. Messages on topics without a handler, such as
+    /// those subscribed to with [`Behaviour::subscribe_topics`], are ignored for propagation
+    /// and returned as a raw [`DriverEvent::Gossipsub`].
+    fn handle_gossipsub_event(&mut self, event: libp2p::gossipsub::Event) -> Option<DriverEvent> {
         match event {
             libp2p::gossipsub::Event::Message {
                 propagation_source: src,
@@ -96,34 +431,649 @@ impl GossipDriver {
             } => {
                 trace!(target: "p2p::gossip::driver", "Received message with topic: {}", message.topic);
                 if self.handler.topics().contains(&message.topic) {
+                    let Ok(permit) = self.validation_permits.clone().try_acquire_owned() else {
+                        debug!(target: "p2p::gossip::driver", "Too many messages in validation, ignoring message on topic: {}", message.topic);
+                        _ = self.swarm.behaviour_mut().gossipsub.report_message_validation_result(
+                            &id,
+                            &src,
+                            MessageAcceptance::Ignore,
+                        );
+                        return None;
+                    };
+
                     debug!(target: "p2p::gossip::driver", "Handling message with topic: {}", message.topic);
-                    let status = self.handler.handle(message);
-                    debug!(target: "p2p::gossip::driver", "Reporting message validation result: {:?}", status);
-                    _ = self
-                        .swarm
-                        .behaviour_mut()
-                        .gossipsub
-                        .report_message_validation_result(&id, &src, status);
+                    let handler = self.handler.clone();
+                    let timeout = self.validation_timeout;
+                    self.validations.spawn(async move {
+                        let topic = message.topic.clone();
+                        let result =
+                            validate_with_timeout(&handler, message, timeout, permit).await;
+                        Validation { id, source: src, topic, result }
+                    });
+                    return None;
                 }
+                debug!(target: "p2p::gossip::driver", "Surfacing message on topic without handler: {}", message.topic);
+                _ = self.swarm.behaviour_mut().gossipsub.report_message_validation_result(
+                    &id,
+                    &src,
+                    MessageAcceptance::Ignore,
+                );
+                Some(DriverEvent::Gossipsub(libp2p::gossipsub::Event::Message {
+                    propagation_source: src,
+                    message_id: id,
+                    message,
+                }))
             }
             _ => {
-                warn!(target: "p2p::gossip::driver", "Ignoring non-message gossipsub event: {:?}", event)
+                warn!(target: "p2p::gossip::driver", "Ignoring non-message gossipsub event: {:?}", event);
+                None
             }
         }
     }
 
-    /// Handles the [`SwarmEvent<Event>`].
-    pub fn handle_event(&mut self, event: SwarmEvent<Event>) {
-        let SwarmEvent::Behaviour(event) = event else {
-            warn!(target: "p2p::gossip::driver", "Ignoring non-behaviour in event handler: {:?}", event);
+    /// Records a connection to a peer with the [`SubnetDiversity`] tracker, if enabled,
+    /// disconnecting the peer if its subnet is over the limit and pruning is enabled.
+    fn record_subnet_diversity(&mut self, peer: libp2p::PeerId, addr: &Multiaddr) {
+        let Some(diversity) = self.diversity.as_mut() else {
             return;
         };
+        if let Some(DriverEvent::LowSubnetDiversity { subnet, peers }) =
+            diversity.record_connected(peer, addr)
+        {
+            warn!(target: "p2p::gossip::driver", "Low subnet diversity: {} peers in subnet {}", peers, subnet);
+        }
+        if diversity.should_prune(&peer) {
+            debug!(target: "p2p::gossip::driver", "Pruning peer {} from crowded subnet", peer);
+            _ = self.swarm.disconnect_peer_id(peer);
+        }
+    }
+
+    /// Records the address a peer observes the node at, adding it to the swarm's external
+    /// addresses once it is confirmed by the [`ObservedAddresses`] tracker.
+    fn record_observed_addr(&mut self, peer: libp2p::PeerId, addr: Multiaddr) {
+        if let Some(addr) = self.observed.record(peer, addr) {
+            info!(target: "p2p::gossip::driver", "Confirmed external address: {}", addr);
+            self.swarm.add_external_address(addr);
+        }
+    }
+
+    /// Handles the [`SwarmEvent<Event>`].
+    ///
+    /// Peer connects and disconnects are recorded by the [`PeerChurn`] tracker, and an
+    /// [`DriverEvent::HighChurn`] is returned when the churn rate crosses its threshold. If
+    /// enabled, they are also recorded by the [`SubnetDiversity`] tracker. Gossip messages on
+    /// topics without a handler are returned as a raw [`DriverEvent::Gossipsub`], and invalid
+    /// messages accepted on a permissive topic are returned as an
+    /// [`DriverEvent::FlaggedMessage`].
+    pub async fn handle_event(&mut self, event: SwarmEvent<Event>) -> Option<DriverEvent> {
+        let event = match event {
+            SwarmEvent::Behaviour(event) => event,
+            SwarmEvent::ConnectionEstablished { peer_id, endpoint, .. } => {
+                self.record_subnet_diversity(peer_id, endpoint.get_remote_address());
+                return self.churn.record(Instant::now());
+            }
+            SwarmEvent::ConnectionClosed { peer_id, num_established, .. } => {
+                if num_established == 0 {
+                    self.observed.remove(&peer_id);
+                    self.application_scores.remove(&peer_id);
+                    if let Some(duplicates) = self.duplicates.as_mut() {
+                        duplicates.remove(&peer_id);
+                    }
+                    if let Some(diversity) = self.diversity.as_mut() {
+                        diversity.record_disconnected(&peer_id);
+                    }
+                }
+                return self.churn.record(Instant::now());
+            }
+            SwarmEvent::NewListenAddr { address, .. } => {
+                info!(target: "p2p::gossip::driver", "Swarm bound listen address: {}", address);
+                return None;
+            }
+            SwarmEvent::ListenerError { error, .. } => {
+                warn!(target: "p2p::gossip::driver", "Swarm listener error: {:?}", error);
+                return None;
+            }
+            event => {
+                warn!(target: "p2p::gossip::driver", "Ignoring non-behaviour in event handler: {:?}", event);
+                return None;
+            }
+        };
 
         match event {
             Event::Ping(libp2p::ping::Event { peer, result, .. }) => {
                 trace!(target: "p2p::gossip::driver", "Ping from peer: {:?} | Result: {:?}", peer, result);
             }
-            Event::Gossipsub(e) => self.handle_gossipsub_event(e),
+            Event::Gossipsub(e) => return self.handle_gossipsub_event(e),
+            Event::Identify(e) => {
+                if let libp2p::identify::Event::Received { peer_id, info, .. } = *e {
+                    trace!(target: "p2p::gossip::driver", "Peer {} observes us at {}", peer_id, info.observed_addr);
+                    self.swarm.behaviour_mut().peers.record_identify(&peer_id, &info);
+                    self.record_observed_addr(peer_id, info.observed_addr);
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Validates a message with [`Handler::validate`] on a blocking thread, treating it as
+/// [`MessageAcceptance::Ignore`] if the handler does not complete within `timeout`.
+///
+/// Ignoring rather than rejecting the message avoids penalizing the peer that propagated it for
+/// a slow handler. A handler that times out cannot be cancelled, so it is left to finish on its
+/// thread, and its result is discarded. The `permit` is held by the thread until the handler
+/// returns, which bounds the number of threads that timed-out handlers may occupy.
+pub async fn validate_with_timeout<H>(
+    handler: &H,
+    message: Message,
+    timeout: Duration,
+    permit: OwnedSemaphorePermit,
+) -> ValidationResult
+where
+    H: Handler + Clone + 'static,
+{
+    let handler = handler.clone();
+    let validation = tokio::task::spawn_blocking(move || {
+        let _permit = permit;
+        handler.validate(message)
+    });
+    match tokio::time::timeout(timeout, validation).await {
+        Ok(Ok(result)) => result,
+        Ok(Err(e)) => {
+            warn!(target: "p2p::gossip::driver", "Message validation failed: {:?}", e);
+            ValidationResult::ignore()
+        }
+        Err(_) => {
+            warn!(target: "p2p::gossip::driver", "Message validation timed out after {:?}, ignoring message", timeout);
+            ValidationResult::ignore()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RejectionReason;
+    use alloy_primitives::{Address, Bloom, Bytes, PrimitiveSignature, U256};
+    use alloy_rpc_types_engine::ExecutionPayloadV1;
+    use kona_genesis::{HardForkConfig, RollupConfig};
+    use libp2p::{
+        SwarmBuilder, Transport,
+        core::{ConnectedPoint, transport::MemoryTransport, upgrade::Version},
+        gossipsub::{
+            IdentTopic, MessageAuthenticity, PeerScoreParams, PeerScoreThresholds, TopicHash,
+        },
+        swarm::{ConnectionId, FromSwarm, NetworkBehaviour, behaviour::ConnectionEstablished},
+    };
+    use op_alloy_rpc_types_engine::{OpExecutionPayload, PayloadHash};
+    use ssz::Encode;
+    use std::sync::{Arc, Mutex};
+
+    fn test_driver() -> GossipDriver {
+        let (_, recv) = tokio::sync::watch::channel(Address::default());
+        let (handler, _) = BlockHandler::new(0, recv);
+        test_driver_with(handler)
+    }
+
+    fn test_driver_with(handler: BlockHandler) -> GossipDriver {
+        let cfg = crate::default_config().unwrap();
+        let behaviour =
+            Behaviour::new(cfg, &[Box::new(handler.clone())], MessageAuthenticity::Anonymous)
+                .unwrap();
+        let swarm = SwarmBuilder::with_new_identity()
+            .with_tokio()
+            .with_tcp(
+                Default::default(),
+                libp2p::noise::Config::new,
+                libp2p::yamux::Config::default,
+            )
+            .unwrap()
+            .with_behaviour(|_| behaviour)
+            .unwrap()
+            .build();
+        GossipDriver::new(swarm, "/ip4/127.0.0.1/tcp/0".parse().unwrap(), handler)
+    }
+
+    /// Returns a [GossipDriver] for the given handler, whose swarm runs over the in-memory
+    /// transport and listens on a random memory port.
+    fn memory_driver(handler: BlockHandler) -> GossipDriver {
+        let cfg = crate::default_config().unwrap();
+        let behaviour =
+            Behaviour::new(cfg, &[Box::new(handler.clone())], MessageAuthenticity::Anonymous)
+                .unwrap();
+        let swarm = SwarmBuilder::with_new_identity()
+            .with_tokio()
+            .with_other_transport(|key| {
+                MemoryTransport::default()
+                    .upgrade(Version::V1)
+                    .authenticate(libp2p::noise::Config::new(key).unwrap())
+                    .multiplex(libp2p::yamux::Config::default())
+            })
+            .unwrap()
+            .with_behaviour(|_| behaviour)
+            .unwrap()
+            .with_swarm_config(|c| c.with_idle_connection_timeout(Duration::from_secs(30)))
+            .build();
+        GossipDriver::new(swarm, "/memory/0".parse().unwrap(), handler)
+    }
+
+    /// A handler whose validation blocks until the paired sender is dropped.
+    #[derive(Debug, Clone)]
+    struct HangingHandler(Arc<Mutex<std::sync::mpsc::Receiver<()>>>);
+
+    impl Handler for HangingHandler {
+        fn handle(&self, _: Message) -> MessageAcceptance {
+            _ = self.0.lock().unwrap().recv();
+            MessageAcceptance::Accept
+        }
+
+        fn topics(&self) -> Vec<TopicHash> {
+            vec![]
+        }
+    }
+
+    fn test_message() -> Message {
+        Message {
+            source: None,
+            data: vec![],
+            sequence_number: None,
+            topic: TopicHash::from_raw("test"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_validate_with_timeout_ignores_hanging_handler() {
+        let (release, recv) = std::sync::mpsc::channel();
+        let handler = HangingHandler(Arc::new(Mutex::new(recv)));
+
+        let permits = Arc::new(Semaphore::new(1));
+        let permit = permits.clone().try_acquire_owned().unwrap();
+
+        let timeout = Duration::from_millis(50);
+        let result = validate_with_timeout(&handler, test_message(), timeout, permit).await;
+        assert!(matches!(result.acceptance, MessageAcceptance::Ignore));
+
+        // The permit is held until the handler returns, even though it timed out.
+        assert_eq!(permits.available_permits(), 0);
+        drop(release);
+        let _permit =
+            tokio::time::timeout(Duration::from_secs(5), permits.acquire()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_validate_with_timeout_completes() {
+        let (release, recv) = std::sync::mpsc::channel();
+        let handler = HangingHandler(Arc::new(Mutex::new(recv)));
+        drop(release);
+
+        let permit = Arc::new(Semaphore::new(1)).try_acquire_owned().unwrap();
+        let result =
+            validate_with_timeout(&handler, test_message(), Duration::from_secs(5), permit).await;
+        assert!(matches!(result.acceptance, MessageAcceptance::Accept));
+    }
+
+    #[tokio::test]
+    async fn test_external_addresses_from_identify() {
+        let mut driver = test_driver();
+        let addr: Multiaddr = "/ip4/203.0.113.7/tcp/9222".parse().unwrap();
+        assert!(driver.external_addresses().is_empty());
+
+        for _ in 0..crate::MIN_ADDRESS_OBSERVERS {
+            driver.record_observed_addr(libp2p::PeerId::random(), addr.clone());
+        }
+        assert_eq!(driver.external_addresses(), vec![addr]);
+    }
+
+    #[tokio::test]
+    async fn test_listen_reports_bound_addrs() {
+        // 203.0.113.0/24 is reserved for documentation, so it is not assigned to a local interface.
+        let unbindable: Multiaddr = "/ip4/203.0.113.1/tcp/0".parse().unwrap();
+        let mut driver = test_driver().with_listen_addrs(vec![unbindable.clone()]);
+
+        let DriverEvent::Listening { bound, failed } = driver.listen().unwrap() else {
+            panic!("expected a listening event");
+        };
+        assert_eq!(bound, vec![driver.addr.clone()]);
+        assert_eq!(failed, vec![unbindable]);
+    }
+
+    #[tokio::test]
+    async fn test_listen_fails_if_none_bound() {
+        let unbindable: Multiaddr = "/ip4/203.0.113.1/tcp/0".parse().unwrap();
+        let mut driver = test_driver();
+        driver.addr = unbindable.clone();
+
+        assert_eq!(driver.listen().unwrap_err(), NoListenAddrBound { failed: vec![unbindable] });
+    }
+
+    #[tokio::test]
+    async fn test_unhandled_topic_message_surfaced() {
+        let mut driver = test_driver();
+        let topic = IdentTopic::new("/optimism/10/2/blocks").hash();
+        driver.behaviour_mut().subscribe_topics([topic.clone()]).unwrap();
+
+        let message = Message { topic: topic.clone(), ..test_message() };
+        let event = libp2p::gossipsub::Event::Message {
+            propagation_source: libp2p::PeerId::random(),
+            message_id: MessageId(vec![1]),
+            message,
+        };
+        let surfaced = driver.handle_event(SwarmEvent::Behaviour(Event::Gossipsub(event))).await;
+        assert!(matches!(
+            surfaced,
+            Some(DriverEvent::Gossipsub(libp2p::gossipsub::Event::Message { message, .. }))
+                if message.topic == topic
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_permissive_topic_flags_invalid_message() {
+        let mut driver = test_driver();
+        // The handler rejects every message on the v4 topic.
+        let topic = driver.handler.topics()[3].clone();
+        let message = || libp2p::gossipsub::Event::Message {
+            propagation_source: libp2p::PeerId::random(),
+            message_id: MessageId(vec![1]),
+            message: Message { topic: topic.clone(), ..test_message() },
+        };
+
+        assert_eq!(driver.validation_mode(&topic), TopicValidationMode::Strict);
+        let event = driver.handle_event(SwarmEvent::Behaviour(Event::Gossipsub(message()))).await;
+        assert!(event.is_none());
+        assert!(driver.next_validated().await.is_none());
+
+        let mut driver =
+            driver.with_validation_mode(topic.clone(), TopicValidationMode::Permissive);
+        driver.handle_event(SwarmEvent::Behaviour(Event::Gossipsub(message()))).await;
+        let event = driver.next_validated().await;
+        assert!(matches!(
+            event,
+            Some(DriverEvent::FlaggedMessage { topic: t, message_id, .. })
+                if t == topic && message_id == MessageId(vec![1])
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rejection_reasons_counted() {
+        let mut driver = test_driver();
+        let topic = driver.handler.topics()[0].clone();
+        let signed_block = |timestamp: u64| {
+            let payload = ExecutionPayloadV1 {
+                parent_hash: B256::ZERO,
+                fee_recipient: Address::default(),
+                state_root: B256::ZERO,
+                receipts_root: B256::ZERO,
+                logs_bloom: Bloom::default(),
+                prev_randao: B256::ZERO,
+                block_number: 1,
+                gas_limit: 0,
+                gas_used: 0,
+                timestamp,
+                extra_data: Bytes::default(),
+                base_fee_per_gas: U256::from(0),
+                block_hash: B256::ZERO,
+                transactions: vec![],
+            };
+            let signature = PrimitiveSignature::test_signature();
+            let data = [signature.as_bytes().as_slice(), &payload.as_ssz_bytes()].concat();
+            snap::raw::Encoder::new().compress_vec(&data).unwrap()
+        };
+
+        // The test signature is not the unsafe block signer's.
+        let bad_signature = signed_block(unix_timestamp());
+        let stale = signed_block(0);
+        // A snappy frame whose header claims a 1 GiB decompressed size.
+        let oversized = vec![0x80, 0x80, 0x80, 0x80, 0x04, 0, 0, 0, 0];
+        let decode_error = snap::raw::Encoder::new().compress_vec(&[0u8; 10]).unwrap();
+
+        for (i, data) in [bad_signature, stale, oversized, decode_error].into_iter().enumerate() {
+            let event = libp2p::gossipsub::Event::Message {
+                propagation_source: libp2p::PeerId::random(),
+                message_id: MessageId(vec![i as u8]),
+                message: Message { data, topic: topic.clone(), ..test_message() },
+            };
+            driver.handle_event(SwarmEvent::Behaviour(Event::Gossipsub(event))).await;
+        }
+        for _ in 0..4 {
+            driver.next_validated().await;
+        }
+
+        let rejections = driver.metrics().rejections;
+        for reason in [
+            RejectionReason::BadSignature,
+            RejectionReason::Stale,
+            RejectionReason::Oversized,
+            RejectionReason::DecodeError,
+        ] {
+            assert_eq!(rejections.get(reason), 1, "{reason}");
+        }
+        assert_eq!(rejections.total(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_blocks_lower_peer_score() {
+        let payload = ExecutionPayloadV1 {
+            parent_hash: B256::ZERO,
+            fee_recipient: Address::default(),
+            state_root: B256::ZERO,
+            receipts_root: B256::ZERO,
+            logs_bloom: Bloom::default(),
+            prev_randao: B256::ZERO,
+            block_number: 1,
+            gas_limit: 0,
+            gas_used: 0,
+            timestamp: unix_timestamp(),
+            extra_data: Bytes::default(),
+            base_fee_per_gas: U256::from(0),
+            block_hash: B256::ZERO,
+            transactions: vec![],
+        };
+        let encoded = payload.as_ssz_bytes();
+        let msg = PayloadHash::from(encoded.as_slice()).signature_message(10);
+        let valid = PrimitiveSignature::test_signature();
+        let signer = valid.recover_address_from_prehash(&msg).unwrap();
+        let (_, signer_recv) = tokio::sync::watch::channel(signer);
+        let (handler, _blocks) = BlockHandler::new(10, signer_recv);
+
+        let mut driver = test_driver_with(handler).with_duplicate_penalty(0.5);
+        let gossipsub = &mut driver.behaviour_mut().gossipsub;
+        gossipsub
+            .with_peer_score(PeerScoreParams::default(), PeerScoreThresholds::default())
+            .unwrap();
+
+        let peer = libp2p::PeerId::random();
+        let addr: Multiaddr = "/ip4/127.0.0.1/tcp/9000".parse().unwrap();
+        gossipsub.on_swarm_event(FromSwarm::ConnectionEstablished(ConnectionEstablished {
+            peer_id: peer,
+            connection_id: ConnectionId::new_unchecked(0),
+            endpoint: &ConnectedPoint::Listener { local_addr: addr.clone(), send_back_addr: addr },
+            failed_addresses: &[],
+            other_established: 0,
+        }));
+
+        // Messages carrying the same payload with the given signature.
+        let topic = driver.handler.topics()[0].clone();
+        let block = |id: u64, signature: PrimitiveSignature| {
+            let data = [signature.as_bytes().as_slice(), &encoded].concat();
+            libp2p::gossipsub::Event::Message {
+                propagation_source: peer,
+                message_id: MessageId(id.to_be_bytes().to_vec()),
+                message: Message {
+                    data: snap::raw::Encoder::new().compress_vec(&data).unwrap(),
+                    topic: topic.clone(),
+                    ..test_message()
+                },
+            }
+        };
+        let invalid = PrimitiveSignature::new(U256::from(1), U256::from(1), false);
+
+        // The handler rejects the invalid copy, so its payload is not remembered, and an invalid
+        // copy of a block cannot front-run the valid block.
+        driver.handle_event(SwarmEvent::Behaviour(Event::Gossipsub(block(0, invalid)))).await;
+        driver.next_validated().await;
+        driver.handle_event(SwarmEvent::Behaviour(Event::Gossipsub(block(1, valid)))).await;
+        driver.next_validated().await;
+        assert_eq!(driver.duplicates.as_ref().unwrap().peer_penalty(&peer), 0.0);
+
+        // Copies of an accepted payload are duplicates, detected once they are validated.
+        let mut score = driver.behaviour_mut().gossipsub.peer_score(&peer).unwrap();
+        for id in 2..5 {
+            driver.handle_event(SwarmEvent::Behaviour(Event::Gossipsub(block(id, valid)))).await;
+            driver.next_validated().await;
+            let lowered = driver.behaviour_mut().gossipsub.peer_score(&peer).unwrap();
+            assert!(lowered < score);
+            score = lowered;
         }
+        assert_eq!(driver.duplicates.as_ref().unwrap().peer_penalty(&peer), 1.5);
+        assert_eq!(driver.application_scores[&peer], -1.5);
+
+        // Other adjustments compose with the duplicate penalty.
+        assert!(driver.adjust_application_score(peer, 1.0));
+        assert_eq!(driver.application_scores[&peer], -0.5);
+    }
+
+    #[tokio::test]
+    async fn test_gossip_round_trip() {
+        let payload = ExecutionPayloadV1 {
+            parent_hash: B256::ZERO,
+            fee_recipient: Address::default(),
+            state_root: B256::ZERO,
+            receipts_root: B256::ZERO,
+            logs_bloom: Bloom::default(),
+            prev_randao: B256::ZERO,
+            block_number: 1,
+            gas_limit: 0,
+            gas_used: 0,
+            timestamp: unix_timestamp(),
+            extra_data: Bytes::default(),
+            base_fee_per_gas: U256::from(0),
+            block_hash: B256::ZERO,
+            transactions: vec![],
+        };
+        let encoded = payload.as_ssz_bytes();
+        let signature = PrimitiveSignature::test_signature();
+        let msg = PayloadHash::from(encoded.as_slice()).signature_message(10);
+        let signer = signature.recover_address_from_prehash(&msg).unwrap();
+        let data = [signature.as_bytes().as_slice(), &encoded].concat();
+        let data = snap::raw::Encoder::new().compress_vec(&data).unwrap();
+
+        let (_, signer_recv) = tokio::sync::watch::channel(signer);
+        let (handler, _) = BlockHandler::new(10, signer_recv.clone());
+        let mut sender = memory_driver(handler);
+        let (handler, blocks) = BlockHandler::new(10, signer_recv);
+        let mut receiver = memory_driver(handler);
+        let topic = receiver.handler.topics()[0].clone();
+        let receiver_id = *receiver.swarm.local_peer_id();
+        receiver.listen().unwrap();
+
+        // Dial the receiver once it is listening, and publish the block once the sender has
+        // grafted the receiver into its mesh for the topic.
+        let round_trip = async {
+            loop {
+                let event = receiver.select_next_some().await;
+                if let SwarmEvent::NewListenAddr { address, .. } = &event {
+                    sender.dial_multiaddr(address.clone());
+                    break;
+                }
+            }
+            let mut published = false;
+            loop {
+                tokio::select! {
+                    _ = sender.next_event() => {}
+                    _ = receiver.next_event() => {}
+                }
+                let mesh = sender.swarm.behaviour().mesh_peers(topic.as_str());
+                if !published && mesh.contains(&receiver_id) {
+                    let publish = OutboundPublish { topic: topic.clone(), data: data.clone() };
+                    sender.publish(publish).unwrap();
+                    published = true;
+                }
+                if let Ok(block) = blocks.try_recv() {
+                    return block;
+                }
+            }
+        };
+
+        // The receiver validates the block with its handler before forwarding it.
+        let block = tokio::time::timeout(Duration::from_secs(10), round_trip).await.unwrap();
+        assert_eq!(block.payload, OpExecutionPayload::V1(payload));
+        assert_eq!(block.signature, signature);
+    }
+
+    #[tokio::test]
+    async fn test_topic_isolated_without_mesh_peers() {
+        let mut driver = test_driver();
+        let mut topics = driver.handler.topics();
+        topics.sort();
+
+        // Topics are not reported while the mesh may still be forming.
+        assert!(driver.check_topic_isolation().is_empty());
+        assert!(topics.iter().all(|topic| !driver.isolation.is_isolated(topic)));
+
+        // Without any connected peers, every subscribed topic is isolated after the grace period.
+        driver.isolation = TopicIsolation::new(Duration::ZERO);
+        let mut isolated = driver
+            .check_topic_isolation()
+            .into_iter()
+            .map(|event| match event {
+                DriverEvent::TopicIsolated { topic } => topic,
+                event => panic!("unexpected event: {event:?}"),
+            })
+            .collect::<Vec<_>>();
+        isolated.sort();
+        assert_eq!(isolated, topics);
+        assert!(topics.iter().all(|topic| driver.isolation.is_isolated(topic)));
+
+        // The topics are not reported again until their mesh has regained a peer.
+        assert!(driver.check_topic_isolation().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_confirm_subscriptions() {
+        let mut driver = test_driver();
+        assert!(driver.confirm_subscriptions(Duration::from_millis(50)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_fork_schedule_subscriptions() {
+        let ecotone_time = unix_timestamp() + 3600;
+        let config = RollupConfig {
+            hardforks: HardForkConfig {
+                canyon_time: Some(0),
+                ecotone_time: Some(ecotone_time),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (_, recv) = tokio::sync::watch::channel(Address::default());
+        let (handler, _) = BlockHandler::new(0, recv);
+        let mut driver = test_driver_with(handler.with_fork_schedule(Arc::new(config)));
+        let [v1, v2, v3, v4] = driver.handler.topics().try_into().unwrap();
+
+        // The topics of versions that are not yet active are not subscribed to.
+        assert!(driver.confirm_subscriptions(Duration::from_millis(50)).await.is_ok());
+        let behaviour = driver.behaviour_mut();
+        assert!(behaviour.is_subscribed(&v1) && behaviour.is_subscribed(&v2));
+        assert!(!behaviour.is_subscribed(&v3) && !behaviour.is_subscribed(&v4));
+
+        // The v3 topic is subscribed to once Ecotone activates.
+        driver.sync_subscriptions().unwrap();
+        assert!(!driver.behaviour_mut().is_subscribed(&v3));
+        driver.sync_subscriptions_at(ecotone_time).unwrap();
+        assert!(driver.behaviour_mut().is_subscribed(&v3));
+        assert!(!driver.behaviour_mut().is_subscribed(&v4));
+    }
+
+    #[tokio::test]
+    async fn test_confirm_subscriptions_timeout() {
+        let mut driver = test_driver();
+        let topic = IdentTopic::new(driver.handler.topics()[0].to_string());
+        assert!(driver.behaviour_mut().gossipsub.unsubscribe(&topic));
+
+        let timeout = Duration::from_millis(50);
+        assert_eq!(
+            driver.confirm_subscriptions(timeout).await,
+            Err(BehaviourError::SubscriptionTimeout(timeout))
+        );
     }
 }
@@ -0,0 +1,121 @@
+//! Duplicate block tracking for the gossip swarm.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use alloy_primitives::B256;
+use libp2p::PeerId;
+
+/// The default application score penalty applied to a peer for each duplicate block it sends.
+pub const DEFAULT_DUPLICATE_PENALTY: f64 = 0.1;
+
+/// The number of recently received block payloads remembered for duplicate detection.
+pub const DUPLICATE_CACHE_SIZE: usize = 1024;
+
+/// Detects duplicate blocks received over gossip, and accumulates a penalty for the peers that
+/// send them.
+///
+/// Messages with identical data are already deduplicated by gossipsub through their message id.
+/// Blocks are duplicates if their payload was recently accepted, even if the message differs,
+/// such as when the payload is re-signed. Payloads are identified by the hash the block handler
+/// computes while validating the message, so that no block is decoded outside of validation. Each
+/// duplicate adds the configured penalty to the sending peer's total, which the
+/// [crate::GossipDriver] applies as the peer's application score, so that peers that chronically
+/// send duplicates are eventually pruned. The penalty is kept apart from the gossipsub penalties
+/// for invalid messages, and is forgotten once the peer disconnects.
+#[derive(Debug, Clone)]
+pub struct DuplicateTracker {
+    /// The penalty applied for each duplicate block.
+    penalty: f64,
+    /// The hashes of the recently received block payloads.
+    seen: HashSet<B256>,
+    /// The hashes of the recently received block payloads, in the order they were received.
+    order: VecDeque<B256>,
+    /// The accumulated penalty of each peer that sent duplicate blocks.
+    penalties: HashMap<PeerId, f64>,
+}
+
+impl Default for DuplicateTracker {
+    fn default() -> Self {
+        Self::new(DEFAULT_DUPLICATE_PENALTY)
+    }
+}
+
+impl DuplicateTracker {
+    /// Creates a new [DuplicateTracker] applying the given penalty for each duplicate block.
+    pub fn new(penalty: f64) -> Self {
+        Self { penalty, seen: HashSet::new(), order: VecDeque::new(), penalties: HashMap::new() }
+    }
+
+    /// Returns the penalty applied for each duplicate block.
+    pub const fn penalty(&self) -> f64 {
+        self.penalty
+    }
+
+    /// Returns the accumulated penalty of the given peer.
+    pub fn peer_penalty(&self, peer: &PeerId) -> f64 {
+        self.penalties.get(peer).copied().unwrap_or_default()
+    }
+
+    /// Records a block payload received from the given peer, returning the peer's accumulated
+    /// penalty if the payload was already accepted.
+    ///
+    /// Payloads are only remembered once they are accepted with [Self::record_accepted], so that
+    /// an invalid copy of a block cannot front-run the valid block.
+    pub fn record(&mut self, peer: PeerId, hash: B256) -> Option<f64> {
+        if !self.seen.contains(&hash) {
+            return None;
+        }
+
+        let total = self.penalties.entry(peer).or_default();
+        *total += self.penalty;
+        Some(*total)
+    }
+
+    /// Remembers a block payload that was accepted by the block handler, so that later copies of
+    /// it are detected as duplicates.
+    pub fn record_accepted(&mut self, hash: B256) {
+        if self.seen.insert(hash) {
+            self.order.push_back(hash);
+            if self.order.len() > DUPLICATE_CACHE_SIZE {
+                if let Some(evicted) = self.order.pop_front() {
+                    self.seen.remove(&evicted);
+                }
+            }
+        }
+    }
+
+    /// Forgets the accumulated penalty of a disconnected peer.
+    pub fn remove(&mut self, peer: &PeerId) {
+        self.penalties.remove(peer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::keccak256;
+
+    #[test]
+    fn test_duplicate_tracker_penalizes_duplicates() {
+        let mut tracker = DuplicateTracker::new(0.5);
+        let peer = PeerId::random();
+        let other = PeerId::random();
+        let block = keccak256(b"block");
+        let other_block = keccak256(b"other block");
+
+        // Payloads that were not accepted are not duplicates.
+        assert_eq!(tracker.record(peer, block), None);
+        assert_eq!(tracker.record(peer, block), None);
+
+        tracker.record_accepted(block);
+        tracker.record_accepted(other_block);
+        assert_eq!(tracker.record(peer, block), Some(0.5));
+        assert_eq!(tracker.record(peer, block), Some(1.0));
+        assert_eq!(tracker.record(other, other_block), Some(0.5));
+        assert_eq!(tracker.peer_penalty(&peer), 1.0);
+
+        tracker.remove(&peer);
+        assert_eq!(tracker.peer_penalty(&peer), 0.0);
+        assert_eq!(tracker.peer_penalty(&other), 0.5);
+    }
+}
@@ -1,6 +1,7 @@
 //! Event Handling Module.
 
-use libp2p::{gossipsub, ping};
+use libp2p::{Multiaddr, PeerId, gossipsub, identify, ping};
+use std::{convert::Infallible, net::IpAddr};
 
 /// The type of message received
 #[derive(Debug)]
@@ -10,6 +11,56 @@ pub enum Event {
     Ping(ping::Event),
     /// Represents a [gossipsub::Event]
     Gossipsub(gossipsub::Event),
+    /// Represents an [identify::Event]
+    Identify(Box<identify::Event>),
+}
+
+/// An event surfaced by the [crate::GossipDriver].
+///
+/// Unlike an [Event], which is emitted by the [crate::Behaviour], these events are produced by the
+/// driver itself, from the state it tracks about the swarm.
+#[derive(Debug)]
+pub enum DriverEvent {
+    /// A [gossipsub::Event] that the driver does not handle, such as a message on a topic without
+    /// a [crate::Handler].
+    Gossipsub(gossipsub::Event),
+    /// Emitted when peer churn exceeds the configured threshold.
+    HighChurn {
+        /// The number of peer connects and disconnects within the last minute.
+        rate: usize,
+    },
+    /// Emitted when the number of peers sharing a subnet exceeds the configured threshold.
+    LowSubnetDiversity {
+        /// The crowded subnet.
+        subnet: IpAddr,
+        /// The number of peers in the subnet.
+        peers: usize,
+    },
+    /// Emitted when the mesh of a subscribed topic drops to zero remote peers, so that the
+    /// topic's messages are propagated to nobody.
+    TopicIsolated {
+        /// The isolated topic.
+        topic: gossipsub::TopicHash,
+    },
+    /// Emitted when a message that failed validation is accepted and forwarded anyway, because
+    /// its topic is in [TopicValidationMode::Permissive] mode.
+    ///
+    /// [TopicValidationMode::Permissive]: crate::TopicValidationMode::Permissive
+    FlaggedMessage {
+        /// The topic of the message.
+        topic: gossipsub::TopicHash,
+        /// The id of the message.
+        message_id: gossipsub::MessageId,
+        /// The peer that propagated the message.
+        propagation_source: PeerId,
+    },
+    /// Emitted when the swarm starts listening, reporting which listen addresses were bound.
+    Listening {
+        /// The listen addresses that were bound.
+        bound: Vec<Multiaddr>,
+        /// The listen addresses that failed to bind.
+        failed: Vec<Multiaddr>,
+    },
 }
 
 impl From<ping::Event> for Event {
@@ -19,6 +70,20 @@ impl From<ping::Event> for Event {
     }
 }
 
+impl From<identify::Event> for Event {
+    /// Converts [identify::Event] to [Event]
+    fn from(value: identify::Event) -> Self {
+        Event::Identify(Box::new(value))
+    }
+}
+
+impl From<Infallible> for Event {
+    /// Converts the [Infallible] events of protocol-less behaviours to [Event]
+    fn from(value: Infallible) -> Self {
+        match value {}
+    }
+}
+
 impl From<gossipsub::Event> for Event {
     /// Converts [gossipsub::Event] to [Event]
     fn from(value: gossipsub::Event) -> Self {
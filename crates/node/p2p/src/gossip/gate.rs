@@ -0,0 +1,244 @@
+//! Connection gating by IP subnet.
+
+use libp2p::{
+    Multiaddr, PeerId,
+    core::{Endpoint, transport::PortUse},
+    swarm::{
+        ConnectionDenied, ConnectionId, FromSwarm, NetworkBehaviour, THandler, THandlerInEvent,
+        THandlerOutEvent, ToSwarm, dummy,
+    },
+};
+use std::{
+    collections::{HashMap, HashSet},
+    convert::Infallible,
+    net::IpAddr,
+    task::{Context, Poll},
+};
+
+use super::diversity::subnet;
+
+/// The prefix length of the IPv4 subnets that peers are limited by.
+pub const IPV4_SUBNET_PREFIX_LEN: u8 = 24;
+
+/// The prefix length of the IPv6 subnets that peers are limited by.
+pub const IPV6_SUBNET_PREFIX_LEN: u8 = 64;
+
+/// A connection was denied because its subnet already has the maximum number of peers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("subnet {subnet} already has the maximum of {max} peers")]
+pub struct SubnetLimitExceeded {
+    /// The subnet of the denied connection.
+    pub subnet: IpAddr,
+    /// The maximum number of peers per subnet.
+    pub max: usize,
+}
+
+/// Limits the number of peers connected from each IP subnet.
+///
+/// A single actor controlling many addresses within one subnet could otherwise dominate the peer
+/// set. Peers are grouped by their /24 IPv4 or /64 IPv6 subnet, and connections from new peers
+/// are denied once their subnet has the maximum number of peers. Further connections to an already
+/// connected peer are always allowed. This is a [NetworkBehaviour] that does not handle any
+/// protocols, so that it can be composed into the [crate::Behaviour].
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionGate {
+    /// The maximum number of peers per subnet, or `None` if unlimited.
+    max_peers_per_subnet: Option<usize>,
+    /// The subnet and open connections of each connected peer.
+    peers: HashMap<PeerId, (IpAddr, HashSet<ConnectionId>)>,
+    /// The number of connected peers in each subnet.
+    subnets: HashMap<IpAddr, usize>,
+}
+
+impl ConnectionGate {
+    /// Creates a new [ConnectionGate] allowing at most `max_peers_per_subnet` peers per subnet,
+    /// or any number of peers if `None`.
+    pub fn new(max_peers_per_subnet: Option<usize>) -> Self {
+        Self { max_peers_per_subnet, ..Default::default() }
+    }
+
+    /// Returns the maximum number of peers per subnet, or `None` if unlimited.
+    pub const fn max_peers_per_subnet(&self) -> Option<usize> {
+        self.max_peers_per_subnet
+    }
+
+    /// Sets the maximum number of peers per subnet, or removes the limit if `None`.
+    ///
+    /// Connected peers are not disconnected if their subnet is over the new limit.
+    pub fn set_max_peers_per_subnet(&mut self, max: Option<usize>) {
+        self.max_peers_per_subnet = max;
+    }
+
+    /// Returns the number of connected peers in the given subnet.
+    pub fn peers_in_subnet(&self, subnet: &IpAddr) -> usize {
+        self.subnets.get(subnet).copied().unwrap_or_default()
+    }
+
+    /// Admits a connection to a peer at the given address, denying it if the peer is not
+    /// connected yet and its subnet already has the maximum number of peers.
+    fn admit(
+        &mut self,
+        connection: ConnectionId,
+        peer: PeerId,
+        addr: &Multiaddr,
+    ) -> Result<(), ConnectionDenied> {
+        if let Some((_, connections)) = self.peers.get_mut(&peer) {
+            connections.insert(connection);
+            return Ok(());
+        }
+        let Some(subnet) = subnet(addr, IPV4_SUBNET_PREFIX_LEN, IPV6_SUBNET_PREFIX_LEN) else {
+            return Ok(());
+        };
+
+        let peers = self.subnets.entry(subnet).or_default();
+        if let Some(max) = self.max_peers_per_subnet.filter(|max| *peers >= *max) {
+            debug!(target: "p2p::gossip::gate", "Denying connection from peer {} in full subnet {}", peer, subnet);
+            return Err(ConnectionDenied::new(SubnetLimitExceeded { subnet, max }));
+        }
+        *peers += 1;
+        self.peers.insert(peer, (subnet, HashSet::from([connection])));
+        Ok(())
+    }
+
+    /// Releases a connection, forgetting its peer once it has no connections left.
+    fn release(&mut self, connection: ConnectionId) {
+        let Some(peer) = self
+            .peers
+            .iter_mut()
+            .find_map(|(peer, (_, connections))| connections.remove(&connection).then_some(*peer))
+        else {
+            return;
+        };
+        if !self.peers.get(&peer).is_some_and(|(_, connections)| connections.is_empty()) {
+            return;
+        }
+
+        let Some((subnet, _)) = self.peers.remove(&peer) else {
+            return;
+        };
+        let peers = self.subnets.entry(subnet).or_default();
+        *peers = peers.saturating_sub(1);
+        if *peers == 0 {
+            self.subnets.remove(&subnet);
+        }
+    }
+}
+
+impl NetworkBehaviour for ConnectionGate {
+    type ConnectionHandler = dummy::ConnectionHandler;
+    type ToSwarm = Infallible;
+
+    fn handle_established_inbound_connection(
+        &mut self,
+        connection: ConnectionId,
+        peer: PeerId,
+        _: &Multiaddr,
+        remote_addr: &Multiaddr,
+    ) -> Result<THandler<Self>, ConnectionDenied> {
+        self.admit(connection, peer, remote_addr)?;
+        Ok(dummy::ConnectionHandler)
+    }
+
+    fn handle_established_outbound_connection(
+        &mut self,
+        connection: ConnectionId,
+        peer: PeerId,
+        addr: &Multiaddr,
+        _: Endpoint,
+        _: PortUse,
+    ) -> Result<THandler<Self>, ConnectionDenied> {
+        self.admit(connection, peer, addr)?;
+        Ok(dummy::ConnectionHandler)
+    }
+
+    fn on_swarm_event(&mut self, event: FromSwarm<'_>) {
+        // Connections denied by another behaviour after being admitted are reported as dial or
+        // listen failures, rather than as closed connections.
+        match event {
+            FromSwarm::ConnectionClosed(closed) => self.release(closed.connection_id),
+            FromSwarm::DialFailure(failure) => self.release(failure.connection_id),
+            FromSwarm::ListenFailure(failure) => self.release(failure.connection_id),
+            _ => {}
+        }
+    }
+
+    fn on_connection_handler_event(
+        &mut self,
+        _: PeerId,
+        _: ConnectionId,
+        event: THandlerOutEvent<Self>,
+    ) {
+        match event {}
+    }
+
+    fn poll(&mut self, _: &mut Context<'_>) -> Poll<ToSwarm<Self::ToSwarm, THandlerInEvent<Self>>> {
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    fn connect(
+        gate: &mut ConnectionGate,
+        id: usize,
+        peer: PeerId,
+        ip: &str,
+    ) -> Result<(), ConnectionDenied> {
+        let addr: Multiaddr = format!("/ip4/{ip}/tcp/9222").parse().unwrap();
+        let local: Multiaddr = "/ip4/127.0.0.1/tcp/9222".parse().unwrap();
+        gate.handle_established_inbound_connection(
+            ConnectionId::new_unchecked(id),
+            peer,
+            &local,
+            &addr,
+        )
+        .map(|_| ())
+    }
+
+    #[test]
+    fn test_connection_gate_denies_excess_subnet_peers() {
+        let mut gate = ConnectionGate::new(Some(2));
+        let subnet = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 0));
+
+        assert!(connect(&mut gate, 0, PeerId::random(), "10.0.0.1").is_ok());
+        assert!(connect(&mut gate, 1, PeerId::random(), "10.0.0.2").is_ok());
+
+        let err = connect(&mut gate, 2, PeerId::random(), "10.0.0.3").unwrap_err();
+        assert_eq!(
+            err.downcast::<SubnetLimitExceeded>().unwrap(),
+            SubnetLimitExceeded { subnet, max: 2 }
+        );
+        assert_eq!(gate.peers_in_subnet(&subnet), 2);
+
+        // Peers from other subnets are still admitted.
+        assert!(connect(&mut gate, 3, PeerId::random(), "10.0.1.1").is_ok());
+    }
+
+    #[test]
+    fn test_connection_gate_releases_closed_peers() {
+        let mut gate = ConnectionGate::new(Some(1));
+        let peer = PeerId::random();
+
+        assert!(connect(&mut gate, 0, peer, "10.0.0.1").is_ok());
+        // Further connections to a connected peer are not counted against the limit.
+        assert!(connect(&mut gate, 1, peer, "10.0.0.1").is_ok());
+        assert!(connect(&mut gate, 2, PeerId::random(), "10.0.0.2").is_err());
+
+        gate.release(ConnectionId::new_unchecked(0));
+        assert!(connect(&mut gate, 3, PeerId::random(), "10.0.0.2").is_err());
+        gate.release(ConnectionId::new_unchecked(1));
+        assert!(connect(&mut gate, 4, PeerId::random(), "10.0.0.2").is_ok());
+    }
+
+    #[test]
+    fn test_connection_gate_unlimited() {
+        let mut gate = ConnectionGate::default();
+        for id in 0..8 {
+            assert!(connect(&mut gate, id, PeerId::random(), "10.0.0.1").is_ok());
+        }
+        assert_eq!(gate.peers_in_subnet(&IpAddr::V4(Ipv4Addr::new(10, 0, 0, 0))), 8);
+    }
+}
@@ -1,14 +1,24 @@
 //! Block Handler
 
 use std::{
-    sync::mpsc::{Receiver, Sender, channel},
+    collections::VecDeque,
+    sync::{
+        Arc, Mutex,
+        mpsc::{Receiver, Sender, channel},
+    },
     time::SystemTime,
 };
 
 use alloy_primitives::Address;
+use kona_genesis::RollupConfig;
 use libp2p::gossipsub::{IdentTopic, Message, MessageAcceptance, TopicHash};
 use op_alloy_rpc_types_engine::OpNetworkPayloadEnvelope;
-use tokio::sync::watch;
+use tokio::sync::{broadcast, watch};
+
+use crate::gossip::{
+    decode::decode_signed_block,
+    validation::{RejectionReason, ValidationResult},
+};
 
 /// This trait defines the functionality required to process incoming messages
 /// and determine their acceptance within the network.
@@ -19,8 +29,50 @@ pub trait Handler: Send {
     /// Manages validation and further processing of messages
     fn handle(&self, msg: Message) -> MessageAcceptance;
 
+    /// Validates and processes a message as [Handler::handle] does, returning a
+    /// [ValidationResult] that carries why the message was rejected. By default, no reason is
+    /// given.
+    fn validate(&self, msg: Message) -> ValidationResult {
+        self.handle(msg).into()
+    }
+
     /// Specifies which topics the handler is interested in
     fn topics(&self) -> Vec<TopicHash>;
+
+    /// Specifies which of the handler's topics should be subscribed to at the given unix
+    /// timestamp, in seconds. By default, every topic is.
+    fn active_topics(&self, _timestamp: u64) -> Vec<TopicHash> {
+        self.topics()
+    }
+}
+
+/// Returns the current unix timestamp, in seconds.
+pub(crate) fn unix_timestamp() -> u64 {
+    SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+/// The policy applied to valid blocks received while the engine reports that it is syncing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SyncBlockPolicy {
+    /// Buffer up to this many blocks, forwarding them once syncing has finished. Blocks received
+    /// while the buffer is full are dropped.
+    Buffer(usize),
+    /// Drop the blocks.
+    #[default]
+    Drop,
+}
+
+/// How the messages on a topic are validated.
+///
+/// A topic may be made permissive during a fork transition, while blocks on a new topic may not
+/// validate under the current handler yet.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TopicValidationMode {
+    /// Messages that fail validation are rejected, penalizing the peer that propagated them.
+    #[default]
+    Strict,
+    /// Messages that fail validation are accepted and forwarded, but flagged.
+    Permissive,
 }
 
 /// Responsible for managing blocks received via p2p gossip
@@ -31,6 +83,9 @@ pub struct BlockHandler {
     pub chain_id: u64,
     /// A channel sender to forward new blocks to other modules
     pub block_sender: Sender<OpNetworkPayloadEnvelope>,
+    /// An optional [broadcast::Sender] to fan out new blocks to any number of observers, such as
+    /// metrics sinks or RPC subscriptions, without interfering with the [Self::block_sender].
+    pub block_broadcast: Option<broadcast::Sender<OpNetworkPayloadEnvelope>>,
     /// A [Receiver] to monitor changes to the unsafe block signer.
     pub unsafe_signer_recv: watch::Receiver<Address>,
     /// The libp2p topic for pre Canyon/Shangai blocks.
@@ -41,44 +96,61 @@ pub struct BlockHandler {
     pub blocks_v3_topic: IdentTopic,
     /// The libp2p topic for V4 blocks.
     pub blocks_v4_topic: IdentTopic,
+    /// The rollup config whose hardfork schedule gates the block topics that are subscribed to,
+    /// if enabled with [BlockHandler::with_fork_schedule].
+    pub fork_schedule: Option<Arc<RollupConfig>>,
+    /// A [watch::Receiver] of whether the engine is syncing, if the [SyncBlockPolicy] is applied.
+    pub syncing_recv: Option<watch::Receiver<bool>>,
+    /// The policy applied to blocks received while the engine is syncing.
+    pub sync_policy: SyncBlockPolicy,
+    /// The blocks buffered while the engine is syncing, shared between clones of the handler.
+    sync_buffer: Arc<Mutex<VecDeque<OpNetworkPayloadEnvelope>>>,
 }
 
 impl Handler for BlockHandler {
     /// Checks validity of a block received via p2p gossip, and sends to the block update channel if
     /// valid.
     fn handle(&self, msg: Message) -> MessageAcceptance {
-        let decoded = if msg.topic == self.blocks_v1_topic.hash() {
+        self.validate(msg).acceptance
+    }
+
+    /// Checks validity of a block received via p2p gossip, and sends to the block update channel if
+    /// valid, returning the [RejectionReason] of an invalid block.
+    fn validate(&self, msg: Message) -> ValidationResult {
+        let version = if msg.topic == self.blocks_v1_topic.hash() {
             debug!(target: "p2p::block_handler", "received v1 block");
-            OpNetworkPayloadEnvelope::decode_v1(&msg.data)
+            1
         } else if msg.topic == self.blocks_v2_topic.hash() {
             debug!(target: "p2p::block_handler", "received v2 block");
-            OpNetworkPayloadEnvelope::decode_v2(&msg.data)
+            2
         } else if msg.topic == self.blocks_v3_topic.hash() {
             debug!(target: "p2p::block_handler", "received v3 block");
-            OpNetworkPayloadEnvelope::decode_v3(&msg.data)
+            3
         } else if msg.topic == self.blocks_v4_topic.hash() {
             debug!(target: "p2p::block_handler", "received v4 block");
             warn!(target: "p2p::block_handler", "v4 decoding unsupported");
-            return MessageAcceptance::Reject;
-            // OpNetworkPayloadEnvelope::decode_v4(&msg.data)
+            return ValidationResult::reject(RejectionReason::DecodeError);
         } else {
             warn!(target: "p2p::block_handler", "Received block with unknown topic: {:?}", msg.topic);
-            return MessageAcceptance::Reject;
+            return ValidationResult::reject(RejectionReason::UnknownTopic);
         };
 
-        match decoded {
-            Ok(envelope) => {
-                if self.block_valid(&envelope) {
-                    _ = self.block_sender.send(envelope);
-                    MessageAcceptance::Accept
-                } else {
-                    warn!(target: "p2p::block_handler", "Invalid block received");
-                    MessageAcceptance::Reject
-                }
-            }
+        let envelope = match decode_signed_block(version, &msg.data) {
+            Ok(envelope) => envelope,
             Err(err) => {
                 warn!(target: "p2p::block_handler", "Failed to decode block: {:?}", err);
-                MessageAcceptance::Reject
+                return ValidationResult::reject(RejectionReason::from(&err));
+            }
+        };
+        match self.check_block(&envelope) {
+            Ok(()) => {
+                let payload_hash = envelope.payload_hash.0;
+                self.forward_block(envelope);
+                ValidationResult::accept().with_payload_hash(payload_hash)
+            }
+            Err(reason) => {
+                warn!(target: "p2p::block_handler", "Invalid block received: {}", reason);
+                ValidationResult::reject(reason)
             }
         }
     }
@@ -92,6 +164,28 @@ impl Handler for BlockHandler {
             self.blocks_v4_topic.hash(),
         ]
     }
+
+    /// Returns the block topics whose version is activated by the fork schedule at the given
+    /// timestamp, or every block topic if no fork schedule is set.
+    ///
+    /// The v1 topic is always active, while the v2, v3 and v4 topics are activated by the Canyon,
+    /// Ecotone and Isthmus hardforks respectively. Topics of earlier versions remain active.
+    fn active_topics(&self, timestamp: u64) -> Vec<TopicHash> {
+        let Some(config) = &self.fork_schedule else {
+            return self.topics();
+        };
+        let mut topics = vec![self.blocks_v1_topic.hash()];
+        if config.is_canyon_active(timestamp) {
+            topics.push(self.blocks_v2_topic.hash());
+        }
+        if config.is_ecotone_active(timestamp) {
+            topics.push(self.blocks_v3_topic.hash());
+        }
+        if config.is_isthmus_active(timestamp) {
+            topics.push(self.blocks_v4_topic.hash());
+        }
+        topics
+    }
 }
 
 impl BlockHandler {
@@ -105,37 +199,164 @@ impl BlockHandler {
         let handler = Self {
             chain_id,
             block_sender: sender,
+            block_broadcast: None,
             unsafe_signer_recv: unsafe_recv,
             blocks_v1_topic: IdentTopic::new(format!("/optimism/{}/0/blocks", chain_id)),
             blocks_v2_topic: IdentTopic::new(format!("/optimism/{}/1/blocks", chain_id)),
             blocks_v3_topic: IdentTopic::new(format!("/optimism/{}/2/blocks", chain_id)),
             blocks_v4_topic: IdentTopic::new(format!("/optimism/{}/3/blocks", chain_id)),
+            fork_schedule: None,
+            syncing_recv: None,
+            sync_policy: SyncBlockPolicy::default(),
+            sync_buffer: Arc::new(Mutex::new(VecDeque::new())),
         };
 
         (handler, recv)
     }
 
+    /// Enables publishing valid blocks to a [broadcast] channel with the given capacity, which may
+    /// be observed through [BlockHandler::subscribe].
+    pub fn with_broadcast(mut self, capacity: usize) -> Self {
+        self.block_broadcast = Some(broadcast::channel(capacity).0);
+        self
+    }
+
+    /// Subscribes only to the block topics whose version is activated by the hardfork schedule of
+    /// the given rollup config, rather than to every block topic. See [Handler::active_topics].
+    ///
+    /// Topics are subscribed to as their hardfork activates, by
+    /// [crate::GossipDriver::sync_subscriptions].
+    pub fn with_fork_schedule(mut self, config: Arc<RollupConfig>) -> Self {
+        self.fork_schedule = Some(config);
+        self
+    }
+
+    /// Returns a new [broadcast::Receiver] of the valid blocks received by the handler, or `None`
+    /// if the broadcast channel is not enabled.
+    pub fn subscribe(&self) -> Option<broadcast::Receiver<OpNetworkPayloadEnvelope>> {
+        self.block_broadcast.as_ref().map(broadcast::Sender::subscribe)
+    }
+
+    /// Applies the given [SyncBlockPolicy] to valid blocks received while the given
+    /// [watch::Receiver] reports that the engine is syncing.
+    pub fn with_sync_policy(
+        mut self,
+        policy: SyncBlockPolicy,
+        syncing: watch::Receiver<bool>,
+    ) -> Self {
+        self.sync_policy = policy;
+        self.syncing_recv = Some(syncing);
+        self
+    }
+
+    /// Returns `true` if the engine reports that it is syncing.
+    pub fn is_syncing(&self) -> bool {
+        self.syncing_recv.as_ref().is_some_and(|syncing| *syncing.borrow())
+    }
+
+    /// Returns the number of blocks buffered while the engine is syncing.
+    pub fn buffered_blocks(&self) -> usize {
+        self.sync_buffer.lock().map(|buffer| buffer.len()).unwrap_or_default()
+    }
+
+    /// Forwards the blocks buffered while the engine was syncing, in the order they were
+    /// received. Buffered blocks are also flushed ahead of the next block received after syncing
+    /// has finished.
+    pub fn flush_sync_buffer(&self) {
+        let Ok(mut buffer) = self.sync_buffer.lock() else {
+            return;
+        };
+        for envelope in buffer.drain(..) {
+            self.send_block(envelope);
+        }
+    }
+
+    /// Waits until the engine reports that it has finished syncing, then forwards the blocks
+    /// buffered while it was syncing with [BlockHandler::flush_sync_buffer].
+    ///
+    /// This is cancel safe. It never completes if no [SyncBlockPolicy] is applied, or once the
+    /// sender of the syncing [watch::Receiver] has been dropped.
+    pub async fn flush_when_synced(&mut self) {
+        let Some(syncing) = self.syncing_recv.as_mut() else {
+            return std::future::pending().await;
+        };
+        loop {
+            if syncing.changed().await.is_err() {
+                return std::future::pending().await;
+            }
+            if !*syncing.borrow_and_update() {
+                break;
+            }
+        }
+        self.flush_sync_buffer();
+    }
+
+    /// Forwards a valid block, applying the [SyncBlockPolicy] if the engine is syncing.
+    fn forward_block(&self, envelope: OpNetworkPayloadEnvelope) {
+        if !self.is_syncing() {
+            self.flush_sync_buffer();
+            self.send_block(envelope);
+            return;
+        }
+
+        match self.sync_policy {
+            SyncBlockPolicy::Buffer(capacity) => {
+                let Ok(mut buffer) = self.sync_buffer.lock() else {
+                    return;
+                };
+                if buffer.len() < capacity {
+                    buffer.push_back(envelope);
+                } else {
+                    debug!(target: "p2p::block_handler", "Sync buffer full, dropping block");
+                }
+            }
+            SyncBlockPolicy::Drop => {
+                debug!(target: "p2p::block_handler", "Engine syncing, dropping block");
+            }
+        }
+    }
+
+    /// Sends a block to the broadcast subscribers, if any, and to the block channel.
+    fn send_block(&self, envelope: OpNetworkPayloadEnvelope) {
+        if let Some(broadcast) = &self.block_broadcast {
+            // Sending only fails if there are no subscribers, in which case there is nobody to
+            // notify.
+            _ = broadcast.send(envelope.clone());
+        }
+        _ = self.block_sender.send(envelope);
+    }
+
     /// Determines if a block is valid.
     ///
     /// True if the block is less than 1 minute old, and correctly signed by the unsafe block
     /// signer.
     pub fn block_valid(&self, envelope: &OpNetworkPayloadEnvelope) -> bool {
+        self.check_block(envelope).is_ok()
+    }
+
+    /// Checks that a block is less than 1 minute old, and correctly signed by the unsafe block
+    /// signer, returning the [RejectionReason] of an invalid block.
+    pub fn check_block(&self, envelope: &OpNetworkPayloadEnvelope) -> Result<(), RejectionReason> {
         let current_timestamp =
             SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs();
 
         let is_future = envelope.payload.timestamp() > current_timestamp + 5;
         let is_past = envelope.payload.timestamp() < current_timestamp - 60;
-        let time_valid = !(is_future || is_past);
+        if is_future || is_past {
+            return Err(RejectionReason::Stale);
+        }
 
         let msg = envelope.payload_hash.signature_message(self.chain_id);
         let block_signer = *self.unsafe_signer_recv.borrow();
         let Ok(msg_signer) = envelope.signature.recover_address_from_prehash(&msg) else {
             warn!(target: "p2p::block_handler", "Failed to recover address from message");
-            return false;
+            return Err(RejectionReason::BadSignature);
         };
 
-        let signer_valid = msg_signer == block_signer;
-        time_valid && signer_valid
+        if msg_signer != block_signer {
+            return Err(RejectionReason::BadSignature);
+        }
+        Ok(())
     }
 }
 
@@ -145,6 +366,7 @@ mod tests {
     use alloy_primitives::{Address, B256, Bloom, Bytes, PrimitiveSignature, U256};
     use alloy_rpc_types_engine::ExecutionPayloadV1;
     use op_alloy_rpc_types_engine::{OpExecutionPayload, PayloadHash};
+    use std::time::Duration;
 
     #[test]
     fn test_block_valid() {
@@ -182,4 +404,107 @@ mod tests {
 
         assert!(handler.block_valid(&envelope));
     }
+
+    #[test]
+    fn test_block_broadcast() {
+        let (_, unsafe_signer) = tokio::sync::watch::channel(Address::default());
+        let (handler, block_recv) = BlockHandler::new(10, unsafe_signer);
+        assert!(handler.subscribe().is_none());
+
+        let handler = handler.with_broadcast(16);
+        let mut first = handler.subscribe().unwrap();
+        let mut second = handler.subscribe().unwrap();
+
+        let envelope = test_envelope(1);
+        handler.forward_block(envelope.clone());
+
+        // Every subscriber observes the block, and the block channel still receives it.
+        assert_eq!(first.try_recv().unwrap(), envelope);
+        assert_eq!(second.try_recv().unwrap(), envelope);
+        assert_eq!(block_recv.try_recv().unwrap(), envelope);
+    }
+
+    fn test_envelope(block_number: u64) -> OpNetworkPayloadEnvelope {
+        OpNetworkPayloadEnvelope {
+            payload: OpExecutionPayload::V1(ExecutionPayloadV1 {
+                parent_hash: B256::ZERO,
+                fee_recipient: Address::default(),
+                state_root: B256::ZERO,
+                receipts_root: B256::ZERO,
+                logs_bloom: Bloom::default(),
+                prev_randao: B256::ZERO,
+                block_number,
+                gas_limit: 0,
+                gas_used: 0,
+                timestamp: 0,
+                extra_data: Bytes::default(),
+                base_fee_per_gas: U256::from(0),
+                block_hash: B256::ZERO,
+                transactions: vec![],
+            }),
+            signature: PrimitiveSignature::test_signature(),
+            payload_hash: PayloadHash(B256::ZERO),
+            parent_beacon_block_root: None,
+        }
+    }
+
+    #[test]
+    fn test_sync_policy_buffer() {
+        let (_, unsafe_signer) = tokio::sync::watch::channel(Address::default());
+        let (syncing_sender, syncing) = tokio::sync::watch::channel(true);
+        let (handler, block_recv) = BlockHandler::new(10, unsafe_signer);
+        let handler = handler.with_sync_policy(SyncBlockPolicy::Buffer(2), syncing);
+
+        for number in 0..4 {
+            handler.forward_block(test_envelope(number));
+        }
+        // Only the first two blocks are retained while syncing, and none are forwarded.
+        assert_eq!(handler.buffered_blocks(), 2);
+        assert!(block_recv.try_recv().is_err());
+
+        // Once syncing has finished, the buffered blocks are forwarded ahead of the next block.
+        syncing_sender.send(false).unwrap();
+        handler.forward_block(test_envelope(4));
+        assert_eq!(handler.buffered_blocks(), 0);
+        let forwarded = block_recv.try_iter().map(|e| e.payload.block_number()).collect::<Vec<_>>();
+        assert_eq!(forwarded, vec![0, 1, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_sync_policy_flushed_when_synced() {
+        let (_, unsafe_signer) = tokio::sync::watch::channel(Address::default());
+        let (syncing_sender, syncing) = tokio::sync::watch::channel(true);
+        let (handler, block_recv) = BlockHandler::new(10, unsafe_signer);
+        let mut handler = handler.with_sync_policy(SyncBlockPolicy::Buffer(2), syncing);
+
+        handler.forward_block(test_envelope(0));
+        handler.forward_block(test_envelope(1));
+        assert_eq!(handler.buffered_blocks(), 2);
+
+        // The buffer is not flushed while the engine is still syncing.
+        syncing_sender.send(true).unwrap();
+        let wait = tokio::time::timeout(Duration::from_millis(50), handler.flush_when_synced());
+        assert!(wait.await.is_err());
+        assert_eq!(handler.buffered_blocks(), 2);
+
+        // Once syncing has finished, the buffered blocks are forwarded without waiting for the
+        // next block.
+        syncing_sender.send(false).unwrap();
+        handler.flush_when_synced().await;
+        assert_eq!(handler.buffered_blocks(), 0);
+        let forwarded = block_recv.try_iter().map(|e| e.payload.block_number()).collect::<Vec<_>>();
+        assert_eq!(forwarded, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_sync_policy_drop() {
+        let (_, unsafe_signer) = tokio::sync::watch::channel(Address::default());
+        let (_syncing_sender, syncing) = tokio::sync::watch::channel(true);
+        let (handler, block_recv) = BlockHandler::new(10, unsafe_signer);
+        let handler = handler.with_sync_policy(SyncBlockPolicy::Drop, syncing);
+
+        handler.forward_block(test_envelope(0));
+        assert_eq!(handler.buffered_blocks(), 0);
+        assert!(block_recv.try_recv().is_err());
+    }
 }
@@ -0,0 +1,121 @@
+//! Topic isolation tracking for the gossip swarm.
+
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, Instant},
+};
+
+use libp2p::gossipsub::TopicHash;
+
+use crate::DriverEvent;
+
+/// How long a subscribed topic's mesh must remain empty before the topic is reported as isolated.
+///
+/// This gives the mesh time to form after startup, and to recover from a transient drop.
+pub const TOPIC_ISOLATION_GRACE: Duration = Duration::from_secs(30);
+
+/// Tracks the subscribed topics whose mesh has no remote peers.
+///
+/// A node that is the only member of a topic mesh propagates the topic's messages to nobody.
+/// When a subscribed topic's mesh has had zero remote peers for longer than the grace period, an
+/// [DriverEvent::TopicIsolated] is emitted. The event is emitted once per topic, and again only
+/// after the mesh has regained a peer.
+#[derive(Debug, Clone)]
+pub struct TopicIsolation {
+    /// How long a topic's mesh must remain empty before the topic is isolated.
+    grace: Duration,
+    /// The instant from which each subscribed topic's mesh has been empty.
+    empty_since: HashMap<TopicHash, Instant>,
+    /// The subscribed topics that have been reported as isolated.
+    isolated: HashSet<TopicHash>,
+}
+
+impl Default for TopicIsolation {
+    fn default() -> Self {
+        Self::new(TOPIC_ISOLATION_GRACE)
+    }
+}
+
+impl TopicIsolation {
+    /// Creates a new [TopicIsolation] tracker with the given grace period.
+    pub fn new(grace: Duration) -> Self {
+        Self { grace, empty_since: HashMap::new(), isolated: HashSet::new() }
+    }
+
+    /// Returns how long a topic's mesh must remain empty before the topic is isolated.
+    pub const fn grace(&self) -> Duration {
+        self.grace
+    }
+
+    /// Returns `true` if the given topic has been reported as isolated, and its mesh has not
+    /// regained a peer since.
+    pub fn is_isolated(&self, topic: &TopicHash) -> bool {
+        self.isolated.contains(topic)
+    }
+
+    /// Records the number of remote mesh peers of every subscribed topic at `now`, returning an
+    /// [DriverEvent::TopicIsolated] for each topic whose mesh has been empty for the grace period.
+    ///
+    /// Topics that are no longer subscribed to are forgotten.
+    pub fn record(
+        &mut self,
+        meshes: impl IntoIterator<Item = (TopicHash, usize)>,
+        now: Instant,
+    ) -> Vec<DriverEvent> {
+        let mut empty_since = HashMap::new();
+        let mut isolated = HashSet::new();
+        let mut events = Vec::new();
+        for (topic, peers) in meshes {
+            if peers > 0 {
+                continue;
+            }
+            let since = self.empty_since.get(&topic).copied().unwrap_or(now);
+            if self.isolated.contains(&topic) {
+                isolated.insert(topic.clone());
+            } else if now.saturating_duration_since(since) >= self.grace {
+                events.push(DriverEvent::TopicIsolated { topic: topic.clone() });
+                isolated.insert(topic.clone());
+            }
+            empty_since.insert(topic, since);
+        }
+        self.empty_since = empty_since;
+        self.isolated = isolated;
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_topic_isolated_fires_once_until_rejoined() {
+        let mut isolation = TopicIsolation::new(Duration::from_secs(30));
+        let topic = TopicHash::from_raw("/optimism/10/0/blocks");
+        let other = TopicHash::from_raw("/optimism/10/1/blocks");
+        let start = Instant::now();
+
+        // An empty mesh is not reported within the grace period.
+        assert!(isolation.record([(topic.clone(), 0), (other.clone(), 2)], start).is_empty());
+        let later = start + Duration::from_secs(29);
+        assert!(isolation.record([(topic.clone(), 0), (other.clone(), 2)], later).is_empty());
+        assert!(!isolation.is_isolated(&topic));
+
+        let now = start + Duration::from_secs(30);
+        let events = isolation.record([(topic.clone(), 0), (other.clone(), 2)], now);
+        assert!(
+            matches!(events.as_slice(), [DriverEvent::TopicIsolated { topic: t }] if *t == topic)
+        );
+        assert!(isolation.is_isolated(&topic));
+        assert!(!isolation.is_isolated(&other));
+
+        // The event is not emitted again while the mesh remains empty.
+        assert!(isolation.record([(topic.clone(), 0), (other.clone(), 2)], now).is_empty());
+
+        // Once the mesh regains a peer, the event is re-armed, and the grace period restarts.
+        assert!(isolation.record([(topic.clone(), 1)], now).is_empty());
+        assert!(!isolation.is_isolated(&topic));
+        assert!(isolation.record([(topic.clone(), 0)], now).is_empty());
+        assert_eq!(isolation.record([(topic, 0)], now + Duration::from_secs(30)).len(), 1);
+    }
+}
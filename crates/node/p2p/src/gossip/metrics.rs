@@ -0,0 +1,16 @@
+//! Metrics for the gossip swarm.
+
+use crate::RejectionCounts;
+
+/// A snapshot of the [crate::GossipDriver]'s metrics.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GossipMetrics {
+    /// The number of connected peers.
+    pub connected_peers: usize,
+    /// The total number of peer connects and disconnects.
+    pub peer_churn: u64,
+    /// The number of peer connects and disconnects within the last minute.
+    pub churn_rate: usize,
+    /// The number of gossip messages rejected for each reason.
+    pub rejections: RejectionCounts,
+}
@@ -6,16 +6,61 @@ pub use behaviour::{Behaviour, BehaviourError};
 mod config;
 pub use config::{
     DEFAULT_MESH_D, DEFAULT_MESH_DHI, DEFAULT_MESH_DLAZY, DEFAULT_MESH_DLO,
-    GLOBAL_VALIDATE_THROTTLE, GOSSIP_HEARTBEAT, MAX_GOSSIP_SIZE, MAX_OUTBOUND_QUEUE,
-    MAX_VALIDATE_QUEUE, MIN_GOSSIP_SIZE, PEER_SCORE_INSPECT_FREQUENCY, SEEN_MESSAGES_TTL,
-    default_config, default_config_builder,
+    GLOBAL_VALIDATE_THROTTLE, GOSSIP_HEARTBEAT, IDENTIFY_PROTOCOL_VERSION,
+    MAX_CONCURRENT_VALIDATIONS, MAX_GOSSIP_SIZE, MAX_OUTBOUND_QUEUE, MAX_PEERS_PER_SUBNET,
+    MAX_SUBSCRIBED_TOPICS, MAX_VALIDATE_QUEUE, MIN_ADDRESS_OBSERVERS, MIN_GOSSIP_SIZE,
+    PEER_CHURN_THRESHOLD, PEER_SCORE_INSPECT_FREQUENCY, PUBLISH_QUEUE_DEPTH, SEEN_MESSAGES_TTL,
+    SUBSCRIPTION_TIMEOUT, VALIDATION_TIMEOUT, default_config, default_config_builder,
 };
 
+mod gate;
+pub use gate::{
+    ConnectionGate, IPV4_SUBNET_PREFIX_LEN, IPV6_SUBNET_PREFIX_LEN, SubnetLimitExceeded,
+};
+
+mod allowlist;
+pub use allowlist::TopicAllowlist;
+
 mod event;
-pub use event::Event;
+pub use event::{DriverEvent, Event};
+
+mod churn;
+pub use churn::{PEER_CHURN_WINDOW, PeerChurn};
+
+mod isolation;
+pub use isolation::{TOPIC_ISOLATION_GRACE, TopicIsolation};
+
+mod diversity;
+pub use diversity::{SubnetDiversity, SubnetDiversityConfig};
+
+mod observed;
+pub use observed::ObservedAddresses;
+
+mod peer_info;
+pub use peer_info::{PeerInfo, PeerInfoStore};
+
+mod duplicates;
+pub use duplicates::{DEFAULT_DUPLICATE_PENALTY, DUPLICATE_CACHE_SIZE, DuplicateTracker};
+
+mod metrics;
+pub use metrics::GossipMetrics;
+
+mod decode;
+pub use decode::{
+    DecodeError, SIGNATURE_LENGTH, check_decompressed_size, decode_signed_block,
+    decode_signed_block_with_limit,
+};
+
+mod publish;
+pub use publish::{
+    OutboundPublish, PublishOverflow, PublishQueue, PublishQueueError, PublishSender,
+};
+
+mod validation;
+pub use validation::{RejectionCounts, RejectionReason, ValidationResult};
 
 mod handler;
-pub use handler::{BlockHandler, Handler};
+pub use handler::{BlockHandler, Handler, SyncBlockPolicy, TopicValidationMode};
 
 mod driver;
-pub use driver::GossipDriver;
+pub use driver::{GossipDriver, NoListenAddrBound, validate_with_timeout};
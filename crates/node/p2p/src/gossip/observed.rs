@@ -0,0 +1,82 @@
+//! Tracks the external addresses of the node as observed by its peers.
+
+use libp2p::{Multiaddr, PeerId};
+use std::collections::HashMap;
+
+use crate::MIN_ADDRESS_OBSERVERS;
+
+/// Tracks the addresses that peers observe the node at, as reported by `identify`.
+///
+/// A single peer may report any address, so an observed address is only confirmed once it has
+/// been reported by a minimum number of distinct peers. Only the latest observation of each
+/// connected peer is kept, so the tracker is bounded by the number of peers.
+#[derive(Debug, Clone)]
+pub struct ObservedAddresses {
+    /// The number of distinct peers that must observe an address before it is confirmed.
+    min_observers: usize,
+    /// The latest address observed by each peer.
+    observations: HashMap<PeerId, Multiaddr>,
+}
+
+impl Default for ObservedAddresses {
+    fn default() -> Self {
+        Self::new(MIN_ADDRESS_OBSERVERS)
+    }
+}
+
+impl ObservedAddresses {
+    /// Creates a new [ObservedAddresses] tracker, confirming addresses once they are observed by
+    /// `min_observers` distinct peers.
+    pub fn new(min_observers: usize) -> Self {
+        Self { min_observers, observations: HashMap::new() }
+    }
+
+    /// Returns the number of distinct peers whose latest observation is the given address.
+    pub fn observers(&self, addr: &Multiaddr) -> usize {
+        self.observations.values().filter(|observed| *observed == addr).count()
+    }
+
+    /// Returns `true` if the given address has been observed by enough distinct peers.
+    pub fn is_confirmed(&self, addr: &Multiaddr) -> bool {
+        self.observers(addr) >= self.min_observers
+    }
+
+    /// Records the address a peer observes the node at, returning the address if this
+    /// observation has just confirmed it.
+    pub fn record(&mut self, peer: PeerId, addr: Multiaddr) -> Option<Multiaddr> {
+        let was_confirmed = self.is_confirmed(&addr);
+        self.observations.insert(peer, addr.clone());
+        (!was_confirmed && self.is_confirmed(&addr)).then_some(addr)
+    }
+
+    /// Forgets the observation of a disconnected peer. Addresses that were already confirmed stay
+    /// confirmed on the swarm.
+    pub fn remove(&mut self, peer: &PeerId) {
+        self.observations.remove(peer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_observed_addresses_confirmation() {
+        let mut observed = ObservedAddresses::new(2);
+        let addr: Multiaddr = "/ip4/203.0.113.7/tcp/9222".parse().unwrap();
+        let peer = PeerId::random();
+
+        assert_eq!(observed.record(peer, addr.clone()), None);
+        // Repeated observations from the same peer do not confirm the address.
+        assert_eq!(observed.record(peer, addr.clone()), None);
+        assert_eq!(observed.observers(&addr), 1);
+
+        assert_eq!(observed.record(PeerId::random(), addr.clone()), Some(addr.clone()));
+        assert!(observed.is_confirmed(&addr));
+        // The address is only reported once, when it is first confirmed.
+        assert_eq!(observed.record(PeerId::random(), addr.clone()), None);
+
+        observed.remove(&peer);
+        assert_eq!(observed.observers(&addr), 2);
+    }
+}
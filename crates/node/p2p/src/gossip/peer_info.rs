@@ -0,0 +1,186 @@
+//! Tracks diagnostic information about connected peers.
+
+use libp2p::{
+    Multiaddr, PeerId, StreamProtocol,
+    core::{Endpoint, transport::PortUse},
+    identify,
+    swarm::{
+        ConnectionClosed, ConnectionDenied, ConnectionId, FromSwarm, NetworkBehaviour, THandler,
+        THandlerInEvent, THandlerOutEvent, ToSwarm, behaviour::ConnectionEstablished, dummy,
+    },
+};
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    task::{Context, Poll},
+};
+
+/// Diagnostic information about a connected peer.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PeerInfo {
+    /// The remote addresses of the connections to the peer.
+    pub connected_addrs: Vec<Multiaddr>,
+    /// The agent version the peer reported over `identify`, if it has identified itself.
+    pub agent_version: Option<String>,
+    /// The protocol version the peer reported over `identify`, if it has identified itself.
+    pub protocol_version: Option<String>,
+    /// The protocols the peer supports, as reported over `identify`.
+    pub protocols: Vec<StreamProtocol>,
+    /// The addresses the peer listens on, as reported over `identify`.
+    pub listen_addrs: Vec<Multiaddr>,
+    /// The address the peer observes the node at, as reported over `identify`.
+    pub observed_addr: Option<Multiaddr>,
+}
+
+/// Tracks the [PeerInfo] of each connected peer, combining the remote addresses of its
+/// connections with the information it reports over `identify`.
+///
+/// The information of a peer is forgotten once its last connection is closed. This is a
+/// [NetworkBehaviour] that does not handle any protocols, so that it can be composed into the
+/// [crate::Behaviour].
+#[derive(Debug, Clone, Default)]
+pub struct PeerInfoStore {
+    /// The information of each connected peer.
+    peers: HashMap<PeerId, PeerInfo>,
+}
+
+impl PeerInfoStore {
+    /// Returns the [PeerInfo] of the given peer, if it is connected.
+    pub fn get(&self, peer: &PeerId) -> Option<PeerInfo> {
+        self.peers.get(peer).cloned()
+    }
+
+    /// Records the information a connected peer reported over `identify`. Peers that are not
+    /// connected are ignored.
+    pub fn record_identify(&mut self, peer: &PeerId, info: &identify::Info) {
+        let Some(entry) = self.peers.get_mut(peer) else {
+            return;
+        };
+        entry.agent_version = Some(info.agent_version.clone());
+        entry.protocol_version = Some(info.protocol_version.clone());
+        entry.protocols = info.protocols.clone();
+        entry.listen_addrs = info.listen_addrs.clone();
+        entry.observed_addr = Some(info.observed_addr.clone());
+    }
+
+    /// Records a new connection to a peer at the given remote address.
+    fn record_connected(&mut self, peer: PeerId, addr: &Multiaddr) {
+        let entry = self.peers.entry(peer).or_default();
+        if !entry.connected_addrs.contains(addr) {
+            entry.connected_addrs.push(addr.clone());
+        }
+    }
+
+    /// Records a closed connection to a peer at the given remote address, forgetting the peer if
+    /// it has no remaining connections.
+    fn record_disconnected(&mut self, peer: &PeerId, addr: &Multiaddr, remaining: usize) {
+        if remaining == 0 {
+            self.peers.remove(peer);
+        } else if let Some(entry) = self.peers.get_mut(peer) {
+            entry.connected_addrs.retain(|a| a != addr);
+        }
+    }
+}
+
+impl NetworkBehaviour for PeerInfoStore {
+    type ConnectionHandler = dummy::ConnectionHandler;
+    type ToSwarm = Infallible;
+
+    fn handle_established_inbound_connection(
+        &mut self,
+        _: ConnectionId,
+        _: PeerId,
+        _: &Multiaddr,
+        _: &Multiaddr,
+    ) -> Result<THandler<Self>, ConnectionDenied> {
+        Ok(dummy::ConnectionHandler)
+    }
+
+    fn handle_established_outbound_connection(
+        &mut self,
+        _: ConnectionId,
+        _: PeerId,
+        _: &Multiaddr,
+        _: Endpoint,
+        _: PortUse,
+    ) -> Result<THandler<Self>, ConnectionDenied> {
+        Ok(dummy::ConnectionHandler)
+    }
+
+    fn on_swarm_event(&mut self, event: FromSwarm<'_>) {
+        match event {
+            FromSwarm::ConnectionEstablished(ConnectionEstablished {
+                peer_id, endpoint, ..
+            }) => self.record_connected(peer_id, endpoint.get_remote_address()),
+            FromSwarm::ConnectionClosed(ConnectionClosed {
+                peer_id,
+                endpoint,
+                remaining_established,
+                ..
+            }) => {
+                self.record_disconnected(
+                    &peer_id,
+                    endpoint.get_remote_address(),
+                    remaining_established,
+                );
+            }
+            _ => {}
+        }
+    }
+
+    fn on_connection_handler_event(
+        &mut self,
+        _: PeerId,
+        _: ConnectionId,
+        event: THandlerOutEvent<Self>,
+    ) {
+        match event {}
+    }
+
+    fn poll(&mut self, _: &mut Context<'_>) -> Poll<ToSwarm<Self::ToSwarm, THandlerInEvent<Self>>> {
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libp2p::identity::Keypair;
+
+    #[test]
+    fn test_peer_info_from_identify() {
+        let mut store = PeerInfoStore::default();
+        let peer = PeerId::random();
+        let addr: Multiaddr = "/ip4/198.51.100.1/tcp/9222".parse().unwrap();
+        let info = identify::Info {
+            public_key: Keypair::generate_ed25519().public(),
+            protocol_version: "/optimism/1.0.0".to_string(),
+            agent_version: "op-node/v1.0.0".to_string(),
+            listen_addrs: vec![addr.clone()],
+            protocols: vec![StreamProtocol::new("/meshsub/1.1.0")],
+            observed_addr: "/ip4/203.0.113.7/tcp/9222".parse().unwrap(),
+        };
+
+        // Peers that are not connected are not tracked.
+        store.record_identify(&peer, &info);
+        assert_eq!(store.get(&peer), None);
+
+        store.record_connected(peer, &addr);
+        assert_eq!(
+            store.get(&peer),
+            Some(PeerInfo { connected_addrs: vec![addr.clone()], ..Default::default() })
+        );
+
+        store.record_identify(&peer, &info);
+        let peer_info = store.get(&peer).unwrap();
+        assert_eq!(peer_info.agent_version.as_deref(), Some("op-node/v1.0.0"));
+        assert_eq!(peer_info.protocol_version.as_deref(), Some("/optimism/1.0.0"));
+        assert_eq!(peer_info.protocols, info.protocols);
+        assert_eq!(peer_info.listen_addrs, vec![addr.clone()]);
+        assert_eq!(peer_info.observed_addr, Some(info.observed_addr));
+
+        // The information is forgotten once the last connection is closed.
+        store.record_disconnected(&peer, &addr, 0);
+        assert_eq!(store.get(&peer), None);
+    }
+}
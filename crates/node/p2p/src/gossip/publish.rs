@@ -0,0 +1,186 @@
+//! Outbound gossip publish queue.
+
+use libp2p::gossipsub::TopicHash;
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
+use tokio::sync::mpsc;
+
+/// An error that can occur when enqueueing an outbound publish.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum PublishQueueError {
+    /// The [PublishQueue] has been dropped.
+    #[error("publish queue closed")]
+    Closed,
+}
+
+/// The policy applied when publishing to a full [PublishQueue].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PublishOverflow {
+    /// Wait until the queue has capacity, applying backpressure to the producer.
+    #[default]
+    Backpressure,
+    /// Drop the publish, counting it in [PublishSender::dropped].
+    Drop,
+}
+
+/// A message waiting to be published to the gossip network.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutboundPublish {
+    /// The topic to publish the message to.
+    pub topic: TopicHash,
+    /// The message data.
+    pub data: Vec<u8>,
+}
+
+/// The producer handle of a [PublishQueue].
+#[derive(Debug, Clone)]
+pub struct PublishSender {
+    /// The sender side of the queue.
+    sender: mpsc::Sender<OutboundPublish>,
+    /// The policy applied when the queue is full.
+    overflow: PublishOverflow,
+    /// The number of publishes dropped because the queue was full.
+    dropped: Arc<AtomicU64>,
+}
+
+impl PublishSender {
+    /// Enqueues a message to be published to the given topic.
+    ///
+    /// If the queue is full, this either waits for capacity or drops the message, depending on the
+    /// [PublishOverflow] policy of the queue. Returns [PublishQueueError::Closed] if the
+    /// [PublishQueue] has been dropped.
+    pub async fn publish(&self, topic: TopicHash, data: Vec<u8>) -> Result<(), PublishQueueError> {
+        let publish = OutboundPublish { topic, data };
+        match self.overflow {
+            PublishOverflow::Backpressure => {
+                self.sender.send(publish).await.map_err(|_| PublishQueueError::Closed)
+            }
+            PublishOverflow::Drop => match self.sender.try_send(publish) {
+                Ok(()) => Ok(()),
+                Err(mpsc::error::TrySendError::Full(_)) => {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                    warn!(target: "p2p::gossip::publish", "Publish queue full, dropping publish");
+                    Ok(())
+                }
+                Err(mpsc::error::TrySendError::Closed(_)) => Err(PublishQueueError::Closed),
+            },
+        }
+    }
+
+    /// Returns the number of publishes dropped because the queue was full.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+/// A bounded FIFO queue of [OutboundPublish]es.
+///
+/// Messages are yielded in the order they were enqueued by the [PublishSender]s, so that blocks
+/// are published in order even when they are produced faster than the swarm publishes them.
+#[derive(Debug)]
+pub struct PublishQueue {
+    /// The receiver side of the queue.
+    receiver: mpsc::Receiver<OutboundPublish>,
+    /// The number of publishes dropped because the queue was full.
+    dropped: Arc<AtomicU64>,
+}
+
+impl PublishQueue {
+    /// Creates a new [PublishQueue] holding at most `depth` messages, returning its producer
+    /// handle alongside it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `depth` is zero.
+    pub fn new(depth: usize, overflow: PublishOverflow) -> (PublishSender, Self) {
+        let (sender, receiver) = mpsc::channel(depth);
+        let dropped = Arc::new(AtomicU64::new(0));
+        let queue = Self { receiver, dropped: Arc::clone(&dropped) };
+        (PublishSender { sender, overflow, dropped }, queue)
+    }
+
+    /// Waits for the next message to publish, returning `None` once every [PublishSender] has been
+    /// dropped and the queue is empty.
+    pub async fn next(&mut self) -> Option<OutboundPublish> {
+        self.receiver.recv().await
+    }
+
+    /// Returns the next message to publish, if one is queued.
+    pub fn try_next(&mut self) -> Option<OutboundPublish> {
+        self.receiver.try_recv().ok()
+    }
+
+    /// Returns the number of messages in the queue.
+    pub fn len(&self) -> usize {
+        self.receiver.len()
+    }
+
+    /// Returns `true` if the queue is empty.
+    pub fn is_empty(&self) -> bool {
+        self.receiver.is_empty()
+    }
+
+    /// Returns the number of publishes dropped because the queue was full.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libp2p::gossipsub::IdentTopic;
+    use std::time::Duration;
+
+    fn topic() -> TopicHash {
+        IdentTopic::new("/optimism/10/2/blocks").hash()
+    }
+
+    #[tokio::test]
+    async fn test_publish_queue_backpressure() {
+        let (sender, mut queue) = PublishQueue::new(2, PublishOverflow::Backpressure);
+        sender.publish(topic(), vec![0]).await.unwrap();
+        sender.publish(topic(), vec![1]).await.unwrap();
+
+        // The queue is full, so the producer waits until a message is dequeued.
+        let blocked = tokio::spawn({
+            let sender = sender.clone();
+            async move { sender.publish(topic(), vec![2]).await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!blocked.is_finished());
+        assert_eq!(queue.len(), 2);
+
+        assert_eq!(queue.next().await.unwrap().data, vec![0]);
+        blocked.await.unwrap().unwrap();
+
+        assert_eq!(queue.next().await.unwrap().data, vec![1]);
+        assert_eq!(queue.next().await.unwrap().data, vec![2]);
+        assert!(queue.is_empty());
+        assert_eq!(sender.dropped(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_publish_queue_drop() {
+        let (sender, mut queue) = PublishQueue::new(2, PublishOverflow::Drop);
+        for i in 0..5u8 {
+            sender.publish(topic(), vec![i]).await.unwrap();
+        }
+        assert_eq!(sender.dropped(), 3);
+        assert_eq!(queue.dropped(), 3);
+
+        // The publishes that fit in the queue are kept in order.
+        assert_eq!(queue.try_next().unwrap().data, vec![0]);
+        assert_eq!(queue.try_next().unwrap().data, vec![1]);
+        assert!(queue.try_next().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_publish_queue_closed() {
+        let (sender, queue) = PublishQueue::new(2, PublishOverflow::Backpressure);
+        drop(queue);
+        assert_eq!(sender.publish(topic(), vec![0]).await, Err(PublishQueueError::Closed));
+    }
+}
@@ -0,0 +1,154 @@
+//! Typed outcomes of gossip message validation.
+
+use alloy_primitives::B256;
+use libp2p::gossipsub::MessageAcceptance;
+
+use crate::DecodeError;
+
+/// The reason a gossip message was rejected by its [crate::Handler].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RejectionReason {
+    /// The block was not signed by the unsafe block signer, or its signature is malformed.
+    BadSignature,
+    /// The block exceeds the maximum gossip size.
+    Oversized,
+    /// The block timestamp is outside of the window in which blocks are accepted.
+    Stale,
+    /// The block could not be decoded.
+    DecodeError,
+    /// The message was received on a topic the handler does not validate.
+    UnknownTopic,
+}
+
+impl RejectionReason {
+    /// Returns the name of the reason, as it is logged.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::BadSignature => "bad-signature",
+            Self::Oversized => "oversized",
+            Self::Stale => "stale",
+            Self::DecodeError => "decode-error",
+            Self::UnknownTopic => "unknown-topic",
+        }
+    }
+}
+
+impl core::fmt::Display for RejectionReason {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<&DecodeError> for RejectionReason {
+    fn from(err: &DecodeError) -> Self {
+        match err {
+            DecodeError::TooLarge { .. } => Self::Oversized,
+            DecodeError::InvalidSignature => Self::BadSignature,
+            _ => Self::DecodeError,
+        }
+    }
+}
+
+/// The outcome of validating a gossip message.
+///
+/// Only the [MessageAcceptance] is reported to gossipsub. The [RejectionReason] of a rejected
+/// message is logged and counted by the [crate::GossipDriver], so that rejections are observable.
+#[derive(Debug)]
+pub struct ValidationResult {
+    /// The acceptance reported to gossipsub.
+    pub acceptance: MessageAcceptance,
+    /// Why the message was rejected, if it was rejected with a known reason.
+    pub reason: Option<RejectionReason>,
+    /// The hash of the block payload carried by the message, if it was decoded.
+    pub payload_hash: Option<B256>,
+}
+
+impl ValidationResult {
+    /// Returns a [ValidationResult] accepting the message.
+    pub const fn accept() -> Self {
+        Self { acceptance: MessageAcceptance::Accept, reason: None, payload_hash: None }
+    }
+
+    /// Returns a [ValidationResult] ignoring the message.
+    pub const fn ignore() -> Self {
+        Self { acceptance: MessageAcceptance::Ignore, reason: None, payload_hash: None }
+    }
+
+    /// Returns a [ValidationResult] rejecting the message for the given reason.
+    pub const fn reject(reason: RejectionReason) -> Self {
+        Self { acceptance: MessageAcceptance::Reject, reason: Some(reason), payload_hash: None }
+    }
+
+    /// Sets the hash of the block payload carried by the message.
+    pub const fn with_payload_hash(mut self, hash: B256) -> Self {
+        self.payload_hash = Some(hash);
+        self
+    }
+
+    /// Returns `true` if the message was rejected.
+    pub const fn is_rejected(&self) -> bool {
+        matches!(self.acceptance, MessageAcceptance::Reject)
+    }
+}
+
+impl From<MessageAcceptance> for ValidationResult {
+    fn from(acceptance: MessageAcceptance) -> Self {
+        Self { acceptance, reason: None, payload_hash: None }
+    }
+}
+
+/// The number of gossip messages rejected for each [RejectionReason].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RejectionCounts {
+    /// Messages rejected with [RejectionReason::BadSignature].
+    pub bad_signature: u64,
+    /// Messages rejected with [RejectionReason::Oversized].
+    pub oversized: u64,
+    /// Messages rejected with [RejectionReason::Stale].
+    pub stale: u64,
+    /// Messages rejected with [RejectionReason::DecodeError].
+    pub decode_error: u64,
+    /// Messages rejected with [RejectionReason::UnknownTopic].
+    pub unknown_topic: u64,
+    /// Messages rejected without a reason.
+    pub unknown: u64,
+}
+
+impl RejectionCounts {
+    /// Counts the given [ValidationResult] if it is a rejection.
+    pub const fn record(&mut self, result: &ValidationResult) {
+        if !result.is_rejected() {
+            return;
+        }
+        let count = match result.reason {
+            Some(RejectionReason::BadSignature) => &mut self.bad_signature,
+            Some(RejectionReason::Oversized) => &mut self.oversized,
+            Some(RejectionReason::Stale) => &mut self.stale,
+            Some(RejectionReason::DecodeError) => &mut self.decode_error,
+            Some(RejectionReason::UnknownTopic) => &mut self.unknown_topic,
+            None => &mut self.unknown,
+        };
+        *count = count.saturating_add(1);
+    }
+
+    /// Returns the number of messages rejected for the given reason.
+    pub const fn get(&self, reason: RejectionReason) -> u64 {
+        match reason {
+            RejectionReason::BadSignature => self.bad_signature,
+            RejectionReason::Oversized => self.oversized,
+            RejectionReason::Stale => self.stale,
+            RejectionReason::DecodeError => self.decode_error,
+            RejectionReason::UnknownTopic => self.unknown_topic,
+        }
+    }
+
+    /// Returns the total number of rejected messages.
+    pub const fn total(&self) -> u64 {
+        self.bad_signature +
+            self.oversized +
+            self.stale +
+            self.decode_error +
+            self.unknown_topic +
+            self.unknown
+    }
+}
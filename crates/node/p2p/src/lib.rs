@@ -14,14 +14,24 @@ mod builder;
 pub use builder::{NetworkDriverBuilder, NetworkDriverBuilderError};
 
 mod driver;
-pub use driver::NetworkDriver;
+pub use driver::{NetworkDriver, NetworkDriverError};
 
 mod gossip;
 pub use gossip::{
-    Behaviour, BehaviourError, BlockHandler, DEFAULT_MESH_D, DEFAULT_MESH_DHI, DEFAULT_MESH_DLAZY,
-    DEFAULT_MESH_DLO, Event, GLOBAL_VALIDATE_THROTTLE, GOSSIP_HEARTBEAT, GossipDriver, Handler,
-    MAX_GOSSIP_SIZE, MAX_OUTBOUND_QUEUE, MAX_VALIDATE_QUEUE, MIN_GOSSIP_SIZE,
-    PEER_SCORE_INSPECT_FREQUENCY, SEEN_MESSAGES_TTL, default_config, default_config_builder,
+    Behaviour, BehaviourError, BlockHandler, ConnectionGate, DEFAULT_DUPLICATE_PENALTY,
+    DEFAULT_MESH_D, DEFAULT_MESH_DHI, DEFAULT_MESH_DLAZY, DEFAULT_MESH_DLO, DUPLICATE_CACHE_SIZE,
+    DecodeError, DriverEvent, DuplicateTracker, Event, GLOBAL_VALIDATE_THROTTLE, GOSSIP_HEARTBEAT,
+    GossipDriver, GossipMetrics, Handler, IDENTIFY_PROTOCOL_VERSION, IPV4_SUBNET_PREFIX_LEN,
+    IPV6_SUBNET_PREFIX_LEN, MAX_CONCURRENT_VALIDATIONS, MAX_GOSSIP_SIZE, MAX_OUTBOUND_QUEUE,
+    MAX_PEERS_PER_SUBNET, MAX_SUBSCRIBED_TOPICS, MAX_VALIDATE_QUEUE, MIN_ADDRESS_OBSERVERS,
+    MIN_GOSSIP_SIZE, NoListenAddrBound, ObservedAddresses, OutboundPublish, PEER_CHURN_THRESHOLD,
+    PEER_CHURN_WINDOW, PEER_SCORE_INSPECT_FREQUENCY, PUBLISH_QUEUE_DEPTH, PeerChurn, PeerInfo,
+    PeerInfoStore, PublishOverflow, PublishQueue, PublishQueueError, PublishSender,
+    RejectionCounts, RejectionReason, SEEN_MESSAGES_TTL, SIGNATURE_LENGTH, SUBSCRIPTION_TIMEOUT,
+    SubnetDiversity, SubnetDiversityConfig, SubnetLimitExceeded, SyncBlockPolicy,
+    TOPIC_ISOLATION_GRACE, TopicAllowlist, TopicIsolation, TopicValidationMode, VALIDATION_TIMEOUT,
+    ValidationResult, check_decompressed_size, decode_signed_block, decode_signed_block_with_limit,
+    default_config, default_config_builder, validate_with_timeout,
 };
 
 mod peers;
@@ -32,7 +42,9 @@ pub use peers::{
 
 mod discv5;
 pub use discv5::{
-    Discv5Builder, Discv5BuilderError, Discv5Driver, Discv5Handler, HandlerRequest, HandlerResponse,
+    DEFAULT_HIGH_PEER_WATERMARK, DEFAULT_LOW_PEER_WATERMARK, DiscoveryPace, Discv5Builder,
+    Discv5BuilderError, Discv5Driver, Discv5Handler, HandlerRequest, HandlerResponse,
+    PeerWatermarks,
 };
 
 mod utils;
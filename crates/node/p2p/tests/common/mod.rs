@@ -2,14 +2,22 @@
 
 use alloy_primitives::Address;
 use kona_p2p::{Behaviour, BlockHandler, GossipDriver};
-use libp2p::{Multiaddr, SwarmBuilder, identity::Keypair, multiaddr::Protocol};
-use std::net::Ipv4Addr;
+use libp2p::{
+    Multiaddr, SwarmBuilder, gossipsub::MessageAuthenticity, identity::Keypair, multiaddr::Protocol,
+};
+use std::{net::Ipv4Addr, time::Duration};
 use tokio::sync::watch::channel;
 
 /// Helper function to create a new gossip driver instance.
 pub fn gossip_driver(port: u16) -> GossipDriver {
+    gossip_driver_with_keepalive(port, None)
+}
+
+/// Helper function to create a new gossip driver instance, sending keepalive pings at the given
+/// interval if set.
+pub fn gossip_driver_with_keepalive(port: u16, keepalive: Option<Duration>) -> GossipDriver {
     let chain_id = 10;
-    let timeout = std::time::Duration::from_secs(60);
+    let timeout = Duration::from_secs(60);
     let mut addr = Multiaddr::empty();
     addr.push(Protocol::Ip4(Ipv4Addr::new(0, 0, 0, 0)));
     addr.push(Protocol::Tcp(port));
@@ -21,8 +29,12 @@ pub fn gossip_driver(port: u16) -> GossipDriver {
     let unsafe_block_signer = Address::default();
     let (_, unsafe_block_signer_recv) = channel(unsafe_block_signer);
     let (handler, _unsafe_block_recv) = BlockHandler::new(chain_id, unsafe_block_signer_recv);
-    let behaviour =
-        Behaviour::new(config, &[Box::new(handler.clone())]).expect("creates behaviour");
+    let mut behaviour =
+        Behaviour::new(config, &[Box::new(handler.clone())], MessageAuthenticity::Anonymous)
+            .expect("creates behaviour");
+    if let Some(interval) = keepalive {
+        behaviour.set_keepalive_interval(interval);
+    }
 
     // Construct the
     let keypair = Keypair::generate_secp256k1();
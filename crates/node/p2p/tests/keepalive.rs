@@ -0,0 +1,46 @@
+//! Test that idle connections are kept alive with periodic pings.
+
+mod common;
+
+use kona_p2p::Event;
+use libp2p::{Multiaddr, swarm::SwarmEvent};
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_idle_connection_receives_keepalive_pings() {
+    let interval = Duration::from_millis(100);
+    let mut driver = common::gossip_driver_with_keepalive(4007, Some(interval));
+    assert!(driver.listen().is_ok());
+
+    let mut driver_2 = common::gossip_driver(4008);
+    assert!(driver_2.listen().is_ok());
+
+    let peer_2 = *driver_2.local_peer_id();
+    let addr: Multiaddr = "/ip4/127.0.0.1/tcp/4008".parse().unwrap();
+    driver.swarm.dial(addr).unwrap();
+
+    // No gossip is published, so the only traffic on the connection is the keepalive pings.
+    let pings = tokio::time::timeout(Duration::from_secs(10), async {
+        let mut pings = 0;
+        while pings < 3 {
+            tokio::select! {
+                event = driver.select_next_some() => {
+                    if let SwarmEvent::Behaviour(Event::Ping(libp2p::ping::Event {
+                        peer,
+                        result: Ok(_),
+                        ..
+                    })) = event
+                    {
+                        assert_eq!(peer, peer_2);
+                        pings += 1;
+                    }
+                }
+                _ = driver_2.select_next_some() => {}
+            }
+        }
+        pings
+    })
+    .await
+    .expect("receives keepalive pings within the timeout");
+    assert_eq!(pings, 3);
+}
@@ -3,8 +3,7 @@
 use crate::NodeActor;
 use alloy_primitives::Address;
 use async_trait::async_trait;
-use kona_p2p::NetworkDriver;
-use libp2p::TransportError;
+use kona_p2p::{NetworkDriver, NetworkDriverError};
 use op_alloy_rpc_types_engine::OpNetworkPayloadEnvelope;
 use thiserror::Error;
 use tokio::{
@@ -87,7 +86,7 @@ impl NodeActor for NetworkActor {
         };
 
         // Start the network driver.
-        self.driver.start()?;
+        self.driver.start().await?;
 
         loop {
             match unsafe_block_receiver.recv() {
@@ -134,7 +133,7 @@ impl NodeActor for NetworkActor {
 pub enum NetworkActorError {
     /// Driver startup failed.
     #[error(transparent)]
-    DriverStartup(#[from] TransportError<std::io::Error>),
+    DriverStartup(#[from] NetworkDriverError),
     /// The network driver was missing its unsafe block receiver.
     #[error("Missing unsafe block receiver in network driver")]
     MissingUnsafeBlockReceiver,
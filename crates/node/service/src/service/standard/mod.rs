@@ -107,6 +107,7 @@ impl ValidatorNodeService for RollupNode {
             // Only in chain config and not rollup config...
             .with_unsafe_block_signer(Default::default())
             .with_chain_id(self.config.l2_chain_id)
+            .with_fork_schedule(self.config.clone())
             .with_gossip_addr(gossip_addr)
             .with_discovery_addr(discovery_addr)
             .with_keypair(keypair)
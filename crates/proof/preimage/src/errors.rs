@@ -1,5 +1,6 @@
 //! Errors for the `kona-preimage` crate.
 
+use crate::PreimageKey;
 use alloc::string::String;
 use thiserror::Error;
 
@@ -19,6 +20,11 @@ pub enum PreimageOracleError {
     /// Key not found.
     #[error("Key not found.")]
     KeyNotFound,
+    /// The preimage served for the key was rejected by the [PreimageVerifier].
+    ///
+    /// [PreimageVerifier]: crate::PreimageVerifier
+    #[error("Invalid preimage for key {0}.")]
+    InvalidPreimage(PreimageKey),
     /// Buffer length mismatch.
     #[error("Buffer length mismatch. Expected {0}, got {1}.")]
     BufferLengthMismatch(usize, usize),
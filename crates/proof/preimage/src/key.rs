@@ -1,7 +1,7 @@
 //! Contains the [PreimageKey] type, which is used to identify preimages that may be fetched from
-//! the preimage oracle.
+//! the preimage oracle, and the [BlobKey] type, which identifies a single field element of a blob.
 
-use alloy_primitives::{B256, Keccak256, U256};
+use alloy_primitives::{B256, Keccak256, U256, keccak256};
 #[cfg(feature = "rkyv")]
 use rkyv::{Archive, Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
 #[cfg(feature = "serde")]
@@ -158,6 +158,56 @@ impl core::fmt::Display for PreimageKey {
     }
 }
 
+/// The number of field elements in a blob.
+const FIELD_ELEMENTS_PER_BLOB: u64 = 4096;
+
+/// A blob key identifies a single field element of a blob, by the blob's KZG commitment and the
+/// index of the field element within the blob.
+///
+/// The [PreimageKey] of the field element is constructed as
+/// `keccak256(commitment ++ uint256(index))`, with the high-order byte of the digest set to the
+/// [PreimageKeyType::Blob] type byte.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct BlobKey {
+    /// The KZG commitment of the blob.
+    pub commitment: [u8; 48],
+    /// The index of the field element within the blob.
+    pub index: u64,
+}
+
+impl BlobKey {
+    /// Creates a new [BlobKey] for the field element at `index` of the blob with the given KZG
+    /// commitment.
+    pub const fn new(commitment: [u8; 48], index: u64) -> Self {
+        Self { commitment, index }
+    }
+
+    /// Returns an iterator over the [BlobKey]s of all 4096 field elements of the blob with the
+    /// given KZG commitment, in order.
+    pub fn field_elements(commitment: [u8; 48]) -> impl Iterator<Item = Self> {
+        (0..FIELD_ELEMENTS_PER_BLOB).map(move |index| Self::new(commitment, index))
+    }
+
+    /// Returns the keccak256 preimage of the key, `abi.encodePacked(commitment, uint256(index))`.
+    pub fn preimage(&self) -> [u8; 80] {
+        let mut preimage = [0u8; 80];
+        preimage[..48].copy_from_slice(&self.commitment);
+        preimage[72..].copy_from_slice(&self.index.to_be_bytes());
+        preimage
+    }
+
+    /// Returns the [PreimageKeyType::Blob] [PreimageKey] of the field element.
+    pub fn key(&self) -> PreimageKey {
+        PreimageKey::new(*keccak256(self.preimage()), PreimageKeyType::Blob)
+    }
+}
+
+impl From<BlobKey> for PreimageKey {
+    fn from(key: BlobKey) -> Self {
+        key.key()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -230,4 +280,40 @@ mod test {
             assert_eq!(actual, rendered_key);
         }
     }
+
+    #[test]
+    fn test_blob_key() {
+        let key = BlobKey::new([0xAAu8; 48], 7);
+        let mut preimage = [0u8; 80];
+        preimage[..48].copy_from_slice(&[0xAAu8; 48]);
+        preimage[79] = 7;
+        assert_eq!(key.preimage(), preimage);
+
+        let preimage_key = PreimageKey::from(key);
+        assert_eq!(preimage_key.key_type(), PreimageKeyType::Blob);
+        assert_eq!(preimage_key, PreimageKey::new(*keccak256(preimage), PreimageKeyType::Blob));
+    }
+
+    #[test]
+    fn test_blob_key_field_elements_reassemble_blob() {
+        use alloc::{collections::BTreeMap, vec::Vec};
+
+        let commitment = [0xBBu8; 48];
+        let blob = (0..FIELD_ELEMENTS_PER_BLOB as usize * 32)
+            .map(|i| (i % 251) as u8)
+            .collect::<Vec<u8>>();
+
+        // Store each field element under its blob key, as the host does.
+        let store = blob
+            .chunks(32)
+            .enumerate()
+            .map(|(i, element)| (BlobKey::new(commitment, i as u64).key(), element.to_vec()))
+            .collect::<BTreeMap<PreimageKey, Vec<u8>>>();
+        assert_eq!(store.len(), FIELD_ELEMENTS_PER_BLOB as usize);
+
+        let reassembled = BlobKey::field_elements(commitment)
+            .flat_map(|key| store[&key.key()].clone())
+            .collect::<Vec<u8>>();
+        assert_eq!(reassembled, blob);
+    }
 }
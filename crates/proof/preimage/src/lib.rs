@@ -15,11 +15,14 @@ extern crate tracing;
 pub mod errors;
 
 mod key;
-pub use key::{PreimageKey, PreimageKeyType};
+pub use key::{BlobKey, PreimageKey, PreimageKeyType};
 
 mod oracle;
 pub use oracle::{OracleReader, OracleServer};
 
+mod verify;
+pub use verify::{HashVerify, NoVerify, PreimageVerifier};
+
 mod hint;
 pub use hint::{HintReader, HintWriter};
 
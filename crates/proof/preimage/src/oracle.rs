@@ -1,14 +1,18 @@
 use crate::{
-    PreimageKey, PreimageOracleClient, PreimageOracleServer,
-    errors::{PreimageOracleError, PreimageOracleResult},
+    NoVerify, PreimageKey, PreimageOracleClient, PreimageOracleServer, PreimageVerifier,
+    errors::{ChannelError, PreimageOracleError, PreimageOracleResult},
     traits::{Channel, PreimageFetcher},
 };
 use alloc::{boxed::Box, vec::Vec};
 
 /// An [OracleReader] is a high-level interface to the preimage oracle channel.
+///
+/// Preimages read from the channel are checked with the [PreimageVerifier] before they are
+/// returned. By default, preimages are not verified.
 #[derive(Debug, Clone, Copy)]
-pub struct OracleReader<C> {
+pub struct OracleReader<C, V = NoVerify> {
     channel: C,
+    verifier: V,
 }
 
 impl<C> OracleReader<C>
@@ -17,7 +21,18 @@ where
 {
     /// Create a new [OracleReader] from a [Channel].
     pub const fn new(channel: C) -> Self {
-        Self { channel }
+        Self { channel, verifier: NoVerify }
+    }
+}
+
+impl<C, V> OracleReader<C, V>
+where
+    C: Channel,
+    V: PreimageVerifier,
+{
+    /// Sets the [PreimageVerifier] that preimages are checked with before they are returned.
+    pub fn with_verifier<V2: PreimageVerifier>(self, verifier: V2) -> OracleReader<C, V2> {
+        OracleReader { channel: self.channel, verifier }
     }
 
     /// Set the preimage key for the global oracle reader. This will overwrite any existing key, and
@@ -33,12 +48,54 @@ where
         self.channel.read_exact(&mut length_buffer).await?;
         Ok(u64::from_be_bytes(length_buffer) as usize)
     }
+
+    /// Get the data corresponding to the given key from the host, writing it into the provided
+    /// buffer like [PreimageOracleClient::get_exact], and invoking `on_progress` with the total
+    /// number of bytes read each time a chunk of the preimage is read from the channel.
+    ///
+    /// This allows progress to be reported while a large preimage is read, rather than only once
+    /// it has been read in full.
+    pub async fn get_exact_with_progress<F>(
+        &self,
+        key: PreimageKey,
+        buf: &mut [u8],
+        mut on_progress: F,
+    ) -> PreimageOracleResult<()>
+    where
+        F: FnMut(usize),
+    {
+        trace!(target: "oracle_client", "Requesting data from preimage oracle. Key {key}");
+
+        // Write the key to the host and read the length of the preimage.
+        let length = self.write_key(key).await?;
+
+        // Ensure the buffer is the correct size.
+        if buf.len() != length {
+            return Err(PreimageOracleError::BufferLengthMismatch(length, buf.len()));
+        }
+
+        let mut read = 0;
+        while read < length {
+            let chunk = self.channel.read(&mut buf[read..]).await?;
+            if chunk == 0 {
+                return Err(ChannelError::UnexpectedEOF.into());
+            }
+            read += chunk;
+            on_progress(read);
+        }
+        self.verifier.verify(key, buf)?;
+
+        trace!(target: "oracle_client", "Successfully read data from preimage oracle. Key: {key}");
+
+        Ok(())
+    }
 }
 
 #[async_trait::async_trait]
-impl<C> PreimageOracleClient for OracleReader<C>
+impl<C, V> PreimageOracleClient for OracleReader<C, V>
 where
     C: Channel + Send + Sync,
+    V: PreimageVerifier + Send + Sync,
 {
     /// Get the data corresponding to the currently set key from the host. Return the data in a new
     /// heap allocated `Vec<u8>`
@@ -47,16 +104,14 @@ where
 
         let length = self.write_key(key).await?;
 
-        if length == 0 {
-            return Ok(Default::default());
-        }
-
         let mut data_buffer = alloc::vec![0; length];
+        if length > 0 {
+            trace!(target: "oracle_client", "Reading data from preimage oracle. Key {key}");
 
-        trace!(target: "oracle_client", "Reading data from preimage oracle. Key {key}");
-
-        // Grab a read lock on the preimage channel to read the data.
-        self.channel.read_exact(&mut data_buffer).await?;
+            // Grab a read lock on the preimage channel to read the data.
+            self.channel.read_exact(&mut data_buffer).await?;
+        }
+        self.verifier.verify(key, &data_buffer)?;
 
         trace!(target: "oracle_client", "Successfully read data from preimage oracle. Key: {key}");
 
@@ -78,11 +133,10 @@ where
             return Err(PreimageOracleError::BufferLengthMismatch(length, buf.len()));
         }
 
-        if length == 0 {
-            return Ok(());
+        if length > 0 {
+            self.channel.read_exact(buf).await?;
         }
-
-        self.channel.read_exact(buf).await?;
+        self.verifier.verify(key, buf)?;
 
         trace!(target: "oracle_client", "Successfully read data from preimage oracle. Key: {key}");
 
@@ -138,7 +192,9 @@ where
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::{PreimageKeyType, native_channel::BidirectionalChannel};
+    use crate::{
+        HashVerify, PreimageKeyType, errors::ChannelResult, native_channel::BidirectionalChannel,
+    };
     use alloc::sync::Arc;
     use alloy_primitives::keccak256;
     use std::collections::HashMap;
@@ -202,6 +258,105 @@ mod test {
         assert_eq!(contents_b, MOCK_DATA_B);
     }
 
+    /// A channel that serves a length-prefixed preimage at most [ChunkedChannel::CHUNK_SIZE]
+    /// bytes per read.
+    struct ChunkedChannel(std::sync::Mutex<std::collections::VecDeque<u8>>);
+
+    impl ChunkedChannel {
+        const CHUNK_SIZE: usize = 4;
+
+        fn new(preimage: &[u8]) -> Self {
+            let response =
+                (preimage.len() as u64).to_be_bytes().into_iter().chain(preimage.to_vec());
+            Self(std::sync::Mutex::new(response.collect()))
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Channel for ChunkedChannel {
+        async fn read(&self, buf: &mut [u8]) -> ChannelResult<usize> {
+            let mut response = self.0.lock().unwrap();
+            let n = buf.len().min(Self::CHUNK_SIZE).min(response.len());
+            buf.iter_mut().take(n).for_each(|b| *b = response.pop_front().unwrap());
+            Ok(n)
+        }
+
+        async fn read_exact(&self, buf: &mut [u8]) -> ChannelResult<usize> {
+            let mut response = self.0.lock().unwrap();
+            if response.len() < buf.len() {
+                return Err(ChannelError::UnexpectedEOF);
+            }
+            buf.iter_mut().for_each(|b| *b = response.pop_front().unwrap());
+            Ok(buf.len())
+        }
+
+        async fn write(&self, buf: &[u8]) -> ChannelResult<usize> {
+            Ok(buf.len())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_oracle_reader_get_exact_with_progress() {
+        const MOCK_DATA: &[u8] = b"a preimage read in chunks";
+        let key = PreimageKey::new(*keccak256(MOCK_DATA), PreimageKeyType::Keccak256);
+        let oracle_reader = OracleReader::new(ChunkedChannel::new(MOCK_DATA));
+
+        let mut progress = Vec::new();
+        let mut contents = [0u8; MOCK_DATA.len()];
+        oracle_reader
+            .get_exact_with_progress(key, &mut contents, |read| progress.push(read))
+            .await
+            .unwrap();
+        assert_eq!(contents, MOCK_DATA);
+
+        // Progress is reported after each chunk, with strictly increasing counts.
+        assert_eq!(progress.len(), MOCK_DATA.len().div_ceil(ChunkedChannel::CHUNK_SIZE));
+        assert!(progress.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(progress.last(), Some(&MOCK_DATA.len()));
+    }
+
+    #[tokio::test]
+    async fn test_oracle_reader_get_exact_with_progress_eof() {
+        // The host claims a longer preimage than it serves.
+        let channel = ChunkedChannel::new(b"short");
+        channel.0.lock().unwrap().truncate(8 + 2);
+        let oracle_reader = OracleReader::new(channel);
+
+        let mut contents = [0u8; 5];
+        let err = oracle_reader
+            .get_exact_with_progress(PreimageKey::new_local(0), &mut contents, |_| {})
+            .await
+            .unwrap_err();
+        assert!(matches!(err, PreimageOracleError::IOError(ChannelError::UnexpectedEOF)));
+    }
+
+    #[tokio::test]
+    async fn test_oracle_reader_verifier() {
+        const MOCK_DATA: &[u8] = b"the real preimage";
+        let key = PreimageKey::new(*keccak256(MOCK_DATA), PreimageKeyType::Keccak256);
+        let tampered = b"a tampered preimage";
+
+        // Without verification, the tampered preimage is returned.
+        let oracle_reader = OracleReader::new(ChunkedChannel::new(tampered));
+        assert_eq!(oracle_reader.get(key).await.unwrap(), tampered);
+
+        let oracle_reader =
+            OracleReader::new(ChunkedChannel::new(tampered)).with_verifier(HashVerify);
+        let err = oracle_reader.get(key).await.unwrap_err();
+        assert!(matches!(err, PreimageOracleError::InvalidPreimage(k) if k == key));
+
+        let oracle_reader =
+            OracleReader::new(ChunkedChannel::new(tampered)).with_verifier(HashVerify);
+        let mut contents = [0u8; 19];
+        let err = oracle_reader.get_exact(key, &mut contents).await.unwrap_err();
+        assert!(matches!(err, PreimageOracleError::InvalidPreimage(k) if k == key));
+
+        // The real preimage passes verification.
+        let oracle_reader =
+            OracleReader::new(ChunkedChannel::new(MOCK_DATA)).with_verifier(HashVerify);
+        assert_eq!(oracle_reader.get(key).await.unwrap(), MOCK_DATA);
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
     async fn test_oracle_client_and_host() {
         const MOCK_DATA_A: &[u8] = b"1234567890";
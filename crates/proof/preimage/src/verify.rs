@@ -0,0 +1,90 @@
+//! Contains the [PreimageVerifier] trait and its built-in implementations.
+
+use crate::{
+    PreimageKey, PreimageKeyType,
+    errors::{PreimageOracleError, PreimageOracleResult},
+};
+use alloy_primitives::keccak256;
+
+/// A [PreimageVerifier] checks the preimages served by the host against the keys they were
+/// requested with, before they are returned by the [OracleReader].
+///
+/// Verification trades speed for safety: programs that validate the structure of every preimage
+/// they consume may skip verification with [NoVerify], while [HashVerify] catches a host serving
+/// the wrong data for a key. Custom strategies may implement this trait, which is also
+/// implemented for closures with the signature of [PreimageVerifier::verify].
+///
+/// [OracleReader]: crate::OracleReader
+pub trait PreimageVerifier {
+    /// Verifies the preimage served for the given key, returning
+    /// [PreimageOracleError::InvalidPreimage] if it is rejected.
+    fn verify(&self, key: PreimageKey, preimage: &[u8]) -> PreimageOracleResult<()>;
+}
+
+impl<F> PreimageVerifier for F
+where
+    F: Fn(PreimageKey, &[u8]) -> PreimageOracleResult<()>,
+{
+    fn verify(&self, key: PreimageKey, preimage: &[u8]) -> PreimageOracleResult<()> {
+        self(key, preimage)
+    }
+}
+
+/// A [PreimageVerifier] that accepts every preimage.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct NoVerify;
+
+impl PreimageVerifier for NoVerify {
+    fn verify(&self, _: PreimageKey, _: &[u8]) -> PreimageOracleResult<()> {
+        Ok(())
+    }
+}
+
+/// A [PreimageVerifier] that recomputes the key of [PreimageKeyType::Keccak256] preimages from
+/// their contents, rejecting preimages whose hash does not match the requested key.
+///
+/// Keys of other types are not a hash of their preimage, so their preimages are accepted.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct HashVerify;
+
+impl PreimageVerifier for HashVerify {
+    fn verify(&self, key: PreimageKey, preimage: &[u8]) -> PreimageOracleResult<()> {
+        if key.key_type() == PreimageKeyType::Keccak256 &&
+            PreimageKey::new_keccak256(*keccak256(preimage)) != key
+        {
+            return Err(PreimageOracleError::InvalidPreimage(key));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_verify() {
+        let preimage = b"preimage";
+        let key = PreimageKey::new_keccak256(*keccak256(preimage));
+        assert!(HashVerify.verify(key, preimage).is_ok());
+        assert!(matches!(
+            HashVerify.verify(key, b"tampered"),
+            Err(PreimageOracleError::InvalidPreimage(k)) if k == key
+        ));
+
+        // Keys that are not a hash of their preimage cannot be checked.
+        assert!(HashVerify.verify(PreimageKey::new_local(1), b"tampered").is_ok());
+    }
+
+    #[test]
+    fn test_custom_verify() {
+        let non_empty = |key, preimage: &[u8]| {
+            if preimage.is_empty() {
+                return Err(PreimageOracleError::InvalidPreimage(key));
+            }
+            Ok(())
+        };
+        assert!(non_empty.verify(PreimageKey::new_local(1), b"data").is_ok());
+        assert!(non_empty.verify(PreimageKey::new_local(1), b"").is_err());
+    }
+}
@@ -207,14 +207,7 @@ where
     type Error = OracleProviderError;
 
     fn trie_node_by_hash(&self, key: B256) -> Result<TrieNode, Self::Error> {
-        kona_proof::block_on(async move {
-            let trie_node_rlp = self
-                .oracle
-                .get(PreimageKey::new(*key, PreimageKeyType::Keccak256))
-                .await
-                .map_err(OracleProviderError::Preimage)?;
-            TrieNode::decode(&mut trie_node_rlp.as_ref()).map_err(OracleProviderError::Rlp)
-        })
+        kona_proof::block_on(kona_proof::fetch_trie_node(self.oracle.as_ref(), key))
     }
 }
 
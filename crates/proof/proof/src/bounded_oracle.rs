@@ -0,0 +1,276 @@
+//! Contains the [BoundedOracle], which is a wrapper around an oracle that limits the number of
+//! in-flight preimage requests.
+
+use alloc::{boxed::Box, collections::VecDeque, sync::Arc, vec::Vec};
+use async_trait::async_trait;
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+use kona_preimage::{
+    HintWriterClient, PreimageKey, PreimageOracleClient, errors::PreimageOracleResult,
+};
+use spin::Mutex;
+
+/// The default maximum number of in-flight preimage requests of a [BoundedOracle].
+pub const DEFAULT_MAX_IN_FLIGHT: usize = 16;
+
+/// A wrapper around an oracle that limits the number of [PreimageOracleClient::get] and
+/// [PreimageOracleClient::get_exact] calls that may be in flight at once.
+///
+/// When preimage requests are pipelined, an unbounded number of requests may queue up on the host.
+/// Once the limit is reached, further requests wait for an in-flight request to complete before
+/// they are sent, applying backpressure to the caller. Hint writes are not limited. Clones of the
+/// oracle share the same limit.
+#[derive(Debug, Clone)]
+pub struct BoundedOracle<T> {
+    /// The wrapped oracle.
+    inner: T,
+    /// The maximum number of in-flight preimage requests.
+    max_in_flight: usize,
+    /// The in-flight preimage requests, and the tasks waiting to send one.
+    state: Arc<Mutex<InFlight>>,
+}
+
+/// The in-flight preimage requests of a [BoundedOracle].
+#[derive(Debug, Default)]
+struct InFlight {
+    /// The number of in-flight preimage requests.
+    count: usize,
+    /// The identifier of the next waiting task.
+    next_waiter: u64,
+    /// The tasks waiting for an in-flight request to complete, in the order they started waiting.
+    waiters: VecDeque<(u64, Waker)>,
+    /// The waiting tasks that were handed the slot of a completed request, but have not been
+    /// polled since.
+    granted: Vec<u64>,
+}
+
+impl InFlight {
+    /// Releases a slot, handing it over to the longest-waiting task if there is one.
+    fn release(&mut self) {
+        match self.waiters.pop_front() {
+            Some((id, waker)) => {
+                self.granted.push(id);
+                waker.wake();
+            }
+            None => self.count -= 1,
+        }
+    }
+}
+
+impl<T> BoundedOracle<T> {
+    /// Creates a new [BoundedOracle] that wraps the given oracle, allowing at most `max_in_flight`
+    /// preimage requests at once. A limit of `0` is treated as `1`.
+    pub fn new(inner: T, max_in_flight: usize) -> Self {
+        Self {
+            inner,
+            max_in_flight: max_in_flight.max(1),
+            state: Arc::new(Mutex::new(InFlight::default())),
+        }
+    }
+
+    /// Returns the maximum number of in-flight preimage requests.
+    pub const fn max_in_flight(&self) -> usize {
+        self.max_in_flight
+    }
+
+    /// Returns the number of preimage requests currently in flight.
+    pub fn in_flight(&self) -> usize {
+        self.state.lock().count
+    }
+
+    /// Waits until a preimage request may be sent, returning a [Slot] that releases it once
+    /// dropped.
+    fn acquire(&self) -> Acquire<'_> {
+        Acquire { state: &self.state, max_in_flight: self.max_in_flight, waiter: None }
+    }
+}
+
+/// A future that resolves once a preimage request may be sent through a [BoundedOracle].
+///
+/// Waiting tasks are served in the order they started waiting. A completed request hands its
+/// slot directly to the longest-waiting task, so that only that task is woken.
+#[derive(Debug)]
+struct Acquire<'a> {
+    /// The in-flight preimage requests of the oracle.
+    state: &'a Mutex<InFlight>,
+    /// The maximum number of in-flight preimage requests.
+    max_in_flight: usize,
+    /// The identifier of this task in the waiter queue, if it is waiting.
+    waiter: Option<u64>,
+}
+
+impl<'a> Future for Acquire<'a> {
+    type Output = Slot<'a>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let mut state = this.state.lock();
+        match this.waiter {
+            Some(id) => {
+                if let Some(index) = state.granted.iter().position(|&g| g == id) {
+                    state.granted.swap_remove(index);
+                    this.waiter = None;
+                    return Poll::Ready(Slot { state: this.state });
+                }
+                if let Some((_, waker)) = state.waiters.iter_mut().find(|(w, _)| *w == id) {
+                    waker.clone_from(cx.waker());
+                }
+            }
+            None => {
+                if state.count < this.max_in_flight && state.waiters.is_empty() {
+                    state.count += 1;
+                    return Poll::Ready(Slot { state: this.state });
+                }
+                let id = state.next_waiter;
+                state.next_waiter += 1;
+                state.waiters.push_back((id, cx.waker().clone()));
+                this.waiter = Some(id);
+            }
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop for Acquire<'_> {
+    fn drop(&mut self) {
+        let Some(id) = self.waiter else { return };
+
+        // A cancelled task gives up its place in the queue, or passes on a slot it was handed.
+        let mut state = self.state.lock();
+        if let Some(index) = state.granted.iter().position(|&g| g == id) {
+            state.granted.swap_remove(index);
+            state.release();
+        } else {
+            state.waiters.retain(|(w, _)| *w != id);
+        }
+    }
+}
+
+/// An in-flight preimage request of a [BoundedOracle], which is released once dropped.
+#[derive(Debug)]
+struct Slot<'a> {
+    /// The in-flight preimage requests of the oracle.
+    state: &'a Mutex<InFlight>,
+}
+
+impl Drop for Slot<'_> {
+    fn drop(&mut self) {
+        self.state.lock().release();
+    }
+}
+
+#[async_trait]
+impl<T> PreimageOracleClient for BoundedOracle<T>
+where
+    T: PreimageOracleClient + Send + Sync,
+{
+    async fn get(&self, key: PreimageKey) -> PreimageOracleResult<Vec<u8>> {
+        let _slot = self.acquire().await;
+        self.inner.get(key).await
+    }
+
+    async fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> PreimageOracleResult<()> {
+        let _slot = self.acquire().await;
+        self.inner.get_exact(key, buf).await
+    }
+}
+
+#[async_trait]
+impl<T> HintWriterClient for BoundedOracle<T>
+where
+    T: HintWriterClient + Send + Sync,
+{
+    async fn write(&self, hint: &str) -> PreimageOracleResult<()> {
+        self.inner.write(hint).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    /// An oracle that serves empty preimages after a delay, and records the highest number of
+    /// requests it served at once.
+    #[derive(Debug, Default)]
+    struct ConcurrencyOracle {
+        active: AtomicUsize,
+        peak: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl PreimageOracleClient for ConcurrencyOracle {
+        async fn get(&self, _: PreimageKey) -> PreimageOracleResult<Vec<u8>> {
+            let active = self.active.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(active, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            self.active.fetch_sub(1, Ordering::SeqCst);
+            Ok(Vec::new())
+        }
+
+        async fn get_exact(&self, key: PreimageKey, _: &mut [u8]) -> PreimageOracleResult<()> {
+            self.get(key).await.map(|_| ())
+        }
+    }
+
+    /// Sends four concurrent preimage requests through the oracle, returning the highest number
+    /// that were in flight at once.
+    async fn peak_in_flight(max_in_flight: usize) -> usize {
+        let oracle = BoundedOracle::new(ConcurrencyOracle::default(), max_in_flight);
+        let key = PreimageKey::new_local(1);
+        let (a, b, c, d) = tokio::join!(
+            oracle.get(key),
+            oracle.get(key),
+            oracle.get_exact(key, &mut []),
+            oracle.get(key)
+        );
+        assert!(a.is_ok() && b.is_ok() && c.is_ok() && d.is_ok());
+        assert_eq!(oracle.in_flight(), 0);
+        oracle.inner.peak.load(Ordering::SeqCst)
+    }
+
+    #[tokio::test]
+    async fn test_bounded_oracle_serializes_requests() {
+        assert_eq!(peak_in_flight(1).await, 1);
+        assert_eq!(peak_in_flight(0).await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_bounded_oracle_limits_requests() {
+        assert_eq!(peak_in_flight(2).await, 2);
+        assert_eq!(peak_in_flight(DEFAULT_MAX_IN_FLIGHT).await, 4);
+    }
+
+    #[tokio::test]
+    async fn test_bounded_oracle_cancelled_waiter() {
+        let oracle = BoundedOracle::new(ConcurrencyOracle::default(), 1);
+        let slot = oracle.acquire().await;
+
+        // A waiter that is cancelled while queued does not hold on to the slot.
+        let waiter = tokio::time::timeout(Duration::from_millis(5), oracle.acquire()).await;
+        assert!(waiter.is_err());
+        drop(slot);
+        assert_eq!(oracle.in_flight(), 0);
+        assert!(oracle.state.lock().waiters.is_empty());
+
+        // A waiter that is cancelled after being handed the slot passes it on.
+        let slot = oracle.acquire().await;
+        let mut handed = Box::pin(oracle.acquire());
+        assert!(poll_once(handed.as_mut()).is_pending());
+        drop(slot);
+        drop(handed);
+        assert_eq!(oracle.in_flight(), 0);
+        assert!(oracle.get(PreimageKey::new_local(1)).await.is_ok());
+    }
+
+    /// Polls the future once with a no-op waker.
+    fn poll_once<F: Future + ?Sized>(future: Pin<&mut F>) -> Poll<F::Output> {
+        future.poll(&mut Context::from_waker(Waker::noop()))
+    }
+}
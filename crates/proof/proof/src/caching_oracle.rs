@@ -4,18 +4,49 @@
 //! [OracleReader]: kona_preimage::OracleReader
 //! [HintWriter]: kona_preimage::HintWriter
 
-use alloc::{boxed::Box, sync::Arc, vec::Vec};
+use crate::{HintCategory, HintType, errors::CacheSnapshotError};
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, VecDeque},
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
 use async_trait::async_trait;
-use core::num::NonZeroUsize;
+use core::{num::NonZeroUsize, str::FromStr};
 use kona_preimage::{
     HintWriterClient, PreimageKey, PreimageOracleClient, errors::PreimageOracleResult,
 };
 use lru::LruCache;
 use spin::Mutex;
 
+/// The policy applied by a [CachingOracle] to the preimages fetched for a [HintCategory].
+///
+/// The client program has no clock, so cached preimages live until they are evicted as the least
+/// recently used entry.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CachePolicy {
+    /// Preimages are stored in the cache.
+    #[default]
+    Cache,
+    /// Preimages bypass the cache, so that they do not evict more reusable entries. They are
+    /// neither stored in nor served from it.
+    Bypass,
+}
+
 /// A wrapper around an [OracleReader] and [HintWriter] that stores a configurable number of
 /// responses in an [LruCache] for quick retrieval.
 ///
+/// Preimages are attributed to the [HintCategory] of the most recent hint written through the
+/// oracle, and cached according to the [CachePolicy] set for it with
+/// [CachingOracle::with_cache_policy]. Preimages of categories without a policy, and those fetched
+/// before any hint is written, are cached.
+///
+/// Hints are forwarded to the [HintWriter] as they are written, unless lazy hinting is enabled
+/// with [CachingOracle::with_lazy_hints]. Lazy hints are queued until a preimage is fetched that
+/// is not in the cache, and are forwarded in order just before that fetch. In a warm-cache run,
+/// where every preimage the hints would prepare is already cached, the hints are never written.
+///
 /// [OracleReader]: kona_preimage::OracleReader
 /// [HintWriter]: kona_preimage::HintWriter
 #[allow(unreachable_pub)]
@@ -31,6 +62,16 @@ where
     oracle_reader: OR,
     /// Hint writer type.
     hint_writer: HW,
+    /// The cache policy of each hint category.
+    policies: BTreeMap<HintCategory, CachePolicy>,
+    /// The category of the most recent hint written through the oracle.
+    last_category: Arc<Mutex<Option<HintCategory>>>,
+    /// Whether hints are queued until the next cache miss, rather than forwarded as they are
+    /// written.
+    lazy_hints: bool,
+    /// The hints written through the oracle that have not been forwarded to the hint writer yet,
+    /// oldest first.
+    pending_hints: Arc<Mutex<VecDeque<String>>>,
 }
 
 impl<OR, HW> CachingOracle<OR, HW>
@@ -49,8 +90,135 @@ where
             ))),
             oracle_reader,
             hint_writer,
+            policies: BTreeMap::new(),
+            last_category: Arc::new(Mutex::new(None)),
+            lazy_hints: false,
+            pending_hints: Arc::new(Mutex::new(VecDeque::new())),
         }
     }
+
+    /// Enables lazy hinting, queueing hints until a preimage is fetched that is not in the cache.
+    ///
+    /// At most [MAX_PENDING_HINTS] hints are queued. Once the queue is full, the oldest hint is
+    /// forwarded to make room for the next one.
+    pub const fn with_lazy_hints(mut self) -> Self {
+        self.lazy_hints = true;
+        self
+    }
+
+    /// Sets the [CachePolicy] applied to preimages fetched for hints of the given category.
+    pub fn with_cache_policy(mut self, category: HintCategory, policy: CachePolicy) -> Self {
+        self.policies.insert(category, policy);
+        self
+    }
+
+    /// Returns the [CachePolicy] applied to preimages fetched for hints of the given category.
+    pub fn cache_policy(&self, category: HintCategory) -> CachePolicy {
+        self.policies.get(&category).copied().unwrap_or_default()
+    }
+
+    /// Returns `true` if preimages fetched now should bypass the cache, according to the policy of
+    /// the category of the most recent hint.
+    fn bypasses_cache(&self) -> bool {
+        self.last_category
+            .lock()
+            .is_some_and(|category| self.cache_policy(category) == CachePolicy::Bypass)
+    }
+
+    /// Returns the cached preimage for the given key, if any.
+    fn cached(&self, key: &PreimageKey) -> Option<Vec<u8>> {
+        self.cache.lock().get(key).cloned()
+    }
+
+    /// Forwards the pending hints to the hint writer, in the order they were written. Called
+    /// before fetching a preimage that is not in the cache, so that the host has been hinted for
+    /// it.
+    async fn flush_hints(&self) -> PreimageOracleResult<()> {
+        loop {
+            let hint = self.pending_hints.lock().pop_front();
+            match hint {
+                Some(hint) => self.hint_writer.write(&hint).await?,
+                None => return Ok(()),
+            }
+        }
+    }
+
+    /// Serializes the entries of the cache into a snapshot, which may be persisted and restored
+    /// into another [CachingOracle] with [CachingOracle::load].
+    ///
+    /// **Layout**:
+    /// | Bytes                | Description                                 |
+    /// |----------------------|---------------------------------------------|
+    /// | [0, 1)               | Snapshot format version                     |
+    /// | [1, 9)               | Big-endian number of entries                |
+    /// | [9, ..)              | Entries, least recently used first          |
+    ///
+    /// Each entry is the 32-byte preimage key, followed by the big-endian 8-byte length of the
+    /// preimage and the preimage itself.
+    pub fn save(&self) -> Vec<u8> {
+        let cache_lock = self.cache.lock();
+
+        let mut snapshot = Vec::new();
+        snapshot.push(CACHE_SNAPSHOT_VERSION);
+        snapshot.extend_from_slice(&(cache_lock.len() as u64).to_be_bytes());
+        for (key, value) in cache_lock.iter().rev() {
+            snapshot.extend_from_slice(&<[u8; 32]>::from(*key));
+            snapshot.extend_from_slice(&(value.len() as u64).to_be_bytes());
+            snapshot.extend_from_slice(value);
+        }
+        snapshot
+    }
+
+    /// Loads the entries of a snapshot produced by [CachingOracle::save] into the cache,
+    /// preserving their recency.
+    ///
+    /// The snapshot is validated in full before any entries are inserted, so the cache is left
+    /// untouched if the snapshot is invalid. If the snapshot holds more entries than the cache
+    /// capacity, only the most recently used entries are retained.
+    pub fn load(&self, snapshot: &[u8]) -> Result<(), CacheSnapshotError> {
+        let (&version, mut rest) = snapshot.split_first().ok_or(CacheSnapshotError::Truncated)?;
+        if version != CACHE_SNAPSHOT_VERSION {
+            return Err(CacheSnapshotError::UnsupportedVersion(version));
+        }
+
+        let count = u64::from_be_bytes(take(&mut rest)?);
+        let mut entries = Vec::new();
+        for _ in 0..count {
+            let key = PreimageKey::try_from(take::<32>(&mut rest)?)
+                .map_err(|_| CacheSnapshotError::InvalidKey)?;
+            let len = usize::try_from(u64::from_be_bytes(take(&mut rest)?))
+                .map_err(|_| CacheSnapshotError::Truncated)?;
+            if rest.len() < len {
+                return Err(CacheSnapshotError::Truncated);
+            }
+            let (value, remaining) = rest.split_at(len);
+            entries.push((key, value.to_vec()));
+            rest = remaining;
+        }
+
+        let mut cache_lock = self.cache.lock();
+        for (key, value) in entries {
+            cache_lock.put(key, value);
+        }
+        Ok(())
+    }
+}
+
+/// The maximum number of hints a [CachingOracle] with lazy hinting queues before forwarding the
+/// oldest.
+pub const MAX_PENDING_HINTS: usize = 256;
+
+/// The current format version of [CachingOracle] snapshots.
+const CACHE_SNAPSHOT_VERSION: u8 = 1;
+
+/// Splits the first `N` bytes off of `data`.
+fn take<const N: usize>(data: &mut &[u8]) -> Result<[u8; N], CacheSnapshotError> {
+    if data.len() < N {
+        return Err(CacheSnapshotError::Truncated);
+    }
+    let (head, rest) = data.split_at(N);
+    *data = rest;
+    Ok(head.try_into().expect("slice has length N"))
 }
 
 /// A trait that provides a method to flush a cache.
@@ -77,28 +245,36 @@ where
     HW: HintWriterClient + Sync,
 {
     async fn get(&self, key: PreimageKey) -> PreimageOracleResult<Vec<u8>> {
-        let mut cache_lock = self.cache.lock();
-        if let Some(value) = cache_lock.get(&key) {
-            Ok(value.clone())
-        } else {
-            let value = self.oracle_reader.get(key).await?;
-            cache_lock.put(key, value.clone());
-            Ok(value)
+        if self.bypasses_cache() {
+            self.flush_hints().await?;
+            return self.oracle_reader.get(key).await;
+        }
+
+        if let Some(value) = self.cached(&key) {
+            return Ok(value);
         }
+        self.flush_hints().await?;
+        let value = self.oracle_reader.get(key).await?;
+        self.cache.lock().put(key, value.clone());
+        Ok(value)
     }
 
     async fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> PreimageOracleResult<()> {
-        let mut cache_lock = self.cache.lock();
-        if let Some(value) = cache_lock.get(&key) {
+        if self.bypasses_cache() {
+            self.flush_hints().await?;
+            return self.oracle_reader.get_exact(key, buf).await;
+        }
+
+        if let Some(value) = self.cached(&key) {
             // SAFETY: The value never enters the cache unless the preimage length matches the
             // buffer length, due to the checks in the OracleReader.
             buf.copy_from_slice(value.as_slice());
-            Ok(())
-        } else {
-            self.oracle_reader.get_exact(key, buf).await?;
-            cache_lock.put(key, buf.to_vec());
-            Ok(())
+            return Ok(());
         }
+        self.flush_hints().await?;
+        self.oracle_reader.get_exact(key, buf).await?;
+        self.cache.lock().put(key, buf.to_vec());
+        Ok(())
     }
 }
 
@@ -108,7 +284,200 @@ where
     OR: PreimageOracleClient + Sync,
     HW: HintWriterClient + Sync,
 {
+    /// Forwards the hint to the hint writer, or with lazy hinting, queues it until the next
+    /// cache miss.
     async fn write(&self, hint: &str) -> PreimageOracleResult<()> {
-        self.hint_writer.write(hint).await
+        // Strip the hint data and any compression suffix to find the hint type.
+        let raw_type = hint.split([' ', '+']).next().unwrap_or_default();
+        *self.last_category.lock() = HintType::from_str(raw_type).ok().map(|ty| ty.category());
+        if !self.lazy_hints {
+            return self.hint_writer.write(hint).await;
+        }
+
+        let evicted = {
+            let mut pending = self.pending_hints.lock();
+            pending.push_back(hint.to_string());
+            if pending.len() > MAX_PENDING_HINTS { pending.pop_front() } else { None }
+        };
+        match evicted {
+            Some(evicted) => self.hint_writer.write(&evicted).await,
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use kona_preimage::errors::PreimageOracleError;
+
+    /// An oracle that serves the key itself as the preimage, and counts the fetches made.
+    #[derive(Debug, Default)]
+    struct CountingOracle(AtomicUsize);
+
+    #[async_trait]
+    impl PreimageOracleClient for CountingOracle {
+        async fn get(&self, key: PreimageKey) -> PreimageOracleResult<Vec<u8>> {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            Ok(<[u8; 32]>::from(key).to_vec())
+        }
+
+        async fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> PreimageOracleResult<()> {
+            let value = self.get(key).await?;
+            if buf.len() != value.len() {
+                return Err(PreimageOracleError::BufferLengthMismatch(value.len(), buf.len()));
+            }
+            buf.copy_from_slice(&value);
+            Ok(())
+        }
+    }
+
+    #[derive(Debug)]
+    struct NoopHintWriter;
+
+    #[async_trait]
+    impl HintWriterClient for NoopHintWriter {
+        async fn write(&self, _: &str) -> PreimageOracleResult<()> {
+            Ok(())
+        }
+    }
+
+    /// A hint writer that records the hints written to it.
+    #[derive(Debug, Default)]
+    struct RecordingHintWriter(Mutex<Vec<String>>);
+
+    #[async_trait]
+    impl HintWriterClient for RecordingHintWriter {
+        async fn write(&self, hint: &str) -> PreimageOracleResult<()> {
+            self.0.lock().push(hint.to_string());
+            Ok(())
+        }
+    }
+
+    fn keys() -> Vec<PreimageKey> {
+        (0..4u64).map(PreimageKey::new_local).collect()
+    }
+
+    #[tokio::test]
+    async fn test_caching_oracle_snapshot_roundtrip() {
+        let oracle = CachingOracle::new(16, CountingOracle::default(), NoopHintWriter);
+        for key in keys() {
+            oracle.get(key).await.unwrap();
+        }
+        assert_eq!(oracle.oracle_reader.0.load(Ordering::Relaxed), 4);
+
+        let snapshot = oracle.save();
+        assert_eq!(snapshot[0], CACHE_SNAPSHOT_VERSION);
+
+        let restored = CachingOracle::new(16, CountingOracle::default(), NoopHintWriter);
+        restored.load(&snapshot).unwrap();
+        for key in keys() {
+            assert_eq!(restored.get(key).await.unwrap(), <[u8; 32]>::from(key).to_vec());
+        }
+        assert_eq!(restored.oracle_reader.0.load(Ordering::Relaxed), 0);
+
+        // Recency is preserved, and the keys were accessed in their original order above.
+        assert_eq!(restored.save(), snapshot);
+    }
+
+    #[tokio::test]
+    async fn test_caching_oracle_bypass_policy() {
+        let oracle = CachingOracle::new(16, CountingOracle::default(), NoopHintWriter)
+            .with_cache_policy(HintCategory::Other, CachePolicy::Bypass);
+        let [code_key, output_key, ..] = keys()[..] else { unreachable!() };
+
+        // Preimages of a cached category are fetched once.
+        HintType::L2Code.with_data(&[&[0; 32]]).send(&oracle).await.unwrap();
+        oracle.get(code_key).await.unwrap();
+        oracle.get(code_key).await.unwrap();
+        assert_eq!(oracle.oracle_reader.0.load(Ordering::Relaxed), 1);
+
+        // Preimages of a bypassed category are fetched every time, and never cached.
+        HintType::StartingL2Output.with_data(&[&[0; 32]]).send(&oracle).await.unwrap();
+        oracle.get(output_key).await.unwrap();
+        let mut buf = [0u8; 32];
+        oracle.get_exact(output_key, &mut buf).await.unwrap();
+        assert_eq!(oracle.oracle_reader.0.load(Ordering::Relaxed), 3);
+        assert!(!oracle.cache.lock().contains(&output_key));
+
+        // While a bypassed category is active, cached entries are not served either, but they are
+        // kept in the cache.
+        oracle.get(code_key).await.unwrap();
+        assert_eq!(oracle.oracle_reader.0.load(Ordering::Relaxed), 4);
+        assert_eq!(oracle.cache_policy(HintCategory::Code), CachePolicy::Cache);
+        assert!(oracle.cache.lock().contains(&code_key));
+    }
+
+    #[tokio::test]
+    async fn test_caching_oracle_hints_eagerly_by_default() {
+        let oracle =
+            CachingOracle::new(16, CountingOracle::default(), RecordingHintWriter::default());
+        let hint = HintType::L2Code.with_data(&[&[1; 32]]);
+        hint.send(&oracle).await.unwrap();
+        assert_eq!(oracle.hint_writer.0.lock().as_slice(), [hint.encode()]);
+    }
+
+    #[tokio::test]
+    async fn test_caching_oracle_lazy_hints_only_on_miss() {
+        let oracle =
+            CachingOracle::new(16, CountingOracle::default(), RecordingHintWriter::default())
+                .with_lazy_hints();
+        let [warm_key, cold_key, ..] = keys()[..] else { unreachable!() };
+        oracle.get(warm_key).await.unwrap();
+
+        // A warm key is served from the cache without writing its hint.
+        let warm_hint = HintType::L2Code.with_data(&[&[1; 32]]);
+        warm_hint.send(&oracle).await.unwrap();
+        oracle.get(warm_key).await.unwrap();
+        assert!(oracle.hint_writer.0.lock().is_empty());
+
+        // A cold key writes every pending hint once and in order, before it is fetched.
+        let cold_hint = HintType::L2Code.with_data(&[&[2; 32]]);
+        cold_hint.send(&oracle).await.unwrap();
+        let mut buf = [0u8; 32];
+        oracle.get_exact(cold_key, &mut buf).await.unwrap();
+        oracle.get(keys()[2]).await.unwrap();
+        assert_eq!(
+            oracle.hint_writer.0.lock().as_slice(),
+            [warm_hint.encode(), cold_hint.encode()]
+        );
+        assert_eq!(oracle.oracle_reader.0.load(Ordering::Relaxed), 3);
+    }
+
+    #[tokio::test]
+    async fn test_caching_oracle_lazy_hints_bounded() {
+        let oracle =
+            CachingOracle::new(16, CountingOracle::default(), RecordingHintWriter::default())
+                .with_lazy_hints();
+        let hints = (0..=MAX_PENDING_HINTS as u64)
+            .map(|i| HintType::L2Code.with_data(&[&i.to_be_bytes()]))
+            .collect::<Vec<_>>();
+        for hint in &hints {
+            hint.send(&oracle).await.unwrap();
+        }
+
+        // The oldest hint is forwarded once the queue is full.
+        assert_eq!(oracle.hint_writer.0.lock().as_slice(), [hints[0].encode()]);
+        assert_eq!(oracle.pending_hints.lock().len(), MAX_PENDING_HINTS);
+    }
+
+    #[test]
+    fn test_caching_oracle_snapshot_invalid() {
+        let oracle = CachingOracle::new(16, CountingOracle::default(), NoopHintWriter);
+        assert_eq!(oracle.load(&[]), Err(CacheSnapshotError::Truncated));
+        assert_eq!(oracle.load(&[2]), Err(CacheSnapshotError::UnsupportedVersion(2)));
+
+        let mut snapshot = vec![CACHE_SNAPSHOT_VERSION];
+        snapshot.extend_from_slice(&1u64.to_be_bytes());
+        snapshot.extend_from_slice(&[0u8; 32]);
+        assert_eq!(oracle.load(&snapshot), Err(CacheSnapshotError::InvalidKey));
+
+        snapshot[9] = 1;
+        snapshot.extend_from_slice(&8u64.to_be_bytes());
+        snapshot.extend_from_slice(&[0u8; 4]);
+        assert_eq!(oracle.load(&snapshot), Err(CacheSnapshotError::Truncated));
+        assert!(oracle.cache.lock().is_empty());
     }
 }
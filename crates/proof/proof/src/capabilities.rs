@@ -0,0 +1,192 @@
+//! Contains the [HintCapabilities] exchanged in the [HintType::Hello] handshake, and the
+//! [negotiate] helper.
+
+use crate::{HintType, errors::OracleProviderError};
+use kona_preimage::CommsClient;
+
+/// The version of the hint ABI implemented by this crate.
+pub const HINT_ABI_VERSION: u64 = 1;
+
+/// The ABI version and the set of [HintType]s supported by one side of the preimage oracle.
+///
+/// The supported types are stored as a bitmap, indexed by [HintType::capability_bit].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HintCapabilities {
+    /// The version of the hint ABI.
+    pub abi_version: u64,
+    /// The bitmap of supported [HintType]s.
+    pub supported: u64,
+}
+
+impl Default for HintCapabilities {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl HintCapabilities {
+    /// The length of the encoded capabilities, in bytes.
+    pub const ENCODED_LEN: usize = 16;
+
+    /// Creates new [HintCapabilities] supporting none of the [HintType]s, at the given ABI
+    /// version.
+    pub const fn none(abi_version: u64) -> Self {
+        Self { abi_version, supported: 0 }
+    }
+
+    /// Creates new [HintCapabilities] supporting every [HintType] known to this crate, at the
+    /// [HINT_ABI_VERSION].
+    pub fn all() -> Self {
+        HintType::ALL.iter().copied().fold(Self::none(HINT_ABI_VERSION), Self::with)
+    }
+
+    /// Returns the capabilities with support for the given [HintType] added.
+    pub const fn with(mut self, hint_type: HintType) -> Self {
+        self.supported |= 1 << hint_type.capability_bit();
+        self
+    }
+
+    /// Returns the capabilities with support for the given [HintType] removed.
+    pub const fn without(mut self, hint_type: HintType) -> Self {
+        self.supported &= !(1 << hint_type.capability_bit());
+        self
+    }
+
+    /// Returns `true` if the given [HintType] is supported.
+    pub const fn supports(&self, hint_type: HintType) -> bool {
+        self.supported & (1 << hint_type.capability_bit()) != 0
+    }
+
+    /// Returns an iterator over the supported [HintType]s known to this crate.
+    pub fn iter(&self) -> impl Iterator<Item = HintType> + '_ {
+        HintType::ALL.iter().copied().filter(|hint_type| self.supports(*hint_type))
+    }
+
+    /// Returns the capabilities shared with `other`: the lower of the two ABI versions, and the
+    /// [HintType]s supported by both.
+    pub fn intersect(&self, other: &Self) -> Self {
+        Self {
+            abi_version: self.abi_version.min(other.abi_version),
+            supported: self.supported & other.supported,
+        }
+    }
+
+    /// Encodes the capabilities as the big-endian ABI version followed by the big-endian bitmap.
+    pub fn encode(&self) -> [u8; Self::ENCODED_LEN] {
+        let mut buf = [0u8; Self::ENCODED_LEN];
+        buf[..8].copy_from_slice(&self.abi_version.to_be_bytes());
+        buf[8..].copy_from_slice(&self.supported.to_be_bytes());
+        buf
+    }
+
+    /// Decodes capabilities encoded with [HintCapabilities::encode].
+    pub fn decode(data: &[u8]) -> Result<Self, OracleProviderError> {
+        let data: [u8; Self::ENCODED_LEN] =
+            data.try_into().map_err(OracleProviderError::SliceConversion)?;
+        let (abi_version, supported) = data.split_at(8);
+        Ok(Self {
+            abi_version: u64::from_be_bytes(abi_version.try_into().expect("8 bytes")),
+            supported: u64::from_be_bytes(supported.try_into().expect("8 bytes")),
+        })
+    }
+}
+
+/// Negotiates the hints that may be sent to the host, by sending the `local` capabilities in a
+/// [HintType::Hello] hint and intersecting them with the capabilities served by the host.
+///
+/// The host serves its capabilities under the [HintType::generic_key] of the hint data. Hints
+/// outside of the returned capabilities cannot be served by the host, and should not be sent.
+pub async fn negotiate<T: CommsClient>(
+    oracle: &T,
+    local: &HintCapabilities,
+) -> Result<HintCapabilities, OracleProviderError> {
+    let data = local.encode();
+    let key = HintType::Hello.generic_key(&data);
+    let remote = HintType::Hello.with_data(&[&data]).send_and_get(oracle, key).await?;
+    Ok(local.intersect(&HintCapabilities::decode(&remote)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, sync::Arc, vec::Vec};
+    use async_trait::async_trait;
+    use kona_preimage::{
+        HintWriterClient, PreimageKey, PreimageOracleClient,
+        errors::{PreimageOracleError, PreimageOracleResult},
+    };
+    use spin::Mutex;
+
+    /// A mock host that serves its capabilities in response to a [HintType::Hello] hint.
+    #[derive(Debug, Clone)]
+    struct MockHost {
+        capabilities: HintCapabilities,
+        hello: Arc<Mutex<Option<Vec<u8>>>>,
+    }
+
+    #[async_trait]
+    impl PreimageOracleClient for MockHost {
+        async fn get(&self, key: PreimageKey) -> PreimageOracleResult<Vec<u8>> {
+            let hello = self.hello.lock();
+            match hello.as_ref() {
+                Some(data) if key == HintType::Hello.generic_key(data) => {
+                    Ok(self.capabilities.encode().into())
+                }
+                _ => Err(PreimageOracleError::KeyNotFound),
+            }
+        }
+
+        async fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> PreimageOracleResult<()> {
+            buf.copy_from_slice(&self.get(key).await?);
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl HintWriterClient for MockHost {
+        async fn write(&self, hint: &str) -> PreimageOracleResult<()> {
+            let hint = hint.parse::<crate::Hint<HintType>>().unwrap();
+            assert_eq!(hint.ty, HintType::Hello);
+            *self.hello.lock() = Some(hint.data.into());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_capabilities_roundtrip() {
+        let capabilities = HintCapabilities::all();
+        assert!(HintType::ALL.iter().all(|hint_type| capabilities.supports(*hint_type)));
+        assert_eq!(HintCapabilities::decode(&capabilities.encode()).unwrap(), capabilities);
+        assert!(HintCapabilities::decode(&[0u8; 15]).is_err());
+
+        let without = capabilities.without(HintType::L2Code);
+        assert!(!without.supports(HintType::L2Code));
+        assert_eq!(without.with(HintType::L2Code), capabilities);
+    }
+
+    #[tokio::test]
+    async fn test_negotiate_differing_capabilities() {
+        let client = HintCapabilities::none(HINT_ABI_VERSION + 1)
+            .with(HintType::L1BlockHeader)
+            .with(HintType::L1BeaconBlock)
+            .with(HintType::L2StorageSlots);
+        let host = MockHost {
+            capabilities: HintCapabilities::none(HINT_ABI_VERSION)
+                .with(HintType::L1BlockHeader)
+                .with(HintType::L2StorageSlots)
+                .with(HintType::L2Code),
+            hello: Arc::new(Mutex::new(None)),
+        };
+
+        let negotiated = negotiate(&host, &client).await.unwrap();
+        assert_eq!(negotiated.abi_version, HINT_ABI_VERSION);
+        assert_eq!(
+            negotiated.iter().collect::<Vec<_>>(),
+            [HintType::L1BlockHeader, HintType::L2StorageSlots]
+        );
+
+        // Neither side's exclusive hints are negotiated.
+        assert!(!negotiated.supports(HintType::L1BeaconBlock));
+        assert!(!negotiated.supports(HintType::L2Code));
+    }
+}
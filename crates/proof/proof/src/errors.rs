@@ -1,6 +1,7 @@
 //! Error types for the proof program.
 
 use alloc::string::{String, ToString};
+use alloy_primitives::B256;
 use kona_derive::errors::{PipelineError, PipelineErrorKind};
 use kona_mpt::{OrderedListWalkerError, TrieNodeError};
 use kona_preimage::errors::PreimageOracleError;
@@ -13,9 +14,51 @@ pub enum OracleProviderError {
     /// Requested block number is past the chain head.
     #[error("Block number ({0}) past chain head ({_1})")]
     BlockNumberPastHead(u64, u64),
+    /// The requested block range is empty, or spans more than the maximum number of blocks.
+    #[error("Invalid block range: {0} to {1}")]
+    InvalidBlockRange(u64, u64),
+    /// A header in a block range does not link back from the L1 head.
+    #[error("Broken header chain at block {0}")]
+    BrokenHeaderChain(u64),
     /// Preimage oracle error.
     #[error("Preimage oracle error: {0}")]
     Preimage(#[from] PreimageOracleError),
+    /// The preimage oracle does not have the requested preimage.
+    #[error("Preimage not found for key: {key}")]
+    NotFound {
+        /// The key of the missing preimage.
+        key: B256,
+    },
+    /// The hint for a preimage was acknowledged, but the oracle served an empty preimage.
+    #[error("Empty preimage served for key: {key}")]
+    EmptyResponse {
+        /// The key of the empty preimage.
+        key: B256,
+    },
+    /// The root of the fetched transactions does not match the header's transactions root.
+    #[error("Transactions root mismatch: expected {expected}, computed {computed}")]
+    TransactionsRootMismatch {
+        /// The transactions root committed to in the header.
+        expected: B256,
+        /// The root of the fetched transactions.
+        computed: B256,
+    },
+    /// The hash of a fetched trie node does not match the hash it was requested with.
+    #[error("Trie node hash mismatch: expected {expected}, computed {computed}")]
+    TrieNodeHashMismatch {
+        /// The hash the trie node was requested with.
+        expected: B256,
+        /// The hash of the fetched trie node.
+        computed: B256,
+    },
+    /// The output root recomputed from its components does not match the claimed output root.
+    #[error("Output root mismatch: claimed {claimed}, computed {computed}")]
+    OutputRootMismatch {
+        /// The claimed output root.
+        claimed: B256,
+        /// The output root recomputed from its components.
+        computed: B256,
+    },
     /// List walker error.
     #[error("Trie walker error: {0}")]
     TrieWalker(#[from] OrderedListWalkerError),
@@ -37,6 +80,9 @@ pub enum OracleProviderError {
     /// Serde error.
     #[error("Serde error: {0}")]
     Serde(serde_json::Error),
+    /// A preimage response could not be decoded.
+    #[error("Invalid preimage response: {0}")]
+    InvalidResponse(String),
     /// Unknown Chain ID
     #[error("Unknown chain ID: {0}")]
     UnknownChainId(u64),
@@ -55,3 +101,19 @@ impl From<OracleProviderError> for PipelineErrorKind {
 #[derive(Error, Debug)]
 #[error("Hint parsing error: {_0}")]
 pub struct HintParsingError(pub String);
+
+/// Error loading a [CachingOracle] snapshot.
+///
+/// [CachingOracle]: crate::CachingOracle
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum CacheSnapshotError {
+    /// The snapshot was written with an unsupported format version.
+    #[error("Unsupported cache snapshot version: {0}")]
+    UnsupportedVersion(u8),
+    /// The snapshot ended before all of its entries were read.
+    #[error("Truncated cache snapshot")]
+    Truncated,
+    /// The snapshot contains an invalid preimage key.
+    #[error("Invalid preimage key in cache snapshot")]
+    InvalidKey,
+}
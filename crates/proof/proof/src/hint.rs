@@ -5,9 +5,13 @@ use alloc::{
     string::{String, ToString},
     vec::Vec,
 };
-use alloy_primitives::{Bytes, hex};
+use alloy_primitives::{Bytes, hex, keccak256};
+use alloy_trie::KECCAK_EMPTY;
 use core::{fmt::Display, str::FromStr};
-use kona_preimage::HintWriterClient;
+use kona_preimage::{
+    CommsClient, HintWriterClient, PreimageKey, PreimageKeyType, PreimageOracleClient,
+    errors::PreimageOracleError,
+};
 
 /// A [Hint] is parsed in the format `<hint_type> <hint_data>`, where `<hint_type>` is a string that
 /// represents the type of hint, and `<hint_data>` is the data associated with the hint (bytes
@@ -53,10 +57,254 @@ where
         comms.write(&self.encode()).await.map_err(OracleProviderError::Preimage)
     }
 
-    /// Encodes the hint as a string.
+    /// Sends the hint to the passed [CommsClient], and then fetches the preimage for the given
+    /// [PreimageKey].
+    ///
+    /// Local keys are known to the host without a hint, so for a [PreimageKeyType::Local] key the
+    /// hint is not sent, and the preimage is fetched with [get_preimage_or_not_found].
+    ///
+    /// If the oracle reports that the preimage does not exist, [OracleProviderError::NotFound] is
+    /// returned rather than [OracleProviderError::Preimage], so that missing data can be
+    /// distinguished from a broken channel.
+    ///
+    /// If the hint was acknowledged but the oracle serves an empty preimage,
+    /// [OracleProviderError::EmptyResponse] is returned, since this usually means that the host
+    /// accepted the hint without fulfilling it. The empty preimage of the keccak256 hash of empty
+    /// data is the only exception, as it is legitimately empty.
+    pub async fn send_and_get<T: CommsClient>(
+        &self,
+        comms: &T,
+        key: PreimageKey,
+    ) -> Result<Vec<u8>, OracleProviderError> {
+        if key.key_type() == PreimageKeyType::Local {
+            return get_preimage_or_not_found(comms, key).await;
+        }
+
+        self.send(comms).await?;
+        let preimage = get_preimage_or_not_found(comms, key).await?;
+        if preimage.is_empty() && key != PreimageKey::new_keccak256(*KECCAK_EMPTY) {
+            return Err(OracleProviderError::EmptyResponse { key: key.into() });
+        }
+        Ok(preimage)
+    }
+
+    /// Sends the hint to the passed [CommsClient], and then fetches the preimage response for the
+    /// given [PreimageKey], decoding it with [decode_preimage_response].
+    ///
+    /// Hosts prefix large responses, which they may compress, with a [ResponseFlag]. Errors are
+    /// reported as in [Hint::send_and_get].
+    #[cfg(feature = "compression")]
+    pub async fn send_and_get_flagged<T: CommsClient>(
+        &self,
+        comms: &T,
+        key: PreimageKey,
+    ) -> Result<Vec<u8>, OracleProviderError> {
+        decode_preimage_response(&self.send_and_get(comms, key).await?)
+    }
+
+    /// Encodes the hint as a string, in the format parsed by [Hint::from_str], which is its exact
+    /// inverse.
     pub fn encode(&self) -> String {
         alloc::format!("{} {}", self.ty, self.data)
     }
+
+    /// Encodes the hint as a string, compressing the hint data with zlib at the given
+    /// [CompressionLevel]. The hint type is suffixed with [COMPRESSED_HINT_SUFFIX] so that the
+    /// receiver knows to decompress the data.
+    ///
+    /// Higher levels trade CPU time for smaller hints, which may be worthwhile for very large
+    /// hint data such as execution witnesses.
+    #[cfg(feature = "compression")]
+    pub fn encode_with(&self, level: CompressionLevel) -> String {
+        let compressed = miniz_oxide::deflate::compress_to_vec_zlib(&self.data, level.get());
+        alloc::format!("{}{} {}", self.ty, COMPRESSED_HINT_SUFFIX, Bytes::from(compressed))
+    }
+}
+
+/// Incrementally accumulates the data fields of a [Hint], for hints with many fields.
+///
+/// Fields are concatenated in the order they are pushed, as with [HintType::with_data].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HintBuilder<HT> {
+    /// The type of hint.
+    ty: HT,
+    /// The data accumulated so far.
+    data: Vec<u8>,
+}
+
+impl<HT> HintBuilder<HT>
+where
+    HT: Display + Clone,
+{
+    /// Creates a new [HintBuilder] for a hint of the specified type, with no data.
+    pub const fn new(ty: HT) -> Self {
+        Self { ty, data: Vec::new() }
+    }
+
+    /// Appends a field to the hint data.
+    pub fn push(&mut self, field: &[u8]) -> &mut Self {
+        self.data.extend_from_slice(field);
+        self
+    }
+
+    /// Returns the length of the hint data accumulated so far.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if no hint data has been accumulated.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Builds the [Hint] from the accumulated data.
+    pub fn build(&self) -> Hint<HT> {
+        Hint::new(self.ty.clone(), self.data.clone())
+    }
+
+    /// Encodes the hint as a string. See [Hint::encode].
+    pub fn encode(&self) -> String {
+        self.build().encode()
+    }
+
+    /// Encodes the hint as a string, compressing the hint data at the given [CompressionLevel].
+    /// See [Hint::encode_with].
+    #[cfg(feature = "compression")]
+    pub fn encode_with(&self, level: CompressionLevel) -> String {
+        self.build().encode_with(level)
+    }
+}
+
+/// Fetches the preimage for a key from the passed [PreimageOracleClient], without writing a hint,
+/// reporting a missing preimage as [OracleProviderError::NotFound].
+///
+/// Preimages of [PreimageKeyType::Local] keys are fetched this way, since writing a hint for a
+/// local key is wasteful, and may confuse hosts that do not expect one.
+pub async fn get_preimage_or_not_found<T: PreimageOracleClient>(
+    oracle: &T,
+    key: PreimageKey,
+) -> Result<Vec<u8>, OracleProviderError> {
+    oracle.get(key).await.map_err(|e| match e {
+        PreimageOracleError::KeyNotFound => OracleProviderError::NotFound { key: key.into() },
+        e => OracleProviderError::Preimage(e),
+    })
+}
+
+/// The suffix appended to the hint type of hints whose data is zlib-compressed.
+#[cfg(feature = "compression")]
+pub const COMPRESSED_HINT_SUFFIX: &str = "+zlib";
+
+/// The maximum length, in bytes, that compressed hint data and preimage responses may be
+/// decompressed to. Larger payloads are rejected rather than inflated, so that a small compressed
+/// payload cannot exhaust the memory of the receiver.
+#[cfg(feature = "compression")]
+pub const MAX_DECOMPRESSED_LEN: usize = 1 << 26;
+
+/// The zlib compression level used by [Hint::encode_with], ranging from `0` (no compression) to
+/// `9` (best compression).
+#[cfg(feature = "compression")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CompressionLevel(u8);
+
+#[cfg(feature = "compression")]
+impl CompressionLevel {
+    /// No compression.
+    pub const NONE: Self = Self(0);
+    /// The fastest compression level.
+    pub const FASTEST: Self = Self(1);
+    /// A balance between compression speed and size.
+    pub const BALANCED: Self = Self(6);
+    /// The best compression level.
+    pub const BEST: Self = Self(9);
+
+    /// Creates a new [CompressionLevel], clamping `level` to the maximum of `9`.
+    pub const fn new(level: u8) -> Self {
+        if level > Self::BEST.0 { Self::BEST } else { Self(level) }
+    }
+
+    /// Returns the numeric compression level.
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+}
+
+#[cfg(feature = "compression")]
+impl Default for CompressionLevel {
+    fn default() -> Self {
+        Self::BALANCED
+    }
+}
+
+/// The leading flag byte of a preimage response encoded with [encode_preimage_response],
+/// indicating whether the rest of the response is compressed.
+///
+/// Preimage responses are only flagged when both the host and the client opt into it, as a flag
+/// byte changes the preimage. It must not be used for preimages whose key commits to their
+/// contents, such as [PreimageKeyType::Keccak256] preimages.
+#[cfg(feature = "compression")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum ResponseFlag {
+    /// The response is not compressed.
+    #[default]
+    Raw = 0x00,
+    /// The response is zlib-compressed.
+    Zlib = 0x01,
+}
+
+#[cfg(feature = "compression")]
+impl TryFrom<u8> for ResponseFlag {
+    type Error = OracleProviderError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x00 => Ok(Self::Raw),
+            0x01 => Ok(Self::Zlib),
+            flag => Err(OracleProviderError::InvalidResponse(alloc::format!(
+                "Unknown response flag: {flag:#04x}"
+            ))),
+        }
+    }
+}
+
+/// Encodes a preimage response, prefixed with its [ResponseFlag].
+///
+/// The response is zlib-compressed at the given [CompressionLevel], or left raw if `None` is
+/// passed. Responses flagged as [ResponseFlag::Raw] are the preimage with a single leading zero
+/// byte.
+#[cfg(feature = "compression")]
+pub fn encode_preimage_response(preimage: &[u8], level: Option<CompressionLevel>) -> Vec<u8> {
+    level.map_or_else(
+        || [&[ResponseFlag::Raw as u8], preimage].concat(),
+        |level| {
+            let compressed = miniz_oxide::deflate::compress_to_vec_zlib(preimage, level.get());
+            [&[ResponseFlag::Zlib as u8], compressed.as_slice()].concat()
+        },
+    )
+}
+
+/// Decodes a preimage response encoded with [encode_preimage_response], reading its leading
+/// [ResponseFlag] to decide whether to decompress it.
+///
+/// An empty response, a response with an unknown flag, or a compressed response that cannot be
+/// decompressed within [MAX_DECOMPRESSED_LEN] bytes is reported as
+/// [OracleProviderError::InvalidResponse].
+#[cfg(feature = "compression")]
+pub fn decode_preimage_response(response: &[u8]) -> Result<Vec<u8>, OracleProviderError> {
+    let Some((&flag, body)) = response.split_first() else {
+        return Err(OracleProviderError::InvalidResponse("Missing response flag".to_string()));
+    };
+    match ResponseFlag::try_from(flag)? {
+        ResponseFlag::Raw => Ok(body.to_vec()),
+        ResponseFlag::Zlib => {
+            miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(body, MAX_DECOMPRESSED_LEN)
+                .map_err(|e| {
+                    OracleProviderError::InvalidResponse(alloc::format!(
+                        "Invalid compressed response: {e}"
+                    ))
+                })
+        }
+    }
 }
 
 impl<HT> FromStr for Hint<HT>
@@ -65,21 +313,104 @@ where
 {
     type Err = HintParsingError;
 
+    /// Parses a hint in the format `<hint_type> <hint_data>`, as encoded by [Hint::encode].
+    ///
+    /// The hint is split at its first space only, and the rest is decoded as a single hex field,
+    /// so that stray whitespace in large hint data is reported with its position rather than as a
+    /// malformed hint. Compressed hint data may decompress to at most `MAX_DECOMPRESSED_LEN`
+    /// bytes.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parts = s.split(' ').collect::<Vec<_>>();
-
-        if parts.len() != 2 {
+        let Some((raw_type, raw_data)) = s.split_once(' ') else {
             return Err(HintParsingError(alloc::format!("Invalid hint format: {}", s)));
+        };
+
+        if let Some(position) = raw_data.find(char::is_whitespace) {
+            return Err(HintParsingError(alloc::format!(
+                "Invalid data for {} hint: unexpected whitespace at position {}",
+                raw_type,
+                position
+            )));
         }
+        let hint_data = hex::decode(raw_data).map_err(|e| {
+            HintParsingError(alloc::format!("Invalid data for {} hint: {}", raw_type, e))
+        })?;
+
+        #[cfg(feature = "compression")]
+        if let Some(raw_type) = raw_type.strip_suffix(COMPRESSED_HINT_SUFFIX) {
+            let hint_data = miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(
+                &hint_data,
+                MAX_DECOMPRESSED_LEN,
+            )
+            .map_err(|e| HintParsingError(alloc::format!("Invalid compressed hint data: {}", e)))?;
+            return Ok(Self { ty: raw_type.parse::<HT>()?, data: hint_data.into() });
+        }
+
+        Ok(Self { ty: raw_type.parse::<HT>()?, data: hint_data.into() })
+    }
+}
+
+/// The maximum number of layer 1 block headers that may be requested by a single
+/// [HintType::L1BlockRange] hint.
+pub const MAX_L1_BLOCK_RANGE: u64 = 256;
+
+/// The maximum number of storage slots that may be requested by a single
+/// [HintType::L2StorageRange] hint.
+pub const MAX_L2_STORAGE_RANGE: u64 = 256;
 
-        let hint_type = parts.remove(0).parse::<HT>()?;
-        let hint_data =
-            hex::decode(parts.remove(0)).map_err(|e| HintParsingError(e.to_string()))?.into();
+/// The maximum number of storage slots that may be requested by a single
+/// [HintType::L2StorageSlots] hint.
+pub const MAX_L2_STORAGE_SLOTS: usize = 256;
 
-        Ok(Self { ty: hint_type, data: hint_data })
+/// The length of a field in the data of a [Hint].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FieldLength {
+    /// The field is exactly this many bytes long.
+    Fixed(usize),
+    /// The field takes up the remainder of the hint data.
+    Variable,
+}
+
+/// Describes a field in the data of a [Hint], for generic host code and documentation tooling.
+///
+/// Fields are concatenated in order, and integers are big-endian.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FieldSpec {
+    /// The name of the field.
+    pub name: &'static str,
+    /// The length of the field.
+    pub length: FieldLength,
+}
+
+impl FieldSpec {
+    /// Creates a new [FieldSpec] for a field of a fixed length.
+    pub const fn fixed(name: &'static str, length: usize) -> Self {
+        Self { name, length: FieldLength::Fixed(length) }
+    }
+
+    /// Creates a new [FieldSpec] for a field that takes up the remainder of the hint data.
+    pub const fn variable(name: &'static str) -> Self {
+        Self { name, length: FieldLength::Variable }
     }
 }
 
+/// A broad category of [HintType]s whose preimages have similar reuse characteristics, as
+/// returned by [HintType::category].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HintCategory {
+    /// Block headers, transactions and receipts, on either layer.
+    Block,
+    /// Blobs and their KZG commitments and proofs.
+    Blob,
+    /// Precompile call results.
+    Precompile,
+    /// Layer 2 state trie nodes and proofs.
+    State,
+    /// Layer 2 contract code.
+    Code,
+    /// One-off values, such as the starting output root or the active fork.
+    Other,
+}
+
 /// The [HintType] enum is used to specify the type of hint that was received.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum HintType {
@@ -93,11 +424,43 @@ pub enum HintType {
     L1Blob,
     /// A hint that specifies a precompile call on layer 1.
     L1Precompile,
+    /// A hint that specifies the Merkle inclusion proof of a blob's KZG commitment within the
+    /// body of a layer 1 beacon block.
+    L1BlobInclusionProof,
+    /// A hint that specifies the KZG proof of a blob against its commitment, without the blob
+    /// itself. Like [HintType::L1Blob], the blob is identified by its versioned hash, along with
+    /// its index and the timestamp of its block so that the host can locate its sidecar.
+    L1BlobProof,
+    /// A hint that specifies a full layer 1 beacon block, identified either by its slot or by its
+    /// block root. The host serves the SSZ-encoded signed beacon block under the
+    /// [HintType::generic_key] of the hint data, as a flagged response that is fetched with
+    /// `Hint::send_and_get_flagged`.
+    L1BeaconBlock,
+    /// A hint that specifies the finalized block header of layer 1, as seen from a given layer 1
+    /// head.
+    L1FinalizedHead,
+    /// A hint that specifies the parent hash of a layer 1 block, for walking layer 1 history
+    /// backwards without fetching full headers.
+    L1ParentHash,
+    /// A hint that specifies an inclusive range of layer 1 block headers, by their starting and
+    /// ending block numbers. The range may span at most [MAX_L1_BLOCK_RANGE] blocks.
+    L1BlockRange,
     /// A hint that specifies the block header of a layer 2 block.
     L2BlockHeader,
     /// A hint that specifies the transactions of a layer 2 block.
     L2Transactions,
-    /// A hint that specifies the code of a contract on layer 2.
+    /// A hint that specifies the genesis block of a layer 2 chain, identified by its chain ID.
+    L2Genesis,
+    /// A hint that specifies the layer 2 hardfork that is active at a given timestamp.
+    L2ActiveFork,
+    /// A hint that specifies the layer 2 block at a given timestamp, or the latest block before
+    /// it if no block was produced exactly at that timestamp. The host serves the hash and number
+    /// of the block, saving the client a binary search over headers.
+    L2BlockByTimestamp,
+    /// A hint that specifies the code of a contract on layer 2. The host also serves the length
+    /// of the code, under the [code_length_key] of its hash.
+    ///
+    /// [code_length_key]: crate::l2::code_length_key
     L2Code,
     /// A hint that specifies the preimage of the starting L2 output root on layer 2.
     StartingL2Output,
@@ -108,12 +471,164 @@ pub enum HintType {
     /// A hint that specifies the proof on the path to a storage slot in an account within in the
     /// L2 state trie.
     L2AccountStorageProof,
+    /// A hint that specifies the proofs of a contiguous range of storage slots in an account
+    /// within the L2 state trie, by the starting slot and the number of slots. The range may span
+    /// at most [MAX_L2_STORAGE_RANGE] slots.
+    L2StorageRange,
+    /// A hint that specifies the proofs of a batch of arbitrary storage slots in an account within
+    /// the L2 state trie, sharing a single account proof. The batch may hold at most
+    /// [MAX_L2_STORAGE_SLOTS] slots.
+    L2StorageSlots,
     /// A hint that specifies bulk storage of all the code, state and keys generated by an
     /// execution witness.
     L2PayloadWitness,
+    /// A hint that specifies the deposit nonce of a deposit transaction on layer 2, identified by
+    /// its transaction hash. The host serves the nonce from the transaction's deposit receipt.
+    L2DepositNonce,
+    /// A hint that specifies the L1 origin of an L2 block, identified by its block number, which
+    /// marks the boundary of the sequencing epoch the block belongs to. The host serves the L1
+    /// origin's hash and number, along with its header.
+    L1EpochBoundary,
+    /// A hint that specifies the sequencer's signature over an L2 block, identified by its block
+    /// hash, for audit trails that attest to the block independently of gossip. Hosts that retain
+    /// the signatures of gossiped blocks serve the 65-byte signature under the
+    /// [HintType::generic_key] of the hint data.
+    L2BlockSignature,
+    /// A hint that specifies a trusted layer 1 checkpoint to anchor bootstrapping from,
+    /// identified by its block number. The host serves the checkpoint's block hash and state root
+    /// under the [HintType::generic_key] of the hint data, along with its header.
+    L1Checkpoint,
+    /// A hint that specifies a raw batch, identified by the number of the layer 1 block it was
+    /// submitted in and its index among the block's batcher transactions. The host serves the
+    /// batch data as it sees it under the [HintType::generic_key] of the hint data, as a flagged
+    /// response that is fetched with `Hint::send_and_get_flagged`. Intended for debugging
+    /// derivation.
+    L2BatchData,
+    /// A handshake hint that specifies the ABI version and [HintCapabilities] of the client. The
+    /// host serves its own capabilities, so that the client can [negotiate] the hints it may send.
+    ///
+    /// [HintCapabilities]: crate::HintCapabilities
+    /// [negotiate]: crate::negotiate
+    Hello,
+    /// A debugging hint that instructs the host to echo the hint data back as the preimage of its
+    /// keccak256 hash. Only available with the `debug-echo` feature, and must not be enabled in
+    /// production builds.
+    #[cfg(feature = "debug-echo")]
+    DebugEcho,
 }
 
 impl HintType {
+    /// Every [HintType], in the order of their [HintType::capability_bit].
+    pub const ALL: &'static [Self] = &[
+        Self::L1BlockHeader,
+        Self::L1Transactions,
+        Self::L1Receipts,
+        Self::L1Blob,
+        Self::L1Precompile,
+        Self::L1BlobInclusionProof,
+        Self::L1BlobProof,
+        Self::L1BeaconBlock,
+        Self::L1FinalizedHead,
+        Self::L1ParentHash,
+        Self::L1BlockRange,
+        Self::L2BlockHeader,
+        Self::L2Transactions,
+        Self::L2Genesis,
+        Self::L2ActiveFork,
+        Self::L2BlockByTimestamp,
+        Self::L2Code,
+        Self::StartingL2Output,
+        Self::L2StateNode,
+        Self::L2AccountProof,
+        Self::L2AccountStorageProof,
+        Self::L2StorageRange,
+        Self::L2StorageSlots,
+        Self::L2PayloadWitness,
+        Self::Hello,
+        #[cfg(feature = "debug-echo")]
+        Self::DebugEcho,
+        Self::L2DepositNonce,
+        Self::L1EpochBoundary,
+        Self::L2BlockSignature,
+        Self::L1Checkpoint,
+        Self::L2BatchData,
+    ];
+
+    /// Returns the index of the bit representing this type in a capability bitmap.
+    ///
+    /// The bits are part of the handshake ABI, and must never be reassigned. New types must be
+    /// given the next free bit.
+    pub const fn capability_bit(&self) -> u32 {
+        match self {
+            Self::L1BlockHeader => 0,
+            Self::L1Transactions => 1,
+            Self::L1Receipts => 2,
+            Self::L1Blob => 3,
+            Self::L1Precompile => 4,
+            Self::L1BlobInclusionProof => 5,
+            Self::L1BlobProof => 6,
+            Self::L1BeaconBlock => 7,
+            Self::L1FinalizedHead => 8,
+            Self::L1ParentHash => 9,
+            Self::L1BlockRange => 10,
+            Self::L2BlockHeader => 11,
+            Self::L2Transactions => 12,
+            Self::L2Genesis => 13,
+            Self::L2ActiveFork => 14,
+            Self::L2BlockByTimestamp => 15,
+            Self::L2Code => 16,
+            Self::StartingL2Output => 17,
+            Self::L2StateNode => 18,
+            Self::L2AccountProof => 19,
+            Self::L2AccountStorageProof => 20,
+            Self::L2StorageRange => 21,
+            Self::L2StorageSlots => 22,
+            Self::L2PayloadWitness => 23,
+            Self::Hello => 24,
+            #[cfg(feature = "debug-echo")]
+            Self::DebugEcho => 25,
+            Self::L2DepositNonce => 26,
+            Self::L1EpochBoundary => 27,
+            Self::L2BlockSignature => 28,
+            Self::L1Checkpoint => 29,
+            Self::L2BatchData => 30,
+        }
+    }
+
+    /// Returns the [HintCategory] of hints of this type.
+    pub const fn category(&self) -> HintCategory {
+        match self {
+            Self::L1BlockHeader |
+            Self::L1Transactions |
+            Self::L1Receipts |
+            Self::L1FinalizedHead |
+            Self::L1ParentHash |
+            Self::L1BlockRange |
+            Self::L1BeaconBlock |
+            Self::L2BlockHeader |
+            Self::L2Transactions |
+            Self::L2Genesis |
+            Self::L2BlockByTimestamp |
+            Self::L2DepositNonce |
+            Self::L1EpochBoundary |
+            Self::L2BlockSignature |
+            Self::L1Checkpoint |
+            Self::L2BatchData => HintCategory::Block,
+            Self::L1Blob | Self::L1BlobInclusionProof | Self::L1BlobProof => HintCategory::Blob,
+            Self::L1Precompile => HintCategory::Precompile,
+            Self::L2StateNode |
+            Self::L2AccountProof |
+            Self::L2AccountStorageProof |
+            Self::L2StorageRange |
+            Self::L2StorageSlots |
+            Self::L2PayloadWitness => HintCategory::State,
+            Self::L2Code => HintCategory::Code,
+            Self::StartingL2Output | Self::L2ActiveFork | Self::Hello => HintCategory::Other,
+            #[cfg(feature = "debug-echo")]
+            Self::DebugEcho => HintCategory::Other,
+        }
+    }
+
     /// Creates a new [Hint] from `self` and the specified data. The data passed will be
     /// concatenated into a single byte array before being stored in the resulting [Hint].
     pub fn with_data(self, data: &[&[u8]]) -> Hint<Self> {
@@ -124,8 +639,172 @@ impl HintType {
         });
         Hint::new(self, hint_data)
     }
+
+    /// Returns the [PreimageKeyType::GlobalGeneric] key under which the host serves the response
+    /// to a hint of this type with the given data.
+    ///
+    /// The key is the keccak256 hash of the hint type, a space, and the hint data. Prefixing the
+    /// hint type separates the responses of hints whose data is equal, such as a block number sent
+    /// with different hint types, and the space keeps the prefixes of hint types such as
+    /// [HintType::L1Blob] and [HintType::L1BlobProof] from running into the data.
+    pub fn generic_key(&self, data: &[u8]) -> PreimageKey {
+        let preimage = [alloc::format!("{self} ").as_bytes(), data].concat();
+        PreimageKey::new(*keccak256(preimage), PreimageKeyType::GlobalGeneric)
+    }
+
+    /// Creates a new [HintBuilder] for a hint of this type, to accumulate its data field by field.
+    pub const fn builder(self) -> HintBuilder<Self> {
+        HintBuilder::new(self)
+    }
+
+    /// Returns the fields of the data expected with hints of this type, in order.
+    ///
+    /// Hints sent by the layer 2 providers may additionally be suffixed with an 8 byte chain ID,
+    /// which is not part of the schema.
+    pub const fn schema(&self) -> &'static [FieldSpec] {
+        const BLOCK_HASH: &[FieldSpec] = &[FieldSpec::fixed("block_hash", 32)];
+        const BLOB: &[FieldSpec] = &[
+            FieldSpec::fixed("blob_hash", 32),
+            FieldSpec::fixed("index", 8),
+            FieldSpec::fixed("timestamp", 8),
+        ];
+        const BLOCK_ID: &[FieldSpec] = &[FieldSpec::variable("block_id")];
+        const PRECOMPILE: &[FieldSpec] =
+            &[FieldSpec::fixed("address", 20), FieldSpec::variable("input")];
+        const L1_HEAD: &[FieldSpec] = &[FieldSpec::fixed("l1_head", 32)];
+        const BLOCK_RANGE: &[FieldSpec] =
+            &[FieldSpec::fixed("start", 8), FieldSpec::fixed("end", 8)];
+        const CHAIN_ID: &[FieldSpec] = &[FieldSpec::fixed("chain_id", 8)];
+        const TIMESTAMP: &[FieldSpec] = &[FieldSpec::fixed("timestamp", 8)];
+        const CODE_HASH: &[FieldSpec] = &[FieldSpec::fixed("code_hash", 32)];
+        const OUTPUT_ROOT: &[FieldSpec] = &[FieldSpec::fixed("output_root", 32)];
+        const NODE_HASH: &[FieldSpec] = &[FieldSpec::fixed("node_hash", 32)];
+        const ACCOUNT: &[FieldSpec] =
+            &[FieldSpec::fixed("block_number", 8), FieldSpec::fixed("address", 20)];
+        const STORAGE_SLOT: &[FieldSpec] = &[
+            FieldSpec::fixed("block_number", 8),
+            FieldSpec::fixed("address", 20),
+            FieldSpec::fixed("slot", 32),
+        ];
+        const STORAGE_RANGE: &[FieldSpec] = &[
+            FieldSpec::fixed("block_number", 8),
+            FieldSpec::fixed("address", 20),
+            FieldSpec::fixed("start_slot", 32),
+            FieldSpec::fixed("count", 8),
+        ];
+        const STORAGE_SLOTS: &[FieldSpec] = &[
+            FieldSpec::fixed("block_number", 8),
+            FieldSpec::fixed("address", 20),
+            FieldSpec::variable("slots"),
+        ];
+        const PAYLOAD_WITNESS: &[FieldSpec] =
+            &[FieldSpec::fixed("parent_block_hash", 32), FieldSpec::variable("payload_attributes")];
+        const TX_HASH: &[FieldSpec] = &[FieldSpec::fixed("tx_hash", 32)];
+        const L2_BLOCK_NUMBER: &[FieldSpec] = &[FieldSpec::fixed("l2_block_number", 8)];
+        const L1_BLOCK_NUMBER: &[FieldSpec] = &[FieldSpec::fixed("l1_block_number", 8)];
+        const BATCH: &[FieldSpec] =
+            &[FieldSpec::fixed("l1_block_number", 8), FieldSpec::fixed("batch_index", 8)];
+        const HELLO: &[FieldSpec] =
+            &[FieldSpec::fixed("abi_version", 8), FieldSpec::fixed("capabilities", 8)];
+        #[cfg(feature = "debug-echo")]
+        const DATA: &[FieldSpec] = &[FieldSpec::variable("data")];
+
+        match self {
+            Self::L1BlockHeader | Self::L1Transactions | Self::L1Receipts => BLOCK_HASH,
+            Self::L1Blob | Self::L1BlobInclusionProof | Self::L1BlobProof => BLOB,
+            Self::L1BeaconBlock => BLOCK_ID,
+            Self::L1Precompile => PRECOMPILE,
+            Self::L1FinalizedHead => L1_HEAD,
+            Self::L1ParentHash => BLOCK_HASH,
+            Self::L1BlockRange => BLOCK_RANGE,
+            Self::L2BlockHeader | Self::L2Transactions => BLOCK_HASH,
+            Self::L2Genesis => CHAIN_ID,
+            Self::L2ActiveFork | Self::L2BlockByTimestamp => TIMESTAMP,
+            Self::L2Code => CODE_HASH,
+            Self::StartingL2Output => OUTPUT_ROOT,
+            Self::L2StateNode => NODE_HASH,
+            Self::L2AccountProof => ACCOUNT,
+            Self::L2AccountStorageProof => STORAGE_SLOT,
+            Self::L2StorageRange => STORAGE_RANGE,
+            Self::L2StorageSlots => STORAGE_SLOTS,
+            Self::L2PayloadWitness => PAYLOAD_WITNESS,
+            Self::L2DepositNonce => TX_HASH,
+            Self::L1EpochBoundary => L2_BLOCK_NUMBER,
+            Self::L2BlockSignature => BLOCK_HASH,
+            Self::L1Checkpoint => L1_BLOCK_NUMBER,
+            Self::L2BatchData => BATCH,
+            Self::Hello => HELLO,
+            #[cfg(feature = "debug-echo")]
+            Self::DebugEcho => DATA,
+        }
+    }
+
+    /// Validates the length of the data of a hint of this type against its [HintType::schema].
+    ///
+    /// If every field has a fixed length, the data must be exactly as long as the fields combined.
+    /// Otherwise, it must be at least as long as the fixed-length fields combined.
+    ///
+    /// The data of a [HintType::L2PayloadWitness] hint is additionally checked to hold payload
+    /// attributes after the parent block hash, as described in
+    /// [HintType::validate_payload_witness].
+    pub fn validate_data(&self, data: &[u8]) -> Result<(), HintParsingError> {
+        if *self == Self::L2PayloadWitness {
+            return Self::validate_payload_witness(data);
+        }
+
+        let schema = self.schema();
+        let fixed = schema
+            .iter()
+            .map(|field| match field.length {
+                FieldLength::Fixed(len) => len,
+                FieldLength::Variable => 0,
+            })
+            .sum::<usize>();
+        let variable = schema.iter().any(|field| field.length == FieldLength::Variable);
+
+        let valid = if variable { data.len() >= fixed } else { data.len() == fixed };
+        if valid {
+            return Ok(());
+        }
+        Err(HintParsingError(alloc::format!(
+            "Invalid data length for {} hint: expected {}{} bytes, got {}",
+            self,
+            if variable { "at least " } else { "" },
+            fixed,
+            data.len()
+        )))
+    }
+
+    /// Validates the data of a [HintType::L2PayloadWitness] hint.
+    ///
+    /// The data is the 32-byte parent block hash, followed by the JSON-encoded payload attributes
+    /// and, optionally, the 8-byte chain ID. The payload attributes must be at least an empty JSON
+    /// object, as the host cannot execute the payload otherwise.
+    fn validate_payload_witness(data: &[u8]) -> Result<(), HintParsingError> {
+        let min = MIN_PAYLOAD_WITNESS_DATA_LEN;
+        if data.len() < min {
+            return Err(HintParsingError(alloc::format!(
+                "Invalid data length for {} hint: expected at least {} bytes for the parent block \
+                 hash and payload attributes, got {}",
+                Self::L2PayloadWitness,
+                min,
+                data.len()
+            )));
+        }
+        if data[32] != b'{' {
+            return Err(HintParsingError(alloc::format!(
+                "Invalid data for {} hint: payload attributes at offset 32 are not a JSON object",
+                Self::L2PayloadWitness
+            )));
+        }
+        Ok(())
+    }
 }
 
+/// The minimum length of the data of a [HintType::L2PayloadWitness] hint: the 32-byte parent
+/// block hash, followed by the smallest JSON object, `{}`.
+const MIN_PAYLOAD_WITNESS_DATA_LEN: usize = 32 + 2;
+
 impl FromStr for HintType {
     type Err = HintParsingError;
 
@@ -136,14 +815,33 @@ impl FromStr for HintType {
             "l1-receipts" => Ok(Self::L1Receipts),
             "l1-blob" => Ok(Self::L1Blob),
             "l1-precompile" => Ok(Self::L1Precompile),
+            "l1-blob-inclusion-proof" => Ok(Self::L1BlobInclusionProof),
+            "l1-blob-proof" => Ok(Self::L1BlobProof),
+            "l1-beacon-block" => Ok(Self::L1BeaconBlock),
+            "l1-finalized-head" => Ok(Self::L1FinalizedHead),
+            "l1-parent-hash" => Ok(Self::L1ParentHash),
+            "l1-block-range" => Ok(Self::L1BlockRange),
             "l2-block-header" => Ok(Self::L2BlockHeader),
             "l2-transactions" => Ok(Self::L2Transactions),
+            "l2-genesis" => Ok(Self::L2Genesis),
+            "l2-active-fork" => Ok(Self::L2ActiveFork),
+            "l2-block-by-timestamp" => Ok(Self::L2BlockByTimestamp),
             "l2-code" => Ok(Self::L2Code),
             "starting-l2-output" => Ok(Self::StartingL2Output),
             "l2-state-node" => Ok(Self::L2StateNode),
             "l2-account-proof" => Ok(Self::L2AccountProof),
             "l2-account-storage-proof" => Ok(Self::L2AccountStorageProof),
+            "l2-storage-range" => Ok(Self::L2StorageRange),
+            "l2-storage-slots" => Ok(Self::L2StorageSlots),
             "l2-payload-witness" => Ok(Self::L2PayloadWitness),
+            "l2-deposit-nonce" => Ok(Self::L2DepositNonce),
+            "l1-epoch-boundary" => Ok(Self::L1EpochBoundary),
+            "l2-block-signature" => Ok(Self::L2BlockSignature),
+            "l1-checkpoint" => Ok(Self::L1Checkpoint),
+            "l2-batch-data" => Ok(Self::L2BatchData),
+            "hello" => Ok(Self::Hello),
+            #[cfg(feature = "debug-echo")]
+            "debug-echo" => Ok(Self::DebugEcho),
             _ => Err(HintParsingError(value.to_string())),
         }
     }
@@ -157,14 +855,33 @@ impl From<HintType> for &str {
             HintType::L1Receipts => "l1-receipts",
             HintType::L1Blob => "l1-blob",
             HintType::L1Precompile => "l1-precompile",
+            HintType::L1BlobInclusionProof => "l1-blob-inclusion-proof",
+            HintType::L1BlobProof => "l1-blob-proof",
+            HintType::L1BeaconBlock => "l1-beacon-block",
+            HintType::L1FinalizedHead => "l1-finalized-head",
+            HintType::L1ParentHash => "l1-parent-hash",
+            HintType::L1BlockRange => "l1-block-range",
             HintType::L2BlockHeader => "l2-block-header",
             HintType::L2Transactions => "l2-transactions",
+            HintType::L2Genesis => "l2-genesis",
+            HintType::L2ActiveFork => "l2-active-fork",
+            HintType::L2BlockByTimestamp => "l2-block-by-timestamp",
             HintType::L2Code => "l2-code",
             HintType::StartingL2Output => "starting-l2-output",
             HintType::L2StateNode => "l2-state-node",
             HintType::L2AccountProof => "l2-account-proof",
             HintType::L2AccountStorageProof => "l2-account-storage-proof",
+            HintType::L2StorageRange => "l2-storage-range",
+            HintType::L2StorageSlots => "l2-storage-slots",
             HintType::L2PayloadWitness => "l2-payload-witness",
+            HintType::L2DepositNonce => "l2-deposit-nonce",
+            HintType::L1EpochBoundary => "l1-epoch-boundary",
+            HintType::L2BlockSignature => "l2-block-signature",
+            HintType::L1Checkpoint => "l1-checkpoint",
+            HintType::L2BatchData => "l2-batch-data",
+            HintType::Hello => "hello",
+            #[cfg(feature = "debug-echo")]
+            HintType::DebugEcho => "debug-echo",
         }
     }
 }
@@ -175,3 +892,498 @@ impl Display for HintType {
         write!(f, "{}", s)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MockOracle;
+    use alloc::{boxed::Box, vec};
+    use alloy_primitives::{B256, keccak256};
+    use async_trait::async_trait;
+    use kona_preimage::errors::{ChannelError, PreimageOracleResult};
+
+    /// A mock oracle that fails every preimage request with the given error.
+    #[derive(Debug, Clone)]
+    struct FailingOracle(fn() -> PreimageOracleError);
+
+    #[async_trait]
+    impl PreimageOracleClient for FailingOracle {
+        async fn get(&self, _: PreimageKey) -> PreimageOracleResult<Vec<u8>> {
+            Err((self.0)())
+        }
+
+        async fn get_exact(&self, _: PreimageKey, _: &mut [u8]) -> PreimageOracleResult<()> {
+            Err((self.0)())
+        }
+    }
+
+    #[async_trait]
+    impl HintWriterClient for FailingOracle {
+        async fn write(&self, _: &str) -> PreimageOracleResult<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_and_get_not_found() {
+        let oracle = FailingOracle(|| PreimageOracleError::KeyNotFound);
+        let key = PreimageKey::new_keccak256(*keccak256(b"missing"));
+
+        let err = HintType::L1BlockHeader.with_data(&[b"missing"]).send_and_get(&oracle, key).await;
+        assert!(
+            matches!(err, Err(OracleProviderError::NotFound { key: k }) if k == B256::from(key))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_and_get_channel_error() {
+        let oracle = FailingOracle(|| PreimageOracleError::IOError(ChannelError::Closed));
+        let key = PreimageKey::new_keccak256(*keccak256(b"missing"));
+
+        let err = HintType::L1BlockHeader.with_data(&[b"missing"]).send_and_get(&oracle, key).await;
+        assert!(matches!(
+            err,
+            Err(OracleProviderError::Preimage(PreimageOracleError::IOError(ChannelError::Closed)))
+        ));
+    }
+
+    /// A mock oracle that acknowledges every hint, but serves an empty preimage for every key.
+    #[derive(Debug, Clone)]
+    struct EmptyOracle;
+
+    #[async_trait]
+    impl PreimageOracleClient for EmptyOracle {
+        async fn get(&self, _: PreimageKey) -> PreimageOracleResult<Vec<u8>> {
+            Ok(Vec::new())
+        }
+
+        async fn get_exact(&self, _: PreimageKey, _: &mut [u8]) -> PreimageOracleResult<()> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl HintWriterClient for EmptyOracle {
+        async fn write(&self, _: &str) -> PreimageOracleResult<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_and_get_empty_response() {
+        let key = PreimageKey::new_keccak256(*keccak256(b"unfulfilled"));
+        let hint = HintType::L1BlockHeader.with_data(&[b"unfulfilled"]);
+
+        let err = hint.send_and_get(&EmptyOracle, key).await;
+        assert!(
+            matches!(err, Err(OracleProviderError::EmptyResponse { key: k }) if k == B256::from(key))
+        );
+
+        // The preimage of the hash of empty data is legitimately empty.
+        let key = PreimageKey::new_keccak256(*keccak256(b""));
+        assert_eq!(hint.send_and_get(&EmptyOracle, key).await.unwrap(), Vec::<u8>::new());
+    }
+
+    /// Returns a mock oracle serving a single local preimage.
+    fn local_oracle() -> MockOracle {
+        let mut oracle = MockOracle::default();
+        oracle.insert(PreimageKey::new_local(1), [0xAA; 32]);
+        oracle
+    }
+
+    #[tokio::test]
+    async fn test_get_preimage_or_not_found() {
+        let oracle = local_oracle();
+        assert_eq!(
+            get_preimage_or_not_found(&oracle, PreimageKey::new_local(1)).await.unwrap(),
+            vec![0xAA; 32]
+        );
+        assert!(matches!(
+            get_preimage_or_not_found(&oracle, PreimageKey::new_local(2)).await,
+            Err(OracleProviderError::NotFound { .. })
+        ));
+        assert!(oracle.hints.lock().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_send_and_get_local_key_skips_hint() {
+        let oracle = local_oracle();
+        let hint = HintType::L1BlockHeader.with_data(&[&[0xFF; 32]]);
+
+        let preimage = hint.send_and_get(&oracle, PreimageKey::new_local(1)).await.unwrap();
+        assert_eq!(preimage, vec![0xAA; 32]);
+        assert!(oracle.hints.lock().is_empty());
+
+        // Non-local keys still send the hint first.
+        let key = PreimageKey::new_keccak256([0xFF; 32]);
+        assert!(hint.send_and_get(&oracle, key).await.is_err());
+        assert_eq!(*oracle.hints.lock(), [hint.encode()]);
+    }
+
+    #[test]
+    fn test_hint_type_validate_data() {
+        assert!(HintType::L1BlockHeader.validate_data(&[0; 32]).is_ok());
+        let err = HintType::L1BlockHeader.validate_data(&[0; 31]).unwrap_err();
+        assert_eq!(
+            err.0,
+            "Invalid data length for l1-block-header hint: expected 32 bytes, got 31"
+        );
+        assert!(HintType::L1BlockHeader.validate_data(&[0; 40]).is_err());
+
+        // Hints with a variable-length field may be longer than their fixed-length fields.
+        assert!(HintType::L1Precompile.validate_data(&[0; 20]).is_ok());
+        assert!(HintType::L1Precompile.validate_data(&[0; 52]).is_ok());
+        assert!(HintType::L1Precompile.validate_data(&[0; 19]).is_err());
+    }
+
+    #[test]
+    fn test_hint_type_schema() {
+        assert_eq!(HintType::L1BlockHeader.schema(), &[FieldSpec::fixed("block_hash", 32)]);
+        assert_eq!(HintType::L1Blob.schema().len(), 3);
+        assert_eq!(HintType::L1BlockRange.schema().len(), 2);
+        assert_eq!(HintType::L2AccountStorageProof.schema().len(), 3);
+        assert_eq!(
+            HintType::L2PayloadWitness.schema().last().map(|f| f.length),
+            Some(FieldLength::Variable)
+        );
+
+        // The fixed fields of the schema add up to the data length sent by the client.
+        let hint = HintType::L2AccountStorageProof.with_data(&[&[0; 8], &[0; 20], &[0; 32]]);
+        let len: usize = HintType::L2AccountStorageProof
+            .schema()
+            .iter()
+            .map(|f| match f.length {
+                FieldLength::Fixed(len) => len,
+                FieldLength::Variable => 0,
+            })
+            .sum();
+        assert_eq!(len, hint.data.len());
+    }
+
+    #[test]
+    fn test_hint_type_generic_key() {
+        // Hints of different types with equal data are served under different keys.
+        let data = [0xAA; 48];
+        assert_ne!(
+            HintType::L1BlobProof.generic_key(&data),
+            HintType::L1BlobInclusionProof.generic_key(&data)
+        );
+        let timestamp = 1_700_000_000u64.to_be_bytes();
+        let keys = HintType::ALL.iter().map(|ty| ty.generic_key(&timestamp)).collect::<Vec<_>>();
+        assert!(keys.iter().enumerate().all(|(i, key)| !keys[i + 1..].contains(key)));
+
+        // A hint type that prefixes another does not run into the data.
+        assert_ne!(HintType::L1Blob.generic_key(b"-proof"), HintType::L1BlobProof.generic_key(b""));
+        assert_eq!(HintType::L1Blob.generic_key(&data).key_type(), PreimageKeyType::GlobalGeneric);
+    }
+
+    #[test]
+    fn test_hint_type_roundtrip() {
+        let names = [
+            (HintType::L1BlockHeader, "l1-block-header"),
+            (HintType::L1Transactions, "l1-transactions"),
+            (HintType::L1Receipts, "l1-receipts"),
+            (HintType::L1Blob, "l1-blob"),
+            (HintType::L1Precompile, "l1-precompile"),
+            (HintType::L1BlobInclusionProof, "l1-blob-inclusion-proof"),
+            (HintType::L1BlobProof, "l1-blob-proof"),
+            (HintType::L1BeaconBlock, "l1-beacon-block"),
+            (HintType::L1FinalizedHead, "l1-finalized-head"),
+            (HintType::L1ParentHash, "l1-parent-hash"),
+            (HintType::L1BlockRange, "l1-block-range"),
+            (HintType::L2BlockHeader, "l2-block-header"),
+            (HintType::L2Transactions, "l2-transactions"),
+            (HintType::L2Genesis, "l2-genesis"),
+            (HintType::L2ActiveFork, "l2-active-fork"),
+            (HintType::L2BlockByTimestamp, "l2-block-by-timestamp"),
+            (HintType::L2Code, "l2-code"),
+            (HintType::StartingL2Output, "starting-l2-output"),
+            (HintType::L2StateNode, "l2-state-node"),
+            (HintType::L2AccountProof, "l2-account-proof"),
+            (HintType::L2AccountStorageProof, "l2-account-storage-proof"),
+            (HintType::L2StorageRange, "l2-storage-range"),
+            (HintType::L2StorageSlots, "l2-storage-slots"),
+            (HintType::L2PayloadWitness, "l2-payload-witness"),
+            (HintType::Hello, "hello"),
+            #[cfg(feature = "debug-echo")]
+            (HintType::DebugEcho, "debug-echo"),
+            (HintType::L2DepositNonce, "l2-deposit-nonce"),
+            (HintType::L1EpochBoundary, "l1-epoch-boundary"),
+            (HintType::L2BlockSignature, "l2-block-signature"),
+            (HintType::L1Checkpoint, "l1-checkpoint"),
+            (HintType::L2BatchData, "l2-batch-data"),
+        ];
+        assert_eq!(names.map(|(ty, _)| ty).as_slice(), HintType::ALL);
+
+        for (ty, name) in names {
+            let s: &str = ty.into();
+            assert_eq!(s, name);
+            assert_eq!(ty.to_string(), name);
+            assert_eq!(HintType::from_str(name).unwrap(), ty);
+
+            // Hint data laid out according to the schema of the type round-trips through its
+            // encoding, and is valid for the type. Variable-length fields hold a JSON object, as
+            // the payload attributes of an `l2-payload-witness` hint must.
+            let fields = ty
+                .schema()
+                .iter()
+                .enumerate()
+                .map(|(i, field)| match field.length {
+                    FieldLength::Fixed(len) => vec![i as u8 + 1; len],
+                    FieldLength::Variable => b"{}".to_vec(),
+                })
+                .collect::<Vec<_>>();
+            let hint = ty.with_data(&fields.iter().map(Vec::as_slice).collect::<Vec<_>>());
+            let encoded = hint.encode();
+            assert!(encoded.starts_with(&alloc::format!("{name} 0x")), "{encoded}");
+            assert_eq!(Hint::<HintType>::from_str(&encoded).unwrap(), hint);
+            assert!(ty.validate_data(&hint.data).is_ok(), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_hint_l1_blob_inclusion_proof_roundtrip() {
+        let hint = HintType::L1BlobInclusionProof.with_data(&[&[0xFF; 32], &[0x01; 8], &[0x02; 8]]);
+        let encoded = hint.encode();
+        assert!(encoded.starts_with("l1-blob-inclusion-proof 0x"));
+        assert_eq!(Hint::<HintType>::from_str(&encoded).unwrap(), hint);
+    }
+
+    #[cfg(feature = "debug-echo")]
+    #[tokio::test]
+    async fn test_debug_echo_roundtrip() {
+        use alloc::{collections::BTreeMap, sync::Arc};
+        use spin::Mutex;
+
+        /// A mock host that serves the data of `debug-echo` hints as the preimage of their hash.
+        #[derive(Debug, Clone, Default)]
+        struct EchoHost(Arc<Mutex<BTreeMap<PreimageKey, Vec<u8>>>>);
+
+        #[async_trait]
+        impl PreimageOracleClient for EchoHost {
+            async fn get(&self, key: PreimageKey) -> PreimageOracleResult<Vec<u8>> {
+                self.0.lock().get(&key).cloned().ok_or(PreimageOracleError::KeyNotFound)
+            }
+
+            async fn get_exact(
+                &self,
+                key: PreimageKey,
+                buf: &mut [u8],
+            ) -> PreimageOracleResult<()> {
+                buf.copy_from_slice(&self.get(key).await?);
+                Ok(())
+            }
+        }
+
+        #[async_trait]
+        impl HintWriterClient for EchoHost {
+            async fn write(&self, hint: &str) -> PreimageOracleResult<()> {
+                let hint = Hint::<HintType>::from_str(hint)
+                    .map_err(|e| PreimageOracleError::Other(e.to_string()))?;
+                assert_eq!(hint.ty, HintType::DebugEcho);
+
+                let key = PreimageKey::new_keccak256(*keccak256(hint.data.as_ref()));
+                self.0.lock().insert(key, hint.data.to_vec());
+                Ok(())
+            }
+        }
+
+        let data = b"hello from the client";
+        let key = PreimageKey::new_keccak256(*keccak256(data));
+        let echoed = HintType::DebugEcho
+            .with_data(&[data])
+            .send_and_get(&EchoHost::default(), key)
+            .await
+            .unwrap();
+        assert_eq!(echoed, data);
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn test_hint_encode_with_compression_levels() {
+        let witness = (0..4096u32).flat_map(|i| (i % 7).to_be_bytes()).collect::<Vec<_>>();
+        let hint = HintType::L2PayloadWitness.with_data(&[&witness]);
+
+        let levels = [
+            CompressionLevel::NONE,
+            CompressionLevel::FASTEST,
+            CompressionLevel::default(),
+            CompressionLevel::BEST,
+        ];
+        for level in levels {
+            let encoded = hint.encode_with(level);
+            assert!(encoded.starts_with("l2-payload-witness+zlib 0x"));
+            assert_eq!(Hint::<HintType>::from_str(&encoded).unwrap(), hint);
+        }
+
+        // Compression should shrink repetitive data relative to the uncompressed encoding.
+        assert!(hint.encode_with(CompressionLevel::BEST).len() < hint.encode().len());
+    }
+
+    #[test]
+    fn test_hint_builder() {
+        let mut builder = HintType::L2AccountStorageProof.builder();
+        assert!(builder.is_empty());
+        builder.push(&1u64.to_be_bytes()).push(&[0xAA; 20]).push(&[0xBB; 32]);
+        assert_eq!(builder.len(), 60);
+
+        let hint = HintType::L2AccountStorageProof.with_data(&[
+            &1u64.to_be_bytes(),
+            &[0xAA; 20],
+            &[0xBB; 32],
+        ]);
+        assert_eq!(builder.build(), hint);
+        assert_eq!(builder.encode(), hint.encode());
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn test_hint_builder_encode_with() {
+        let witness = (0..1024u32).flat_map(|i| (i % 7).to_be_bytes()).collect::<Vec<_>>();
+        let mut builder = HintType::L2PayloadWitness.builder();
+        builder.push(&[0x11; 32]).push(&witness);
+
+        let hint = HintType::L2PayloadWitness.with_data(&[&[0x11; 32], &witness]);
+        for level in [CompressionLevel::NONE, CompressionLevel::default(), CompressionLevel::BEST] {
+            assert_eq!(builder.encode_with(level), hint.encode_with(level));
+        }
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn test_compression_level_clamped() {
+        assert_eq!(CompressionLevel::new(42), CompressionLevel::BEST);
+        assert_eq!(CompressionLevel::new(3).get(), 3);
+        assert_eq!(CompressionLevel::default(), CompressionLevel::BALANCED);
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn test_hint_invalid_compressed_data() {
+        let err = Hint::<HintType>::from_str("l1-block-header+zlib 0xdeadbeef").unwrap_err();
+        assert!(err.0.starts_with("Invalid compressed hint data"));
+
+        // Hint data that decompresses beyond the limit is rejected.
+        let bomb = Hint::new(HintType::L2PayloadWitness, vec![0u8; MAX_DECOMPRESSED_LEN + 1])
+            .encode_with(CompressionLevel::FASTEST);
+        let err = Hint::<HintType>::from_str(&bomb).unwrap_err();
+        assert!(err.0.starts_with("Invalid compressed hint data"));
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn test_preimage_response_roundtrip() {
+        let preimage = (0..1024u32).flat_map(|i| (i % 7).to_be_bytes()).collect::<Vec<_>>();
+
+        let raw = encode_preimage_response(&preimage, None);
+        assert_eq!(raw[0], ResponseFlag::Raw as u8);
+        assert_eq!(&raw[1..], preimage.as_slice());
+        assert_eq!(decode_preimage_response(&raw).unwrap(), preimage);
+
+        let compressed = encode_preimage_response(&preimage, Some(CompressionLevel::BEST));
+        assert_eq!(compressed[0], ResponseFlag::Zlib as u8);
+        assert!(compressed.len() < raw.len());
+        assert_eq!(decode_preimage_response(&compressed).unwrap(), preimage);
+
+        // The default flag is raw, for compatibility with hosts that do not compress.
+        assert_eq!(ResponseFlag::default() as u8, 0x00);
+        assert!(decode_preimage_response(&[]).is_err());
+        assert!(decode_preimage_response(&[0x02, 0xAA]).is_err());
+        assert!(decode_preimage_response(&[ResponseFlag::Zlib as u8, 0xde, 0xad]).is_err());
+
+        // Responses that decompress beyond the limit are rejected.
+        let bomb = encode_preimage_response(
+            &vec![0u8; MAX_DECOMPRESSED_LEN + 1],
+            Some(CompressionLevel::FASTEST),
+        );
+        assert!(bomb.len() < MAX_DECOMPRESSED_LEN / 100);
+        assert!(decode_preimage_response(&bomb).is_err());
+    }
+
+    #[cfg(feature = "compression")]
+    #[tokio::test]
+    async fn test_send_and_get_flagged() {
+        let hint = HintType::L2BatchData.with_data(&[&7u64.to_be_bytes(), &0u64.to_be_bytes()]);
+        let key = HintType::L2BatchData.generic_key(&hint.data);
+        let batch = [0xAA; 512];
+
+        let mut oracle = MockOracle::default();
+        oracle.insert(key, encode_preimage_response(&batch, Some(CompressionLevel::BALANCED)));
+        assert_eq!(hint.send_and_get_flagged(&oracle, key).await.unwrap(), batch);
+        assert_eq!(oracle.hints.lock().as_slice(), &[hint.encode()]);
+
+        oracle.insert(key, [0x02]);
+        assert!(matches!(
+            hint.send_and_get_flagged(&oracle, key).await,
+            Err(OracleProviderError::InvalidResponse(_))
+        ));
+    }
+
+    #[test]
+    fn test_hint_encode_roundtrip_every_type() {
+        for &ty in HintType::ALL {
+            for data in [&[][..], &[0xAB; 32][..], &[0x01; 1024][..]] {
+                let hint = Hint::new(ty, data.to_vec());
+                assert_eq!(Hint::<HintType>::from_str(&hint.encode()).unwrap(), hint);
+            }
+        }
+    }
+
+    #[test]
+    fn test_hint_from_str_malformed_spacing() {
+        let parse = |s: &str| Hint::<HintType>::from_str(s).unwrap_err().0;
+
+        assert_eq!(parse("l1-block-header"), "Invalid hint format: l1-block-header");
+        assert_eq!(
+            parse("l1-block-header 0xdead "),
+            "Invalid data for l1-block-header hint: unexpected whitespace at position 6"
+        );
+        assert_eq!(
+            parse("l1-block-header  0xdead"),
+            "Invalid data for l1-block-header hint: unexpected whitespace at position 0"
+        );
+        assert_eq!(
+            parse("l2-payload-witness 0xdead\tbeef"),
+            "Invalid data for l2-payload-witness hint: unexpected whitespace at position 6"
+        );
+    }
+
+    #[test]
+    fn test_hint_type_validate_payload_witness() {
+        let ty = HintType::L2PayloadWitness;
+        let attributes = br#"{"timestamp":"0x1"}"#;
+        assert!(ty.validate_data(&[&[0xAA; 32][..], attributes].concat()).is_ok());
+
+        // The chain ID may follow the payload attributes.
+        let data = [&[0xAA; 32][..], attributes, &10u64.to_be_bytes()].concat();
+        let hint = Hint::<HintType>::from_str(&ty.with_data(&[&data]).encode()).unwrap();
+        assert!(hint.ty.validate_data(&hint.data).is_ok());
+
+        assert_eq!(
+            ty.validate_data(&[0xAA; 33]).unwrap_err().0,
+            "Invalid data length for l2-payload-witness hint: expected at least 34 bytes for the \
+             parent block hash and payload attributes, got 33"
+        );
+        assert_eq!(
+            ty.validate_data(&[0xAA; 64]).unwrap_err().0,
+            "Invalid data for l2-payload-witness hint: payload attributes at offset 32 are not a \
+             JSON object"
+        );
+    }
+
+    #[test]
+    fn test_hint_type_validate_oversized_data() {
+        let hint = HintType::L2BlockHeader.with_data(&[&[0xAA; 33]]);
+        let hint = Hint::<HintType>::from_str(&hint.encode()).unwrap();
+        assert_eq!(
+            hint.ty.validate_data(&hint.data).unwrap_err().0,
+            "Invalid data length for l2-block-header hint: expected 32 bytes, got 33"
+        );
+    }
+
+    #[test]
+    fn test_hint_type_unknown() {
+        let err = HintType::from_str("l1-blob-inclusion").unwrap_err();
+        assert_eq!(err.0, "l1-blob-inclusion");
+    }
+}
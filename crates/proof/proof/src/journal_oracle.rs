@@ -0,0 +1,165 @@
+//! Contains the [JournalingOracle], which is a wrapper around an oracle that records the integrity
+//! of every preimage it serves, and the [get_preimage_verified] helper.
+
+use crate::errors::OracleProviderError;
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
+use alloy_primitives::{B256, keccak256};
+use async_trait::async_trait;
+use kona_preimage::{
+    HashVerify, HintWriterClient, PreimageKey, PreimageKeyType, PreimageOracleClient,
+    PreimageVerifier, errors::PreimageOracleResult,
+};
+use spin::Mutex;
+
+/// An entry of the journal of a [JournalingOracle], recording a preimage served by the wrapped
+/// oracle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JournalEntry {
+    /// The key the preimage was requested with.
+    pub key: PreimageKey,
+    /// The keccak256 hash of the served preimage.
+    pub response_hash: B256,
+    /// Whether the served preimage matches its key, or `None` if the key is not a hash of the
+    /// preimage and cannot be checked.
+    pub matched: Option<bool>,
+}
+
+impl JournalEntry {
+    /// Creates a new [JournalEntry] for the preimage served for the given key, checking
+    /// [PreimageKeyType::Keccak256] preimages against their key.
+    fn new(key: PreimageKey, preimage: &[u8]) -> Self {
+        let response_hash = keccak256(preimage);
+        let matched = (key.key_type() == PreimageKeyType::Keccak256)
+            .then(|| PreimageKey::new_keccak256(*response_hash) == key);
+        Self { key, response_hash, matched }
+    }
+}
+
+/// A wrapper around an oracle that records a [JournalEntry] for every preimage served through
+/// [PreimageOracleClient::get] and [PreimageOracleClient::get_exact].
+///
+/// Preimages are journaled rather than rejected, so that a full run may be audited afterwards
+/// without re-fetching any preimage. Use [get_preimage_verified] to reject a mismatched preimage
+/// as it is served instead. Clones of the oracle share the same journal.
+#[derive(Debug, Clone)]
+pub struct JournalingOracle<T> {
+    /// The wrapped oracle.
+    inner: T,
+    /// The entries recorded so far, in the order the preimages were served.
+    journal: Arc<Mutex<Vec<JournalEntry>>>,
+}
+
+impl<T> JournalingOracle<T> {
+    /// Creates a new [JournalingOracle] that wraps the given oracle, with an empty journal.
+    pub fn new(inner: T) -> Self {
+        Self { inner, journal: Arc::new(Mutex::new(Vec::new())) }
+    }
+
+    /// Returns the journal entries recorded so far, in the order the preimages were served.
+    pub fn journal(&self) -> Vec<JournalEntry> {
+        self.journal.lock().clone()
+    }
+
+    /// Returns the journal entries of the preimages that did not match their key.
+    pub fn mismatches(&self) -> Vec<JournalEntry> {
+        self.journal.lock().iter().filter(|entry| entry.matched == Some(false)).copied().collect()
+    }
+
+    /// Records the preimage served for the given key.
+    fn record(&self, key: PreimageKey, preimage: &[u8]) {
+        self.journal.lock().push(JournalEntry::new(key, preimage));
+    }
+}
+
+#[async_trait]
+impl<T> PreimageOracleClient for JournalingOracle<T>
+where
+    T: PreimageOracleClient + Send + Sync,
+{
+    async fn get(&self, key: PreimageKey) -> PreimageOracleResult<Vec<u8>> {
+        let preimage = self.inner.get(key).await?;
+        self.record(key, &preimage);
+        Ok(preimage)
+    }
+
+    async fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> PreimageOracleResult<()> {
+        self.inner.get_exact(key, buf).await?;
+        self.record(key, buf);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<T> HintWriterClient for JournalingOracle<T>
+where
+    T: HintWriterClient + Send + Sync,
+{
+    async fn write(&self, hint: &str) -> PreimageOracleResult<()> {
+        self.inner.write(hint).await
+    }
+}
+
+/// Fetches the preimage for the given key from the passed [PreimageOracleClient], rejecting it
+/// with [PreimageOracleError::InvalidPreimage] if it does not match its key.
+///
+/// Only [PreimageKeyType::Keccak256] preimages can be checked, as with [HashVerify]. Preimages of
+/// other key types are returned as served.
+///
+/// [PreimageOracleError::InvalidPreimage]: kona_preimage::errors::PreimageOracleError::InvalidPreimage
+pub async fn get_preimage_verified<T: PreimageOracleClient>(
+    oracle: &T,
+    key: PreimageKey,
+) -> Result<Vec<u8>, OracleProviderError> {
+    let preimage = oracle.get(key).await?;
+    HashVerify.verify(key, &preimage)?;
+    Ok(preimage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MockOracle;
+
+    use kona_preimage::errors::PreimageOracleError;
+
+    #[tokio::test]
+    async fn test_journal_flags_tampered_preimage() {
+        let honest = PreimageKey::new_keccak256(*keccak256(b"honest"));
+        let tampered = PreimageKey::new_keccak256(*keccak256(b"expected"));
+        let local = PreimageKey::new_local(1);
+
+        let mut inner = MockOracle::default();
+        inner.preimages.insert(honest, b"honest".to_vec());
+        inner.preimages.insert(tampered, b"tampered".to_vec());
+        inner.preimages.insert(local, b"local".to_vec());
+        let oracle = JournalingOracle::new(inner);
+
+        oracle.get(honest).await.unwrap();
+        let mut buf = [0u8; 8];
+        oracle.get_exact(tampered, &mut buf).await.unwrap();
+        oracle.get(local).await.unwrap();
+        assert!(oracle.get(PreimageKey::new_local(2)).await.is_err());
+
+        // Every served preimage is journaled, and only the tampered one is flagged.
+        let journal = oracle.journal();
+        assert_eq!(journal.len(), 3);
+        assert_eq!(journal[0].matched, Some(true));
+        assert_eq!(journal[2].matched, None);
+        assert_eq!(
+            oracle.mismatches(),
+            [JournalEntry {
+                key: tampered,
+                response_hash: keccak256(b"tampered"),
+                matched: Some(false)
+            }]
+        );
+
+        // The tampered preimage is rejected when fetched with verification.
+        assert_eq!(get_preimage_verified(&oracle, honest).await.unwrap(), b"honest");
+        assert!(matches!(
+            get_preimage_verified(&oracle, tampered).await,
+            Err(OracleProviderError::Preimage(PreimageOracleError::InvalidPreimage(key)))
+                if key == tampered
+        ));
+    }
+}
@@ -1,13 +1,12 @@
 //! Contains the concrete implementation of the [BlobProvider] trait for the client program.
 
-use crate::{HintType, errors::OracleProviderError};
+use crate::{HintType, errors::OracleProviderError, get_preimage_or_not_found};
 use alloc::{boxed::Box, sync::Arc, vec::Vec};
 use alloy_consensus::Blob;
-use alloy_eips::eip4844::{FIELD_ELEMENTS_PER_BLOB, IndexedBlobHash};
-use alloy_primitives::keccak256;
+use alloy_eips::eip4844::IndexedBlobHash;
 use async_trait::async_trait;
 use kona_derive::traits::BlobProvider;
-use kona_preimage::{CommsClient, PreimageKey, PreimageKeyType};
+use kona_preimage::{BlobKey, CommsClient, PreimageKey, PreimageKeyType, PreimageOracleClient};
 use kona_protocol::BlockInfo;
 
 /// An oracle-backed blob provider.
@@ -51,23 +50,7 @@ impl<T: CommsClient> OracleBlobProvider<T> {
             .await
             .map_err(OracleProviderError::Preimage)?;
 
-        // Reconstruct the blob from the 4096 field elements.
-        let mut blob = Blob::default();
-        let mut field_element_key = [0u8; 80];
-        field_element_key[..48].copy_from_slice(commitment.as_ref());
-        for i in 0..FIELD_ELEMENTS_PER_BLOB {
-            field_element_key[72..].copy_from_slice(i.to_be_bytes().as_ref());
-
-            let mut field_element = [0u8; 32];
-            self.oracle
-                .get_exact(
-                    PreimageKey::new(*keccak256(field_element_key), PreimageKeyType::Blob),
-                    &mut field_element,
-                )
-                .await
-                .map_err(OracleProviderError::Preimage)?;
-            blob[(i as usize) << 5..(i as usize + 1) << 5].copy_from_slice(field_element.as_ref());
-        }
+        let blob = reassemble_blob(self.oracle.as_ref(), commitment).await?;
 
         tracing::info!(target: "client_oracle", "Retrieved blob {blob_hash:?} from the oracle.");
 
@@ -75,6 +58,33 @@ impl<T: CommsClient> OracleBlobProvider<T> {
     }
 }
 
+/// Reassembles the blob with the given KZG commitment from the preimages of its 4096 field
+/// elements, which are keyed by their [BlobKey]s.
+///
+/// Each field element is copied into the blob in full. The high byte of each element is only
+/// stripped when the blob data is decoded, since it is part of the blob that the commitment is
+/// computed over.
+///
+/// The hint for the blob must have been sent prior to this call. If the preimage of any field
+/// element is missing, [OracleProviderError::NotFound] is returned.
+pub async fn reassemble_blob<T: PreimageOracleClient>(
+    oracle: &T,
+    commitment: [u8; 48],
+) -> Result<Blob, OracleProviderError> {
+    let mut blob = Blob::default();
+    for key in BlobKey::field_elements(commitment) {
+        let i = key.index as usize;
+
+        let field_element: [u8; 32] = get_preimage_or_not_found(oracle, key.key())
+            .await?
+            .as_slice()
+            .try_into()
+            .map_err(OracleProviderError::SliceConversion)?;
+        blob[i << 5..(i + 1) << 5].copy_from_slice(field_element.as_ref());
+    }
+    Ok(blob)
+}
+
 #[async_trait]
 impl<T: CommsClient + Sync + Send> BlobProvider for OracleBlobProvider<T> {
     type Error = OracleProviderError;
@@ -91,3 +101,46 @@ impl<T: CommsClient + Sync + Send> BlobProvider for OracleBlobProvider<T> {
         Ok(blobs)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MockOracle;
+
+    use alloy_eips::eip4844::FIELD_ELEMENTS_PER_BLOB;
+    use alloy_primitives::B256;
+
+    /// Stores each field element of the blob under its blob key, as the host does.
+    fn serve_blob(blob: &Blob, commitment: [u8; 48]) -> MockOracle {
+        let mut oracle = MockOracle::default();
+        for (i, element) in blob.chunks(32).enumerate() {
+            oracle.insert(BlobKey::new(commitment, i as u64).key(), element);
+        }
+        oracle
+    }
+
+    #[tokio::test]
+    async fn test_reassemble_blob() {
+        let commitment = [0xBB; 48];
+        let mut blob = Blob::default();
+        for (i, byte) in blob.iter_mut().enumerate() {
+            // Keep the high byte of each field element zero, as in a canonical blob.
+            *byte = if i % 32 == 0 { 0 } else { (i % 251) as u8 };
+        }
+
+        let oracle = serve_blob(&blob, commitment);
+        assert_eq!(oracle.preimages.len(), FIELD_ELEMENTS_PER_BLOB as usize);
+        assert_eq!(reassemble_blob(&oracle, commitment).await.unwrap(), blob);
+    }
+
+    #[tokio::test]
+    async fn test_reassemble_blob_missing_field_element() {
+        let commitment = [0xBB; 48];
+        let mut oracle = serve_blob(&Blob::default(), commitment);
+        let missing = BlobKey::new(commitment, 4095).key();
+        oracle.preimages.remove(&missing);
+
+        let err = reassemble_blob(&oracle, commitment).await.unwrap_err();
+        assert!(matches!(err, OracleProviderError::NotFound { key } if key == B256::from(missing)));
+    }
+}
@@ -1,6 +1,6 @@
 //! Contains the concrete implementation of the [ChainProvider] trait for the proof.
 
-use crate::{HintType, errors::OracleProviderError};
+use crate::{HintType, MAX_L1_BLOCK_RANGE, errors::OracleProviderError};
 use alloc::{boxed::Box, sync::Arc, vec::Vec};
 use alloy_consensus::{Header, Receipt, ReceiptEnvelope, TxEnvelope};
 use alloy_eips::eip2718::Decodable2718;
@@ -9,7 +9,7 @@ use alloy_rlp::Decodable;
 use async_trait::async_trait;
 use kona_derive::traits::ChainProvider;
 use kona_mpt::{OrderedListWalker, TrieNode, TrieProvider};
-use kona_preimage::{CommsClient, PreimageKey, PreimageKeyType};
+use kona_preimage::{CommsClient, PreimageKey};
 use kona_protocol::BlockInfo;
 
 /// The oracle-backed L1 chain provider for the client program.
@@ -26,20 +26,86 @@ impl<T: CommsClient> OracleL1ChainProvider<T> {
     pub const fn new(l1_head: B256, oracle: Arc<T>) -> Self {
         Self { l1_head, oracle }
     }
-}
 
-#[async_trait]
-impl<T: CommsClient + Sync + Send> ChainProvider for OracleL1ChainProvider<T> {
-    type Error = OracleProviderError;
+    /// Fetches the headers of the layer 1 blocks from `start` to `end`, inclusive, in order, with
+    /// a single [HintType::L1BlockRange] hint.
+    ///
+    /// The range may span at most [MAX_L1_BLOCK_RANGE] blocks. The block hashes returned by the
+    /// host cannot be verified on their own, so the range is anchored at the L1 head: the hash of
+    /// the last block in the range is found by walking back from the L1 head, and each header is
+    /// verified against that hash or the parent hash of the header after it.
+    pub async fn headers_by_range(
+        &self,
+        start: u64,
+        end: u64,
+    ) -> Result<Vec<Header>, OracleProviderError> {
+        if end < start || end - start >= MAX_L1_BLOCK_RANGE {
+            return Err(OracleProviderError::InvalidBlockRange(start, end));
+        }
 
-    async fn header_by_hash(&mut self, hash: B256) -> Result<Header, Self::Error> {
+        // The host stores the ordered block hashes of the range under the generic key of the
+        // hint, and each header by its hash.
+        let hint_data = [start.to_be_bytes(), end.to_be_bytes()].concat();
+        HintType::L1BlockRange.with_data(&[&hint_data]).send(self.oracle.as_ref()).await?;
+        let hashes = self.oracle.get(HintType::L1BlockRange.generic_key(&hint_data)).await?;
+        if hashes.len() as u64 != (end - start + 1) * 32 {
+            return Err(OracleProviderError::InvalidBlockRange(start, end));
+        }
+
+        // Walk back from the L1 head to find the hash of the last block in the range.
+        let mut hash = self.l1_head;
+        let mut header = self.header(hash, true).await?;
+        if end > header.number {
+            return Err(OracleProviderError::BlockNumberPastHead(end, header.number));
+        }
+        while header.number > end {
+            hash = header.parent_hash;
+            header = self.header(hash, true).await?;
+        }
+
+        // Verify the range from its last block down, each header by the parent hash of the next.
+        let mut headers: Vec<Header> = Vec::with_capacity(hashes.len() / 32);
+        let mut last = Some(header);
+        for (number, listed) in (start..=end).rev().zip(hashes.rchunks_exact(32)) {
+            if B256::from_slice(listed) != hash {
+                return Err(OracleProviderError::BrokenHeaderChain(number));
+            }
+            let header = match last.take() {
+                Some(header) => header,
+                None => self.header(hash, false).await?,
+            };
+            if header.number != number || header.hash_slow() != hash {
+                return Err(OracleProviderError::BrokenHeaderChain(number));
+            }
+            hash = header.parent_hash;
+            headers.push(header);
+        }
+        headers.reverse();
+
+        Ok(headers)
+    }
+
+    /// Fetches the header with the given hash, sending a [HintType::L1BlockHeader] hint for it
+    /// first if `hint` is set.
+    async fn header(&self, hash: B256, hint: bool) -> Result<Header, OracleProviderError> {
         // Fetch the header RLP from the oracle.
-        HintType::L1BlockHeader.with_data(&[hash.as_ref()]).send(self.oracle.as_ref()).await?;
+        if hint {
+            HintType::L1BlockHeader.with_data(&[hash.as_ref()]).send(self.oracle.as_ref()).await?;
+        }
         let header_rlp = self.oracle.get(PreimageKey::new_keccak256(*hash)).await?;
 
         // Decode the header RLP into a Header.
         Header::decode(&mut header_rlp.as_slice()).map_err(OracleProviderError::Rlp)
     }
+}
+
+#[async_trait]
+impl<T: CommsClient + Sync + Send> ChainProvider for OracleL1ChainProvider<T> {
+    type Error = OracleProviderError;
+
+    async fn header_by_hash(&mut self, hash: B256) -> Result<Header, Self::Error> {
+        self.header(hash, true).await
+    }
 
     async fn block_info_by_number(&mut self, block_number: u64) -> Result<BlockInfo, Self::Error> {
         // Fetch the starting block header.
@@ -126,16 +192,109 @@ impl<T: CommsClient> TrieProvider for OracleL1ChainProvider<T> {
     fn trie_node_by_hash(&self, key: B256) -> Result<TrieNode, Self::Error> {
         // On L1, trie node preimages are stored as keccak preimage types in the oracle. We assume
         // that a hint for these preimages has already been sent, prior to this call.
-        crate::block_on(async move {
-            TrieNode::decode(
-                &mut self
-                    .oracle
-                    .get(PreimageKey::new(*key, PreimageKeyType::Keccak256))
-                    .await
-                    .map_err(OracleProviderError::Preimage)?
-                    .as_ref(),
-            )
-            .map_err(OracleProviderError::Rlp)
-        })
+        crate::block_on(crate::fetch_trie_node(self.oracle.as_ref(), key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MockOracle;
+    use alloc::format;
+    use alloy_primitives::Bytes;
+
+    use alloy_rlp::Encodable;
+
+    /// Returns a chain of `count` linked headers starting at block `start`.
+    fn header_chain(start: u64, count: u64) -> Vec<Header> {
+        let mut parent_hash = B256::ZERO;
+        (start..start + count)
+            .map(|number| {
+                let header = Header { number, parent_hash, ..Default::default() };
+                parent_hash = header.hash_slow();
+                header
+            })
+            .collect()
+    }
+
+    /// Populates the oracle with the headers of a block range, as the host does.
+    fn serve_range(oracle: &mut MockOracle, start: u64, end: u64, headers: &[Header]) {
+        let mut hashes = Vec::new();
+        for header in headers {
+            let mut rlp = Vec::new();
+            header.encode(&mut rlp);
+            let hash = header.hash_slow();
+            oracle.preimages.insert(PreimageKey::new_keccak256(*hash), rlp);
+            hashes.extend_from_slice(hash.as_slice());
+        }
+        let hint_data = [start.to_be_bytes(), end.to_be_bytes()].concat();
+        oracle.insert(HintType::L1BlockRange.generic_key(&hint_data), hashes);
+    }
+
+    #[tokio::test]
+    async fn test_headers_by_range() {
+        let headers = header_chain(100, 10);
+        let mut oracle = MockOracle::default();
+        serve_range(&mut oracle, 100, 109, &headers);
+        serve_range(&mut oracle, 100, 107, &headers[..8]);
+
+        // The range is anchored at the L1 head, two blocks past its end.
+        let l1_head = headers[9].hash_slow();
+        let provider = OracleL1ChainProvider::new(l1_head, Arc::new(oracle));
+        assert_eq!(provider.headers_by_range(100, 107).await.unwrap(), headers[..8]);
+        assert_eq!(
+            *provider.oracle.hints.lock(),
+            [
+                "l1-block-range 0x0000000000000064000000000000006b".into(),
+                format!("l1-block-header {l1_head}"),
+                format!("l1-block-header {}", headers[9].parent_hash),
+                format!("l1-block-header {}", headers[8].parent_hash),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_headers_by_range_broken_chain() {
+        let mut headers = header_chain(100, 4);
+        headers[2].parent_hash = B256::repeat_byte(0xFF);
+        let mut oracle = MockOracle::default();
+        serve_range(&mut oracle, 100, 103, &headers);
+
+        let provider = OracleL1ChainProvider::new(headers[3].hash_slow(), Arc::new(oracle));
+        assert!(matches!(
+            provider.headers_by_range(100, 103).await,
+            Err(OracleProviderError::BrokenHeaderChain(102))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_headers_by_range_unanchored() {
+        // A linked range that does not lead to the L1 head is rejected.
+        let headers = header_chain(100, 4);
+        let mut forged = headers.clone();
+        forged[3].extra_data = Bytes::from_static(&[0xFF]);
+        let mut oracle = MockOracle::default();
+        serve_range(&mut oracle, 100, 103, &forged);
+        serve_range(&mut oracle, 103, 103, &headers[3..]);
+
+        let provider = OracleL1ChainProvider::new(headers[3].hash_slow(), Arc::new(oracle));
+        assert!(matches!(
+            provider.headers_by_range(100, 103).await,
+            Err(OracleProviderError::BrokenHeaderChain(103))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_headers_by_range_invalid() {
+        let provider = OracleL1ChainProvider::new(B256::ZERO, Arc::new(MockOracle::default()));
+        assert!(matches!(
+            provider.headers_by_range(10, 9).await,
+            Err(OracleProviderError::InvalidBlockRange(10, 9))
+        ));
+        assert!(matches!(
+            provider.headers_by_range(0, MAX_L1_BLOCK_RANGE).await,
+            Err(OracleProviderError::InvalidBlockRange(0, MAX_L1_BLOCK_RANGE))
+        ));
+        assert!(provider.oracle.hints.lock().is_empty());
     }
 }
@@ -0,0 +1,165 @@
+//! Contains the [fetch_l2_block] helper, which fetches a full L2 block by its hash.
+
+use crate::{HintType, errors::OracleProviderError};
+use alloc::vec::Vec;
+use alloy_consensus::{BlockBody, Header, proofs::calculate_transaction_root};
+use alloy_eips::{eip2718::Decodable2718, eip4895::Withdrawals};
+use alloy_primitives::B256;
+use alloy_rlp::Decodable;
+use kona_mpt::{OrderedListWalker, TrieNode, TrieProvider};
+use kona_preimage::{CommsClient, PreimageKey, PreimageKeyType};
+use op_alloy_consensus::{OpBlock, OpTxEnvelope};
+
+/// Fetches the L2 block with the given hash from the oracle.
+///
+/// This sends the [HintType::L2BlockHeader] and [HintType::L2Transactions] hints, decodes the
+/// header and the transactions in its transactions trie, and checks that the root of the decoded
+/// transactions matches the `transactions_root` committed to in the header. A mismatch is
+/// reported as [OracleProviderError::TransactionsRootMismatch].
+///
+/// The body has an empty withdrawals list if the header has a `withdrawals_root`, as L2 blocks
+/// never contain withdrawals.
+pub async fn fetch_l2_block<T: CommsClient>(
+    oracle: &T,
+    hash: B256,
+) -> Result<OpBlock, OracleProviderError> {
+    // Fetch the header.
+    HintType::L2BlockHeader.with_data(&[hash.as_ref()]).send(oracle).await?;
+    let header_rlp = oracle.get(PreimageKey::new_keccak256(*hash)).await?;
+    let header = Header::decode(&mut header_rlp.as_slice()).map_err(OracleProviderError::Rlp)?;
+
+    // Fetch the transactions in the block.
+    HintType::L2Transactions.with_data(&[hash.as_ref()]).send(oracle).await?;
+    let trie_walker =
+        OrderedListWalker::try_new_hydrated(header.transactions_root, &OracleTrieProvider(oracle))
+            .map_err(OracleProviderError::TrieWalker)?;
+    let transactions = trie_walker
+        .into_iter()
+        .map(|(_, rlp)| {
+            // note: not short-handed for error type coersion w/ `?`.
+            let tx = OpTxEnvelope::decode_2718(&mut rlp.as_ref())?;
+            Ok(tx)
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(OracleProviderError::Rlp)?;
+
+    // Check that the transactions are the ones committed to in the header.
+    let computed = calculate_transaction_root(&transactions);
+    if computed != header.transactions_root {
+        return Err(OracleProviderError::TransactionsRootMismatch {
+            expected: header.transactions_root,
+            computed,
+        });
+    }
+
+    let withdrawals = header.withdrawals_root.map(|_| Withdrawals::new(Vec::new()));
+    Ok(OpBlock { header, body: BlockBody { transactions, ommers: Vec::new(), withdrawals } })
+}
+
+/// A [TrieProvider] that fetches trie nodes from the oracle by their keccak256 hash.
+#[derive(Debug)]
+struct OracleTrieProvider<'a, T>(&'a T);
+
+impl<T: CommsClient> TrieProvider for OracleTrieProvider<'_, T> {
+    type Error = OracleProviderError;
+
+    fn trie_node_by_hash(&self, key: B256) -> Result<TrieNode, OracleProviderError> {
+        // Trie node preimages are stored as keccak preimage types in the oracle. We assume that a
+        // hint for these preimages has already been sent, prior to this call.
+        crate::block_on(async move {
+            TrieNode::decode(
+                &mut self
+                    .0
+                    .get(PreimageKey::new(*key, PreimageKeyType::Keccak256))
+                    .await
+                    .map_err(OracleProviderError::Preimage)?
+                    .as_ref(),
+            )
+            .map_err(OracleProviderError::Rlp)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MockOracle;
+
+    use alloy_consensus::Sealed;
+    use alloy_eips::eip2718::Encodable2718;
+    use alloy_primitives::keccak256;
+    use alloy_rlp::Encodable;
+
+    use kona_mpt::ordered_trie_with_encoder;
+
+    use op_alloy_consensus::TxDeposit;
+
+    fn transactions(count: u8) -> Vec<OpTxEnvelope> {
+        (0..count)
+            .map(|i| {
+                let tx = TxDeposit { source_hash: B256::repeat_byte(i), ..Default::default() };
+                OpTxEnvelope::Deposit(Sealed::new(tx))
+            })
+            .collect()
+    }
+
+    /// Populates the oracle with the nodes of the transactions trie, returning its root.
+    fn serve_transactions(oracle: &mut MockOracle, transactions: &[OpTxEnvelope]) -> B256 {
+        let mut trie = ordered_trie_with_encoder(transactions, |tx, buf| tx.encode_2718(buf));
+        let root = trie.root();
+        for (_, node) in trie.take_proof_nodes().into_inner() {
+            oracle.preimages.insert(PreimageKey::new_keccak256(*keccak256(&node)), node.into());
+        }
+        root
+    }
+
+    /// Populates the oracle with the header, returning its hash.
+    fn serve_header(oracle: &mut MockOracle, header: &Header) -> B256 {
+        let mut rlp = Vec::new();
+        header.encode(&mut rlp);
+        let hash = header.hash_slow();
+        oracle.preimages.insert(PreimageKey::new_keccak256(*hash), rlp);
+        hash
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_fetch_l2_block() {
+        let mut oracle = MockOracle::default();
+        let transactions = transactions(3);
+        let transactions_root = serve_transactions(&mut oracle, &transactions);
+        let header = Header { number: 10, transactions_root, ..Default::default() };
+        let hash = serve_header(&mut oracle, &header);
+
+        let block = fetch_l2_block(&oracle, hash).await.unwrap();
+        assert_eq!(block.header, header);
+        assert_eq!(block.body.transactions, transactions);
+        assert!(block.body.withdrawals.is_none());
+
+        let hints = oracle.hints.lock();
+        assert_eq!(hints.len(), 2);
+        assert!(hints[0].starts_with("l2-block-header"));
+        assert!(hints[1].starts_with("l2-transactions"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_fetch_l2_block_transactions_root_mismatch() {
+        let mut oracle = MockOracle::default();
+        let transactions = transactions(2);
+        let root = serve_transactions(&mut oracle, &transactions);
+
+        // Serve the root node of the transactions trie under a different key, as a faulty host
+        // might, so that the decoded transactions do not match the header.
+        let expected = B256::repeat_byte(0xff);
+        let root_node = oracle.preimages[&PreimageKey::new_keccak256(*root)].clone();
+        oracle.preimages.insert(PreimageKey::new_keccak256(*expected), root_node);
+        let header = Header { transactions_root: expected, ..Default::default() };
+        let hash = serve_header(&mut oracle, &header);
+
+        let err = fetch_l2_block(&oracle, hash).await.unwrap_err();
+        assert!(matches!(
+            err,
+            OracleProviderError::TransactionsRootMismatch { expected: e, computed: c }
+                if e == expected && c == root
+        ));
+    }
+}
@@ -1,6 +1,8 @@
 //! Contains the concrete implementation of the [L2ChainProvider] trait for the client program.
 
-use crate::{HintType, eip2935::eip_2935_history_lookup, errors::OracleProviderError};
+use crate::{
+    HintType, eip2935::eip_2935_history_lookup, errors::OracleProviderError, l2::get_code_length,
+};
 use alloc::{boxed::Box, sync::Arc, vec::Vec};
 use alloy_consensus::{BlockBody, Header};
 use alloy_eips::eip2718::Decodable2718;
@@ -12,7 +14,7 @@ use kona_driver::PipelineCursor;
 use kona_executor::TrieDBProvider;
 use kona_genesis::{RollupConfig, SystemConfig};
 use kona_mpt::{OrderedListWalker, TrieHinter, TrieNode, TrieProvider};
-use kona_preimage::{CommsClient, PreimageKey, PreimageKeyType};
+use kona_preimage::{CommsClient, PreimageKey};
 use kona_protocol::{BatchValidationProvider, L2BlockInfo, to_system_config};
 use op_alloy_consensus::{OpBlock, OpTxEnvelope};
 use spin::RwLock;
@@ -48,6 +50,12 @@ impl<T: CommsClient> OracleL2ChainProvider<T> {
         self.cursor = Some(cursor);
     }
 
+    /// Fetches the length of the contract code with the given hash, without transferring the
+    /// code. See [get_code_length].
+    pub async fn code_length(&self, hash: B256) -> Result<usize, OracleProviderError> {
+        get_code_length(self.oracle.as_ref(), hash, self.chain_id).await
+    }
+
     /// Fetches the latest known safe head block hash according to the derivation pipeline cursor
     /// or uses the initial l2_head value if no cursor is set.
     pub async fn l2_safe_head(&self) -> Result<B256, OracleProviderError> {
@@ -174,17 +182,7 @@ impl<T: CommsClient> TrieProvider for OracleL2ChainProvider<T> {
     fn trie_node_by_hash(&self, key: B256) -> Result<TrieNode, OracleProviderError> {
         // On L2, trie node preimages are stored as keccak preimage types in the oracle. We assume
         // that a hint for these preimages has already been sent, prior to this call.
-        crate::block_on(async move {
-            TrieNode::decode(
-                &mut self
-                    .oracle
-                    .get(PreimageKey::new(*key, PreimageKeyType::Keccak256))
-                    .await
-                    .map_err(OracleProviderError::Preimage)?
-                    .as_ref(),
-            )
-            .map_err(OracleProviderError::Rlp)
-        })
+        crate::block_on(crate::fetch_trie_node(self.oracle.as_ref(), key))
     }
 }
 
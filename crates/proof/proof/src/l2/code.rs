@@ -0,0 +1,79 @@
+//! Contains the [get_code_length] helper, which fetches the length of an L2 contract's code
+//! without fetching the code itself.
+
+use crate::{HintType, errors::OracleProviderError};
+use alloc::vec::Vec;
+use alloy_primitives::{B256, keccak256};
+use kona_preimage::{CommsClient, PreimageKey, PreimageKeyType};
+
+/// The domain of the preimage keys of code lengths, which separates them from the
+/// [HintType::generic_key]s of other hint responses.
+const CODE_LENGTH_DOMAIN: &[u8] = b"code-length";
+
+/// Returns the preimage key of the length of the code with the given hash.
+///
+/// Alongside the code, the host serves its length as an 8-byte big-endian integer under this key
+/// in response to a [HintType::L2Code] hint.
+pub fn code_length_key(code_hash: B256) -> PreimageKey {
+    let data = [CODE_LENGTH_DOMAIN, code_hash.as_slice()].concat();
+    PreimageKey::new(*keccak256(data), PreimageKeyType::GlobalGeneric)
+}
+
+/// Fetches the length of the L2 contract code with the given hash, without transferring the code.
+///
+/// The length may be used to pre-size the buffer passed to [PreimageOracleClient::get_exact] when
+/// fetching the code with [PreimageKey::new_keccak256]. The `chain_id` is appended to the hint
+/// data, as for [OracleL2ChainProvider].
+///
+/// [PreimageOracleClient::get_exact]: kona_preimage::PreimageOracleClient::get_exact
+/// [OracleL2ChainProvider]: crate::l2::OracleL2ChainProvider
+pub async fn get_code_length<T: CommsClient>(
+    oracle: &T,
+    code_hash: B256,
+    chain_id: Option<u64>,
+) -> Result<usize, OracleProviderError> {
+    HintType::L2Code
+        .with_data(&[code_hash.as_slice()])
+        .with_data(chain_id.map_or_else(Vec::new, |id| id.to_be_bytes().to_vec()))
+        .send(oracle)
+        .await?;
+
+    let mut length = [0u8; 8];
+    oracle.get_exact(code_length_key(code_hash), &mut length).await?;
+    Ok(u64::from_be_bytes(length) as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MockOracle;
+
+    use kona_preimage::PreimageOracleClient;
+
+    #[tokio::test]
+    async fn test_get_code_length() {
+        // Serve the code and its length, as the host does.
+        let code = [0x60u8, 0x80, 0x60, 0x40, 0x52, 0x00].repeat(100);
+        let code_hash = keccak256(&code);
+        let mut oracle = MockOracle::default();
+        oracle.preimages.insert(PreimageKey::new_keccak256(*code_hash), code.clone());
+        oracle
+            .preimages
+            .insert(code_length_key(code_hash), (code.len() as u64).to_be_bytes().to_vec());
+
+        let length = get_code_length(&oracle, code_hash, Some(10)).await.unwrap();
+        assert_eq!(length, code.len());
+        assert_eq!(
+            oracle.hints.lock().as_slice(),
+            [HintType::L2Code.with_data(&[code_hash.as_slice(), &10u64.to_be_bytes()]).encode()]
+        );
+
+        // The length sizes the buffer the code is fetched into.
+        let mut buf = alloc::vec![0u8; length];
+        oracle.get_exact(PreimageKey::new_keccak256(*code_hash), &mut buf).await.unwrap();
+        assert_eq!(buf, code);
+
+        // Unknown code has no length.
+        assert!(get_code_length(&oracle, B256::ZERO, None).await.is_err());
+    }
+}
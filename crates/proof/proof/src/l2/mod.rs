@@ -1,4 +1,13 @@
 //! Contains the L2-specific constructs of the client program.
 
+mod block;
+pub use block::fetch_l2_block;
+
+mod code;
+pub use code::{code_length_key, get_code_length};
+
+mod storage;
+pub use storage::{StorageSlotProof, fetch_storage_proofs};
+
 mod chain_provider;
 pub use chain_provider::OracleL2ChainProvider;
@@ -0,0 +1,211 @@
+//! Contains the [fetch_storage_proofs] helper, which fetches a batch of storage slots of an L2
+//! account along with their proofs.
+
+use crate::{HintType, MAX_L2_STORAGE_SLOTS, errors::OracleProviderError};
+use alloc::{collections::BTreeMap, vec::Vec};
+use alloy_primitives::{Address, B256, Bytes, U256, keccak256};
+use alloy_rlp::Decodable;
+use alloy_trie::TrieAccount;
+use core::cell::RefCell;
+use kona_mpt::{Nibbles, TrieNode, TrieProvider};
+use kona_preimage::CommsClient;
+
+/// The value of a storage slot, along with its proof.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StorageSlotProof {
+    /// The value of the storage slot. Slots that are not set in the storage trie are zero.
+    pub value: U256,
+    /// The RLP-encoded storage trie nodes on the path from the storage root to the slot, in
+    /// order. Nodes that are embedded in their parent are not included.
+    pub proof: Vec<Bytes>,
+}
+
+/// Fetches the given storage slots of an account from the L2 state trie with the given root, as
+/// of the given block number.
+///
+/// Rather than sending an [HintType::L2AccountStorageProof] hint per slot, the slots are hinted in
+/// batches of `batch_size` with [HintType::L2StorageSlots], which share a single account proof.
+/// The batch size is clamped to `1..=`[MAX_L2_STORAGE_SLOTS].
+///
+/// Returns the value and proof of every requested slot, keyed by slot. If the account does not
+/// exist, every slot is zero with an empty proof.
+pub async fn fetch_storage_proofs<T: CommsClient>(
+    oracle: &T,
+    state_root: B256,
+    block_number: u64,
+    address: Address,
+    slots: &[B256],
+    batch_size: usize,
+) -> Result<BTreeMap<B256, StorageSlotProof>, OracleProviderError> {
+    let batch_size = batch_size.clamp(1, MAX_L2_STORAGE_SLOTS);
+    let mut proofs = BTreeMap::new();
+    let mut account = None;
+
+    for batch in slots.chunks(batch_size) {
+        let batch_slots = batch.iter().flat_map(|slot| slot.0).collect::<Vec<_>>();
+        HintType::L2StorageSlots
+            .with_data(&[block_number.to_be_bytes().as_ref(), address.as_slice(), &batch_slots])
+            .send(oracle)
+            .await?;
+
+        // The account proof is shared by every batch, so the account is only opened once.
+        if account.is_none() {
+            account = Some(account_storage_root(oracle, state_root, address)?);
+        }
+        let Some(Some(storage_root)) = account else {
+            proofs.extend(batch.iter().map(|slot| (*slot, StorageSlotProof::default())));
+            continue;
+        };
+
+        for slot in batch {
+            let provider = RecordingTrieProvider::new(oracle);
+            let mut root = TrieNode::new_blinded(storage_root);
+            let value = root
+                .open(&Nibbles::unpack(keccak256(slot)), &provider)?
+                .map(|rlp| U256::decode(&mut rlp.as_ref()))
+                .transpose()
+                .map_err(OracleProviderError::Rlp)?
+                .unwrap_or_default();
+            proofs.insert(*slot, StorageSlotProof { value, proof: provider.into_nodes() });
+        }
+    }
+
+    Ok(proofs)
+}
+
+/// Returns the storage root of the account, or `None` if it does not exist in the state trie.
+fn account_storage_root<T: CommsClient>(
+    oracle: &T,
+    state_root: B256,
+    address: Address,
+) -> Result<Option<B256>, OracleProviderError> {
+    let provider = RecordingTrieProvider::new(oracle);
+    let mut root = TrieNode::new_blinded(state_root);
+    root.open(&Nibbles::unpack(keccak256(address)), &provider)?
+        .map(|rlp| TrieAccount::decode(&mut rlp.as_ref()).map(|account| account.storage_root))
+        .transpose()
+        .map_err(OracleProviderError::Rlp)
+}
+
+/// A [TrieProvider] that fetches trie nodes from the oracle by their keccak256 hash, and records
+/// the fetched nodes in order.
+#[derive(Debug)]
+struct RecordingTrieProvider<'a, T> {
+    /// The oracle to fetch trie nodes from.
+    oracle: &'a T,
+    /// The RLP-encoded trie nodes fetched so far.
+    nodes: RefCell<Vec<Bytes>>,
+}
+
+impl<'a, T> RecordingTrieProvider<'a, T> {
+    /// Creates a new [RecordingTrieProvider] with no recorded nodes.
+    const fn new(oracle: &'a T) -> Self {
+        Self { oracle, nodes: RefCell::new(Vec::new()) }
+    }
+
+    /// Returns the recorded trie nodes, in the order they were fetched.
+    fn into_nodes(self) -> Vec<Bytes> {
+        self.nodes.into_inner()
+    }
+}
+
+impl<T: CommsClient> TrieProvider for RecordingTrieProvider<'_, T> {
+    type Error = OracleProviderError;
+
+    fn trie_node_by_hash(&self, key: B256) -> Result<TrieNode, OracleProviderError> {
+        // Trie node preimages are stored as keccak preimage types in the oracle. The hint for
+        // these preimages has already been sent, prior to this call.
+        crate::block_on(async move {
+            let rlp = crate::fetch_trie_node_rlp(self.oracle, key).await?;
+            let node = TrieNode::decode(&mut rlp.as_slice()).map_err(OracleProviderError::Rlp)?;
+            self.nodes.borrow_mut().push(rlp.into());
+            Ok(node)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MockOracle;
+    use alloc::vec;
+    use alloy_rlp::Encodable;
+    use alloy_trie::{HashBuilder, proof::ProofRetainer};
+
+    use kona_preimage::PreimageKey;
+
+    /// Builds a trie from the given RLP-encoded values keyed by their hashed keys, populating the
+    /// oracle with its nodes and returning its root.
+    fn serve_trie(oracle: &mut MockOracle, mut leaves: Vec<(B256, Vec<u8>)>) -> B256 {
+        leaves.sort_by_key(|(key, _)| *key);
+
+        let targets = leaves.iter().map(|(key, _)| Nibbles::unpack(key)).collect();
+        let mut hb = HashBuilder::default().with_proof_retainer(ProofRetainer::new(targets));
+        for (key, value) in &leaves {
+            hb.add_leaf(Nibbles::unpack(key), value);
+        }
+        let root = hb.root();
+        for (_, node) in hb.take_proof_nodes().into_inner() {
+            oracle.preimages.insert(PreimageKey::new_keccak256(*keccak256(&node)), node.into());
+        }
+        root
+    }
+
+    fn rlp(value: impl Encodable) -> Vec<u8> {
+        let mut buf = Vec::new();
+        value.encode(&mut buf);
+        buf
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_fetch_storage_proofs() {
+        let mut oracle = MockOracle::default();
+        let slots = (1..=5u8).map(B256::with_last_byte).collect::<Vec<_>>();
+        let storage_root = serve_trie(
+            &mut oracle,
+            slots[..4]
+                .iter()
+                .map(|slot| (keccak256(slot), rlp(U256::from(slot[31]) * U256::from(100))))
+                .collect(),
+        );
+
+        let address = Address::repeat_byte(0xAA);
+        let account = TrieAccount { storage_root, ..Default::default() };
+        let state_root = serve_trie(
+            &mut oracle,
+            vec![(keccak256(address), rlp(account)), (B256::ZERO, rlp(TrieAccount::default()))],
+        );
+
+        let proofs =
+            fetch_storage_proofs(&oracle, state_root, 7, address, &slots, 2).await.unwrap();
+        assert_eq!(proofs.len(), 5);
+        for slot in &slots[..4] {
+            let proof = &proofs[slot];
+            assert_eq!(proof.value, U256::from(slot[31]) * U256::from(100));
+            assert_eq!(keccak256(&proof.proof[0]), storage_root);
+        }
+
+        // Slots that are not set are zero.
+        assert_eq!(proofs[&slots[4]].value, U256::ZERO);
+
+        // The slots are hinted in batches of the requested size.
+        let hints = oracle.hints.lock();
+        assert_eq!(hints.len(), 3);
+        assert!(hints.iter().all(|hint| hint.starts_with("l2-storage-slots")));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_fetch_storage_proofs_missing_account() {
+        let mut oracle = MockOracle::default();
+        let state_root = serve_trie(&mut oracle, vec![(B256::ZERO, rlp(TrieAccount::default()))]);
+
+        let slots = vec![B256::with_last_byte(1), B256::with_last_byte(2)];
+        let proofs =
+            fetch_storage_proofs(&oracle, state_root, 7, Address::ZERO, &slots, 0).await.unwrap();
+        assert_eq!(proofs.len(), 2);
+        assert!(proofs.values().all(|proof| *proof == StorageSlotProof::default()));
+
+        // A batch size of zero is clamped to one.
+        assert_eq!(oracle.hints.lock().len(), 2);
+    }
+}
@@ -9,6 +9,9 @@
 
 extern crate alloc;
 
+#[cfg(feature = "std")]
+extern crate std;
+
 #[macro_use]
 extern crate tracing;
 
@@ -23,16 +26,58 @@ pub mod errors;
 pub mod executor;
 
 mod hint;
-pub use hint::{Hint, HintType};
+#[cfg(feature = "compression")]
+pub use hint::{
+    COMPRESSED_HINT_SUFFIX, CompressionLevel, MAX_DECOMPRESSED_LEN, ResponseFlag,
+    decode_preimage_response, encode_preimage_response,
+};
+pub use hint::{
+    FieldLength, FieldSpec, Hint, HintBuilder, HintCategory, HintType, MAX_L1_BLOCK_RANGE,
+    MAX_L2_STORAGE_RANGE, MAX_L2_STORAGE_SLOTS, get_preimage_or_not_found,
+};
 
 pub mod boot;
 pub use boot::BootInfo;
 
+mod capabilities;
+pub use capabilities::{HINT_ABI_VERSION, HintCapabilities, negotiate};
+
 mod caching_oracle;
-pub use caching_oracle::{CachingOracle, FlushableCache};
+pub use caching_oracle::{CachePolicy, CachingOracle, FlushableCache, MAX_PENDING_HINTS};
+
+mod bounded_oracle;
+pub use bounded_oracle::{BoundedOracle, DEFAULT_MAX_IN_FLIGHT};
+
+mod size_warning_oracle;
+pub use size_warning_oracle::SizeWarningOracle;
+
+mod journal_oracle;
+pub use journal_oracle::{JournalEntry, JournalingOracle, get_preimage_verified};
+
+mod recording_oracle;
+pub use recording_oracle::{RecordingOracle, SessionEntry};
+
+#[cfg(feature = "std")]
+pub mod session;
+
+#[cfg(feature = "std")]
+mod timing_oracle;
+#[cfg(feature = "std")]
+pub use timing_oracle::{HintTiming, TimingOracle, UNHINTED};
 
 mod blocking_runtime;
 pub use blocking_runtime::block_on;
 
+mod trie;
+pub use trie::{fetch_trie_node, fetch_trie_node_rlp};
+
+mod output_root;
+pub use output_root::{
+    OUTPUT_ROOT_PREIMAGE_LEN, OUTPUT_ROOT_VERSION, OutputRootComponents, verify_output_root,
+};
+
 mod eip2935;
 pub use eip2935::eip_2935_history_lookup;
+
+#[cfg(test)]
+mod test_utils;
@@ -0,0 +1,99 @@
+//! Contains the [OutputRootComponents] of a version 0 L2 output root, and [verify_output_root].
+
+use crate::errors::OracleProviderError;
+use alloy_primitives::{B256, keccak256};
+
+/// The version of the output roots that [OutputRootComponents] commit to.
+pub const OUTPUT_ROOT_VERSION: u8 = 0;
+
+/// The length of the preimage of a version 0 output root.
+pub const OUTPUT_ROOT_PREIMAGE_LEN: usize = 128;
+
+/// The components that a version 0 L2 output root commits to.
+///
+/// ```text
+/// output_root = keccak256(version .. state_root .. withdrawal_storage_root .. block_hash)
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OutputRootComponents {
+    /// The state root of the L2 block.
+    pub state_root: B256,
+    /// The storage root of the `L2ToL1MessagePasser` contract at the L2 block.
+    pub withdrawal_storage_root: B256,
+    /// The hash of the L2 block.
+    pub block_hash: B256,
+}
+
+impl OutputRootComponents {
+    /// Creates new [OutputRootComponents].
+    pub const fn new(state_root: B256, withdrawal_storage_root: B256, block_hash: B256) -> Self {
+        Self { state_root, withdrawal_storage_root, block_hash }
+    }
+
+    /// Decodes the components from the preimage of an output root. The version word of the
+    /// preimage is not checked, as it is committed to by the output root.
+    pub fn from_preimage(preimage: &[u8; OUTPUT_ROOT_PREIMAGE_LEN]) -> Self {
+        Self {
+            state_root: B256::from_slice(&preimage[32..64]),
+            withdrawal_storage_root: B256::from_slice(&preimage[64..96]),
+            block_hash: B256::from_slice(&preimage[96..128]),
+        }
+    }
+
+    /// Returns the preimage of the output root that commits to the components.
+    pub fn preimage(&self) -> [u8; OUTPUT_ROOT_PREIMAGE_LEN] {
+        let mut preimage = [0u8; OUTPUT_ROOT_PREIMAGE_LEN];
+        preimage[31] = OUTPUT_ROOT_VERSION;
+        preimage[32..64].copy_from_slice(self.state_root.as_slice());
+        preimage[64..96].copy_from_slice(self.withdrawal_storage_root.as_slice());
+        preimage[96..128].copy_from_slice(self.block_hash.as_slice());
+        preimage
+    }
+
+    /// Returns the output root that commits to the components.
+    pub fn hash(&self) -> B256 {
+        keccak256(self.preimage())
+    }
+}
+
+/// Recomputes the output root of the given components, and verifies that it matches the
+/// `claimed` output root.
+pub fn verify_output_root(
+    components: &OutputRootComponents,
+    claimed: B256,
+) -> Result<(), OracleProviderError> {
+    let computed = components.hash();
+    if computed != claimed {
+        return Err(OracleProviderError::OutputRootMismatch { claimed, computed });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::b256;
+
+    #[test]
+    fn test_verify_output_root_mismatch() {
+        let components = OutputRootComponents::new(
+            b256!("0x1111111111111111111111111111111111111111111111111111111111111111"),
+            b256!("0x2222222222222222222222222222222222222222222222222222222222222222"),
+            b256!("0x3333333333333333333333333333333333333333333333333333333333333333"),
+        );
+        let output_root = components.hash();
+        assert_eq!(output_root, keccak256(components.preimage()));
+        assert_eq!(OutputRootComponents::from_preimage(&components.preimage()), components);
+        verify_output_root(&components, output_root).unwrap();
+
+        // A single diverging component changes the output root.
+        let diverged = OutputRootComponents { block_hash: B256::ZERO, ..components };
+        match verify_output_root(&diverged, output_root) {
+            Err(OracleProviderError::OutputRootMismatch { claimed, computed }) => {
+                assert_eq!(claimed, output_root);
+                assert_eq!(computed, diverged.hash());
+            }
+            res => panic!("expected an output root mismatch, got {res:?}"),
+        }
+    }
+}
@@ -0,0 +1,81 @@
+//! Contains the [RecordingOracle], which is a wrapper around an oracle that records every hint
+//! and preimage of a session, in order.
+
+use alloc::{boxed::Box, string::String, sync::Arc, vec::Vec};
+use async_trait::async_trait;
+use kona_preimage::{
+    HintWriterClient, PreimageKey, PreimageOracleClient, errors::PreimageOracleResult,
+};
+use spin::Mutex;
+
+/// An entry of the journal of a [RecordingOracle].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionEntry {
+    /// A hint written through the oracle.
+    Hint(String),
+    /// A preimage served by the oracle.
+    Preimage {
+        /// The key the preimage was requested with.
+        key: PreimageKey,
+        /// The served preimage.
+        data: Vec<u8>,
+    },
+}
+
+/// A wrapper around an oracle that records a [SessionEntry] for every hint written through
+/// [HintWriterClient::write], and every preimage served through [PreimageOracleClient::get] and
+/// [PreimageOracleClient::get_exact], in the order they occurred.
+///
+/// Unlike the [JournalingOracle], the full preimages are recorded, so that the session may be
+/// replayed without the host that served it. Clones of the oracle share the same journal.
+///
+/// [JournalingOracle]: crate::JournalingOracle
+#[derive(Debug, Clone)]
+pub struct RecordingOracle<T> {
+    /// The wrapped oracle.
+    inner: T,
+    /// The entries recorded so far, in order.
+    journal: Arc<Mutex<Vec<SessionEntry>>>,
+}
+
+impl<T> RecordingOracle<T> {
+    /// Creates a new [RecordingOracle] that wraps the given oracle, with an empty journal.
+    pub fn new(inner: T) -> Self {
+        Self { inner, journal: Arc::new(Mutex::new(Vec::new())) }
+    }
+
+    /// Returns the entries recorded so far, in order.
+    pub fn journal(&self) -> Vec<SessionEntry> {
+        self.journal.lock().clone()
+    }
+}
+
+#[async_trait]
+impl<T> PreimageOracleClient for RecordingOracle<T>
+where
+    T: PreimageOracleClient + Send + Sync,
+{
+    async fn get(&self, key: PreimageKey) -> PreimageOracleResult<Vec<u8>> {
+        let data = self.inner.get(key).await?;
+        self.journal.lock().push(SessionEntry::Preimage { key, data: data.clone() });
+        Ok(data)
+    }
+
+    async fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> PreimageOracleResult<()> {
+        self.inner.get_exact(key, buf).await?;
+        self.journal.lock().push(SessionEntry::Preimage { key, data: buf.to_vec() });
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<T> HintWriterClient for RecordingOracle<T>
+where
+    T: HintWriterClient + Send + Sync,
+{
+    async fn write(&self, hint: &str) -> PreimageOracleResult<()> {
+        self.inner.write(hint).await?;
+        self.journal.lock().push(SessionEntry::Hint(hint.into()));
+        Ok(())
+    }
+}
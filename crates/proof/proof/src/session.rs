@@ -0,0 +1,270 @@
+//! Serialization of the hint and preimage sessions recorded by a [RecordingOracle], and the
+//! [ReplayOracle] that serves a session back.
+//!
+//! A session file starts with the [SESSION_MAGIC] and the big-endian `u32` [SESSION_VERSION],
+//! followed by the [SessionEntry]s in the order they were recorded. Each entry is a one-byte tag,
+//! `0` for a hint and `1` for a preimage:
+//! - A hint is followed by its big-endian `u32` length and its UTF-8 bytes.
+//! - A preimage is followed by its 32-byte key, its big-endian `u64` length and its bytes.
+//!
+//! This is only available with the `std` feature.
+//!
+//! [RecordingOracle]: crate::RecordingOracle
+
+use crate::SessionEntry;
+use alloc::{boxed::Box, collections::BTreeMap, string::String, vec::Vec};
+use async_trait::async_trait;
+use kona_preimage::{
+    HintWriterClient, PreimageKey, PreimageOracleClient,
+    errors::{PreimageOracleError, PreimageOracleResult},
+};
+use spin::Mutex;
+use std::io::{Read, Write};
+
+/// The magic bytes that session files start with.
+pub const SESSION_MAGIC: [u8; 8] = *b"KONASESS";
+
+/// The version of the session file format.
+pub const SESSION_VERSION: u32 = 1;
+
+/// The tag of a [SessionEntry::Hint].
+const HINT_TAG: u8 = 0;
+
+/// The tag of a [SessionEntry::Preimage].
+const PREIMAGE_TAG: u8 = 1;
+
+/// An error that can occur when writing or reading a session file.
+#[derive(Debug, thiserror::Error)]
+pub enum SessionError {
+    /// The session file could not be written or read.
+    #[error("session I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    /// The file does not start with the [SESSION_MAGIC].
+    #[error("not a session file")]
+    InvalidMagic,
+    /// The session file has an unsupported version.
+    #[error("unsupported session version {0}, expected {SESSION_VERSION}")]
+    UnsupportedVersion(u32),
+    /// The session file ends in the middle of an entry.
+    #[error("truncated session file")]
+    Truncated,
+    /// An entry has an unknown tag.
+    #[error("invalid session entry tag {0}")]
+    InvalidTag(u8),
+    /// A preimage entry has an invalid key.
+    #[error("invalid preimage key: {0}")]
+    InvalidKey(PreimageOracleError),
+    /// A hint entry is not valid UTF-8.
+    #[error("invalid hint: {0}")]
+    InvalidHint(#[from] alloc::string::FromUtf8Error),
+}
+
+/// Encodes the given journal of a [RecordingOracle] in the session file format.
+///
+/// [RecordingOracle]: crate::RecordingOracle
+pub fn encode(journal: &[SessionEntry]) -> Vec<u8> {
+    let mut out = SESSION_MAGIC.to_vec();
+    out.extend_from_slice(&SESSION_VERSION.to_be_bytes());
+    for entry in journal {
+        match entry {
+            SessionEntry::Hint(hint) => {
+                out.push(HINT_TAG);
+                out.extend_from_slice(&(hint.len() as u32).to_be_bytes());
+                out.extend_from_slice(hint.as_bytes());
+            }
+            SessionEntry::Preimage { key, data } => {
+                out.push(PREIMAGE_TAG);
+                out.extend_from_slice(&<[u8; 32]>::from(*key));
+                out.extend_from_slice(&(data.len() as u64).to_be_bytes());
+                out.extend_from_slice(data);
+            }
+        }
+    }
+    out
+}
+
+/// Decodes a journal from the session file format.
+pub fn decode(mut data: &[u8]) -> Result<Vec<SessionEntry>, SessionError> {
+    if take(&mut data, SESSION_MAGIC.len())? != SESSION_MAGIC {
+        return Err(SessionError::InvalidMagic);
+    }
+    let version = u32::from_be_bytes(take_array(&mut data)?);
+    if version != SESSION_VERSION {
+        return Err(SessionError::UnsupportedVersion(version));
+    }
+
+    let mut journal = Vec::new();
+    while let Some((&tag, rest)) = data.split_first() {
+        data = rest;
+        let entry = match tag {
+            HINT_TAG => {
+                let len = u32::from_be_bytes(take_array(&mut data)?) as usize;
+                SessionEntry::Hint(String::from_utf8(take(&mut data, len)?.to_vec())?)
+            }
+            PREIMAGE_TAG => {
+                let key = PreimageKey::try_from(take_array::<32>(&mut data)?)
+                    .map_err(SessionError::InvalidKey)?;
+                let len = u64::from_be_bytes(take_array(&mut data)?);
+                let len = usize::try_from(len).map_err(|_| SessionError::Truncated)?;
+                SessionEntry::Preimage { key, data: take(&mut data, len)?.to_vec() }
+            }
+            tag => return Err(SessionError::InvalidTag(tag)),
+        };
+        journal.push(entry);
+    }
+    Ok(journal)
+}
+
+/// Writes the given journal of a [RecordingOracle] in the session file format, such as to a
+/// session file.
+///
+/// [RecordingOracle]: crate::RecordingOracle
+pub fn write(mut writer: impl Write, journal: &[SessionEntry]) -> Result<(), SessionError> {
+    writer.write_all(&encode(journal))?;
+    Ok(())
+}
+
+/// Reads a session in the session file format, such as from a session file, returning a
+/// [ReplayOracle] that serves it.
+pub fn replay(mut reader: impl Read) -> Result<ReplayOracle, SessionError> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+    Ok(ReplayOracle::new(decode(&data)?))
+}
+
+/// Splits the first `len` bytes off of `data`.
+fn take<'a>(data: &mut &'a [u8], len: usize) -> Result<&'a [u8], SessionError> {
+    if data.len() < len {
+        return Err(SessionError::Truncated);
+    }
+    let (head, rest) = data.split_at(len);
+    *data = rest;
+    Ok(head)
+}
+
+/// Splits the first `N` bytes off of `data`, as an array.
+fn take_array<const N: usize>(data: &mut &[u8]) -> Result<[u8; N], SessionError> {
+    Ok(take(data, N)?.try_into().expect("length checked"))
+}
+
+/// An oracle that serves the preimages of a recorded session, without a host.
+///
+/// Every preimage of the session is served by its key, regardless of the order it was requested
+/// in. The hints written through the oracle are collected, so that they may be compared with the
+/// recorded ones.
+#[derive(Debug)]
+pub struct ReplayOracle {
+    /// The hints of the recorded session, in order.
+    hints: Vec<String>,
+    /// The preimages of the recorded session.
+    preimages: BTreeMap<PreimageKey, Vec<u8>>,
+    /// The hints written through the oracle, in order.
+    received_hints: Mutex<Vec<String>>,
+}
+
+impl ReplayOracle {
+    /// Creates a new [ReplayOracle] that serves the given journal of a [RecordingOracle].
+    ///
+    /// [RecordingOracle]: crate::RecordingOracle
+    pub fn new(journal: Vec<SessionEntry>) -> Self {
+        let mut hints = Vec::new();
+        let mut preimages = BTreeMap::new();
+        for entry in journal {
+            match entry {
+                SessionEntry::Hint(hint) => hints.push(hint),
+                SessionEntry::Preimage { key, data } => {
+                    preimages.insert(key, data);
+                }
+            }
+        }
+        Self { hints, preimages, received_hints: Mutex::new(Vec::new()) }
+    }
+
+    /// Returns the hints of the recorded session, in order.
+    pub fn hints(&self) -> &[String] {
+        &self.hints
+    }
+
+    /// Returns the hints written through the oracle so far, in order.
+    pub fn received_hints(&self) -> Vec<String> {
+        self.received_hints.lock().clone()
+    }
+}
+
+#[async_trait]
+impl PreimageOracleClient for ReplayOracle {
+    async fn get(&self, key: PreimageKey) -> PreimageOracleResult<Vec<u8>> {
+        self.preimages.get(&key).cloned().ok_or(PreimageOracleError::KeyNotFound)
+    }
+
+    async fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> PreimageOracleResult<()> {
+        let preimage = self.preimages.get(&key).ok_or(PreimageOracleError::KeyNotFound)?;
+        if preimage.len() != buf.len() {
+            return Err(PreimageOracleError::BufferLengthMismatch(preimage.len(), buf.len()));
+        }
+        buf.copy_from_slice(preimage);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl HintWriterClient for ReplayOracle {
+    async fn write(&self, hint: &str) -> PreimageOracleResult<()> {
+        self.received_hints.lock().push(hint.into());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HintType, RecordingOracle, test_utils::MockOracle};
+    use alloc::vec;
+    use alloy_primitives::keccak256;
+
+    /// Runs a session of hints and preimage fetches against the given oracle, returning the
+    /// fetched preimages.
+    async fn run<O: PreimageOracleClient + HintWriterClient>(oracle: &O) -> Vec<Vec<u8>> {
+        let hash = keccak256(b"header");
+        HintType::L1BlockHeader.with_data(&[hash.as_slice()]).send(oracle).await.unwrap();
+        let header = oracle.get(PreimageKey::new_keccak256(*hash)).await.unwrap();
+        let mut local = [0u8; 5];
+        oracle.get_exact(PreimageKey::new_local(1), &mut local).await.unwrap();
+        vec![header, local.to_vec()]
+    }
+
+    #[tokio::test]
+    async fn test_session_replays_identically() {
+        let mut host = MockOracle::default();
+        host.preimages.insert(PreimageKey::new_keccak256(*keccak256(b"header")), b"header".into());
+        host.preimages.insert(PreimageKey::new_local(1), b"local".into());
+        let recording = RecordingOracle::new(host);
+        let recorded = run(&recording).await;
+
+        let mut session = Vec::new();
+        write(&mut session, &recording.journal()).unwrap();
+        let oracle = replay(session.as_slice()).unwrap();
+
+        // The replayed session serves the same preimages for the same hints, without the host.
+        assert_eq!(run(&oracle).await, recorded);
+        assert_eq!(oracle.received_hints(), oracle.hints());
+        assert_eq!(oracle.hints().len(), 1);
+        assert!(oracle.get(PreimageKey::new_local(2)).await.is_err());
+    }
+
+    #[test]
+    fn test_session_format_versioned() {
+        let journal = [
+            SessionEntry::Hint("l1-block-header 0x00".into()),
+            SessionEntry::Preimage { key: PreimageKey::new_local(1), data: b"local".into() },
+        ];
+        let encoded = encode(&journal);
+        assert_eq!(decode(&encoded).unwrap(), journal);
+
+        let mut future = encoded.clone();
+        future[SESSION_MAGIC.len() + 3] = 2;
+        assert!(matches!(decode(&future), Err(SessionError::UnsupportedVersion(2))));
+        assert!(matches!(decode(b"not a session"), Err(SessionError::InvalidMagic)));
+        assert!(matches!(decode(&encoded[..encoded.len() - 1]), Err(SessionError::Truncated)));
+    }
+}
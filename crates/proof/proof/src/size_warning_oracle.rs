@@ -0,0 +1,137 @@
+//! Contains the [SizeWarningOracle], which is a wrapper around an oracle that logs a warning for
+//! unexpectedly large preimages.
+
+use alloc::{boxed::Box, string::String, sync::Arc, vec::Vec};
+use async_trait::async_trait;
+use kona_preimage::{
+    HintWriterClient, PreimageKey, PreimageOracleClient, errors::PreimageOracleResult,
+};
+use spin::Mutex;
+
+/// A wrapper around an oracle that logs a warning when a preimage larger than a configured
+/// threshold is fetched.
+///
+/// Unlike an error, the warning does not fail the fetch. It is meant to surface unexpectedly large
+/// responses from the host, which may point at a misbehaving host or a malformed hint. The warning
+/// names the type of the last hint written through the oracle. Clones of the oracle share the last
+/// hint.
+#[derive(Debug, Clone)]
+pub struct SizeWarningOracle<T> {
+    /// The wrapped oracle.
+    inner: T,
+    /// The size, in bytes, above which fetched preimages are logged as a warning.
+    threshold: usize,
+    /// The type of the last hint written through the oracle.
+    last_hint: Arc<Mutex<Option<String>>>,
+}
+
+impl<T> SizeWarningOracle<T> {
+    /// Creates a new [SizeWarningOracle] that wraps the given oracle, warning about preimages
+    /// larger than `threshold` bytes.
+    pub fn new(inner: T, threshold: usize) -> Self {
+        Self { inner, threshold, last_hint: Arc::new(Mutex::new(None)) }
+    }
+
+    /// Returns the size, in bytes, above which fetched preimages are logged as a warning.
+    pub const fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// Logs a warning if a preimage of `len` bytes is above the threshold.
+    fn check(&self, len: usize) {
+        if len > self.threshold {
+            let last_hint = self.last_hint.lock();
+            warn!(
+                target: "client_oracle",
+                "Preimage for {} hint is {len} bytes, above the warning threshold of {}",
+                last_hint.as_deref().unwrap_or("unknown"),
+                self.threshold
+            );
+        }
+    }
+}
+
+#[async_trait]
+impl<T> PreimageOracleClient for SizeWarningOracle<T>
+where
+    T: PreimageOracleClient + Send + Sync,
+{
+    async fn get(&self, key: PreimageKey) -> PreimageOracleResult<Vec<u8>> {
+        let preimage = self.inner.get(key).await?;
+        self.check(preimage.len());
+        Ok(preimage)
+    }
+
+    async fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> PreimageOracleResult<()> {
+        self.inner.get_exact(key, buf).await?;
+        self.check(buf.len());
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<T> HintWriterClient for SizeWarningOracle<T>
+where
+    T: HintWriterClient + Send + Sync,
+{
+    async fn write(&self, hint: &str) -> PreimageOracleResult<()> {
+        let ty = hint.split_once(' ').map_or(hint, |(ty, _)| ty);
+        *self.last_hint.lock() = Some(ty.into());
+        self.inner.write(hint).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{HintType, test_utils::MockOracle};
+    use alloy_primitives::keccak256;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use tracing_subscriber::{
+        Layer,
+        layer::{Context, SubscriberExt},
+    };
+
+    /// A tracing layer that counts the warnings it receives.
+    #[derive(Debug, Default, Clone)]
+    struct WarningCounter(Arc<AtomicUsize>);
+
+    impl<S: tracing::Subscriber> Layer<S> for WarningCounter {
+        fn on_event(&self, event: &tracing::Event<'_>, _: Context<'_, S>) {
+            if *event.metadata().level() == tracing::Level::WARN {
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_size_warning_oracle() {
+        let warnings = WarningCounter::default();
+        let subscriber = tracing_subscriber::Registry::default().with(warnings.clone());
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let small = PreimageKey::new_keccak256(*keccak256(b"small"));
+        let large = PreimageKey::new_keccak256(*keccak256(b"large"));
+        let mut inner = MockOracle::default();
+        inner.insert(small, [0xAA; 32]);
+        inner.insert(large, [0xAA; 33]);
+        let oracle = SizeWarningOracle::new(inner, 32);
+        let hint = HintType::L2PayloadWitness.with_data(&[b"large"]);
+
+        // Preimages up to the threshold are not warned about.
+        assert_eq!(hint.send_and_get(&oracle, small).await.unwrap().len(), 32);
+        assert_eq!(warnings.0.load(Ordering::Relaxed), 0);
+
+        // Larger preimages are warned about, but are still returned.
+        assert_eq!(hint.send_and_get(&oracle, large).await.unwrap().len(), 33);
+        assert_eq!(warnings.0.load(Ordering::Relaxed), 1);
+        let mut buf = [0u8; 33];
+        oracle.get_exact(large, &mut buf).await.unwrap();
+        assert_eq!(warnings.0.load(Ordering::Relaxed), 2);
+
+        // Each oracle has its own threshold.
+        let lenient = SizeWarningOracle::new(oracle.inner.clone(), 64);
+        assert_eq!(hint.send_and_get(&lenient, large).await.unwrap().len(), 33);
+        assert_eq!(warnings.0.load(Ordering::Relaxed), 2);
+    }
+}
@@ -0,0 +1,51 @@
+//! Test utilities for the proof SDK.
+
+use alloc::{boxed::Box, collections::BTreeMap, string::String, sync::Arc, vec::Vec};
+use async_trait::async_trait;
+use kona_preimage::{
+    HintWriterClient, PreimageKey, PreimageOracleClient,
+    errors::{PreimageOracleError, PreimageOracleResult},
+};
+use spin::Mutex;
+
+/// A mock oracle serving preimages from a map, which records the hints it receives.
+///
+/// Clones of the oracle share the same recorded hints.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct MockOracle {
+    /// The preimages served by the oracle.
+    pub(crate) preimages: BTreeMap<PreimageKey, Vec<u8>>,
+    /// The hints received by the oracle, in order.
+    pub(crate) hints: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockOracle {
+    /// Serves the given preimage under `key`.
+    pub(crate) fn insert(&mut self, key: PreimageKey, preimage: impl Into<Vec<u8>>) {
+        self.preimages.insert(key, preimage.into());
+    }
+}
+
+#[async_trait]
+impl PreimageOracleClient for MockOracle {
+    async fn get(&self, key: PreimageKey) -> PreimageOracleResult<Vec<u8>> {
+        self.preimages.get(&key).cloned().ok_or(PreimageOracleError::KeyNotFound)
+    }
+
+    async fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> PreimageOracleResult<()> {
+        let preimage = self.get(key).await?;
+        if preimage.len() != buf.len() {
+            return Err(PreimageOracleError::BufferLengthMismatch(preimage.len(), buf.len()));
+        }
+        buf.copy_from_slice(&preimage);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl HintWriterClient for MockOracle {
+    async fn write(&self, hint: &str) -> PreimageOracleResult<()> {
+        self.hints.lock().push(hint.into());
+        Ok(())
+    }
+}
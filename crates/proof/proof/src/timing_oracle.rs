@@ -0,0 +1,192 @@
+//! Contains the [TimingOracle], which is a wrapper around an oracle that records the wall-clock
+//! duration of preimage fetches, bucketed by the hint type that preceded them.
+
+use alloc::{
+    boxed::Box,
+    collections::BTreeMap,
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+use async_trait::async_trait;
+use core::time::Duration;
+use kona_preimage::{
+    HintWriterClient, PreimageKey, PreimageOracleClient, errors::PreimageOracleResult,
+};
+use spin::Mutex;
+use std::time::Instant;
+
+/// The bucket that preimage fetches are recorded under if no hint has been sent yet.
+pub const UNHINTED: &str = "unhinted";
+
+/// The timing statistics of the preimage fetches recorded under a hint type.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HintTiming {
+    /// The number of preimage fetches.
+    pub count: u64,
+    /// The total duration of the preimage fetches.
+    pub total: Duration,
+    /// The duration of the slowest preimage fetch.
+    pub max: Duration,
+}
+
+impl HintTiming {
+    /// Records the duration of a preimage fetch.
+    fn record(&mut self, elapsed: Duration) {
+        self.count += 1;
+        self.total += elapsed;
+        self.max = self.max.max(elapsed);
+    }
+}
+
+/// A wrapper around an oracle that records the wall-clock duration of each
+/// [PreimageOracleClient::get] and [PreimageOracleClient::get_exact] call, bucketed by the type of
+/// the most recent hint written through it.
+///
+/// This is intended for profiling which hints are slow to serve in a native host, and is only
+/// available with the `std` feature.
+#[derive(Debug, Clone)]
+pub struct TimingOracle<T> {
+    /// The wrapped oracle.
+    inner: T,
+    /// The type of the most recent hint written through the oracle.
+    last_hint: Arc<Mutex<Option<String>>>,
+    /// The timing statistics of each hint type.
+    timings: Arc<Mutex<BTreeMap<String, HintTiming>>>,
+}
+
+impl<T> TimingOracle<T> {
+    /// Creates a new [TimingOracle] that wraps the given oracle.
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            last_hint: Arc::new(Mutex::new(None)),
+            timings: Arc::new(Mutex::new(BTreeMap::new())),
+        }
+    }
+
+    /// Returns the timing statistics of each hint type, keyed by the hint type string. Fetches
+    /// made before any hint was written are recorded under [UNHINTED].
+    pub fn summary(&self) -> BTreeMap<String, HintTiming> {
+        self.timings.lock().clone()
+    }
+
+    /// Returns the hint types, ordered from the largest to the smallest total fetch duration.
+    pub fn slowest(&self) -> Vec<(String, HintTiming)> {
+        let mut timings = self.summary().into_iter().collect::<Vec<_>>();
+        timings.sort_by(|(_, a), (_, b)| b.total.cmp(&a.total));
+        timings
+    }
+
+    /// Clears the recorded timing statistics.
+    pub fn reset(&self) {
+        self.timings.lock().clear();
+    }
+
+    /// Records the duration of a preimage fetch under the type of the most recent hint.
+    fn record(&self, elapsed: Duration) {
+        let hint_type = self.last_hint.lock().clone().unwrap_or_else(|| UNHINTED.to_string());
+        self.timings.lock().entry(hint_type).or_default().record(elapsed);
+    }
+}
+
+#[async_trait]
+impl<T> PreimageOracleClient for TimingOracle<T>
+where
+    T: PreimageOracleClient + Send + Sync,
+{
+    async fn get(&self, key: PreimageKey) -> PreimageOracleResult<Vec<u8>> {
+        let start = Instant::now();
+        let result = self.inner.get(key).await;
+        self.record(start.elapsed());
+        result
+    }
+
+    async fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> PreimageOracleResult<()> {
+        let start = Instant::now();
+        let result = self.inner.get_exact(key, buf).await;
+        self.record(start.elapsed());
+        result
+    }
+}
+
+#[async_trait]
+impl<T> HintWriterClient for TimingOracle<T>
+where
+    T: HintWriterClient + Send + Sync,
+{
+    async fn write(&self, hint: &str) -> PreimageOracleResult<()> {
+        let hint_type = hint.split(' ').next().unwrap_or_default();
+        self.last_hint.lock().replace(hint_type.to_string());
+        self.inner.write(hint).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::HintType;
+    use kona_preimage::errors::PreimageOracleError;
+
+    /// An oracle that serves empty preimages after a delay, which is the number of milliseconds
+    /// encoded in the last byte of the key.
+    #[derive(Debug, Clone)]
+    struct DelayOracle;
+
+    #[async_trait]
+    impl PreimageOracleClient for DelayOracle {
+        async fn get(&self, key: PreimageKey) -> PreimageOracleResult<Vec<u8>> {
+            let delay = <[u8; 32]>::from(key)[31];
+            tokio::time::sleep(Duration::from_millis(delay as u64)).await;
+            Ok(Vec::new())
+        }
+
+        async fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> PreimageOracleResult<()> {
+            let value = self.get(key).await?;
+            if buf.len() != value.len() {
+                return Err(PreimageOracleError::BufferLengthMismatch(value.len(), buf.len()));
+            }
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl HintWriterClient for DelayOracle {
+        async fn write(&self, _: &str) -> PreimageOracleResult<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_timing_oracle_records_per_hint_type() {
+        let oracle = TimingOracle::new(DelayOracle);
+
+        oracle.get(PreimageKey::new_local(1)).await.unwrap();
+
+        HintType::L1BlockHeader.with_data(&[&[0; 32]]).send(&oracle).await.unwrap();
+        oracle.get(PreimageKey::new_local(10)).await.unwrap();
+
+        HintType::L2Code.with_data(&[&[0; 32]]).send(&oracle).await.unwrap();
+        oracle.get(PreimageKey::new_local(20)).await.unwrap();
+        oracle.get_exact(PreimageKey::new_local(40), &mut []).await.unwrap();
+
+        let summary = oracle.summary();
+        assert_eq!(summary.len(), 3);
+        assert_eq!(summary[UNHINTED].count, 1);
+
+        let header = summary["l1-block-header"];
+        assert_eq!(header.count, 1);
+        assert!(header.total >= Duration::from_millis(10));
+
+        let code = summary["l2-code"];
+        assert_eq!(code.count, 2);
+        assert!(code.total >= Duration::from_millis(60));
+        assert!(code.max >= Duration::from_millis(40));
+        assert!(code.max <= code.total);
+
+        assert_eq!(oracle.slowest()[0].0, "l2-code");
+
+        oracle.reset();
+        assert!(oracle.summary().is_empty());
+    }
+}
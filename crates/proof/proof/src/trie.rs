@@ -0,0 +1,72 @@
+//! Contains the [fetch_trie_node] helper, which fetches a trie node from the oracle by its hash.
+
+use crate::errors::OracleProviderError;
+use alloc::vec::Vec;
+use alloy_primitives::{B256, keccak256};
+use alloy_rlp::Decodable;
+use kona_mpt::TrieNode;
+use kona_preimage::{PreimageKey, PreimageOracleClient};
+
+/// Fetches the RLP encoding of the trie node with the given keccak256 hash from the oracle.
+///
+/// Trie node preimages are keyed strictly by the hash of their RLP encoding, so the fetched bytes
+/// are hashed and checked against the requested hash before they are returned. A mismatch is
+/// reported as [OracleProviderError::TrieNodeHashMismatch]. The hint for the node must have been
+/// sent prior to this call.
+pub async fn fetch_trie_node_rlp<T: PreimageOracleClient + ?Sized>(
+    oracle: &T,
+    hash: B256,
+) -> Result<Vec<u8>, OracleProviderError> {
+    let rlp = oracle.get(PreimageKey::new_keccak256(*hash)).await?;
+    let computed = keccak256(&rlp);
+    if computed != hash {
+        return Err(OracleProviderError::TrieNodeHashMismatch { expected: hash, computed });
+    }
+    Ok(rlp)
+}
+
+/// Fetches the trie node with the given keccak256 hash from the oracle, and decodes it.
+///
+/// See [fetch_trie_node_rlp] for the validation of the fetched preimage.
+pub async fn fetch_trie_node<T: PreimageOracleClient + ?Sized>(
+    oracle: &T,
+    hash: B256,
+) -> Result<TrieNode, OracleProviderError> {
+    let rlp = fetch_trie_node_rlp(oracle, hash).await?;
+    TrieNode::decode(&mut rlp.as_slice()).map_err(OracleProviderError::Rlp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MockOracle;
+
+    use alloy_primitives::Bytes;
+    use alloy_rlp::Encodable;
+
+    #[tokio::test]
+    async fn test_fetch_trie_node_rejects_tampered_node() {
+        let node =
+            TrieNode::Leaf { prefix: Default::default(), value: Bytes::from_static(b"value") };
+        let mut rlp = Vec::new();
+        node.encode(&mut rlp);
+        let hash = keccak256(&rlp);
+
+        let mut oracle = MockOracle::default();
+        oracle.preimages.insert(PreimageKey::new_keccak256(*hash), rlp.clone());
+        assert_eq!(fetch_trie_node_rlp(&oracle, hash).await.unwrap(), rlp);
+        assert_eq!(fetch_trie_node(&oracle, hash).await.unwrap(), node);
+
+        // A node served under the hash of a different node is rejected, even if it decodes.
+        let tampered =
+            TrieNode::Leaf { prefix: Default::default(), value: Bytes::from_static(b"tampered") };
+        let mut tampered_rlp = Vec::new();
+        tampered.encode(&mut tampered_rlp);
+        oracle.preimages.insert(PreimageKey::new_keccak256(*hash), tampered_rlp.clone());
+        assert!(matches!(
+            fetch_trie_node(&oracle, hash).await,
+            Err(OracleProviderError::TrieNodeHashMismatch { expected, computed })
+                if expected == hash && computed == keccak256(&tampered_rlp)
+        ));
+    }
+}
@@ -1,13 +1,14 @@
 //! This module contains a rudamentary channel between two file descriptors, using [crate::io]
 //! for reading and writing from the file descriptors.
 
-use crate::{FileDescriptor, io};
+use crate::{BasicKernelInterface, FileDescriptor, io};
 use alloc::boxed::Box;
 use async_trait::async_trait;
 use core::{
     cell::RefCell,
     cmp::Ordering,
     future::Future,
+    marker::PhantomData,
     pin::Pin,
     task::{Context, Poll},
 };
@@ -49,39 +50,42 @@ impl Channel for FileChannel {
     }
 
     async fn read_exact(&self, buf: &mut [u8]) -> ChannelResult<usize> {
-        ReadFuture::new(*self, buf).await.map_err(|_| ChannelError::Closed)
+        ReadFuture::<io::ClientIO>::new(*self, buf).await.map_err(|_| ChannelError::Closed)
     }
 
     async fn write(&self, buf: &[u8]) -> ChannelResult<usize> {
-        WriteFuture::new(*self, buf).await.map_err(|_| ChannelError::Closed)
+        WriteFuture::<io::ClientIO>::new(*self, buf).await.map_err(|_| ChannelError::Closed)
     }
 }
 
-/// A future that reads from a channel, returning [Poll::Ready] when the buffer is full.
-struct ReadFuture<'a> {
+/// A future that reads from a channel through the kernel interface `K`, returning [Poll::Ready]
+/// when the buffer is full.
+struct ReadFuture<'a, K> {
     /// The channel to read from
     channel: FileChannel,
     /// The buffer to read into
     buf: RefCell<&'a mut [u8]>,
     /// The number of bytes read so far
     read: usize,
+    /// The kernel interface used to read from the channel
+    _kernel: PhantomData<fn() -> K>,
 }
 
-impl<'a> ReadFuture<'a> {
+impl<'a, K> ReadFuture<'a, K> {
     /// Create a new [ReadFuture] from a channel and a buffer.
     #[allow(clippy::missing_const_for_fn)]
     fn new(channel: FileChannel, buf: &'a mut [u8]) -> Self {
-        Self { channel, buf: RefCell::new(buf), read: 0 }
+        Self { channel, buf: RefCell::new(buf), read: 0, _kernel: PhantomData }
     }
 }
 
-impl Future for ReadFuture<'_> {
+impl<K: BasicKernelInterface> Future for ReadFuture<'_, K> {
     type Output = ChannelResult<usize>;
 
     fn poll(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
         let mut buf = self.buf.borrow_mut();
         let buf_len = buf.len();
-        let chunk_read = io::read(self.channel.read_handle, &mut buf[self.read..])
+        let chunk_read = K::read(self.channel.read_handle, &mut buf[self.read..])
             .map_err(|_| ChannelError::Closed)?;
 
         // Drop the borrow on self.
@@ -92,6 +96,9 @@ impl Future for ReadFuture<'_> {
         match self.read.cmp(&buf_len) {
             Ordering::Greater | Ordering::Equal => Poll::Ready(Ok(self.read)),
             Ordering::Less => {
+                // Give a cooperatively scheduled host a chance to run between chunks.
+                io::yield_now();
+
                 // Register the current task to be woken up when it can make progress
                 ctx.waker().wake_by_ref();
                 Poll::Pending
@@ -100,29 +107,31 @@ impl Future for ReadFuture<'_> {
     }
 }
 
-/// A future that writes to a channel, returning [Poll::Ready] when the full buffer has been
-/// written.
-struct WriteFuture<'a> {
+/// A future that writes to a channel through the kernel interface `K`, returning [Poll::Ready]
+/// when the full buffer has been written.
+struct WriteFuture<'a, K> {
     /// The channel to write to
     channel: FileChannel,
     /// The buffer to write
     buf: &'a [u8],
     /// The number of bytes written so far
     written: usize,
+    /// The kernel interface used to write to the channel
+    _kernel: PhantomData<fn() -> K>,
 }
 
-impl<'a> WriteFuture<'a> {
+impl<'a, K> WriteFuture<'a, K> {
     /// Create a new [WriteFuture] from a channel and a buffer.
     const fn new(channel: FileChannel, buf: &'a [u8]) -> Self {
-        Self { channel, buf, written: 0 }
+        Self { channel, buf, written: 0, _kernel: PhantomData }
     }
 }
 
-impl Future for WriteFuture<'_> {
+impl<K: BasicKernelInterface> Future for WriteFuture<'_, K> {
     type Output = ChannelResult<usize>;
 
     fn poll(mut self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Self::Output> {
-        match io::write(self.channel.write_handle(), &self.buf[self.written..]) {
+        match K::write(self.channel.write_handle(), &self.buf[self.written..]) {
             Ok(0) => Poll::Ready(Ok(self.written)), // Finished writing
             Ok(n) => {
                 self.written += n;
@@ -131,6 +140,9 @@ impl Future for WriteFuture<'_> {
                     return Poll::Ready(Ok(self.written));
                 }
 
+                // Give a cooperatively scheduled host a chance to run between chunks.
+                io::yield_now();
+
                 // Register the current task to be woken up when it can make progress
                 ctx.waker().wake_by_ref();
                 Poll::Pending
@@ -152,4 +164,62 @@ mod tests {
         let ref_read_handle = chan.read_handle();
         assert_eq!(read_handle, ref_read_handle);
     }
+
+    #[cfg(feature = "host")]
+    #[test]
+    fn test_yield_hook_invoked_during_multi_chunk_read() {
+        use crate::errors::IOResult;
+        use core::{
+            pin::pin,
+            sync::atomic::{AtomicUsize, Ordering},
+            task::Waker,
+        };
+
+        const DATA: &[u8] = b"a multi-chunk preimage";
+        const CHUNK_SIZE: usize = 4;
+
+        static OFFSET: AtomicUsize = AtomicUsize::new(0);
+        static YIELDS: AtomicUsize = AtomicUsize::new(0);
+
+        /// A kernel interface that serves [DATA] at most [CHUNK_SIZE] bytes at a time.
+        struct ChunkedIO;
+
+        impl BasicKernelInterface for ChunkedIO {
+            fn write(_: FileDescriptor, buf: &[u8]) -> IOResult<usize> {
+                Ok(buf.len())
+            }
+
+            fn read(_: FileDescriptor, buf: &mut [u8]) -> IOResult<usize> {
+                let offset = OFFSET.load(Ordering::SeqCst);
+                let n = buf.len().min(CHUNK_SIZE).min(DATA.len() - offset);
+                buf[..n].copy_from_slice(&DATA[offset..offset + n]);
+                OFFSET.fetch_add(n, Ordering::SeqCst);
+                Ok(n)
+            }
+
+            fn exit(code: usize) -> ! {
+                panic!("unexpected exit: {code}")
+            }
+        }
+
+        io::set_yield_hook(Some(|| {
+            YIELDS.fetch_add(1, Ordering::SeqCst);
+        }));
+
+        let chan = FileChannel::new(FileDescriptor::PreimageRead, FileDescriptor::PreimageWrite);
+        let mut buf = [0u8; DATA.len()];
+        let mut future = pin!(ReadFuture::<ChunkedIO>::new(chan, &mut buf));
+        let mut ctx = Context::from_waker(Waker::noop());
+        let read = loop {
+            if let Poll::Ready(res) = future.as_mut().poll(&mut ctx) {
+                break res.unwrap();
+            }
+        };
+        io::set_yield_hook(None);
+
+        assert_eq!(read, DATA.len());
+        assert_eq!(buf, DATA);
+        // The hook is invoked between each of the chunks.
+        assert_eq!(YIELDS.load(Ordering::SeqCst), DATA.len().div_ceil(CHUNK_SIZE) - 1);
+    }
 }
@@ -2,10 +2,47 @@
 
 use thiserror::Error;
 
+/// The errno of an interrupted system call.
+pub const EINTR: i32 = 4;
+
+/// The errno of a bad file descriptor.
+pub const EBADF: i32 = 9;
+
+/// The errno of a resource that is temporarily unavailable.
+pub const EAGAIN: i32 = 11;
+
+/// The errno of a write to a pipe with no reader.
+pub const EPIPE: i32 = 32;
+
 /// An error that can occur when reading from or writing to a file descriptor.
 #[derive(Error, Debug, PartialEq, Eq)]
 #[error("IO error (errno: {_0})")]
 pub struct IOError(pub i32);
 
+impl IOError {
+    /// Returns `true` if the operation may succeed if retried, such as when it was interrupted
+    /// ([EINTR]) or would have blocked ([EAGAIN]).
+    ///
+    /// Every other error is terminal, including the host having gone away ([EPIPE]) or the file
+    /// descriptor being invalid ([EBADF]).
+    pub const fn is_retryable(&self) -> bool {
+        matches!(self.0, EINTR | EAGAIN)
+    }
+}
+
 /// A [Result] type for the [IOError].
 pub type IOResult<T> = Result<T, IOError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_io_error_is_retryable() {
+        assert!(IOError(EINTR).is_retryable());
+        assert!(IOError(EAGAIN).is_retryable());
+        assert!(!IOError(EPIPE).is_retryable());
+        assert!(!IOError(EBADF).is_retryable());
+        assert!(!IOError(0).is_retryable());
+    }
+}
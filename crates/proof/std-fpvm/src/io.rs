@@ -76,6 +76,39 @@ pub fn read(fd: FileDescriptor, buf: &mut [u8]) -> IOResult<usize> {
     ClientIO::read(fd, buf)
 }
 
+/// The hook invoked by [yield_now], stored as a type-erased `fn()` pointer. Null if unset.
+#[cfg(feature = "host")]
+static YIELD_HOOK: core::sync::atomic::AtomicPtr<()> =
+    core::sync::atomic::AtomicPtr::new(core::ptr::null_mut());
+
+/// Sets the hook invoked by [yield_now], or clears it if `None` is passed.
+///
+/// This allows a host test harness that drives the FPVM I/O cooperatively to interleave other
+/// work between the chunks of long reads and writes.
+#[cfg(feature = "host")]
+pub fn set_yield_hook(hook: Option<fn()>) {
+    let ptr = hook.map_or(core::ptr::null_mut(), |hook| hook as *mut ());
+    YIELD_HOOK.store(ptr, core::sync::atomic::Ordering::Release);
+}
+
+/// A cooperative yield point, called between the chunks of multi-chunk reads and writes.
+///
+/// On the real VM, and without the `host` feature, this is a no-op. With the `host` feature, the
+/// hook set with [set_yield_hook] is invoked, if any.
+#[inline]
+#[allow(clippy::missing_const_for_fn)]
+pub fn yield_now() {
+    #[cfg(feature = "host")]
+    {
+        let hook = YIELD_HOOK.load(core::sync::atomic::Ordering::Acquire);
+        if !hook.is_null() {
+            // SAFETY: Non-null values are only ever stored by `set_yield_hook`, from a `fn()`.
+            let hook = unsafe { core::mem::transmute::<*mut (), fn()>(hook) };
+            hook();
+        }
+    }
+}
+
 /// Exit the process with the given exit code.
 #[inline]
 pub fn exit(code: usize) -> ! {
@@ -10,6 +10,14 @@
 
 extern crate alloc;
 
+// The native fallback `ClientIO` is built on unix file descriptors, so targets that are neither a
+// supported FPVM architecture nor a unix host have no kernel interface at all.
+#[cfg(not(any(target_arch = "mips64", target_arch = "riscv64", unix)))]
+compile_error!(
+    "`kona-std-fpvm` only supports the `mips64` and `riscv64` FPVM target architectures, or a \
+     native unix host"
+);
+
 pub mod errors;
 
 pub mod io;
@@ -19,6 +27,9 @@ pub mod tracing;
 
 pub mod malloc;
 
+mod ring_buffer;
+pub use ring_buffer::RingBuffer;
+
 mod traits;
 pub use traits::BasicKernelInterface;
 
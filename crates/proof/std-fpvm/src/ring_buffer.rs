@@ -0,0 +1,160 @@
+//! This module contains the [RingBuffer], a lock-free, fixed-capacity queue.
+
+use alloc::boxed::Box;
+use core::{
+    marker::PhantomData,
+    ptr,
+    sync::atomic::{AtomicPtr, AtomicUsize, Ordering},
+};
+
+/// A lock-free, single-producer single-consumer ring buffer with a fixed capacity of `N` entries.
+///
+/// When the buffer is full, pushing a new entry overwrites the oldest entry, and the
+/// [RingBuffer::dropped] counter is incremented. Each slot is an [AtomicPtr], so ownership of an
+/// entry is always transferred by a single atomic swap, and entries are never dropped twice.
+#[derive(Debug)]
+pub struct RingBuffer<T, const N: usize> {
+    /// The slots of the buffer. A null pointer denotes an empty slot.
+    slots: [AtomicPtr<T>; N],
+    /// The position of the next entry to read.
+    head: AtomicUsize,
+    /// The position of the next entry to write.
+    tail: AtomicUsize,
+    /// The number of entries that were overwritten before being read.
+    dropped: AtomicUsize,
+    /// The buffer owns the boxed entries behind the slot pointers.
+    _owned: PhantomData<Box<T>>,
+}
+
+impl<T, const N: usize> Default for RingBuffer<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> RingBuffer<T, N> {
+    /// Creates a new, empty [RingBuffer].
+    pub const fn new() -> Self {
+        assert!(N > 0, "RingBuffer capacity must be non-zero");
+        Self {
+            slots: [const { AtomicPtr::new(ptr::null_mut()) }; N],
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+            dropped: AtomicUsize::new(0),
+            _owned: PhantomData,
+        }
+    }
+
+    /// Returns the capacity of the buffer.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns the number of entries in the buffer.
+    pub fn len(&self) -> usize {
+        self.tail.load(Ordering::Acquire).saturating_sub(self.head.load(Ordering::Acquire))
+    }
+
+    /// Returns `true` if the buffer is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of entries that were overwritten before being read.
+    pub fn dropped(&self) -> usize {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Pushes an entry onto the buffer. If the buffer is full, the oldest entry is dropped.
+    pub fn push(&self, value: T) {
+        let tail = self.tail.load(Ordering::Relaxed);
+        let new = Box::into_raw(Box::new(value));
+        let old = self.slots[tail % N].swap(new, Ordering::AcqRel);
+
+        if !old.is_null() {
+            // SAFETY: Non-null pointers in the slots are always created by `Box::into_raw`, and
+            // the atomic swap transfers exclusive ownership of `old` to this call.
+            drop(unsafe { Box::from_raw(old) });
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            // Skip the reader past the overwritten entry.
+            self.head.fetch_max(tail + 1 - N, Ordering::AcqRel);
+        }
+
+        self.tail.store(tail + 1, Ordering::Release);
+    }
+
+    /// Pops the oldest entry from the buffer, if any.
+    pub fn pop(&self) -> Option<T> {
+        loop {
+            let head = self.head.load(Ordering::Acquire);
+            if head >= self.tail.load(Ordering::Acquire) {
+                return None;
+            }
+
+            let ptr = self.slots[head % N].swap(ptr::null_mut(), Ordering::AcqRel);
+            self.head.fetch_max(head + 1, Ordering::AcqRel);
+
+            if !ptr.is_null() {
+                // SAFETY: Non-null pointers in the slots are always created by `Box::into_raw`,
+                // and the atomic swap transfers exclusive ownership of `ptr` to this call.
+                return Some(*unsafe { Box::from_raw(ptr) });
+            }
+        }
+    }
+}
+
+impl<T, const N: usize> Drop for RingBuffer<T, N> {
+    fn drop(&mut self) {
+        for slot in self.slots.iter_mut() {
+            let ptr = *slot.get_mut();
+            if !ptr.is_null() {
+                // SAFETY: Non-null pointers in the slots are always created by `Box::into_raw`,
+                // and `&mut self` guarantees exclusive access.
+                drop(unsafe { Box::from_raw(ptr) });
+            }
+        }
+    }
+}
+
+// SAFETY: Entries are only ever moved in and out of the buffer through atomic swaps, so the buffer
+// may be shared between threads as long as the entries themselves may be sent between threads.
+unsafe impl<T: Send, const N: usize> Sync for RingBuffer<T, N> {}
+
+#[cfg(all(test, not(any(target_arch = "mips64", target_arch = "riscv64"))))]
+mod tests {
+    use super::*;
+    use alloc::{string::String, vec::Vec};
+
+    #[test]
+    fn test_ring_buffer_push_pop() {
+        let buffer = RingBuffer::<u64, 4>::new();
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.pop(), None);
+
+        buffer.push(1);
+        buffer.push(2);
+        assert_eq!(buffer.len(), 2);
+        assert_eq!(buffer.pop(), Some(1));
+        assert_eq!(buffer.pop(), Some(2));
+        assert_eq!(buffer.pop(), None);
+        assert_eq!(buffer.dropped(), 0);
+    }
+
+    #[test]
+    fn test_ring_buffer_overflow_drops_oldest() {
+        let buffer = RingBuffer::<String, 3>::new();
+        for i in 0..5 {
+            buffer.push(alloc::format!("event {i}"));
+        }
+
+        assert_eq!(buffer.len(), 3);
+        assert_eq!(buffer.dropped(), 2);
+
+        let events = core::iter::from_fn(|| buffer.pop()).collect::<Vec<_>>();
+        assert_eq!(events, ["event 2", "event 3", "event 4"]);
+
+        buffer.push(String::from("event 5"));
+        assert_eq!(buffer.pop().as_deref(), Some("event 5"));
+        assert_eq!(buffer.dropped(), 2);
+    }
+}
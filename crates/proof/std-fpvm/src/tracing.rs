@@ -1,9 +1,10 @@
 //! This module contains
 
-use crate::io;
+use crate::{RingBuffer, io};
 use alloc::{
     format,
     string::{String, ToString},
+    sync::Arc,
 };
 use tracing::{
     Event, Level, Metadata, Subscriber,
@@ -11,17 +12,55 @@ use tracing::{
     span::{Attributes, Id, Record},
 };
 
+/// The capacity of the [FpvmTracingSubscriber]'s event buffer.
+pub const TRACE_BUFFER_CAPACITY: usize = 256;
+
+/// The buffer of formatted trace events.
+pub type TraceBuffer = RingBuffer<String, TRACE_BUFFER_CAPACITY>;
+
 /// Custom [Subscriber] implementation that uses [crate::io] to write log entries to
 /// [crate::FileDescriptor::StdOut].
+///
+/// A buffered subscriber, created with [FpvmTracingSubscriber::new_buffered], stores formatted
+/// events in a lock-free [TraceBuffer] rather than writing each one immediately. Buffered events
+/// are written out by [FpvmTracingSubscriber::flush]. If the buffer overflows, the oldest events
+/// are dropped.
 #[derive(Debug, Clone)]
 pub struct FpvmTracingSubscriber {
     min_level: Level,
+    buffer: Option<Arc<TraceBuffer>>,
 }
 
 impl FpvmTracingSubscriber {
     /// Create a new [FpvmTracingSubscriber] with the specified minimum log level.
     pub const fn new(min_level: Level) -> Self {
-        Self { min_level }
+        Self { min_level, buffer: None }
+    }
+
+    /// Create a new buffered [FpvmTracingSubscriber] with the specified minimum log level.
+    pub fn new_buffered(min_level: Level) -> Self {
+        Self { min_level, buffer: Some(Arc::new(TraceBuffer::new())) }
+    }
+
+    /// Writes all buffered events to [crate::FileDescriptor::StdOut]. If any events have been
+    /// dropped due to overflow, a note with the total number of dropped events is written first.
+    pub fn flush(&self) {
+        let Some(buffer) = &self.buffer else {
+            return;
+        };
+
+        let dropped = buffer.dropped();
+        if dropped > 0 {
+            io::print(&format!("[tracing] {dropped} events dropped due to buffer overflow"));
+        }
+        while let Some(entry) = buffer.pop() {
+            io::print(&entry);
+        }
+    }
+
+    /// Returns the number of events dropped due to buffer overflow.
+    pub fn dropped(&self) -> usize {
+        self.buffer.as_ref().map_or(0, |b| b.dropped())
     }
 }
 
@@ -48,7 +87,11 @@ impl Subscriber for FpvmTracingSubscriber {
 
         let mut visitor = FieldVisitor::new();
         event.record(&mut visitor);
-        io::print(&format!("[{}] {}: {}", metadata.level(), metadata.target(), visitor.message));
+        let entry = format!("[{}] {}: {}", metadata.level(), metadata.target(), visitor.message);
+        match &self.buffer {
+            Some(buffer) => buffer.push(entry),
+            None => io::print(&entry),
+        }
     }
 
     fn enter(&self, _span: &Id) {}
@@ -1,6 +1,7 @@
 //! Contains an online implementation of the `BeaconClient` trait.
 
 use alloy_eips::eip4844::IndexedBlobHash;
+use alloy_primitives::B256;
 use alloy_rpc_types_beacon::sidecar::{BeaconBlobBundle, BlobData};
 use async_trait::async_trait;
 use reqwest::Client;
@@ -20,6 +21,55 @@ const GENESIS_METHOD: &str = "eth/v1/beacon/genesis";
 /// The blob sidecars engine api method prefix.
 const SIDECARS_METHOD_PREFIX: &str = "eth/v1/beacon/blob_sidecars";
 
+/// The beacon blocks engine api method prefix.
+const BLOCKS_METHOD_PREFIX: &str = "eth/v2/beacon/blocks";
+
+/// The beacon states engine api method prefix.
+const STATES_METHOD_PREFIX: &str = "eth/v1/beacon/states";
+
+/// The fields of a beacon block response needed to resolve finality.
+#[derive(Debug, Clone, serde::Deserialize)]
+struct BeaconBlockResponse {
+    data: SignedBeaconBlockData,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+struct SignedBeaconBlockData {
+    message: BeaconBlockMessage,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+struct BeaconBlockMessage {
+    state_root: B256,
+    body: BeaconBlockBody,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+struct BeaconBlockBody {
+    execution_payload: ExecutionPayloadHeader,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+struct ExecutionPayloadHeader {
+    block_hash: B256,
+}
+
+/// The fields of a finality checkpoints response needed to resolve finality.
+#[derive(Debug, Clone, serde::Deserialize)]
+struct FinalityCheckpointsResponse {
+    data: FinalityCheckpoints,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+struct FinalityCheckpoints {
+    finalized: Checkpoint,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+struct Checkpoint {
+    root: B256,
+}
+
 /// A reduced genesis data.
 #[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct ReducedGenesisData {
@@ -106,6 +156,53 @@ impl OnlineBeaconClient {
         }
         Self { base, inner: Client::new() }
     }
+
+    /// Fetches the SSZ-encoded signed beacon block with the given block id, which is a slot
+    /// number, a `0x`-prefixed block root, or one of `head`, `genesis` and `finalized`.
+    pub async fn beacon_block(&self, block_id: &str) -> Result<Vec<u8>, reqwest::Error> {
+        let response = self
+            .inner
+            .get(format!("{}/{}/{}", self.base, BLOCKS_METHOD_PREFIX, block_id))
+            .header(reqwest::header::ACCEPT, "application/octet-stream")
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    /// Returns the hash of the execution block that was finalized as of the beacon block with the
+    /// given root.
+    ///
+    /// The finalized checkpoint is read from the post-state of the beacon block, so the result
+    /// does not change as the chain progresses. Both the beacon block and the finalized block must
+    /// carry an execution payload.
+    pub async fn finalized_block_hash(&self, block_root: B256) -> Result<B256, reqwest::Error> {
+        let block = self.beacon_block_json(block_root).await?;
+        let checkpoints = self
+            .inner
+            .get(format!(
+                "{}/{}/{}/finality_checkpoints",
+                self.base, STATES_METHOD_PREFIX, block.data.message.state_root
+            ))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<FinalityCheckpointsResponse>()
+            .await?;
+        let finalized = self.beacon_block_json(checkpoints.data.finalized.root).await?;
+        Ok(finalized.data.message.body.execution_payload.block_hash)
+    }
+
+    /// Fetches the JSON-encoded beacon block with the given root.
+    async fn beacon_block_json(&self, root: B256) -> Result<BeaconBlockResponse, reqwest::Error> {
+        self.inner
+            .get(format!("{}/{}/{}", self.base, BLOCKS_METHOD_PREFIX, root))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<BeaconBlockResponse>()
+            .await
+    }
 }
 
 #[async_trait]
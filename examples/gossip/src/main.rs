@@ -75,7 +75,7 @@ impl GossipCommand {
             .build()?;
         let recv =
             driver.take_unsafe_block_recv().ok_or(anyhow::anyhow!("No unsafe block receiver"))?;
-        driver.start()?;
+        driver.start().await?;
         tracing::info!("Gossip driver started, receiving blocks.");
         loop {
             match recv.recv() {